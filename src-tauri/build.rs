@@ -1,3 +1,6 @@
 fn main() {
-    tauri_build::build()
+    // The library alone has no app config or icons to build
+    if std::env::var_os("CARGO_FEATURE_GUI").is_some() {
+        tauri_build::build()
+    }
 }
@@ -0,0 +1,283 @@
+//! Action items extracted from summarized recordings.
+//!
+//! The summary step asks the chat model for action items as structured data.
+//! They're kept in history with the summary and, when
+//! `summary.action_item_sinks` is set, sent to Apple Reminders or appended to
+//! a todo file as Markdown checkboxes. Delivery runs in the background so a
+//! Reminders permission prompt doesn't hold up the paste.
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+pub struct ActionItem {
+    pub task: String,
+    /// Who should do it, if the transcript says
+    #[serde(default)]
+    pub owner: Option<String>,
+    /// When it's due, as said in the transcript (e.g. "by Friday")
+    #[serde(default)]
+    pub due: Option<String>,
+}
+
+impl ActionItem {
+    /// Owner and due date in parentheses, e.g. "(Anna, by Friday)"
+    fn details(&self) -> Option<String> {
+        let parts: Vec<&str> = [self.owner.as_deref(), self.due.as_deref()]
+            .into_iter()
+            .flatten()
+            .filter(|part| !part.trim().is_empty())
+            .collect();
+        (!parts.is_empty()).then(|| format!("({})", parts.join(", ")))
+    }
+
+    /// One-line description, e.g. "Send the report (Anna, by Friday)"
+    pub fn describe(&self) -> String {
+        match self.details() {
+            Some(details) => format!("{} {}", self.task, details),
+            None => self.task.clone(),
+        }
+    }
+}
+
+/// Where action items go, in order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ActionItemSinkConfig {
+    /// Create a reminder per item. `list` is the Reminders list name
+    /// (None = the default list).
+    Reminders { list: Option<String> },
+    /// Append unchecked Markdown tasks to a file, e.g. `~/Notes/todo.md`
+    TodoFile { path: String },
+}
+
+/// Markdown block appended to a todo file
+fn todo_markdown(items: &[ActionItem], heading: &str) -> String {
+    let mut markdown = format!("\n## {}\n\n", heading);
+    for item in items {
+        markdown.push_str(&format!("- [ ] {}\n", item.describe()));
+    }
+    markdown
+}
+
+/// Expand a leading `~/` to the home directory
+fn expand_home(path: &str) -> std::path::PathBuf {
+    match (path.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => std::path::Path::new(&home).join(rest),
+        _ => std::path::PathBuf::from(path),
+    }
+}
+
+fn append_to_todo_file(path: &str, items: &[ActionItem]) -> Result<(), String> {
+    let heading = Local::now().format("%Y-%m-%d %H:%M").to_string();
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(expand_home(path))
+        .map_err(|e| format!("Failed to open {}: {}", path, e))?;
+    file.write_all(todo_markdown(items, &heading).as_bytes())
+        .map_err(|e| format!("Failed to write {}: {}", path, e))
+}
+
+fn deliver(sinks: &[ActionItemSinkConfig], items: &[ActionItem]) {
+    for sink in sinks {
+        let result = match sink {
+            ActionItemSinkConfig::Reminders { list } => {
+                reminders::create(items, list.as_deref()).map(|_| ())
+            }
+            ActionItemSinkConfig::TodoFile { path } => append_to_todo_file(path, items),
+        };
+        match result {
+            Ok(()) => println!("[Action Items] Sent {} items to {:?}", items.len(), sink),
+            Err(e) => eprintln!("[Action Items] {:?} failed: {}", sink, e),
+        }
+    }
+}
+
+/// Send `items` to every configured sink on a background thread
+pub fn deliver_in_background(sinks: &[ActionItemSinkConfig], items: &[ActionItem]) {
+    if sinks.is_empty() || items.is_empty() {
+        return;
+    }
+
+    let sinks = sinks.to_vec();
+    let items = items.to_vec();
+    std::thread::spawn(move || deliver(&sinks, &items));
+}
+
+#[cfg(target_os = "macos")]
+mod reminders {
+    use super::ActionItem;
+    use block2::RcBlock;
+    use objc2::msg_send;
+    use objc2::rc::Retained;
+    use objc2::runtime::{AnyClass, AnyObject, Bool, Sel};
+    use objc2::sel;
+    use objc2_foundation::{NSError, NSString};
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    #[link(name = "EventKit", kind = "framework")]
+    extern "C" {}
+
+    /// EKEntityTypeReminder
+    const ENTITY_TYPE_REMINDER: usize = 1;
+
+    /// EKAuthorizationStatus: 0 = notDetermined, 1 = restricted, 2 = denied,
+    /// 3 = fullAccess (authorized), 4 = writeOnly
+    const STATUS_NOT_DETERMINED: isize = 0;
+    const STATUS_FULL_ACCESS: isize = 3;
+
+    /// Time the user has to answer the permission prompt
+    const ACCESS_PROMPT_TIMEOUT: Duration = Duration::from_secs(120);
+
+    /// Ask for Reminders access, waiting for the user's answer
+    fn request_access(store: &AnyObject) -> bool {
+        let (tx, rx) = mpsc::channel();
+        let completion = RcBlock::new(move |granted: Bool, _error: *mut NSError| {
+            let _ = tx.send(granted.as_bool());
+        });
+
+        // macOS 14 split full access out; older versions only have the generic request
+        let full_access: Sel = sel!(requestFullAccessToRemindersWithCompletion:);
+        let supports_full_access: bool =
+            unsafe { msg_send![store, respondsToSelector: full_access] };
+        unsafe {
+            if supports_full_access {
+                let _: () =
+                    msg_send![store, requestFullAccessToRemindersWithCompletion: &*completion];
+            } else {
+                let _: () = msg_send![
+                    store,
+                    requestAccessToEntityType: ENTITY_TYPE_REMINDER,
+                    completion: &*completion
+                ];
+            }
+        }
+
+        rx.recv_timeout(ACCESS_PROMPT_TIMEOUT).unwrap_or(false)
+    }
+
+    /// Reminders list named `name`, or the default list for new reminders
+    fn calendar(store: &AnyObject, name: Option<&str>) -> Option<Retained<AnyObject>> {
+        unsafe {
+            let Some(name) = name else {
+                return msg_send![store, defaultCalendarForNewReminders];
+            };
+            let calendars: Retained<AnyObject> =
+                msg_send![store, calendarsForEntityType: ENTITY_TYPE_REMINDER];
+            let count: usize = msg_send![&*calendars, count];
+            (0..count)
+                .map(|i| -> Retained<AnyObject> { msg_send![&*calendars, objectAtIndex: i] })
+                .find(|calendar| {
+                    let title: Option<Retained<NSString>> = msg_send![&**calendar, title];
+                    title.is_some_and(|title| title.to_string() == name)
+                })
+        }
+    }
+
+    /// Create a reminder for each item. Returns how many were saved.
+    pub fn create(items: &[ActionItem], list: Option<&str>) -> Result<usize, String> {
+        let store_class = AnyClass::get(c"EKEventStore").ok_or("EventKit is unavailable")?;
+        let reminder_class = AnyClass::get(c"EKReminder").ok_or("EventKit is unavailable")?;
+
+        let status: isize = unsafe {
+            msg_send![store_class, authorizationStatusForEntityType: ENTITY_TYPE_REMINDER]
+        };
+        let store: Retained<AnyObject> = unsafe { msg_send![store_class, new] };
+
+        let granted = match status {
+            STATUS_FULL_ACCESS => true,
+            STATUS_NOT_DETERMINED => request_access(&store),
+            _ => false,
+        };
+        if !granted {
+            return Err(
+                "Reminders access denied. Allow Dictara in System Settings → Privacy & Security → Reminders."
+                    .to_string(),
+            );
+        }
+
+        let calendar = calendar(&store, list).ok_or_else(|| match list {
+            Some(name) => format!("No Reminders list named \"{}\"", name),
+            None => "No default Reminders list".to_string(),
+        })?;
+
+        let mut saved = 0;
+        for item in items {
+            let title = NSString::from_str(&item.task);
+            let notes = item.details().map(|details| NSString::from_str(&details));
+            let mut error: *mut NSError = std::ptr::null_mut();
+
+            let ok: Bool = unsafe {
+                let reminder: Retained<AnyObject> =
+                    msg_send![reminder_class, reminderWithEventStore: &*store];
+                let _: () = msg_send![&*reminder, setTitle: &*title];
+                if let Some(notes) = &notes {
+                    let _: () = msg_send![&*reminder, setNotes: &**notes];
+                }
+                let _: () = msg_send![&*reminder, setCalendar: &*calendar];
+                msg_send![
+                    &*store,
+                    saveReminder: &*reminder,
+                    commit: Bool::YES,
+                    error: &mut error
+                ]
+            };
+
+            if ok.as_bool() {
+                saved += 1;
+            } else {
+                let reason = unsafe { error.as_ref() }
+                    .map(|error| error.localizedDescription().to_string())
+                    .unwrap_or_else(|| "unknown error".to_string());
+                eprintln!(
+                    "[Action Items] Failed to save reminder \"{}\": {}",
+                    item.task, reason
+                );
+            }
+        }
+
+        if saved == 0 {
+            return Err("No reminders could be saved".to_string());
+        }
+        Ok(saved)
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod reminders {
+    use super::ActionItem;
+
+    pub fn create(_items: &[ActionItem], _list: Option<&str>) -> Result<usize, String> {
+        Err("Reminders are only available on macOS".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(task: &str, owner: Option<&str>, due: Option<&str>) -> ActionItem {
+        ActionItem {
+            task: task.to_string(),
+            owner: owner.map(str::to_string),
+            due: due.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_todo_markdown_lists_unchecked_tasks() {
+        let items = [
+            item("Send the report", Some("Anna"), Some("by Friday")),
+            item("Book a room", None, Some("")),
+        ];
+        assert_eq!(
+            todo_markdown(&items, "2026-10-14 09:30"),
+            "\n## 2026-10-14 09:30\n\n\
+             - [ ] Send the report (Anna, by Friday)\n\
+             - [ ] Book a room\n"
+        );
+    }
+}
@@ -0,0 +1,372 @@
+//! App actions for the command palette.
+//!
+//! `list_actions` returns every action that applies right now, titled for
+//! display: switching provider, turning settings on or off, opening
+//! preferences or the logs, retrying the failed transcription, pasting the last
+//! recording or a history entry, rewriting the selection, and the tray menu
+//! actions. `invoke_action` runs one and closes the palette; pastes and
+//! rewrites wait for the app that was in front to get focus back.
+
+use crate::clients::provider_catalog;
+use crate::clipboard_paste;
+use crate::config::{self, AppConfig, Provider};
+use crate::history::{self, HistoryEntry};
+use crate::keyboard_listener;
+use crate::recording::{
+    LastRecordingState, RecordingCommand, TranscriptionQueueState, TriggerSource,
+};
+use crate::setup::RecordingCommandSender;
+use crate::ui::window;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::Manager;
+use tauri_plugin_opener::OpenerExt;
+use tauri_plugin_store::StoreExt;
+
+/// History entries offered for pasting
+const HISTORY_ACTIONS: usize = 10;
+
+/// Characters of a transcript shown in a paste action's title
+const PREVIEW_CHARS: usize = 50;
+
+/// Time for the previous app to get focus back after the palette closes
+const REFOCUS_DELAY: Duration = Duration::from_millis(150);
+
+/// A setting the palette turns on and off
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum ToggleSetting {
+    DryRun,
+    Summary,
+    Sounds,
+    Announcements,
+}
+
+impl ToggleSetting {
+    const ALL: [ToggleSetting; 4] = [
+        ToggleSetting::DryRun,
+        ToggleSetting::Summary,
+        ToggleSetting::Sounds,
+        ToggleSetting::Announcements,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            ToggleSetting::DryRun => "Dry Run",
+            ToggleSetting::Summary => "Summaries",
+            ToggleSetting::Sounds => "Sounds",
+            ToggleSetting::Announcements => "VoiceOver Announcements",
+        }
+    }
+
+    fn is_on(self, config: &AppConfig) -> bool {
+        match self {
+            ToggleSetting::DryRun => config.dry_run,
+            ToggleSetting::Summary => config.summary.enabled,
+            ToggleSetting::Sounds => config.sounds.enabled,
+            ToggleSetting::Announcements => config.announcements,
+        }
+    }
+
+    fn toggle(self, config: &mut AppConfig) {
+        let value = match self {
+            ToggleSetting::DryRun => &mut config.dry_run,
+            ToggleSetting::Summary => &mut config.summary.enabled,
+            ToggleSetting::Sounds => &mut config.sounds.enabled,
+            ToggleSetting::Announcements => &mut config.announcements,
+        };
+        *value = !*value;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum AppAction {
+    SwitchProvider {
+        provider: Provider,
+    },
+    Toggle {
+        setting: ToggleSetting,
+    },
+    OpenPreferences,
+    OpenLogs,
+    RetryTranscription,
+    PasteLastRecording,
+    /// Paste a history entry again, newest first
+    PasteHistoryEntry {
+        index: u32,
+    },
+    ReadBackLastRecording,
+    /// Dictate how to rewrite the text selected in the app in front
+    RewriteSelection,
+    TranscribeCopiedFile,
+    CancelTranscriptions,
+    ResetInput,
+}
+
+/// An action as the palette lists it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PaletteAction {
+    pub action: AppAction,
+    pub title: String,
+    /// Secondary line, e.g. when a history entry was dictated
+    pub subtitle: Option<String>,
+}
+
+/// What decides which actions apply
+struct Context<'a> {
+    config: &'a AppConfig,
+    can_paste: bool,
+    can_retry: bool,
+    history: &'a [HistoryEntry],
+}
+
+fn palette_action(action: AppAction, title: impl Into<String>) -> PaletteAction {
+    PaletteAction {
+        action,
+        title: title.into(),
+        subtitle: None,
+    }
+}
+
+/// First characters of `text` on one line
+fn preview(text: &str) -> String {
+    let line = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if line.chars().count() <= PREVIEW_CHARS {
+        return line;
+    }
+    let mut preview: String = line.chars().take(PREVIEW_CHARS).collect();
+    preview.push('…');
+    preview
+}
+
+fn actions(context: &Context) -> Vec<PaletteAction> {
+    let mut actions = Vec::new();
+
+    if context.can_retry {
+        actions.push(palette_action(
+            AppAction::RetryTranscription,
+            "Retry Failed Transcription",
+        ));
+    }
+    if context.can_paste {
+        actions.push(palette_action(
+            AppAction::PasteLastRecording,
+            "Paste Last Recording",
+        ));
+        actions.push(palette_action(
+            AppAction::ReadBackLastRecording,
+            "Read Back Last Recording",
+        ));
+    }
+
+    for provider in [Provider::OpenAI, Provider::AzureOpenAI] {
+        if context.config.active_provider.as_ref() != Some(&provider) {
+            let title = format!("Switch to {}", provider_catalog::display_name(&provider));
+            actions.push(palette_action(
+                AppAction::SwitchProvider { provider },
+                title,
+            ));
+        }
+    }
+
+    for setting in ToggleSetting::ALL {
+        let verb = if setting.is_on(context.config) {
+            "Turn Off"
+        } else {
+            "Turn On"
+        };
+        actions.push(palette_action(
+            AppAction::Toggle { setting },
+            format!("{} {}", verb, setting.label()),
+        ));
+    }
+
+    actions.extend([
+        palette_action(AppAction::RewriteSelection, "Rewrite Selection by Voice"),
+        palette_action(
+            AppAction::TranscribeCopiedFile,
+            "Transcribe Copied Audio File",
+        ),
+        palette_action(AppAction::CancelTranscriptions, "Cancel Transcription"),
+        palette_action(AppAction::OpenPreferences, "Open Preferences"),
+        palette_action(AppAction::OpenLogs, "Open Logs Folder"),
+        palette_action(AppAction::ResetInput, "Reset Input Handling"),
+    ]);
+
+    if context.config.history.enabled {
+        for (index, entry) in context.history.iter().take(HISTORY_ACTIONS).enumerate() {
+            actions.push(PaletteAction {
+                action: AppAction::PasteHistoryEntry {
+                    index: index as u32,
+                },
+                title: format!("Paste \"{}\"", preview(&entry.transcript)),
+                subtitle: Some(entry.timestamp.clone()),
+            });
+        }
+    }
+
+    actions
+}
+
+/// Every action that applies right now
+pub fn list(app_handle: &tauri::AppHandle) -> Vec<PaletteAction> {
+    let config = app_handle
+        .store("config.json")
+        .map(|store| config::load_app_config(&store))
+        .unwrap_or_default();
+    let (can_paste, can_retry) = app_handle
+        .try_state::<LastRecordingState>()
+        .and_then(|state| {
+            state
+                .lock()
+                .ok()
+                .map(|last| (last.text.is_some(), last.audio_file_path.is_some()))
+        })
+        .unwrap_or_default();
+    let history = history::load_history(app_handle);
+
+    actions(&Context {
+        config: &config,
+        can_paste,
+        can_retry,
+        history: &history,
+    })
+}
+
+/// Close the palette and run `action`
+pub fn invoke(app_handle: &tauri::AppHandle, action: AppAction) -> Result<(), String> {
+    println!("[Actions] Running {:?}", action);
+    if let Err(e) = window::close_command_palette(app_handle) {
+        eprintln!("[Actions] Failed to close the command palette: {}", e);
+    }
+
+    let store = app_handle
+        .store("config.json")
+        .map_err(|e| format!("Failed to load config: {}", e))?;
+    match action {
+        AppAction::SwitchProvider { provider } => {
+            let config = AppConfig {
+                active_provider: Some(provider),
+                ..config::load_app_config(&store)
+            };
+            config::save_app_config(&store, &config)
+        }
+        AppAction::Toggle { setting } => {
+            let mut config = config::load_app_config(&store);
+            setting.toggle(&mut config);
+            config::save_app_config(&store, &config)
+        }
+        AppAction::OpenPreferences => window::open_preferences_window(app_handle)
+            .map_err(|e| format!("Failed to open preferences: {}", e)),
+        AppAction::OpenLogs => {
+            let dir = app_handle
+                .path()
+                .app_log_dir()
+                .map_err(|e| format!("No log folder: {}", e))?;
+            app_handle
+                .opener()
+                .open_path(dir.to_string_lossy(), None::<&str>)
+                .map_err(|e| format!("Failed to open the log folder: {}", e))
+        }
+        AppAction::RetryTranscription => app_handle
+            .state::<RecordingCommandSender>()
+            .sender
+            .blocking_send(RecordingCommand::RetryTranscription)
+            .map_err(|e| format!("Failed to send RetryTranscription command: {}", e)),
+        AppAction::PasteLastRecording => {
+            let text = app_handle
+                .try_state::<LastRecordingState>()
+                .and_then(|state| state.lock().ok().and_then(|last| last.text.clone()))
+                .ok_or_else(|| "No recording to paste".to_string())?;
+            paste_after_refocus(text);
+            Ok(())
+        }
+        AppAction::PasteHistoryEntry { index } => {
+            let config = config::load_app_config(&store);
+            let text = history::paste_text_at(app_handle, &config, index as usize)
+                .ok_or_else(|| "No such history entry".to_string())?;
+            paste_after_refocus(text);
+            Ok(())
+        }
+        AppAction::ReadBackLastRecording => crate::speech::read_back_last_recording(app_handle),
+        AppAction::RewriteSelection => {
+            let sender = app_handle.state::<RecordingCommandSender>().sender.clone();
+            std::thread::spawn(move || {
+                // The selection is read from the app in front
+                std::thread::sleep(REFOCUS_DELAY);
+                if let Err(e) = sender.blocking_send(RecordingCommand::StartRewrite {
+                    source: TriggerSource::Popup,
+                }) {
+                    eprintln!("[Actions] Failed to send StartRewrite command: {}", e);
+                }
+            });
+            Ok(())
+        }
+        AppAction::TranscribeCopiedFile => {
+            let app_handle = app_handle.clone();
+            std::thread::spawn(move || {
+                let _ = crate::copied_file::transcribe(&app_handle);
+            });
+            Ok(())
+        }
+        AppAction::CancelTranscriptions => {
+            app_handle.state::<TranscriptionQueueState>().abort_all();
+            Ok(())
+        }
+        AppAction::ResetInput => {
+            keyboard_listener::reset_input_handling();
+            Ok(())
+        }
+    }
+}
+
+fn paste_after_refocus(text: String) {
+    std::thread::spawn(move || {
+        std::thread::sleep(REFOCUS_DELAY);
+        if let Err(e) = clipboard_paste::auto_paste_text_cgevent(&text) {
+            eprintln!("[Actions] Failed to paste: {:?}", e);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn titles(context: &Context) -> Vec<String> {
+        actions(context).into_iter().map(|a| a.title).collect()
+    }
+
+    #[test]
+    fn test_actions_follow_state() {
+        let config = AppConfig {
+            active_provider: Some(Provider::OpenAI),
+            dry_run: true,
+            ..AppConfig::default()
+        };
+        let context = Context {
+            config: &config,
+            can_paste: false,
+            can_retry: true,
+            history: &[],
+        };
+        let titles = titles(&context);
+
+        assert_eq!(titles[0], "Retry Failed Transcription");
+        assert!(!titles.contains(&"Paste Last Recording".to_string()));
+        assert!(titles.contains(&"Switch to Azure OpenAI".to_string()));
+        assert!(!titles.contains(&"Switch to OpenAI".to_string()));
+        assert!(titles.contains(&"Turn Off Dry Run".to_string()));
+    }
+
+    #[test]
+    fn test_preview_fits_on_one_line() {
+        assert_eq!(preview("Buy milk\nand eggs"), "Buy milk and eggs");
+        let long = "word ".repeat(20);
+        let preview = preview(&long);
+        assert_eq!(preview.chars().count(), PREVIEW_CHARS + 1);
+        assert!(preview.ends_with('…'));
+    }
+}
@@ -0,0 +1,142 @@
+//! Spoken state changes for VoiceOver users.
+//!
+//! With `announcements` on, each step of a dictation is posted as an
+//! NSAccessibility announcement, which VoiceOver (or any other assistive
+//! technology that's running) reads out. This covers what the popup and tray
+//! icon show for users who can't see them. Unlike sounds, announcements are
+//! not muted by quiet hours.
+
+use crate::config;
+use tauri_plugin_store::StoreExt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Announcement {
+    RecordingStarted,
+    Transcribing,
+    /// The transcript was delivered. `pasted` is false when no paste sink is
+    /// configured (e.g. clipboard or file only).
+    Delivered {
+        words: usize,
+        pasted: bool,
+    },
+    NoSpeech,
+    Cancelled,
+    /// The transcript is over the length cap and waits for confirmation
+    Held {
+        chars: usize,
+    },
+    /// Something went wrong; holds the message the popup shows
+    Failed(String),
+}
+
+impl Announcement {
+    fn message(&self) -> String {
+        match self {
+            Announcement::RecordingStarted => "Recording started".to_string(),
+            Announcement::Transcribing => "Transcribing".to_string(),
+            Announcement::Delivered { words, pasted } => {
+                let verb = if *pasted { "Pasted" } else { "Transcribed" };
+                let unit = if *words == 1 { "word" } else { "words" };
+                format!("{} {} {}", verb, words, unit)
+            }
+            Announcement::NoSpeech => "No speech detected".to_string(),
+            Announcement::Cancelled => "Recording cancelled".to_string(),
+            Announcement::Held { chars } => format!(
+                "Long transcript held, {} characters. Confirm to paste it.",
+                chars
+            ),
+            Announcement::Failed(message) => message.clone(),
+        }
+    }
+}
+
+/// Announce a state change if announcements are enabled (fire and forget)
+pub fn announce(app_handle: &tauri::AppHandle, announcement: Announcement) {
+    let enabled = match app_handle.store("config.json") {
+        Ok(store) => config::load_app_config(&store).announcements,
+        Err(e) => {
+            eprintln!("[Announcements] Failed to load config store: {}", e);
+            return;
+        }
+    };
+    if !enabled {
+        return;
+    }
+
+    let message = announcement.message();
+    println!("[Announcements] {}", message);
+    // AppKit accessibility calls belong on the main thread
+    if let Err(e) = app_handle.run_on_main_thread(move || post(&message)) {
+        eprintln!("[Announcements] Failed to post announcement: {}", e);
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn post(message: &str) {
+    use objc2::msg_send;
+    use objc2::rc::Retained;
+    use objc2::runtime::{AnyClass, AnyObject};
+    use objc2_foundation::{NSNumber, NSString};
+
+    #[link(name = "AppKit", kind = "framework")]
+    extern "C" {
+        static NSAccessibilityAnnouncementRequestedNotification: &'static NSString;
+        static NSAccessibilityAnnouncementKey: &'static NSString;
+        static NSAccessibilityPriorityKey: &'static NSString;
+        fn NSAccessibilityPostNotificationWithUserInfo(
+            element: &AnyObject,
+            notification: &NSString,
+            user_info: &AnyObject,
+        );
+    }
+
+    /// NSAccessibilityPriorityHigh: interrupts other speech
+    const PRIORITY_HIGH: isize = 90;
+
+    let (Some(application_class), Some(dictionary_class)) = (
+        AnyClass::get(c"NSApplication"),
+        AnyClass::get(c"NSMutableDictionary"),
+    ) else {
+        eprintln!("[Announcements] AppKit is unavailable");
+        return;
+    };
+
+    let text = NSString::from_str(message);
+    let priority = NSNumber::new_isize(PRIORITY_HIGH);
+    unsafe {
+        let application: Retained<AnyObject> = msg_send![application_class, sharedApplication];
+        let user_info: Retained<AnyObject> = msg_send![dictionary_class, new];
+        let _: () =
+            msg_send![&*user_info, setObject: &*text, forKey: NSAccessibilityAnnouncementKey];
+        let _: () =
+            msg_send![&*user_info, setObject: &*priority, forKey: NSAccessibilityPriorityKey];
+        NSAccessibilityPostNotificationWithUserInfo(
+            &application,
+            NSAccessibilityAnnouncementRequestedNotification,
+            &user_info,
+        );
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn post(_message: &str) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delivered_message_counts_words() {
+        let pasted = Announcement::Delivered {
+            words: 42,
+            pasted: true,
+        };
+        assert_eq!(pasted.message(), "Pasted 42 words");
+
+        let copied = Announcement::Delivered {
+            words: 1,
+            pasted: false,
+        };
+        assert_eq!(copied.message(), "Transcribed 1 word");
+    }
+}
@@ -0,0 +1,50 @@
+//! Dictara as a library.
+//!
+//! The app is one user of its transcription and text-processing code; this
+//! module is the part of it meant for others, like a command-line tool,
+//! integration tests or another app. What's re-exported here stays stable
+//! across releases, the modules behind it don't.
+//!
+//! None of it needs Tauri: built with `--no-default-features`, which leaves
+//! out the `gui` feature, the crate is this module and the code behind it.
+//! Settings are a plain [`AppConfig`], e.g. deserialized from `config.json`,
+//! and API keys come from the system keychain as in the app:
+//!
+//! ```ignore
+//! use dictara_lib::api::{providers::OpenAIClient, text, AppConfig};
+//!
+//! let config: AppConfig = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+//! let raw = OpenAIClient::new().transcribe_audio_sync(audio, None, &config)?;
+//! println!("{}", text::run(raw, &config));
+//! ```
+
+pub use crate::config::{
+    AppConfig, AudioFormat, AzureOpenAIConfig, FillerWordsConfig, LlmConfig, OpenAIConfig, Provider,
+};
+
+/// Speech-to-text requests to OpenAI and Azure OpenAI
+pub mod providers {
+    pub use crate::clients::openai::{
+        OpenAIClient, TranscriptSegment, TranscriptionError, TranscriptionTiming,
+    };
+    pub use crate::clients::upload::audio_part;
+}
+
+/// Post-processing of transcripts: the step pipeline and its building blocks
+pub mod text {
+    pub use crate::filler_words::remove_fillers;
+    pub use crate::pipeline::{
+        describe, registry, run, run_with_overrides, DictationOverrides, PipelineContext,
+        PipelineStepConfig, PipelineStepInfo, TranscriptMiddleware,
+    };
+    pub use crate::templates::{render, render_text, TemplateVars};
+}
+
+/// Transcribing audio files and writing transcripts or subtitles next to them
+pub mod files {
+    pub use crate::batch::{
+        collect_files, is_supported, sidecar_path, transcribe_one, BatchFileOutcome, BatchOptions,
+        SidecarFormat,
+    };
+    pub use crate::subtitles::{to_srt, to_vtt};
+}
@@ -0,0 +1,434 @@
+//! Batch transcription of a folder of audio files.
+//!
+//! `transcribe_folder` collects the supported audio files in a folder, sends
+//! them to the active provider a few at a time and writes each transcript,
+//! after the transcript pipeline, next to its audio file as `.txt`, `.md`, or
+//! `.srt`/`.vtt` subtitles. Sidecars take the audio file's name unless a
+//! filename template is given (see `templates`).
+//! In the app, `start` runs a batch in the background and reports progress
+//! with `BatchProgress` events and a final `BatchFinished`. Only one batch
+//! runs at a time.
+
+use crate::clients::openai::{OpenAIClient, TranscriptSegment, TranscriptionError};
+#[cfg(feature = "gui")]
+use crate::config;
+use crate::config::AppConfig;
+#[cfg(feature = "gui")]
+use crate::events::{self, BatchFinished, BatchProgress};
+use crate::pipeline;
+use crate::subtitles;
+use crate::templates::{self, TemplateVars};
+use crate::wav::wav_duration_ms;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "gui")]
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "gui")]
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "gui")]
+use std::sync::Mutex;
+#[cfg(feature = "gui")]
+use tauri_plugin_store::StoreExt;
+
+/// Extensions the transcription API accepts
+const SUPPORTED_EXTENSIONS: [&str; 8] = ["wav", "mp3", "m4a", "mp4", "mpga", "flac", "ogg", "webm"];
+
+#[cfg(feature = "gui")]
+const MAX_CONCURRENCY: u32 = 8;
+
+#[cfg(feature = "gui")]
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Clears [`RUNNING`] when the batch thread ends, even by panicking
+#[cfg(feature = "gui")]
+struct RunningGuard;
+
+#[cfg(feature = "gui")]
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        RUNNING.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Transcript file written next to each audio file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum SidecarFormat {
+    #[default]
+    Txt,
+    /// Markdown with the file name as a heading
+    Md,
+    /// SubRip subtitles, from the provider's segment timestamps
+    Srt,
+    /// WebVTT subtitles, from the provider's segment timestamps
+    Vtt,
+}
+
+impl SidecarFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            SidecarFormat::Txt => "txt",
+            SidecarFormat::Md => "md",
+            SidecarFormat::Srt => "srt",
+            SidecarFormat::Vtt => "vtt",
+        }
+    }
+
+    fn is_subtitles(self) -> bool {
+        matches!(self, SidecarFormat::Srt | SidecarFormat::Vtt)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchOptions {
+    /// Files transcribed at the same time (1-8)
+    pub concurrency: u32,
+    pub format: SidecarFormat,
+    /// Include files in subfolders
+    pub recursive: bool,
+    /// Transcribe files that already have a transcript, replacing it
+    pub overwrite: bool,
+    /// Sidecar file name without extension (None = the audio file's name).
+    /// With a template, existing transcripts are only found after transcribing.
+    #[serde(default)]
+    pub filename_template: Option<String>,
+    /// Markdown heading (None = the audio file's name)
+    #[serde(default)]
+    pub title_template: Option<String>,
+}
+
+/// Result for one file of a batch transcription
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum BatchFileOutcome {
+    Transcribed {
+        /// Path of the transcript file
+        output: String,
+    },
+    Skipped {
+        reason: String,
+    },
+    Failed {
+        error: String,
+    },
+}
+
+pub fn is_supported(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| SUPPORTED_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+/// Supported audio files in `dir`, sorted by path
+pub fn collect_files(dir: &Path, recursive: bool) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                if recursive {
+                    pending.push(path);
+                }
+            } else if is_supported(&path) {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+pub fn sidecar_path(audio: &Path, format: SidecarFormat) -> PathBuf {
+    audio.with_extension(format.extension())
+}
+
+/// Template variables for a transcribed file, dated by its modification time
+fn template_vars(audio: &Path, text: &str) -> TemplateVars {
+    let timestamp = fs::metadata(audio)
+        .and_then(|metadata| metadata.modified())
+        .map(DateTime::<Local>::from)
+        .unwrap_or_else(|_| Local::now());
+    TemplateVars {
+        name: audio
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string()),
+        ..TemplateVars::new(timestamp, text)
+    }
+}
+
+/// Plain-text sidecar contents; subtitles are rendered from segments instead
+fn render_sidecar(title: &str, text: &str, format: SidecarFormat) -> String {
+    match format {
+        SidecarFormat::Txt | SidecarFormat::Srt | SidecarFormat::Vtt => format!("{}\n", text),
+        SidecarFormat::Md => format!("# {}\n\n{}\n", title, text),
+    }
+}
+
+/// Plain transcript and subtitle file contents, or None when there was no
+/// speech. The pipeline runs on each segment so cue timing is kept.
+fn transcribe_subtitles(
+    client: &OpenAIClient,
+    audio: &Path,
+    format: SidecarFormat,
+    config: &AppConfig,
+) -> Result<Option<(String, String)>, TranscriptionError> {
+    let segments: Vec<TranscriptSegment> = client
+        .transcribe_segments_sync(audio.to_path_buf(), wav_duration_ms(audio), config)?
+        .into_iter()
+        .map(|segment| TranscriptSegment {
+            text: pipeline::run(segment.text, config),
+            ..segment
+        })
+        .filter(|segment| !segment.text.is_empty())
+        .collect();
+
+    if segments.is_empty() {
+        return Ok(None);
+    }
+    let text = segments
+        .iter()
+        .map(|segment| segment.text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let contents = match format {
+        SidecarFormat::Vtt => subtitles::to_vtt(&segments),
+        _ => subtitles::to_srt(&segments),
+    };
+    Ok(Some((text, contents)))
+}
+
+pub fn transcribe_one(
+    client: &OpenAIClient,
+    audio: &Path,
+    options: &BatchOptions,
+    config: &AppConfig,
+) -> BatchFileOutcome {
+    let already_exists = || BatchFileOutcome::Skipped {
+        reason: "Transcript already exists".to_string(),
+    };
+    if options.filename_template.is_none()
+        && sidecar_path(audio, options.format).exists()
+        && !options.overwrite
+    {
+        return already_exists();
+    }
+
+    let transcript = if options.format.is_subtitles() {
+        transcribe_subtitles(client, audio, options.format, config)
+            .map(|transcript| transcript.map(|(text, contents)| (text, Some(contents))))
+    } else {
+        client
+            .transcribe_audio_sync(audio.to_path_buf(), wav_duration_ms(audio), config)
+            .map(|text| pipeline::run(text, config))
+            .map(|text| (!text.is_empty()).then_some((text, None)))
+    };
+
+    let (text, subtitles) = match transcript {
+        Ok(Some(transcript)) => transcript,
+        Ok(None) => {
+            return BatchFileOutcome::Skipped {
+                reason: "No speech detected".to_string(),
+            }
+        }
+        Err(e) => {
+            eprintln!("[Batch] Failed to transcribe {:?}: {}", audio, e);
+            return BatchFileOutcome::Failed {
+                error: e.user_message(),
+            };
+        }
+    };
+
+    let vars = template_vars(audio, &text);
+    let output = match &options.filename_template {
+        Some(template) => audio.with_file_name(format!(
+            "{}.{}",
+            templates::render_filename(template, &vars),
+            options.format.extension()
+        )),
+        None => sidecar_path(audio, options.format),
+    };
+    if options.filename_template.is_some() && output.exists() && !options.overwrite {
+        return already_exists();
+    }
+
+    let contents = match subtitles {
+        Some(contents) => contents,
+        None => {
+            let title = match &options.title_template {
+                Some(template) => templates::render(template, &vars),
+                None => audio
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+            };
+            render_sidecar(&title, &text, options.format)
+        }
+    };
+
+    match fs::write(&output, contents) {
+        Ok(()) => BatchFileOutcome::Transcribed {
+            output: output.to_string_lossy().to_string(),
+        },
+        Err(e) => BatchFileOutcome::Failed {
+            error: format!("Failed to write transcript: {}", e),
+        },
+    }
+}
+
+#[cfg(feature = "gui")]
+struct Progress {
+    completed: u32,
+    finished: BatchFinished,
+}
+
+#[cfg(feature = "gui")]
+fn run_batch(
+    app_handle: tauri::AppHandle,
+    files: Vec<PathBuf>,
+    options: BatchOptions,
+    config: AppConfig,
+) {
+    let total = files.len() as u32;
+    let workers = options
+        .concurrency
+        .clamp(1, MAX_CONCURRENCY)
+        .min(total.max(1));
+    println!(
+        "[Batch] Transcribing {} files with {} workers",
+        total, workers
+    );
+
+    let queue = Mutex::new(VecDeque::from(files));
+    let progress = Mutex::new(Progress {
+        completed: 0,
+        finished: BatchFinished {
+            transcribed: 0,
+            skipped: 0,
+            failed: 0,
+        },
+    });
+    let client = OpenAIClient::new();
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let Some(audio) = queue.lock().unwrap().pop_front() else {
+                    return;
+                };
+                let outcome = transcribe_one(&client, &audio, &options, &config);
+
+                let event = {
+                    let mut progress = progress.lock().unwrap();
+                    progress.completed += 1;
+                    match outcome {
+                        BatchFileOutcome::Transcribed { .. } => progress.finished.transcribed += 1,
+                        BatchFileOutcome::Skipped { .. } => progress.finished.skipped += 1,
+                        BatchFileOutcome::Failed { .. } => progress.finished.failed += 1,
+                    }
+                    BatchProgress {
+                        file: audio.to_string_lossy().to_string(),
+                        outcome,
+                        completed: progress.completed,
+                        total,
+                    }
+                };
+                events::emit_or_log(&app_handle, event);
+            });
+        }
+    });
+
+    let finished = progress.into_inner().unwrap().finished;
+    println!(
+        "[Batch] Done: {} transcribed, {} skipped, {} failed",
+        finished.transcribed, finished.skipped, finished.failed
+    );
+    events::emit_or_log(&app_handle, finished);
+}
+
+/// Start transcribing `folder` in the background. Returns the number of
+/// audio files found.
+#[cfg(feature = "gui")]
+pub fn start(
+    app_handle: &tauri::AppHandle,
+    folder: &Path,
+    options: BatchOptions,
+) -> Result<u32, String> {
+    let files = collect_files(folder, options.recursive)
+        .map_err(|e| format!("Failed to read folder: {}", e))?;
+    if files.is_empty() {
+        return Err("No supported audio files in this folder".to_string());
+    }
+
+    let store = app_handle
+        .store("config.json")
+        .map_err(|e| format!("Failed to load config: {}", e))?;
+    let config = config::load_app_config(&store);
+
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return Err("A batch transcription is already running".to_string());
+    }
+
+    let count = files.len() as u32;
+    let app_handle = app_handle.clone();
+    std::thread::spawn(move || {
+        let _running = RunningGuard;
+        run_batch(app_handle, files, options, config);
+    });
+
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_files_filters_and_recurses() {
+        let dir = std::env::temp_dir().join(format!("dictara-batch-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        for name in ["b.MP3", "a.wav", "notes.txt", "nested/c.m4a"] {
+            fs::write(dir.join(name), b"").unwrap();
+        }
+
+        let names = |files: Vec<PathBuf>| -> Vec<String> {
+            files
+                .iter()
+                .map(|f| f.strip_prefix(&dir).unwrap().to_string_lossy().to_string())
+                .collect()
+        };
+        assert_eq!(
+            names(collect_files(&dir, false).unwrap()),
+            ["a.wav", "b.MP3"]
+        );
+        assert_eq!(
+            names(collect_files(&dir, true).unwrap()),
+            ["a.wav", "b.MP3", "nested/c.m4a"]
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_sidecar_path_and_contents() {
+        let audio = Path::new("/tmp/interview.m4a");
+        assert_eq!(
+            sidecar_path(audio, SidecarFormat::Md),
+            Path::new("/tmp/interview.md")
+        );
+        assert_eq!(
+            render_sidecar("interview.m4a", "Hello", SidecarFormat::Txt),
+            "Hello\n"
+        );
+        assert_eq!(
+            render_sidecar("interview.m4a", "Hello", SidecarFormat::Md),
+            "# interview.m4a\n\nHello\n"
+        );
+    }
+}
@@ -0,0 +1,249 @@
+//! Meetings from the user's calendar.
+//!
+//! With `calendar.enabled`, the event in progress is looked up with EventKit
+//! (macOS only). While there is one, sounds and notices are suppressed as
+//! during quiet hours (`calendar.mute_sounds`) and dictations are tagged with
+//! its title in history (`calendar.tag_history`).
+//!
+//! Calendar access is asked for by `request_calendar_access`, when the user
+//! turns the setting on; lookups never prompt. All-day events don't count as
+//! meetings. A lookup is reused for a minute, so a meeting may be noticed up
+//! to a minute late.
+
+use crate::config::CalendarConfig;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How long a lookup is reused
+const LOOKUP_TTL: Duration = Duration::from_secs(60);
+
+/// Last lookup and when it was made
+static LAST_LOOKUP: Mutex<Option<(Instant, Option<String>)>> = Mutex::new(None);
+
+/// A calendar event around the current time
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+struct Event {
+    title: String,
+    /// Unix timestamps in seconds
+    start: i64,
+    end: i64,
+    all_day: bool,
+}
+
+/// Title of the meeting going on at `now`: the timed event that started
+/// last, if several overlap
+fn meeting_at(events: &[Event], now: i64) -> Option<String> {
+    events
+        .iter()
+        .filter(|event| !event.all_day && event.start <= now && now < event.end)
+        .max_by_key(|event| event.start)
+        .map(|event| event.title.clone())
+}
+
+/// Title of the meeting in progress, if the calendar is enabled
+pub fn current_meeting(config: &CalendarConfig) -> Option<String> {
+    if !config.enabled {
+        return None;
+    }
+
+    let mut last = LAST_LOOKUP.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((at, meeting)) = last.as_ref() {
+        if at.elapsed() < LOOKUP_TTL {
+            return meeting.clone();
+        }
+    }
+
+    let meeting = match event_kit::events_now() {
+        Ok(events) => meeting_at(&events, chrono::Utc::now().timestamp()),
+        Err(e) => {
+            eprintln!("[Calendar] Failed to look up meetings: {}", e);
+            None
+        }
+    };
+    *last = Some((Instant::now(), meeting.clone()));
+    meeting
+}
+
+/// Whether sounds and notices should be suppressed for a meeting
+pub fn mutes_cues(config: &CalendarConfig) -> bool {
+    config.mute_sounds && current_meeting(config).is_some()
+}
+
+/// Meeting title to keep with a history entry
+pub fn history_tag(config: &CalendarConfig) -> Option<String> {
+    if !config.tag_history {
+        return None;
+    }
+    current_meeting(config)
+}
+
+/// Ask for calendar access, waiting for the user's answer. Returns whether
+/// access is granted.
+pub fn request_access() -> Result<bool, String> {
+    let granted = event_kit::request_access()?;
+    // A lookup made without access shouldn't stick around
+    *LAST_LOOKUP.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    println!("[Calendar] Access granted: {}", granted);
+    Ok(granted)
+}
+
+#[cfg(target_os = "macos")]
+mod event_kit {
+    use super::Event;
+    use block2::RcBlock;
+    use objc2::msg_send;
+    use objc2::rc::Retained;
+    use objc2::runtime::{AnyClass, AnyObject, Bool, Sel};
+    use objc2::sel;
+    use objc2_foundation::{NSError, NSString};
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    #[link(name = "EventKit", kind = "framework")]
+    extern "C" {}
+
+    /// EKEntityTypeEvent
+    const ENTITY_TYPE_EVENT: usize = 0;
+
+    /// EKAuthorizationStatus: 0 = notDetermined, 3 = fullAccess (authorized)
+    const STATUS_NOT_DETERMINED: isize = 0;
+    const STATUS_FULL_ACCESS: isize = 3;
+
+    /// Time the user has to answer the permission prompt
+    const ACCESS_PROMPT_TIMEOUT: Duration = Duration::from_secs(120);
+
+    fn store_class() -> Result<&'static AnyClass, String> {
+        AnyClass::get(c"EKEventStore").ok_or_else(|| "EventKit is unavailable".to_string())
+    }
+
+    fn status(store_class: &AnyClass) -> isize {
+        unsafe { msg_send![store_class, authorizationStatusForEntityType: ENTITY_TYPE_EVENT] }
+    }
+
+    pub fn request_access() -> Result<bool, String> {
+        let store_class = store_class()?;
+        match status(store_class) {
+            STATUS_FULL_ACCESS => return Ok(true),
+            STATUS_NOT_DETERMINED => {}
+            _ => return Ok(false),
+        }
+
+        let store: Retained<AnyObject> = unsafe { msg_send![store_class, new] };
+        let (tx, rx) = mpsc::channel();
+        let completion = RcBlock::new(move |granted: Bool, _error: *mut NSError| {
+            let _ = tx.send(granted.as_bool());
+        });
+
+        // macOS 14 split full access out; older versions only have the generic request
+        let full_access: Sel = sel!(requestFullAccessToEventsWithCompletion:);
+        let supports_full_access: bool =
+            unsafe { msg_send![&*store, respondsToSelector: full_access] };
+        unsafe {
+            if supports_full_access {
+                let _: () =
+                    msg_send![&*store, requestFullAccessToEventsWithCompletion: &*completion];
+            } else {
+                let _: () = msg_send![
+                    &*store,
+                    requestAccessToEntityType: ENTITY_TYPE_EVENT,
+                    completion: &*completion
+                ];
+            }
+        }
+
+        Ok(rx.recv_timeout(ACCESS_PROMPT_TIMEOUT).unwrap_or(false))
+    }
+
+    /// Events in all calendars that are on right now
+    pub fn events_now() -> Result<Vec<Event>, String> {
+        let store_class = store_class()?;
+        if status(store_class) != STATUS_FULL_ACCESS {
+            return Err("Calendar access not granted".to_string());
+        }
+        let date_class = AnyClass::get(c"NSDate").ok_or("NSDate is unavailable")?;
+
+        unsafe {
+            let store: Retained<AnyObject> = msg_send![store_class, new];
+            let now: Retained<AnyObject> = msg_send![date_class, date];
+            let soon: Retained<AnyObject> =
+                msg_send![date_class, dateWithTimeIntervalSinceNow: 1.0f64];
+            let predicate: Retained<AnyObject> = msg_send![
+                &*store,
+                predicateForEventsWithStartDate: &*now,
+                endDate: &*soon,
+                calendars: None::<&AnyObject>
+            ];
+            let events: Retained<AnyObject> =
+                msg_send![&*store, eventsMatchingPredicate: &*predicate];
+
+            let timestamp = |date: Retained<AnyObject>| -> i64 {
+                let seconds: f64 = msg_send![&*date, timeIntervalSince1970];
+                seconds as i64
+            };
+            let count: usize = msg_send![&*events, count];
+            Ok((0..count)
+                .map(|i| {
+                    let event: Retained<AnyObject> = msg_send![&*events, objectAtIndex: i];
+                    let title: Option<Retained<NSString>> = msg_send![&*event, title];
+                    let all_day: Bool = msg_send![&*event, isAllDay];
+                    Event {
+                        title: title.map(|title| title.to_string()).unwrap_or_default(),
+                        start: timestamp(msg_send![&*event, startDate]),
+                        end: timestamp(msg_send![&*event, endDate]),
+                        all_day: all_day.as_bool(),
+                    }
+                })
+                .collect())
+        }
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod event_kit {
+    use super::Event;
+
+    pub fn request_access() -> Result<bool, String> {
+        Err("Calendar access is only available on macOS".to_string())
+    }
+
+    pub fn events_now() -> Result<Vec<Event>, String> {
+        Err("Calendars are only available on macOS".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(title: &str, start: i64, end: i64, all_day: bool) -> Event {
+        Event {
+            title: title.to_string(),
+            start,
+            end,
+            all_day,
+        }
+    }
+
+    #[test]
+    fn test_meeting_at_picks_latest_started_timed_event() {
+        let events = [
+            event("Offsite", 0, 86_400, true),
+            event("Planning", 1_000, 4_600, false),
+            event("Standup", 2_000, 2_900, false),
+        ];
+        assert_eq!(meeting_at(&events, 2_500), Some("Standup".to_string()));
+        // Standup is over, Planning still on
+        assert_eq!(meeting_at(&events, 2_900), Some("Planning".to_string()));
+        // All-day events alone aren't meetings
+        assert_eq!(meeting_at(&events, 5_000), None);
+    }
+
+    #[test]
+    fn test_disabled_calendar_finds_no_meeting() {
+        let config = CalendarConfig::default();
+        assert_eq!(current_meeting(&config), None);
+        assert!(!mutes_cues(&config));
+        assert_eq!(history_tag(&config), None);
+    }
+}
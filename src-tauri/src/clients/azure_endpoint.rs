@@ -0,0 +1,158 @@
+//! Azure OpenAI endpoint parsing.
+//!
+//! Users paste whatever the Azure portal shows them: the resource URL, the
+//! deployment URL, or the full transcription URL with `api-version` already
+//! attached. [`normalize`] turns any of those into the transcription endpoint
+//! without a query string (the client adds its own `api-version`), and rejects
+//! URLs it can't use with a message that says what to fix.
+
+use derive_more::Display;
+use reqwest::Url;
+
+const TRANSCRIPTION_PATH: [&str; 2] = ["audio", "transcriptions"];
+
+#[derive(Debug, Clone, PartialEq, Eq, Display)]
+pub enum EndpointError {
+    #[display("Endpoint is empty")]
+    Empty,
+    #[display("Invalid endpoint URL: {}", _0)]
+    InvalidUrl(String),
+    #[display("Endpoint must use https")]
+    NotHttps,
+    #[display("Endpoint has no deployment")]
+    MissingDeployment,
+    #[display("Endpoint points to {}, not audio transcriptions", _0)]
+    WrongOperation(String),
+}
+
+impl EndpointError {
+    /// Returns a user-friendly error message suitable for display in the UI
+    pub fn user_message(&self) -> String {
+        match self {
+            EndpointError::Empty => "Enter your Azure OpenAI endpoint URL.".to_string(),
+            EndpointError::InvalidUrl(_) => "That doesn't look like a URL. Copy the endpoint from your Azure OpenAI deployment.".to_string(),
+            EndpointError::NotHttps => "The endpoint must start with https://.".to_string(),
+            EndpointError::MissingDeployment => "The URL has no deployment. Use the deployment URL, e.g. https://<resource>.openai.azure.com/openai/deployments/<deployment>.".to_string(),
+            EndpointError::WrongOperation(operation) => format!(
+                "The URL points to {}. Use your Whisper deployment's audio transcriptions endpoint.",
+                operation
+            ),
+        }
+    }
+}
+
+/// Normalize a pasted Azure endpoint into
+/// `https://<host>/openai/deployments/<deployment>/audio/transcriptions`
+pub fn normalize(input: &str) -> Result<String, EndpointError> {
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        return Err(EndpointError::Empty);
+    }
+
+    // Portal hostnames are often copied without the scheme
+    let with_scheme = if trimmed.contains("://") {
+        trimmed.to_string()
+    } else {
+        format!("https://{}", trimmed)
+    };
+
+    let url = Url::parse(&with_scheme).map_err(|e| EndpointError::InvalidUrl(e.to_string()))?;
+    if url.scheme() != "https" {
+        return Err(EndpointError::NotHttps);
+    }
+    let host = url
+        .host_str()
+        .ok_or_else(|| EndpointError::InvalidUrl("missing host".to_string()))?;
+
+    let segments: Vec<&str> = url
+        .path_segments()
+        .map(|segments| segments.filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default();
+
+    // .../openai/deployments/<deployment>[/audio/transcriptions]
+    let deployments_at = segments
+        .windows(2)
+        .position(|pair| pair[0] == "openai" && pair[1] == "deployments")
+        .ok_or(EndpointError::MissingDeployment)?;
+    let deployment = segments
+        .get(deployments_at + 2)
+        .ok_or(EndpointError::MissingDeployment)?;
+
+    let operation = &segments[deployments_at + 3..];
+    if !operation.is_empty() && operation != TRANSCRIPTION_PATH {
+        return Err(EndpointError::WrongOperation(operation.join("/")));
+    }
+
+    let port = url.port().map(|p| format!(":{}", p)).unwrap_or_default();
+    Ok(format!(
+        "https://{}{}/openai/deployments/{}/{}",
+        host,
+        port,
+        deployment,
+        TRANSCRIPTION_PATH.join("/")
+    ))
+}
+
+/// Chat completions URL for another deployment on the same resource as the
+/// normalized transcription `endpoint`
+pub fn chat_completions_url(endpoint: &str, deployment: &str) -> Option<String> {
+    let (resource, _) = endpoint.split_once("/openai/deployments/")?;
+    Some(format!(
+        "{}/openai/deployments/{}/chat/completions",
+        resource,
+        deployment.trim().trim_matches('/')
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPECTED: &str =
+        "https://acme.openai.azure.com/openai/deployments/whisper/audio/transcriptions";
+
+    #[test]
+    fn test_accepts_common_portal_urls() {
+        for input in [
+            EXPECTED,
+            "https://acme.openai.azure.com/openai/deployments/whisper",
+            "https://acme.openai.azure.com/openai/deployments/whisper/",
+            "https://acme.openai.azure.com/openai/deployments/whisper/audio/transcriptions?api-version=2024-06-01",
+            "  acme.openai.azure.com/openai/deployments/whisper  ",
+        ] {
+            assert_eq!(normalize(input).as_deref(), Ok(EXPECTED), "input: {}", input);
+        }
+    }
+
+    #[test]
+    fn test_rejects_unusable_urls() {
+        assert_eq!(normalize("   "), Err(EndpointError::Empty));
+        assert_eq!(
+            normalize("http://acme.openai.azure.com/openai/deployments/whisper"),
+            Err(EndpointError::NotHttps)
+        );
+        assert_eq!(
+            normalize("https://acme.openai.azure.com/"),
+            Err(EndpointError::MissingDeployment)
+        );
+        assert_eq!(
+            normalize("https://acme.openai.azure.com/openai/deployments/gpt4/chat/completions"),
+            Err(EndpointError::WrongOperation(
+                "chat/completions".to_string()
+            ))
+        );
+        assert!(matches!(
+            normalize("https://"),
+            Err(EndpointError::InvalidUrl(_))
+        ));
+    }
+
+    #[test]
+    fn test_chat_completions_url_reuses_resource() {
+        assert_eq!(
+            chat_completions_url(EXPECTED, "gpt-4o-mini").as_deref(),
+            Some("https://acme.openai.azure.com/openai/deployments/gpt-4o-mini/chat/completions")
+        );
+        assert_eq!(chat_completions_url("", "gpt-4o-mini"), None);
+    }
+}
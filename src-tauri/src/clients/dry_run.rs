@@ -0,0 +1,34 @@
+//! Fake transcription provider for demos and screen recordings.
+//!
+//! When dry-run is on, the whole pipeline runs as usual (recording, popup,
+//! paste) but no network request is made and a canned transcript is returned.
+//! Enable it with `dry_run` in the app config or `DICTARA_DRY_RUN=1`.
+
+use crate::config::AppConfig;
+use std::time::Duration;
+
+/// Environment variable that forces dry-run mode
+const DRY_RUN_ENV: &str = "DICTARA_DRY_RUN";
+
+/// Pretend network latency so the transcribing state is visible
+const SIMULATED_LATENCY: Duration = Duration::from_millis(700);
+
+const CANNED_TRANSCRIPT: &str = "This is a dry-run transcription from Dictara.";
+
+/// Whether transcription requests should be answered by the fake provider
+pub fn is_enabled(config: &AppConfig) -> bool {
+    config.dry_run
+        || std::env::var(DRY_RUN_ENV)
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false)
+}
+
+/// Return the canned transcript after a short delay
+pub fn transcribe(duration_ms: u64) -> String {
+    println!(
+        "[Dry Run] Skipping API call for {}ms of audio, returning canned transcript",
+        duration_ms
+    );
+    std::thread::sleep(SIMULATED_LATENCY);
+    CANNED_TRANSCRIPT.to_string()
+}
@@ -0,0 +1,149 @@
+//! Chat completions for post-processing transcripts, e.g. summaries.
+//!
+//! Requests go to the active provider with its transcription key: OpenAI with
+//! `llm.model`, Azure with the `llm.azure_deployment` chat deployment on the
+//! same resource as the Whisper deployment. Failures are reported as
+//! [`TranscriptionError`]s since they come from the same keys and endpoints.
+
+use crate::clients::azure_endpoint;
+use crate::clients::openai::{OpenAIClient, TranscriptionError, AZURE_API_VERSION};
+use crate::config::{AppConfig, Provider};
+use crate::logging;
+use std::time::Duration;
+
+const OPENAI_CHAT_URL: &str = "https://api.openai.com/v1/chat/completions";
+
+/// Summaries of long transcripts can take a while to generate
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Text of the first choice in a chat completion response
+fn reply_text(response: &serde_json::Value) -> Option<String> {
+    let text = response
+        .get("choices")?
+        .get(0)?
+        .get("message")?
+        .get("content")?
+        .as_str()?
+        .trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// Send `user` with the `system` instructions. The model must reply with a
+/// JSON object, which is returned parsed; `system` has to describe it and
+/// mention JSON.
+pub fn complete_json(
+    config: &AppConfig,
+    system: &str,
+    user: &str,
+) -> Result<serde_json::Value, TranscriptionError> {
+    let reply = chat(config, system, user, true)?;
+    serde_json::from_str(&reply).map_err(|e| {
+        eprintln!("[LLM Client] Reply is not JSON: {}", e);
+        TranscriptionError::ApiError(format!("Model returned invalid JSON: {}", e))
+    })
+}
+
+/// Send `user` with the `system` instructions and return the reply text
+pub fn complete(
+    config: &AppConfig,
+    system: &str,
+    user: &str,
+) -> Result<String, TranscriptionError> {
+    chat(config, system, user, false)
+}
+
+/// Send a chat completion and return the reply text. `json` asks for a JSON object.
+fn chat(
+    config: &AppConfig,
+    system: &str,
+    user: &str,
+    json: bool,
+) -> Result<String, TranscriptionError> {
+    let api_config = OpenAIClient::load_config(config)?;
+
+    let mut body = serde_json::json!({
+        "messages": [
+            { "role": "system", "content": system },
+            { "role": "user", "content": user },
+        ],
+        "temperature": 0.2,
+    });
+    if json {
+        body["response_format"] = serde_json::json!({ "type": "json_object" });
+    }
+
+    // OpenAI takes the model in the body, Azure embeds the deployment in the URL
+    let url = match api_config.provider {
+        Provider::OpenAI => {
+            body["model"] = serde_json::Value::String(config.llm.model.clone());
+            OPENAI_CHAT_URL.to_string()
+        }
+        Provider::AzureOpenAI => {
+            let deployment = config.llm.azure_deployment.as_deref().ok_or_else(|| {
+                TranscriptionError::ApiError("No Azure chat deployment configured".to_string())
+            })?;
+            let url = azure_endpoint::chat_completions_url(&api_config.endpoint, deployment)
+                .ok_or_else(|| {
+                    TranscriptionError::ApiError("Azure endpoint has no deployment".to_string())
+                })?;
+            format!("{}?api-version={}", url, AZURE_API_VERSION)
+        }
+    };
+
+    println!(
+        "[LLM Client] Sending chat completion to {:?}",
+        api_config.provider
+    );
+
+    let client = reqwest::blocking::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| TranscriptionError::ApiError(format!("Request failed: {}", e)))?;
+    let request = api_config.add_auth_header(client.post(url));
+
+    let response = request.json(&body).send().map_err(|e| {
+        eprintln!("[LLM Client] Request error: {}", e);
+        TranscriptionError::ApiError(format!("Request failed: {}", e))
+    })?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let error_text = response
+            .text()
+            .unwrap_or_else(|_| "Unknown error".to_string());
+        eprintln!(
+            "[LLM Client] API error response ({}): {}",
+            status,
+            logging::response_body(&error_text)
+        );
+        return Err(TranscriptionError::from_status(status, &error_text));
+    }
+
+    let response: serde_json::Value = response.json().map_err(|e| {
+        eprintln!("[LLM Client] Failed to parse response: {}", e);
+        TranscriptionError::ApiError(format!("Failed to parse response: {}", e))
+    })?;
+
+    reply_text(&response)
+        .ok_or_else(|| TranscriptionError::ApiError("Model returned no text".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reply_text_takes_first_choice() {
+        let response = serde_json::json!({
+            "choices": [
+                { "message": { "role": "assistant", "content": "  Summary:\n- Done\n" } },
+                { "message": { "role": "assistant", "content": "Other" } },
+            ]
+        });
+        assert_eq!(reply_text(&response).as_deref(), Some("Summary:\n- Done"));
+
+        let empty = serde_json::json!({ "choices": [{ "message": { "content": " " } }] });
+        assert_eq!(reply_text(&empty), None);
+        assert_eq!(reply_text(&serde_json::json!({ "error": {} })), None);
+    }
+}
@@ -1 +1,10 @@
+pub mod azure_endpoint;
+pub mod dry_run;
+pub mod llm;
 pub mod openai;
+pub mod provider_catalog;
+#[cfg(feature = "gui")]
+pub mod provider_policy;
+#[cfg(feature = "gui")]
+pub mod reauth;
+pub mod upload;
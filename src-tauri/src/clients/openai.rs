@@ -1,24 +1,50 @@
+use crate::clients::upload::{self, AbortToken};
+use crate::clients::{azure_endpoint, dry_run};
 use crate::config::{AppConfig, AzureOpenAIConfig, OpenAIConfig, Provider};
-use crate::keychain::{self, ProviderAccount};
+use crate::keychain::{self, KeychainFailure, ProviderAccount};
+use crate::logging;
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
 const MIN_AUDIO_DURATION_MS: u64 = 500; // Minimum 0.5 seconds
-const MAX_FILE_SIZE_BYTES: u64 = 25 * 1024 * 1024; // 25MB limit
+pub const MAX_FILE_SIZE_BYTES: u64 = 25 * 1024 * 1024; // 25MB limit
 
 // Azure API version
-const AZURE_API_VERSION: &str = "2024-06-01";
+pub const AZURE_API_VERSION: &str = "2024-06-01";
 
 // OpenAI endpoints
 const OPENAI_MODELS_URL: &str = "https://api.openai.com/v1/models";
 const OPENAI_TRANSCRIPTION_URL: &str = "https://api.openai.com/v1/audio/transcriptions";
+const OPENAI_TRANSCRIPTION_MODEL: &str = "whisper-1";
+
+/// Longest provider error code kept in an error message
+const MAX_ERROR_CODE_LEN: usize = 64;
+
+/// Where the time of a transcription request went. Both are None when no
+/// request was made (dry run, audio too short).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TranscriptionTiming {
+    /// Sending the audio
+    pub upload: Option<Duration>,
+    /// From the last byte sent until the response was read
+    pub provider: Option<Duration>,
+}
 
 #[derive(Debug)]
 pub enum TranscriptionError {
-    FileTooLarge { size_bytes: u64 },
+    FileTooLarge {
+        size_bytes: u64,
+    },
     FileNotFound(String),
     ApiError(String),
     IoError(std::io::Error),
     ApiKeyMissing,
+    /// The keychain holding the API key couldn't be read
+    KeychainUnavailable(KeychainFailure),
+    /// Blocked locally because the previous request was too recent
+    RateLimited {
+        retry_in_ms: u64,
+    },
 }
 
 impl From<std::io::Error> for TranscriptionError {
@@ -45,11 +71,38 @@ impl std::fmt::Display for TranscriptionError {
             TranscriptionError::ApiKeyMissing => {
                 write!(f, "API key not configured")
             }
+            TranscriptionError::KeychainUnavailable(failure) => {
+                write!(f, "Keychain unavailable: {:?}", failure)
+            }
+            TranscriptionError::RateLimited { retry_in_ms } => {
+                write!(
+                    f,
+                    "Transcription requested too soon, retry in {}ms",
+                    retry_in_ms
+                )
+            }
         }
     }
 }
 
 impl TranscriptionError {
+    /// Error for a response that failed with `status`. Only the status and
+    /// the provider's error code are kept: the body can echo what was sent,
+    /// so it's logged through `logging::response_body` instead.
+    pub fn from_status(status: reqwest::StatusCode, body: &str) -> Self {
+        match error_code(body) {
+            Some(code) => {
+                TranscriptionError::ApiError(format!("API returned status {} ({})", status, code))
+            }
+            None => TranscriptionError::ApiError(format!("API returned status {}", status)),
+        }
+    }
+
+    /// Whether the provider rejected the API key (401)
+    pub fn is_auth_rejected(&self) -> bool {
+        matches!(self, TranscriptionError::ApiError(msg) if msg.starts_with("API returned status 401"))
+    }
+
     /// Returns a user-friendly error message suitable for display in the UI
     pub fn user_message(&self) -> String {
         match self {
@@ -76,18 +129,64 @@ impl TranscriptionError {
             TranscriptionError::ApiKeyMissing => {
                 "API key not configured. Please add it in Preferences.".to_string()
             }
+            TranscriptionError::KeychainUnavailable(failure) => failure.user_message(),
+            TranscriptionError::RateLimited { retry_in_ms } => {
+                let seconds = retry_in_ms.div_ceil(1000);
+                format!("Too many dictations in a row. Retry in {}s.", seconds)
+            }
         }
     }
 }
 
+/// `error.code` of an OpenAI or Azure error body, if it's an identifier
+/// like `rate_limit_exceeded` rather than free text
+fn error_code(body: &str) -> Option<String> {
+    let json: serde_json::Value = serde_json::from_str(body).ok()?;
+    let code = json["error"]["code"].as_str()?;
+    let is_identifier = !code.is_empty()
+        && code.len() <= MAX_ERROR_CODE_LEN
+        && code
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.');
+    is_identifier.then(|| logging::mask_secrets(code))
+}
+
+/// A timed piece of a transcript
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranscriptSegment {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
+}
+
+/// Whether a key can use the transcription model, from [`OpenAIClient::probe_model`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModelProbe {
+    Available,
+    /// 401/403: the key is wrong or revoked
+    KeyRejected,
+    /// 404: the model (OpenAI) or deployment (Azure) doesn't exist
+    ModelMissing,
+}
+
 /// Configuration for making API calls
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct ApiConfig {
     pub provider: Provider,
     pub api_key: String,
     pub endpoint: String, // Full transcription endpoint for Azure (without api-version), unused for OpenAI
 }
 
+impl std::fmt::Debug for ApiConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ApiConfig")
+            .field("provider", &self.provider)
+            .field("api_key", &"[REDACTED]")
+            .field("endpoint", &self.endpoint)
+            .finish()
+    }
+}
+
 impl ApiConfig {
     /// Construct the full transcription URL based on provider
     fn transcription_url(&self) -> String {
@@ -120,7 +219,7 @@ impl ApiConfig {
     }
 
     /// Add authentication header to request builder
-    fn add_auth_header(
+    pub fn add_auth_header(
         &self,
         request: reqwest::blocking::RequestBuilder,
     ) -> reqwest::blocking::RequestBuilder {
@@ -152,16 +251,25 @@ impl OpenAIClient {
             Provider::OpenAI => {
                 let openai_config: OpenAIConfig =
                     keychain::load_provider_config(ProviderAccount::OpenAI)
-                        .map_err(|_| TranscriptionError::ApiKeyMissing)?
+                        .map_err(|e| {
+                            TranscriptionError::KeychainUnavailable(keychain::failure_kind(&e))
+                        })?
                         .ok_or(TranscriptionError::ApiKeyMissing)?;
                 (openai_config.api_key, String::new())
             }
             Provider::AzureOpenAI => {
                 let azure_config: AzureOpenAIConfig =
                     keychain::load_provider_config(ProviderAccount::AzureOpenAI)
-                        .map_err(|_| TranscriptionError::ApiKeyMissing)?
+                        .map_err(|e| {
+                            TranscriptionError::KeychainUnavailable(keychain::failure_kind(&e))
+                        })?
                         .ok_or(TranscriptionError::ApiKeyMissing)?;
-                (azure_config.api_key, azure_config.endpoint)
+                let endpoint = crate::config::managed::current()
+                    .azure_endpoint
+                    .unwrap_or(azure_config.endpoint);
+                // Configs saved before endpoint validation may still hold a raw portal URL
+                let endpoint = azure_endpoint::normalize(&endpoint).unwrap_or(endpoint);
+                (azure_config.api_key, endpoint)
             }
         };
 
@@ -178,6 +286,8 @@ impl OpenAIClient {
     /// * `provider` - The provider type (OpenAI or AzureOpenAI)
     /// * `key` - The API key to test
     /// * `endpoint` - Optional Azure endpoint (required for Azure, ignored for OpenAI)
+    /// * `deep` - Azure only: run a real (billed) transcription instead of the
+    ///   lightweight check
     ///
     /// # Returns
     /// * `Ok(true)` - Key is valid
@@ -187,6 +297,7 @@ impl OpenAIClient {
         provider: Provider,
         key: &str,
         endpoint: Option<&str>,
+        deep: bool,
     ) -> Result<bool, TranscriptionError> {
         println!(
             "[OpenAI Client] Testing API key validity for {:?}...",
@@ -226,109 +337,215 @@ impl OpenAIClient {
                         .unwrap_or_else(|_| "Unknown error".to_string());
                     eprintln!(
                         "[OpenAI Client] Unexpected API response ({}): {}",
-                        status, error_text
+                        status,
+                        logging::response_body(&error_text)
                     );
-                    Err(TranscriptionError::ApiError(format!(
-                        "API returned status {}: {}",
-                        status, error_text
-                    )))
+                    Err(TranscriptionError::from_status(status, &error_text))
                 }
             }
+            Provider::AzureOpenAI if deep => {
+                Self::test_azure_with_transcription(key, endpoint.unwrap_or(""))
+            }
             Provider::AzureOpenAI => {
-                // Azure: Test with actual transcription since /deployments endpoint is deprecated
-                println!("[OpenAI Client] Testing Azure with silent audio transcription...");
-
-                // Generate a tiny silent audio file for testing
-                let temp_dir = std::env::temp_dir();
-                let test_audio_path = temp_dir.join("typefree_test_silent.wav");
-
-                // Generate 1 second silent audio
-                let ffmpeg_result = std::process::Command::new("ffmpeg")
-                    .args([
-                        "-f",
-                        "lavfi",
-                        "-i",
-                        "anullsrc=r=16000:cl=mono",
-                        "-t",
-                        "1.0",
-                        "-y",
-                        test_audio_path.to_str().unwrap(),
-                    ])
-                    .output()
-                    .map_err(|e| {
-                        TranscriptionError::ApiError(format!(
-                            "Failed to generate test audio: {}",
-                            e
-                        ))
-                    })?;
-
-                if !ffmpeg_result.status.success() {
-                    return Err(TranscriptionError::ApiError(
-                        "Failed to generate test audio with ffmpeg".to_string(),
-                    ));
+                match Self::probe_azure_deployment(key, endpoint.unwrap_or(""))? {
+                    ModelProbe::Available => Ok(true),
+                    ModelProbe::KeyRejected => Ok(false),
+                    ModelProbe::ModelMissing => Err(TranscriptionError::ApiError(
+                        "Deployment not found (404). Check the endpoint URL and deployment name."
+                            .to_string(),
+                    )),
                 }
+            }
+        }
+    }
 
-                // Test transcription
-                let api_config = ApiConfig {
-                    provider: Provider::AzureOpenAI,
-                    api_key: key.to_string(),
-                    endpoint: endpoint.unwrap_or("").to_string(),
-                };
+    /// Check that the transcription model (OpenAI) or deployment (Azure) can
+    /// be used with `key`, without transcribing anything
+    pub fn probe_model(
+        provider: Provider,
+        key: &str,
+        endpoint: Option<&str>,
+    ) -> Result<ModelProbe, TranscriptionError> {
+        match provider {
+            Provider::OpenAI => Self::probe_openai_model(key),
+            Provider::AzureOpenAI => Self::probe_azure_deployment(key, endpoint.unwrap_or("")),
+        }
+    }
 
-                let form = reqwest::blocking::multipart::Form::new()
-                    .file("file", &test_audio_path)
-                    .map_err(|e| {
-                        TranscriptionError::IoError(std::io::Error::other(format!(
-                            "Failed to read test file: {}",
-                            e
-                        )))
-                    })?
-                    .text("temperature", "0.0")
-                    .text("response_format", "json");
+    fn probe_openai_model(key: &str) -> Result<ModelProbe, TranscriptionError> {
+        let url = format!("{}/{}", OPENAI_MODELS_URL, OPENAI_TRANSCRIPTION_MODEL);
+        let response = reqwest::blocking::Client::new()
+            .get(url)
+            .bearer_auth(key)
+            .send()
+            .map_err(|e| {
+                eprintln!("[OpenAI Client] Model probe failed: {}", e);
+                TranscriptionError::ApiError(format!("Request failed: {}", e))
+            })?;
 
-                let client = reqwest::blocking::Client::new();
-                let request = client.post(api_config.transcription_url());
-                let request = api_config.add_auth_header(request);
+        let status = response.status();
+        println!("[OpenAI Client] Model probe response status: {}", status);
+        Self::model_probe_result(response, status.is_success())
+    }
 
-                let response = request.multipart(form).send().map_err(|e| {
-                    eprintln!("[OpenAI Client] Azure test request failed: {}", e);
-                    TranscriptionError::ApiError(format!("Request failed: {}", e))
-                })?;
+    /// Validate an Azure key without a billable transcription: post a form
+    /// with no audio. Azure authenticates before validating the body, so a 400
+    /// means the key and deployment are fine, 401 a bad key and 404 a wrong
+    /// deployment.
+    fn probe_azure_deployment(key: &str, endpoint: &str) -> Result<ModelProbe, TranscriptionError> {
+        println!("[OpenAI Client] Testing Azure key with an empty request...");
+
+        let api_config = ApiConfig {
+            provider: Provider::AzureOpenAI,
+            api_key: key.to_string(),
+            endpoint: endpoint.to_string(),
+        };
 
-                let status = response.status();
-                println!("[OpenAI Client] Azure test response status: {}", status);
+        let form = reqwest::blocking::multipart::Form::new().text("response_format", "json");
 
-                // Clean up test file
-                let _ = std::fs::remove_file(&test_audio_path);
+        let client = reqwest::blocking::Client::new();
+        let request = client.post(api_config.transcription_url());
+        let request = api_config.add_auth_header(request);
 
-                if status.is_success() {
-                    println!("[OpenAI Client] ✅ Azure API key is valid");
-                    Ok(true)
-                } else if status.as_u16() == 401 {
-                    println!("[OpenAI Client] ❌ Azure API key is invalid (401 Unauthorized)");
-                    Ok(false)
-                } else {
-                    let error_text = response
-                        .text()
-                        .unwrap_or_else(|_| "Unknown error".to_string());
-                    eprintln!(
-                        "[OpenAI Client] Azure test failed ({}): {}",
-                        status, error_text
-                    );
-                    Err(TranscriptionError::ApiError(format!(
-                        "API returned status {}: {}",
-                        status, error_text
-                    )))
-                }
+        let response = request.multipart(form).send().map_err(|e| {
+            eprintln!("[OpenAI Client] Azure test request failed: {}", e);
+            TranscriptionError::ApiError(format!("Request failed: {}", e))
+        })?;
+
+        let status = response.status();
+        println!("[OpenAI Client] Azure test response status: {}", status);
+
+        // 400: authenticated, then rejected for the missing file
+        let available = status.is_success() || status.as_u16() == 400;
+        Self::model_probe_result(response, available)
+    }
+
+    /// Map a probe response: `available` says whether the status means usable
+    fn model_probe_result(
+        response: reqwest::blocking::Response,
+        available: bool,
+    ) -> Result<ModelProbe, TranscriptionError> {
+        let status = response.status();
+        match status.as_u16() {
+            _ if available => {
+                println!("[OpenAI Client] ✅ API key and model are usable");
+                Ok(ModelProbe::Available)
+            }
+            401 | 403 => {
+                println!("[OpenAI Client] ❌ API key is invalid ({})", status);
+                Ok(ModelProbe::KeyRejected)
+            }
+            404 => {
+                println!("[OpenAI Client] ❌ Model or deployment not found");
+                Ok(ModelProbe::ModelMissing)
+            }
+            _ => {
+                let error_text = response
+                    .text()
+                    .unwrap_or_else(|_| "Unknown error".to_string());
+                eprintln!(
+                    "[OpenAI Client] Probe failed ({}): {}",
+                    status,
+                    logging::response_body(&error_text)
+                );
+                Err(TranscriptionError::from_status(status, &error_text))
             }
         }
     }
 
+    /// Validate an Azure key end to end by transcribing a second of silence.
+    /// This is billed, so it only runs when a deep test is requested.
+    fn test_azure_with_transcription(
+        key: &str,
+        endpoint: &str,
+    ) -> Result<bool, TranscriptionError> {
+        println!("[OpenAI Client] Testing Azure with silent audio transcription...");
+
+        // Generate a tiny silent audio file for testing
+        let temp_dir = std::env::temp_dir();
+        let test_audio_path = temp_dir.join("typefree_test_silent.wav");
+
+        // Generate 1 second silent audio
+        let ffmpeg_result = std::process::Command::new("ffmpeg")
+            .args([
+                "-f",
+                "lavfi",
+                "-i",
+                "anullsrc=r=16000:cl=mono",
+                "-t",
+                "1.0",
+                "-y",
+                test_audio_path.to_str().unwrap(),
+            ])
+            .output()
+            .map_err(|e| {
+                TranscriptionError::ApiError(format!("Failed to generate test audio: {}", e))
+            })?;
+
+        if !ffmpeg_result.status.success() {
+            return Err(TranscriptionError::ApiError(
+                "Failed to generate test audio with ffmpeg".to_string(),
+            ));
+        }
+
+        // Test transcription
+        let api_config = ApiConfig {
+            provider: Provider::AzureOpenAI,
+            api_key: key.to_string(),
+            endpoint: endpoint.to_string(),
+        };
+
+        let audio = upload::audio_part(&test_audio_path).map_err(|e| {
+            TranscriptionError::IoError(std::io::Error::other(format!(
+                "Failed to read test file: {}",
+                e
+            )))
+        })?;
+        let form = reqwest::blocking::multipart::Form::new()
+            .part("file", audio)
+            .text("temperature", "0.0")
+            .text("response_format", "json");
+
+        let client = reqwest::blocking::Client::new();
+        let request = client.post(api_config.transcription_url());
+        let request = api_config.add_auth_header(request);
+
+        let response = request.multipart(form).send().map_err(|e| {
+            eprintln!("[OpenAI Client] Azure test request failed: {}", e);
+            TranscriptionError::ApiError(format!("Request failed: {}", e))
+        })?;
+
+        let status = response.status();
+        println!("[OpenAI Client] Azure test response status: {}", status);
+
+        // Clean up test file
+        let _ = std::fs::remove_file(&test_audio_path);
+
+        if status.is_success() {
+            println!("[OpenAI Client] ✅ Azure API key is valid");
+            Ok(true)
+        } else if status.as_u16() == 401 {
+            println!("[OpenAI Client] ❌ Azure API key is invalid (401 Unauthorized)");
+            Ok(false)
+        } else {
+            let error_text = response
+                .text()
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            eprintln!(
+                "[OpenAI Client] Azure test failed ({}): {}",
+                status,
+                logging::response_body(&error_text)
+            );
+            Err(TranscriptionError::from_status(status, &error_text))
+        }
+    }
+
     /// Transcribe audio file to text (blocking/synchronous version)
     ///
     /// # Arguments
     /// * `file_path` - Path to the audio file (WAV, MP3, etc.)
-    /// * `duration_ms` - Duration of the recording in milliseconds (for validation)
+    /// * `duration_ms` - Duration of the recording in milliseconds (for validation).
+    ///   None when unknown, e.g. for compressed files, which skips the length check.
     /// * `config` - App configuration (which provider to use)
     ///
     /// # Returns
@@ -337,21 +554,124 @@ impl OpenAIClient {
     pub fn transcribe_audio_sync(
         &self,
         file_path: PathBuf,
-        duration_ms: u64,
+        duration_ms: Option<u64>,
         config: &AppConfig,
     ) -> Result<String, TranscriptionError> {
+        self.transcribe_audio_timed(file_path, duration_ms, config, None, &AbortToken::default())
+            .map(|(text, _)| text)
+    }
+
+    /// Like [`Self::transcribe_audio_sync`], also returning where the time
+    /// went. `prompt` is text the recording continues, for the model to match
+    /// its spelling and style. The upload fails once `abort` is aborted.
+    pub fn transcribe_audio_timed(
+        &self,
+        file_path: PathBuf,
+        duration_ms: Option<u64>,
+        config: &AppConfig,
+        prompt: Option<&str>,
+        abort: &AbortToken,
+    ) -> Result<(String, TranscriptionTiming), TranscriptionError> {
+        let mut timing = TranscriptionTiming::default();
+        let json = self.request_transcription(
+            file_path,
+            duration_ms,
+            config,
+            false,
+            prompt,
+            abort,
+            &mut timing,
+        )?;
+        let text = json["text"].as_str().unwrap_or("").to_string();
+
+        println!(
+            "[OpenAI Client] Transcription successful: {} characters",
+            text.len()
+        );
+        println!("[OpenAI Client] Text: {}", logging::content(&text));
+
+        Ok((text, timing))
+    }
+
+    /// Transcribe with timestamps, for subtitles. Providers that return no
+    /// segments (and dry runs) yield one segment covering the whole file.
+    pub fn transcribe_segments_sync(
+        &self,
+        file_path: PathBuf,
+        duration_ms: Option<u64>,
+        config: &AppConfig,
+    ) -> Result<Vec<TranscriptSegment>, TranscriptionError> {
+        let json = self.request_transcription(
+            file_path,
+            duration_ms,
+            config,
+            true,
+            None,
+            &AbortToken::default(),
+            &mut TranscriptionTiming::default(),
+        )?;
+
+        let segments: Vec<TranscriptSegment> = json["segments"]
+            .as_array()
+            .map(|segments| {
+                segments
+                    .iter()
+                    .filter_map(|segment| {
+                        Some(TranscriptSegment {
+                            start_ms: (segment["start"].as_f64()? * 1000.0) as u64,
+                            end_ms: (segment["end"].as_f64()? * 1000.0) as u64,
+                            text: segment["text"].as_str()?.trim().to_string(),
+                        })
+                    })
+                    .filter(|segment| !segment.text.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        if !segments.is_empty() {
+            println!(
+                "[OpenAI Client] Transcription successful: {} segments",
+                segments.len()
+            );
+            return Ok(segments);
+        }
+
+        let text = json["text"].as_str().unwrap_or("").trim().to_string();
+        if text.is_empty() {
+            return Ok(Vec::new());
+        }
+        Ok(vec![TranscriptSegment {
+            start_ms: 0,
+            end_ms: duration_ms.unwrap_or(0),
+            text,
+        }])
+    }
+
+    /// Validate the file and send it to the provider. `verbose` asks for
+    /// segment timestamps. Returns the response JSON.
+    #[allow(clippy::too_many_arguments)]
+    fn request_transcription(
+        &self,
+        file_path: PathBuf,
+        duration_ms: Option<u64>,
+        config: &AppConfig,
+        verbose: bool,
+        prompt: Option<&str>,
+        abort: &AbortToken,
+        timing: &mut TranscriptionTiming,
+    ) -> Result<serde_json::Value, TranscriptionError> {
         println!(
-            "[OpenAI Client] Transcribing (sync): {:?} (duration: {}ms)",
+            "[OpenAI Client] Transcribing (sync): {:?} (duration: {:?}ms)",
             file_path, duration_ms
         );
 
         // Validate minimum duration
-        if duration_ms < MIN_AUDIO_DURATION_MS {
+        if let Some(duration_ms) = duration_ms.filter(|&ms| ms < MIN_AUDIO_DURATION_MS) {
             eprintln!(
                 "[OpenAI Client] Audio too short: {}ms < {}ms",
                 duration_ms, MIN_AUDIO_DURATION_MS
             );
-            return Ok("".to_string());
+            return Ok(serde_json::json!({ "text": "" }));
         }
 
         // Check if file exists
@@ -378,28 +698,42 @@ impl OpenAIClient {
 
         println!("[OpenAI Client] File size: {} bytes", file_size);
 
+        if dry_run::is_enabled(config) {
+            let text = dry_run::transcribe(duration_ms.unwrap_or(0));
+            return Ok(serde_json::json!({ "text": text }));
+        }
+
         // Load API configuration
         let api_config = Self::load_config(config)?;
         println!("[OpenAI Client] Using provider: {:?}", api_config.provider);
 
-        // Build multipart form
-        let mut form = reqwest::blocking::multipart::Form::new()
-            .file("file", &file_path)
-            .map_err(|e| {
-                TranscriptionError::IoError(std::io::Error::other(format!(
-                    "Failed to read file: {}",
-                    e
-                )))
-            })?
+        // Build multipart form, streaming the audio from disk
+        let (audio, sent) = upload::audio_part_timed(&file_path, abort).map_err(|e| {
+            TranscriptionError::IoError(std::io::Error::other(format!(
+                "Failed to read file: {}",
+                e
+            )))
+        })?;
+        let form = reqwest::blocking::multipart::Form::new()
+            .part("file", audio)
             .text("temperature", "0.0")
-            // .text("prompt", " ")
-            .text("response_format", "json");
+            .text(
+                "response_format",
+                if verbose { "verbose_json" } else { "json" },
+            );
+        let form = if verbose {
+            form.text("timestamp_granularities[]", "segment")
+        } else {
+            form
+        };
+        let mut form = match prompt {
+            Some(prompt) => form.text("prompt", prompt.to_string()),
+            None => form,
+        };
 
         // OpenAI requires model in form data, Azure embeds it in URL
         if api_config.provider == Provider::OpenAI {
-            let model = "whisper-1";
-            // let model = "gpt-4o-transcribe";
-            form = form.text("model", model);
+            form = form.text("model", OPENAI_TRANSCRIPTION_MODEL);
         }
 
         // Call API
@@ -416,6 +750,7 @@ impl OpenAIClient {
         let request = client.post(api_config.transcription_url());
         let request = api_config.add_auth_header(request);
 
+        let started = Instant::now();
         let response = request.multipart(form).send().map_err(|e| {
             eprintln!("[OpenAI Client] API request error: {}", e);
             TranscriptionError::ApiError(format!("Request failed: {}", e))
@@ -429,28 +764,54 @@ impl OpenAIClient {
                 .unwrap_or_else(|_| "Unknown error".to_string());
             eprintln!(
                 "[OpenAI Client] API error response ({}): {}",
-                status, error_text
+                status,
+                logging::response_body(&error_text)
             );
-            return Err(TranscriptionError::ApiError(format!(
-                "API returned status {}: {}",
-                status, error_text
-            )));
+            return Err(TranscriptionError::from_status(status, &error_text));
         }
 
         // Parse JSON response
-        let json: serde_json::Value = response.json().map_err(|e| {
+        let json = response.json().map_err(|e| {
             eprintln!("[OpenAI Client] Failed to parse response: {}", e);
             TranscriptionError::ApiError(format!("Failed to parse response: {}", e))
         })?;
 
-        let text = json["text"].as_str().unwrap_or("").to_string();
+        // Without a send time the whole request counts as the provider's
+        let sent = sent.get().copied().unwrap_or(started);
+        timing.upload = Some(sent.duration_since(started));
+        timing.provider = Some(sent.elapsed());
+        Ok(json)
+    }
+}
 
-        println!(
-            "[OpenAI Client] Transcription successful: {} characters",
-            text.len()
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::StatusCode;
+
+    #[test]
+    fn test_status_error_leaves_out_the_body() {
+        let body = r#"{"error": {"message": "Could not process: my secret dictation", "code": "invalid_value"}}"#;
+        let error = TranscriptionError::from_status(StatusCode::BAD_REQUEST, body);
+
+        let shown = error.to_string();
+        assert!(!shown.contains("dictation"));
+        assert_eq!(
+            shown,
+            "API error: API returned status 400 Bad Request (invalid_value)"
+        );
+    }
+
+    #[test]
+    fn test_status_error_drops_codes_that_are_free_text() {
+        let body = r#"{"error": {"code": "my secret dictation"}}"#;
+        let error = TranscriptionError::from_status(StatusCode::BAD_REQUEST, body);
+        assert_eq!(
+            error.to_string(),
+            "API error: API returned status 400 Bad Request"
         );
-        println!("[OpenAI Client] Text: {}", text);
 
-        Ok(text)
+        let error = TranscriptionError::from_status(StatusCode::UNAUTHORIZED, "not json");
+        assert!(error.is_auth_rejected());
     }
 }
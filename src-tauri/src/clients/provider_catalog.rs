@@ -0,0 +1,189 @@
+//! What each provider costs and how fast it usually is.
+//!
+//! The table is static and updated with releases, dated by `UPDATED`. The
+//! provider picker shows it for comparison, and it seeds the default prices
+//! of the provider policy. Latency measured on this machine, when there is
+//! some, is returned next to the typical figure. The recorder also checks
+//! here which audio formats a provider takes.
+
+use crate::clients::openai::MAX_FILE_SIZE_BYTES;
+#[cfg(feature = "gui")]
+use crate::clients::provider_policy;
+use crate::config::{AudioFormat, Provider};
+use serde::{Deserialize, Serialize};
+
+/// When the prices and latencies below were last checked
+const UPDATED: &str = "2026-10-01";
+
+struct CatalogRow {
+    provider: Provider,
+    name: &'static str,
+    model: &'static str,
+    usd_per_minute: f32,
+    typical_ms_per_audio_sec: f32,
+    max_sample_rate: u32,
+    max_bits_per_sample: u16,
+    /// Takes audio at any rate and resamples it on its side
+    resamples_server_side: bool,
+}
+
+fn rows() -> [CatalogRow; 2] {
+    [
+        CatalogRow {
+            provider: Provider::OpenAI,
+            name: "OpenAI",
+            model: "whisper-1",
+            usd_per_minute: 0.006,
+            typical_ms_per_audio_sec: 150.0,
+            max_sample_rate: 48_000,
+            max_bits_per_sample: 24,
+            resamples_server_side: true,
+        },
+        CatalogRow {
+            provider: Provider::AzureOpenAI,
+            name: "Azure OpenAI",
+            model: "whisper",
+            usd_per_minute: 0.006,
+            typical_ms_per_audio_sec: 200.0,
+            max_sample_rate: 48_000,
+            max_bits_per_sample: 24,
+            resamples_server_side: true,
+        },
+    ]
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderCatalogEntry {
+    pub provider: Provider,
+    pub name: String,
+    /// Model (OpenAI) or model family behind the deployment (Azure)
+    pub model: String,
+    /// List price in US dollars per minute of audio
+    pub usd_per_minute: f32,
+    /// Transcription time per second of audio, as commonly seen
+    pub typical_ms_per_audio_sec: f32,
+    /// Transcription time per second of audio measured here, None until the
+    /// provider has transcribed something
+    pub measured_ms_per_audio_sec: Option<f32>,
+    /// Largest file the provider accepts
+    pub max_file_bytes: u32,
+    /// Highest recording sample rate the provider takes
+    pub max_sample_rate: u32,
+    pub max_bits_per_sample: u16,
+    /// Whether recordings can keep the microphone's own rate
+    pub accepts_native_rate: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderCatalog {
+    /// Date the figures were checked ("YYYY-MM-DD")
+    pub updated: String,
+    pub entries: Vec<ProviderCatalogEntry>,
+}
+
+/// List price of `provider` in US dollars per minute of audio
+pub fn list_price(provider: &Provider) -> f32 {
+    rows()
+        .into_iter()
+        .find(|row| &row.provider == provider)
+        .map(|row| row.usd_per_minute)
+        .unwrap_or(0.0)
+}
+
+/// Name of `provider` for display
+pub fn display_name(provider: &Provider) -> &'static str {
+    rows()
+        .into_iter()
+        .find(|row| &row.provider == provider)
+        .map(|row| row.name)
+        .unwrap_or("Unknown provider")
+}
+
+/// Reduce `format` to what `provider` takes: a supported bit depth, nothing
+/// above its highest sample rate, and the native rate only if it resamples
+pub fn supported_format(provider: &Provider, format: AudioFormat) -> AudioFormat {
+    let (max_sample_rate, max_bits_per_sample, resamples) = rows()
+        .into_iter()
+        .find(|row| &row.provider == provider)
+        .map(|row| {
+            (
+                row.max_sample_rate,
+                row.max_bits_per_sample,
+                row.resamples_server_side,
+            )
+        })
+        .unwrap_or((16_000, 16, false));
+
+    let bits_per_sample = if format.bits_per_sample >= 24 && max_bits_per_sample >= 24 {
+        24
+    } else {
+        16
+    };
+    AudioFormat {
+        sample_rate: format.sample_rate.clamp(8_000, max_sample_rate),
+        bits_per_sample,
+        native_rate: format.native_rate && resamples,
+    }
+}
+
+/// The catalog, with latency measured on this machine
+#[cfg(feature = "gui")]
+pub fn catalog(app_handle: &tauri::AppHandle) -> ProviderCatalog {
+    ProviderCatalog {
+        updated: UPDATED.to_string(),
+        entries: rows()
+            .into_iter()
+            .map(|row| ProviderCatalogEntry {
+                measured_ms_per_audio_sec: provider_policy::measured_ms_per_audio_sec(
+                    app_handle,
+                    &row.provider,
+                ),
+                provider: row.provider,
+                name: row.name.to_string(),
+                model: row.model.to_string(),
+                usd_per_minute: row.usd_per_minute,
+                typical_ms_per_audio_sec: row.typical_ms_per_audio_sec,
+                max_file_bytes: MAX_FILE_SIZE_BYTES as u32,
+                max_sample_rate: row.max_sample_rate,
+                max_bits_per_sample: row.max_bits_per_sample,
+                accepts_native_rate: row.resamples_server_side,
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_provider_is_listed() {
+        for provider in [Provider::OpenAI, Provider::AzureOpenAI] {
+            assert!(list_price(&provider) > 0.0, "{:?} has no price", provider);
+        }
+    }
+
+    #[test]
+    fn test_formats_are_reduced_to_what_the_provider_takes() {
+        let format = |sample_rate, bits_per_sample| AudioFormat {
+            sample_rate,
+            bits_per_sample,
+            native_rate: false,
+        };
+        let provider = Provider::OpenAI;
+        assert_eq!(
+            supported_format(&provider, format(24_000, 24)),
+            format(24_000, 24)
+        );
+        assert_eq!(
+            supported_format(&provider, format(96_000, 32)),
+            format(48_000, 24)
+        );
+        assert_eq!(
+            supported_format(&provider, format(4_000, 20)),
+            format(8_000, 16)
+        );
+    }
+}
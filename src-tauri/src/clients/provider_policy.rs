@@ -0,0 +1,378 @@
+//! Cost-aware provider selection.
+//!
+//! With `provider_policy.enabled`, each dictation picks among the providers
+//! that have a key (and don't need re-auth): the cheapest one whose predicted
+//! latency meets the target, or the fastest one if none does. Latency is
+//! predicted per provider from past transcriptions, as an average of
+//! milliseconds per second of audio. Providers without samples yet are
+//! assumed to meet the target, so every provider gets measured. A provider
+//! enforced by a managed configuration is never switched away from.
+
+use crate::clients::{dry_run, reauth};
+use crate::config::managed::{self, ManagedConfig};
+use crate::config::{AppConfig, AzureOpenAIConfig, OpenAIConfig, Provider, ProviderPrice};
+use crate::keychain::{self, ProviderAccount};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri_plugin_store::StoreExt;
+
+const LATENCY_STORE: &str = "stats.json";
+const LATENCY_KEY: &str = "provider_latency";
+
+/// Weight of the newest sample in the running average
+const EWMA_ALPHA: f32 = 0.3;
+/// Shorter recordings are dominated by request overhead; don't learn from them
+const MIN_SAMPLE_AUDIO_MS: u64 = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct LatencyEstimate {
+    ms_per_audio_sec: f32,
+    samples: u32,
+}
+
+impl LatencyEstimate {
+    fn update(previous: Option<Self>, ms_per_audio_sec: f32) -> Self {
+        match previous {
+            None => Self {
+                ms_per_audio_sec,
+                samples: 1,
+            },
+            Some(prev) => Self {
+                ms_per_audio_sec: prev.ms_per_audio_sec
+                    + EWMA_ALPHA * (ms_per_audio_sec - prev.ms_per_audio_sec),
+                samples: prev.samples.saturating_add(1),
+            },
+        }
+    }
+
+    /// Expected transcription time for a recording of `duration_ms`
+    fn predict_ms(&self, duration_ms: u64) -> u32 {
+        let seconds = (duration_ms as f32 / 1000.0).max(1.0);
+        (self.ms_per_audio_sec * seconds) as u32
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct LatencyStats {
+    #[serde(default)]
+    open_ai: Option<LatencyEstimate>,
+    #[serde(default)]
+    azure_open_ai: Option<LatencyEstimate>,
+}
+
+impl LatencyStats {
+    fn entry(&mut self, provider: &Provider) -> &mut Option<LatencyEstimate> {
+        match provider {
+            Provider::OpenAI => &mut self.open_ai,
+            Provider::AzureOpenAI => &mut self.azure_open_ai,
+        }
+    }
+
+    fn get(&self, provider: &Provider) -> Option<LatencyEstimate> {
+        match provider {
+            Provider::OpenAI => self.open_ai,
+            Provider::AzureOpenAI => self.azure_open_ai,
+        }
+    }
+}
+
+/// A provider that could take this dictation
+#[derive(Debug, Clone, PartialEq)]
+struct Candidate {
+    provider: Provider,
+    usd_per_minute: Option<f32>,
+    /// None until the provider has been measured
+    predicted_ms: Option<u32>,
+}
+
+/// The provider picked for a dictation and why, for the state journal
+#[derive(Debug, Clone, PartialEq)]
+pub struct Selection {
+    pub provider: Provider,
+    pub reason: String,
+}
+
+fn describe_price(price: Option<f32>) -> String {
+    price
+        .map(|p| format!("${:.4}/min", p))
+        .unwrap_or_else(|| "no price".to_string())
+}
+
+fn describe_latency(predicted_ms: Option<u32>) -> String {
+    predicted_ms
+        .map(|ms| format!("~{}ms", ms))
+        .unwrap_or_else(|| "unmeasured".to_string())
+}
+
+/// Pick a candidate. Unpriced providers sort after priced ones; ties go to
+/// `preferred` (the active provider).
+fn choose(
+    candidates: &[Candidate],
+    max_latency_ms: u32,
+    preferred: Option<&Provider>,
+) -> Option<Selection> {
+    let cost = |c: &Candidate| c.usd_per_minute.unwrap_or(f32::INFINITY);
+    let is_preferred = |c: &Candidate| Some(&c.provider) == preferred;
+
+    let within_target: Vec<&Candidate> = candidates
+        .iter()
+        .filter(|c| c.predicted_ms.is_none_or(|ms| ms <= max_latency_ms))
+        .collect();
+
+    if !within_target.is_empty() {
+        let best = within_target.into_iter().min_by(|a, b| {
+            cost(a)
+                .total_cmp(&cost(b))
+                .then(is_preferred(b).cmp(&is_preferred(a)))
+        })?;
+        return Some(Selection {
+            provider: best.provider.clone(),
+            reason: format!(
+                "cheapest within {}ms ({}, {})",
+                max_latency_ms,
+                describe_price(best.usd_per_minute),
+                describe_latency(best.predicted_ms)
+            ),
+        });
+    }
+
+    // Nobody meets the target: take the fastest
+    let fastest = candidates.iter().min_by(|a, b| {
+        a.predicted_ms
+            .cmp(&b.predicted_ms)
+            .then(is_preferred(b).cmp(&is_preferred(a)))
+    })?;
+    Some(Selection {
+        provider: fastest.provider.clone(),
+        reason: format!(
+            "fastest, none within {}ms ({})",
+            max_latency_ms,
+            describe_latency(fastest.predicted_ms)
+        ),
+    })
+}
+
+fn load_latency(app_handle: &tauri::AppHandle) -> LatencyStats {
+    app_handle
+        .store(LATENCY_STORE)
+        .ok()
+        .and_then(|store| store.get(LATENCY_KEY))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+/// Transcription time per second of audio measured for `provider`, if any
+pub fn measured_ms_per_audio_sec(
+    app_handle: &tauri::AppHandle,
+    provider: &Provider,
+) -> Option<f32> {
+    load_latency(app_handle)
+        .get(provider)
+        .map(|estimate| estimate.ms_per_audio_sec)
+}
+
+/// Whether a key is stored for `provider`
+fn is_configured(provider: &Provider) -> bool {
+    match provider {
+        Provider::OpenAI => matches!(
+            keychain::load_provider_config::<OpenAIConfig>(ProviderAccount::OpenAI),
+            Ok(Some(_))
+        ),
+        Provider::AzureOpenAI => matches!(
+            keychain::load_provider_config::<AzureOpenAIConfig>(ProviderAccount::AzureOpenAI),
+            Ok(Some(_))
+        ),
+    }
+}
+
+fn price_of(prices: &[ProviderPrice], provider: &Provider) -> Option<f32> {
+    prices
+        .iter()
+        .find(|p| &p.provider == provider)
+        .map(|p| p.usd_per_minute)
+}
+
+/// Providers the policy may choose between: only the enforced one under a
+/// managed provider
+fn allowed_providers(managed: &ManagedConfig) -> Vec<Provider> {
+    match &managed.provider {
+        Some(provider) => vec![provider.clone()],
+        None => vec![Provider::OpenAI, Provider::AzureOpenAI],
+    }
+}
+
+/// Choose the provider for a dictation of `duration_ms`. Returns None when
+/// the policy is off or there's nothing to choose between, in which case the
+/// active provider is used as usual.
+pub fn select(
+    app_handle: &tauri::AppHandle,
+    config: &AppConfig,
+    duration_ms: u64,
+) -> Option<Selection> {
+    let policy = &config.provider_policy;
+    if !policy.enabled || dry_run::is_enabled(config) {
+        return None;
+    }
+
+    let latency = load_latency(app_handle);
+    let flagged = reauth::flagged_providers(app_handle);
+    let candidates: Vec<Candidate> = allowed_providers(&managed::current())
+        .into_iter()
+        .filter(|provider| !flagged.contains(provider) && is_configured(provider))
+        .map(|provider| Candidate {
+            usd_per_minute: price_of(&policy.prices, &provider),
+            predicted_ms: latency
+                .get(&provider)
+                .map(|estimate| estimate.predict_ms(duration_ms)),
+            provider,
+        })
+        .collect();
+
+    if candidates.len() < 2 {
+        return None;
+    }
+
+    let selection = choose(
+        &candidates,
+        policy.max_latency_ms,
+        config.active_provider.as_ref(),
+    )?;
+    println!(
+        "[Provider Policy] Using {:?}: {}",
+        selection.provider, selection.reason
+    );
+    Some(selection)
+}
+
+/// Learn from a successful transcription by the provider in `config`
+pub fn record_latency(
+    app_handle: &tauri::AppHandle,
+    config: &AppConfig,
+    duration_ms: u64,
+    elapsed: Duration,
+) {
+    let Some(provider) = config.active_provider.as_ref() else {
+        return;
+    };
+    if duration_ms < MIN_SAMPLE_AUDIO_MS || dry_run::is_enabled(config) {
+        return;
+    }
+
+    let store = match app_handle.store(LATENCY_STORE) {
+        Ok(store) => store,
+        Err(e) => {
+            eprintln!("[Provider Policy] Failed to open stats store: {}", e);
+            return;
+        }
+    };
+
+    let mut latency: LatencyStats = store
+        .get(LATENCY_KEY)
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default();
+
+    let ms_per_audio_sec = elapsed.as_millis() as f32 / (duration_ms as f32 / 1000.0);
+    let entry = latency.entry(provider);
+    *entry = Some(LatencyEstimate::update(*entry, ms_per_audio_sec));
+
+    match serde_json::to_value(&latency) {
+        Ok(value) => store.set(LATENCY_KEY, value),
+        Err(e) => {
+            eprintln!("[Provider Policy] Failed to serialize latency: {}", e);
+            return;
+        }
+    }
+    if let Err(e) = store.save() {
+        eprintln!("[Provider Policy] Failed to save latency: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(provider: Provider, price: Option<f32>, predicted_ms: Option<u32>) -> Candidate {
+        Candidate {
+            provider,
+            usd_per_minute: price,
+            predicted_ms,
+        }
+    }
+
+    #[test]
+    fn test_picks_cheapest_within_target() {
+        let candidates = [
+            candidate(Provider::OpenAI, Some(0.006), Some(1200)),
+            candidate(Provider::AzureOpenAI, Some(0.004), Some(2500)),
+        ];
+        let selection = choose(&candidates, 3000, Some(&Provider::OpenAI)).unwrap();
+        assert_eq!(selection.provider, Provider::AzureOpenAI);
+
+        // The cheaper one is too slow for a tighter target
+        let selection = choose(&candidates, 2000, Some(&Provider::AzureOpenAI)).unwrap();
+        assert_eq!(selection.provider, Provider::OpenAI);
+    }
+
+    #[test]
+    fn test_falls_back_to_fastest_and_prefers_active_on_ties() {
+        let candidates = [
+            candidate(Provider::OpenAI, Some(0.006), Some(5000)),
+            candidate(Provider::AzureOpenAI, Some(0.004), Some(4000)),
+        ];
+        let selection = choose(&candidates, 3000, None).unwrap();
+        assert_eq!(selection.provider, Provider::AzureOpenAI);
+        assert!(selection.reason.starts_with("fastest"));
+
+        let tied = [
+            candidate(Provider::OpenAI, Some(0.006), None),
+            candidate(Provider::AzureOpenAI, Some(0.006), None),
+        ];
+        for preferred in [Provider::OpenAI, Provider::AzureOpenAI] {
+            let selection = choose(&tied, 3000, Some(&preferred)).unwrap();
+            assert_eq!(selection.provider, preferred);
+        }
+
+        // Unpriced providers lose to priced ones
+        let unpriced = [
+            candidate(Provider::OpenAI, None, Some(100)),
+            candidate(Provider::AzureOpenAI, Some(0.01), Some(100)),
+        ];
+        let selection = choose(&unpriced, 3000, Some(&Provider::OpenAI)).unwrap();
+        assert_eq!(selection.provider, Provider::AzureOpenAI);
+    }
+
+    #[test]
+    fn test_policy_stays_on_managed_provider() {
+        let managed = ManagedConfig {
+            provider: Some(Provider::AzureOpenAI),
+            ..ManagedConfig::default()
+        };
+        assert_eq!(allowed_providers(&managed), vec![Provider::AzureOpenAI]);
+
+        // OpenAI would win on price and latency, but isn't a candidate
+        let candidates: Vec<Candidate> = allowed_providers(&managed)
+            .into_iter()
+            .map(|provider| match provider {
+                Provider::OpenAI => candidate(provider, Some(0.001), Some(100)),
+                Provider::AzureOpenAI => candidate(provider, Some(0.01), Some(9000)),
+            })
+            .collect();
+        let selection = choose(&candidates, 3000, Some(&Provider::OpenAI)).unwrap();
+        assert_eq!(selection.provider, Provider::AzureOpenAI);
+
+        assert_eq!(allowed_providers(&ManagedConfig::default()).len(), 2);
+    }
+
+    #[test]
+    fn test_latency_estimate_moves_toward_new_samples() {
+        let first = LatencyEstimate::update(None, 500.0);
+        assert_eq!(first.samples, 1);
+        assert_eq!(first.predict_ms(4000), 2000);
+        // Very short recordings still pay at least a second's worth
+        assert_eq!(first.predict_ms(200), 500);
+
+        let second = LatencyEstimate::update(Some(first), 1500.0);
+        assert_eq!(second.samples, 2);
+        assert!((second.ms_per_audio_sec - 800.0).abs() < 0.01);
+    }
+}
@@ -0,0 +1,293 @@
+//! Detects API keys that stop working.
+//!
+//! A 401 from a key that has transcribed before means it expired or was
+//! revoked, not that it was mistyped. The provider is then flagged as needing
+//! re-auth: the tray shows a badge and a "Re-enter API Key…" item that opens
+//! the API keys page, until a transcription succeeds or a new key is saved.
+//! The flag is persisted so it survives a restart.
+
+use crate::clients::dry_run;
+use crate::clients::openai::TranscriptionError;
+use crate::config::{AppConfig, Provider};
+use crate::events::{self, ProviderAuthChanged};
+use crate::ui::tray;
+use serde::{Deserialize, Serialize};
+use tauri_plugin_store::StoreExt;
+
+const AUTH_STORE: &str = "config.json";
+const AUTH_KEY: &str = "provider_auth";
+
+/// Shown instead of the generic invalid-key message once a provider is flagged
+pub const REAUTH_USER_MESSAGE: &str =
+    "Your API key is no longer accepted. Re-enter it in Preferences → API Keys.";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct ProviderAuth {
+    /// A transcription with the current key has succeeded at least once
+    has_worked: bool,
+    needs_reauth: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct AuthStatus {
+    #[serde(default)]
+    open_ai: ProviderAuth,
+    #[serde(default)]
+    azure_open_ai: ProviderAuth,
+}
+
+/// What a transcription result says about the key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AuthOutcome {
+    Accepted,
+    Rejected,
+    /// Failed for another reason, e.g. network or rate limit
+    Unknown,
+}
+
+impl AuthOutcome {
+    fn of(result: &Result<String, TranscriptionError>) -> Self {
+        match result {
+            Ok(_) => AuthOutcome::Accepted,
+            Err(e) if e.is_auth_rejected() => AuthOutcome::Rejected,
+            Err(_) => AuthOutcome::Unknown,
+        }
+    }
+}
+
+impl AuthStatus {
+    fn entry(&mut self, provider: &Provider) -> &mut ProviderAuth {
+        match provider {
+            Provider::OpenAI => &mut self.open_ai,
+            Provider::AzureOpenAI => &mut self.azure_open_ai,
+        }
+    }
+
+    fn flagged(&self) -> Vec<Provider> {
+        let mut providers = Vec::new();
+        if self.open_ai.needs_reauth {
+            providers.push(Provider::OpenAI);
+        }
+        if self.azure_open_ai.needs_reauth {
+            providers.push(Provider::AzureOpenAI);
+        }
+        providers
+    }
+
+    /// Apply a transcription outcome. Returns the new flag if it changed.
+    fn record(&mut self, provider: &Provider, outcome: AuthOutcome) -> Option<bool> {
+        let auth = self.entry(provider);
+        let before = auth.needs_reauth;
+
+        match outcome {
+            AuthOutcome::Accepted => {
+                auth.has_worked = true;
+                auth.needs_reauth = false;
+            }
+            // A key that never worked is a setup problem, not an expiry
+            AuthOutcome::Rejected if auth.has_worked => auth.needs_reauth = true,
+            AuthOutcome::Rejected | AuthOutcome::Unknown => {}
+        }
+
+        (auth.needs_reauth != before).then_some(auth.needs_reauth)
+    }
+
+    /// Forget everything about the key, e.g. after a new one is saved
+    fn reset(&mut self, provider: &Provider) -> Option<bool> {
+        let auth = self.entry(provider);
+        let was_flagged = auth.needs_reauth;
+        *auth = ProviderAuth::default();
+        was_flagged.then_some(false)
+    }
+}
+
+/// Apply a change to the stored status and persist it
+fn update_status<T>(
+    app_handle: &tauri::AppHandle,
+    update: impl FnOnce(&mut AuthStatus) -> T,
+) -> Option<(AuthStatus, T)> {
+    let store = match app_handle.store(AUTH_STORE) {
+        Ok(store) => store,
+        Err(e) => {
+            eprintln!("[Reauth] Failed to open store: {}", e);
+            return None;
+        }
+    };
+
+    let mut status: AuthStatus = store
+        .get(AUTH_KEY)
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default();
+
+    let result = update(&mut status);
+
+    match serde_json::to_value(&status) {
+        Ok(value) => store.set(AUTH_KEY, value),
+        Err(e) => {
+            eprintln!("[Reauth] Failed to serialize status: {}", e);
+            return None;
+        }
+    }
+
+    if let Err(e) = store.save() {
+        eprintln!("[Reauth] Failed to save status: {}", e);
+    }
+
+    Some((status, result))
+}
+
+/// Show the badge and menu item while any provider is flagged
+fn sync_tray(app_handle: &tauri::AppHandle, needs_reauth: bool) {
+    if let Err(e) = tray::set_reauth_needed(app_handle, needs_reauth) {
+        eprintln!("[Reauth] Failed to update tray: {}", e);
+    }
+}
+
+fn on_change(
+    app_handle: &tauri::AppHandle,
+    status: &AuthStatus,
+    provider: Provider,
+    needs_reauth: bool,
+) {
+    println!(
+        "[Reauth] {:?} {}",
+        provider,
+        if needs_reauth {
+            "needs re-auth"
+        } else {
+            "re-auth cleared"
+        }
+    );
+    sync_tray(app_handle, !status.flagged().is_empty());
+    events::emit_or_log(
+        app_handle,
+        ProviderAuthChanged {
+            provider,
+            needs_reauth,
+        },
+    );
+}
+
+/// Record a transcription result for the active provider.
+/// Returns whether the provider now needs re-auth.
+pub fn record_transcription(
+    app_handle: &tauri::AppHandle,
+    config: &AppConfig,
+    result: &Result<String, TranscriptionError>,
+) -> bool {
+    let Some(provider) = config.active_provider.clone() else {
+        return false;
+    };
+    // Dry runs never reach the provider, so they say nothing about the key
+    if dry_run::is_enabled(config) {
+        return false;
+    }
+
+    let outcome = AuthOutcome::of(result);
+    let Some((status, changed)) =
+        update_status(app_handle, |status| status.record(&provider, outcome))
+    else {
+        return false;
+    };
+
+    if let Some(needs_reauth) = changed {
+        on_change(app_handle, &status, provider.clone(), needs_reauth);
+    }
+    status.flagged().contains(&provider)
+}
+
+/// A new key was saved for `provider`: clear the flag and start over
+pub fn reset(app_handle: &tauri::AppHandle, provider: Provider) {
+    let Some((status, changed)) = update_status(app_handle, |status| status.reset(&provider))
+    else {
+        return;
+    };
+
+    if let Some(needs_reauth) = changed {
+        on_change(app_handle, &status, provider, needs_reauth);
+    }
+}
+
+/// Providers whose key stopped working
+pub fn flagged_providers(app_handle: &tauri::AppHandle) -> Vec<Provider> {
+    app_handle
+        .store(AUTH_STORE)
+        .ok()
+        .and_then(|store| store.get(AUTH_KEY))
+        .and_then(|v| serde_json::from_value::<AuthStatus>(v).ok())
+        .map(|status| status.flagged())
+        .unwrap_or_default()
+}
+
+/// Restore the tray badge from the persisted status. Called once from setup.
+pub fn init(app_handle: &tauri::AppHandle) {
+    let flagged = flagged_providers(app_handle);
+    if !flagged.is_empty() {
+        println!("[Reauth] Providers needing re-auth: {:?}", flagged);
+        sync_tray(app_handle, true);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rejection_flags_only_keys_that_worked() {
+        let mut status = AuthStatus::default();
+
+        // Never worked: a typo, not an expiry
+        assert_eq!(
+            status.record(&Provider::OpenAI, AuthOutcome::Rejected),
+            None
+        );
+        assert!(status.flagged().is_empty());
+
+        assert_eq!(
+            status.record(&Provider::OpenAI, AuthOutcome::Accepted),
+            None
+        );
+        assert_eq!(
+            status.record(&Provider::OpenAI, AuthOutcome::Rejected),
+            Some(true)
+        );
+        // Repeated failures don't re-announce the flag
+        assert_eq!(
+            status.record(&Provider::OpenAI, AuthOutcome::Rejected),
+            None
+        );
+        assert_eq!(status.flagged(), vec![Provider::OpenAI]);
+
+        // Other providers are tracked separately
+        assert_eq!(
+            status.record(&Provider::AzureOpenAI, AuthOutcome::Rejected),
+            None
+        );
+    }
+
+    #[test]
+    fn test_flag_clears_on_success_or_new_key() {
+        let mut status = AuthStatus::default();
+        status.record(&Provider::AzureOpenAI, AuthOutcome::Accepted);
+        status.record(&Provider::AzureOpenAI, AuthOutcome::Rejected);
+
+        // Network errors say nothing about the key
+        assert_eq!(
+            status.record(&Provider::AzureOpenAI, AuthOutcome::Unknown),
+            None
+        );
+        assert_eq!(
+            status.record(&Provider::AzureOpenAI, AuthOutcome::Accepted),
+            Some(false)
+        );
+
+        status.record(&Provider::AzureOpenAI, AuthOutcome::Rejected);
+        assert_eq!(status.reset(&Provider::AzureOpenAI), Some(false));
+        // The new key hasn't worked yet, so a 401 is a setup problem again
+        assert_eq!(
+            status.record(&Provider::AzureOpenAI, AuthOutcome::Rejected),
+            None
+        );
+        assert_eq!(status.reset(&Provider::AzureOpenAI), None);
+    }
+}
@@ -0,0 +1,200 @@
+//! Streaming audio uploads.
+//!
+//! Recordings can be up to 25MB, so the multipart body streams the file from
+//! disk through a fixed-size buffer instead of loading it into memory first.
+//! Aborting an upload's [`AbortToken`] fails the next read of its stream,
+//! which ends the request mid-upload, and stops [`run_abortable`] waiting for
+//! a request that is already waiting on the response. Other uploads keep
+//! going. The stream also notes when its last byte was read, which splits a
+//! request's time into upload and response.
+
+use reqwest::blocking::multipart::Part;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Size of the read buffer used while uploading
+pub const UPLOAD_BUFFER_BYTES: usize = 64 * 1024;
+
+/// How often [`run_abortable`] checks whether it should stop waiting
+const ABORT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Aborts the uploads and requests it is handed to. Clones share the same
+/// state; a default token that is never aborted suits uploads nobody cancels.
+#[derive(Debug, Clone, Default)]
+pub struct AbortToken(Arc<AtomicBool>);
+
+impl AbortToken {
+    /// Abort every upload and request using this token, now and later
+    pub fn abort(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_aborted(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Run `request` on a thread of its own and wait for its result. Returns None
+/// as soon as `abort` is aborted, or if the request panics. An aborted request
+/// ends on its own in the background and its result is dropped.
+pub fn run_abortable<T: Send + 'static>(
+    abort: &AbortToken,
+    request: impl FnOnce() -> T + Send + 'static,
+) -> Option<T> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(request());
+    });
+
+    loop {
+        match rx.recv_timeout(ABORT_POLL_INTERVAL) {
+            Ok(result) => return Some(result),
+            Err(RecvTimeoutError::Timeout) => {
+                if abort.is_aborted() {
+                    println!("[Upload] Request aborted, no longer waiting for it");
+                    return None;
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => return None,
+        }
+    }
+}
+
+/// Set when the last byte of an upload has been read
+pub type UploadSent = Arc<OnceLock<Instant>>;
+
+/// Reader for an upload of `len` bytes. Fails once `abort` is aborted, and
+/// sets `sent` when the end is reached.
+struct UploadReader<R> {
+    inner: R,
+    abort: AbortToken,
+    remaining: u64,
+    sent: UploadSent,
+}
+
+impl<R> UploadReader<R> {
+    fn new(inner: R, len: u64, abort: AbortToken) -> Self {
+        Self {
+            inner,
+            abort,
+            remaining: len,
+            sent: UploadSent::default(),
+        }
+    }
+}
+
+impl<R: Read> Read for UploadReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.abort.is_aborted() {
+            return Err(std::io::Error::other("upload aborted"));
+        }
+        let read = self.inner.read(buf)?;
+        self.remaining = self.remaining.saturating_sub(read as u64);
+        if self.remaining == 0 || (read == 0 && !buf.is_empty()) {
+            let _ = self.sent.set(Instant::now());
+        }
+        Ok(read)
+    }
+}
+
+/// Open `path` for streaming. Returns the buffered reader and the file length.
+fn open_stream(path: &Path) -> std::io::Result<(BufReader<File>, u64)> {
+    let file = File::open(path)?;
+    let len = file.metadata()?.len();
+    Ok((BufReader::with_capacity(UPLOAD_BUFFER_BYTES, file), len))
+}
+
+fn mime_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("wav") => "audio/wav",
+        Some("mp3") => "audio/mpeg",
+        Some("m4a") | Some("mp4") => "audio/mp4",
+        Some("flac") => "audio/flac",
+        Some("ogg") => "audio/ogg",
+        Some("webm") => "audio/webm",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Multipart `file` part that streams the audio file from disk
+pub fn audio_part(path: &Path) -> std::io::Result<Part> {
+    audio_part_timed(path, &AbortToken::default()).map(|(part, _)| part)
+}
+
+/// Like [`audio_part`], failing once `abort` is aborted and also returning
+/// when the file has been sent
+pub fn audio_part_timed(path: &Path, abort: &AbortToken) -> std::io::Result<(Part, UploadSent)> {
+    let (reader, len) = open_stream(path)?;
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "audio.wav".to_string());
+
+    let reader = UploadReader::new(reader, len, abort.clone());
+    let sent = reader.sent.clone();
+    let part = Part::reader_with_length(reader, len)
+        .file_name(file_name)
+        .mime_str(mime_type(path))
+        .map_err(std::io::Error::other)?;
+    Ok((part, sent))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_abort_fails_only_its_own_stream() {
+        let data = [1u8; 8];
+        let aborted = AbortToken::default();
+        let mut cancelled = UploadReader::new(&data[..], 8, aborted.clone());
+        let mut other = UploadReader::new(&data[..], 8, AbortToken::default());
+        let mut buf = [0u8; 4];
+        assert_eq!(cancelled.read(&mut buf).unwrap(), 4);
+        assert_eq!(other.read(&mut buf).unwrap(), 4);
+
+        aborted.abort();
+        assert!(cancelled.read(&mut buf).is_err());
+        assert_eq!(other.read(&mut buf).unwrap(), 4);
+        assert!(other.sent.get().is_some());
+    }
+
+    #[test]
+    fn test_abort_stops_waiting_for_a_request() {
+        let abort = AbortToken::default();
+        assert_eq!(run_abortable(&abort, || 7), Some(7));
+
+        let started = Instant::now();
+        let aborter = {
+            let abort = abort.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(50));
+                abort.abort();
+            })
+        };
+        let result = run_abortable(&abort, || {
+            std::thread::sleep(Duration::from_secs(5));
+            7
+        });
+        aborter.join().unwrap();
+        assert_eq!(result, None);
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_sent_is_set_once_the_last_byte_is_read() {
+        let data = [1u8; 8];
+        let mut reader = UploadReader::new(&data[..], 8, AbortToken::default());
+        let mut buf = [0u8; 4];
+
+        assert_eq!(reader.read(&mut buf).unwrap(), 4);
+        assert!(reader.sent.get().is_none());
+        assert_eq!(reader.read(&mut buf).unwrap(), 4);
+        assert!(reader.sent.get().is_some());
+    }
+}
@@ -1,6 +1,15 @@
 use arboard::Clipboard;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::{thread, time::Duration};
 
+#[cfg(target_os = "macos")]
+use objc2::{
+    msg_send,
+    rc::Retained,
+    runtime::{AnyClass, AnyObject},
+};
+#[cfg(target_os = "macos")]
+use objc2_app_kit::NSPasteboard;
 #[cfg(target_os = "macos")]
 use objc2_core_graphics::{
     CGEvent, CGEventFlags, CGEventSource, CGEventSourceStateID, CGEventTapLocation, CGKeyCode,
@@ -13,6 +22,8 @@ pub enum ClipboardPasteError {
     EmptyText,
     ClipboardAccessFailed(String),
     ClipboardSetFailed(String),
+    /// Another app (e.g. a password field) has secure keyboard input enabled
+    SecureInputActive,
     #[cfg(not(target_os = "macos"))]
     UnsupportedPlatform,
 }
@@ -35,6 +46,9 @@ impl std::fmt::Display for ClipboardPasteError {
             ClipboardPasteError::ClipboardSetFailed(msg) => {
                 write!(f, "Failed to set clipboard text: {}", msg)
             }
+            ClipboardPasteError::SecureInputActive => {
+                write!(f, "Secure keyboard input is enabled by another application")
+            }
             #[cfg(not(target_os = "macos"))]
             ClipboardPasteError::UnsupportedPlatform => {
                 write!(f, "Auto-paste not yet implemented for this platform")
@@ -43,53 +57,312 @@ impl std::fmt::Display for ClipboardPasteError {
     }
 }
 
-/// Returns Ok(()) on success, Err on event creation/posting failure
+impl ClipboardPasteError {
+    /// Whether the text was left on the clipboard, so the user can paste it manually
+    pub fn text_on_clipboard(&self) -> bool {
+        matches!(
+            self,
+            ClipboardPasteError::EventSourceCreationFailed
+                | ClipboardPasteError::KeyEventCreationFailed
+                | ClipboardPasteError::SecureInputActive
+        )
+    }
+
+    /// Returns a user-friendly error message suitable for display in the UI
+    pub fn user_message(&self) -> String {
+        match self {
+            ClipboardPasteError::EventSourceCreationFailed
+            | ClipboardPasteError::KeyEventCreationFailed => {
+                "Couldn't paste. Check Accessibility permission, then press ⌘V - the text is on your clipboard.".to_string()
+            }
+            ClipboardPasteError::EmptyText => "Nothing to paste.".to_string(),
+            ClipboardPasteError::ClipboardAccessFailed(_)
+            | ClipboardPasteError::ClipboardSetFailed(_) => {
+                "Couldn't access the clipboard. Use \"Paste Last Recording\" from the menu.".to_string()
+            }
+            ClipboardPasteError::SecureInputActive => {
+                "Secure input is on (password field?). Press ⌘V - the text is on your clipboard.".to_string()
+            }
+            #[cfg(not(target_os = "macos"))]
+            ClipboardPasteError::UnsupportedPlatform => {
+                "Auto-paste isn't supported on this platform yet.".to_string()
+            }
+        }
+    }
+}
+
+static HIDE_FROM_CLIPBOARD_MANAGERS: AtomicBool = AtomicBool::new(false);
+
+/// Mark auto-pasted text so clipboard managers skip it
+/// (`hide_from_clipboard_managers`)
+pub fn set_hide_from_clipboard_managers(hide: bool) {
+    HIDE_FROM_CLIPBOARD_MANAGERS.store(hide, Ordering::Relaxed);
+}
+
+/// Put `text` on the general pasteboard. When hiding from clipboard managers,
+/// the item also carries the nspasteboard.org transient and concealed markers,
+/// which Paste, Maccy and others use to leave an item out of their history.
 #[cfg(target_os = "macos")]
-pub fn paste_with_cgevent() -> Result<(), ClipboardPasteError> {
-    // Key code for 'V' key on macOS keyboard
-    const V_KEYCODE: CGKeyCode = 9;
+fn set_clipboard_text(clipboard: &mut Clipboard, text: &str) -> Result<(), String> {
+    use objc2_app_kit::NSPasteboardTypeString;
+    use objc2_foundation::NSString;
 
-    println!("[Auto-Paste] Using Core Graphics to simulate Cmd+V");
+    if !HIDE_FROM_CLIPBOARD_MANAGERS.load(Ordering::Relaxed) {
+        return clipboard
+            .set_text(text.to_string())
+            .map_err(|e| e.to_string());
+    }
 
-    // Create event source for HID system state
-    let event_source = CGEventSource::new(CGEventSourceStateID::HIDSystemState)
-        .ok_or(ClipboardPasteError::EventSourceCreationFailed)?;
+    let pasteboard = NSPasteboard::generalPasteboard();
+    pasteboard.clearContents();
+    let string_type = unsafe { NSPasteboardTypeString };
+    if !pasteboard.setString_forType(&NSString::from_str(text), string_type) {
+        return Err("NSPasteboard rejected the text".to_string());
+    }
+    for marker in [
+        "org.nspasteboard.TransientType",
+        "org.nspasteboard.ConcealedType",
+    ] {
+        pasteboard.setString_forType(&NSString::from_str(""), &NSString::from_str(marker));
+    }
+    Ok(())
+}
+
+/// Whether any application currently has secure keyboard input enabled.
+/// While it's on, synthetic key events are silently dropped by the system.
+#[cfg(target_os = "macos")]
+pub fn is_secure_input_enabled() -> bool {
+    #[link(name = "Carbon", kind = "framework")]
+    extern "C" {
+        fn IsSecureEventInputEnabled() -> u8;
+    }
+
+    unsafe { IsSecureEventInputEnabled() != 0 }
+}
 
-    // Step 1: Create V key press event with Command modifier
+/// Post a key down and key up for `keycode` with `flags` held
+#[cfg(target_os = "macos")]
+fn press_key(
+    event_source: &CGEventSource,
+    keycode: CGKeyCode,
+    flags: CGEventFlags,
+) -> Result<(), ClipboardPasteError> {
+    // Step 1: Create key press event with the modifiers
     let key_down_event = CGEvent::new_keyboard_event(
-        Some(&event_source),
-        V_KEYCODE,
+        Some(event_source),
+        keycode,
         true, // key down
     )
     .ok_or(ClipboardPasteError::KeyEventCreationFailed)?;
 
-    // Set Command modifier flag (equivalent to Cmd key being held)
-    CGEvent::set_flags(Some(&key_down_event), CGEventFlags::MaskCommand);
+    // Set modifier flags (equivalent to the modifier keys being held)
+    CGEvent::set_flags(Some(&key_down_event), flags);
 
     // Post the key down event to HID event tap (system-level)
     CGEvent::post(CGEventTapLocation::HIDEventTap, Some(&key_down_event));
 
-    println!("[Auto-Paste] Posted Cmd+V key down event");
-
     // Small delay to ensure key down is processed before key up
     thread::sleep(Duration::from_millis(10));
 
-    // Step 2: Create V key release event
+    // Step 2: Create key release event
     let key_up_event = CGEvent::new_keyboard_event(
-        Some(&event_source),
-        V_KEYCODE,
+        Some(event_source),
+        keycode,
         false, // key up
     )
     .ok_or(ClipboardPasteError::KeyEventCreationFailed)?;
 
-    // Keep Command modifier during key up (some apps need this consistency)
-    CGEvent::set_flags(Some(&key_up_event), CGEventFlags::MaskCommand);
+    // Keep the modifiers during key up (some apps need this consistency)
+    CGEvent::set_flags(Some(&key_up_event), flags);
 
     // Post the key up event
     CGEvent::post(CGEventTapLocation::HIDEventTap, Some(&key_up_event));
 
-    println!("[Auto-Paste] Posted Cmd+V key up event");
+    Ok(())
+}
+
+/// Returns Ok(()) on success, Err on event creation/posting failure
+#[cfg(target_os = "macos")]
+pub fn paste_with_cgevent() -> Result<(), ClipboardPasteError> {
+    // Key code for 'V' key on macOS keyboard
+    const V_KEYCODE: CGKeyCode = 9;
+
+    println!("[Auto-Paste] Using Core Graphics to simulate Cmd+V");
+
+    // Create event source for HID system state
+    let event_source = CGEventSource::new(CGEventSourceStateID::HIDSystemState)
+        .ok_or(ClipboardPasteError::EventSourceCreationFailed)?;
+
+    press_key(&event_source, V_KEYCODE, CGEventFlags::MaskCommand)?;
+
+    println!("[Auto-Paste] Posted Cmd+V");
+
+    Ok(())
+}
+
+/// How long the focused app gets to put its selection on the clipboard
+#[cfg(target_os = "macos")]
+const COPY_TIMEOUT: Duration = Duration::from_millis(300);
+
+/// Everything on the general pasteboard, every type of every item, put back
+/// when this is dropped if the pasteboard changed in the meantime
+#[cfg(target_os = "macos")]
+struct PasteboardSnapshot {
+    change_count: isize,
+    /// Per item, its types with their data
+    items: Vec<Vec<(Retained<AnyObject>, Retained<AnyObject>)>>,
+}
+
+#[cfg(target_os = "macos")]
+impl PasteboardSnapshot {
+    fn take() -> Self {
+        let pasteboard = NSPasteboard::generalPasteboard();
+        let mut items = Vec::new();
+        unsafe {
+            let change_count: isize = msg_send![&*pasteboard, changeCount];
+            let pasteboard_items: Option<Retained<AnyObject>> =
+                msg_send![&*pasteboard, pasteboardItems];
+            if let Some(pasteboard_items) = pasteboard_items {
+                let count: usize = msg_send![&*pasteboard_items, count];
+                for i in 0..count {
+                    let item: Retained<AnyObject> = msg_send![&*pasteboard_items, objectAtIndex: i];
+                    let types: Retained<AnyObject> = msg_send![&*item, types];
+                    let type_count: usize = msg_send![&*types, count];
+                    let mut entries = Vec::new();
+                    for j in 0..type_count {
+                        let pasteboard_type: Retained<AnyObject> =
+                            msg_send![&*types, objectAtIndex: j];
+                        let data: Option<Retained<AnyObject>> =
+                            msg_send![&*item, dataForType: &*pasteboard_type];
+                        if let Some(data) = data {
+                            entries.push((pasteboard_type, data));
+                        }
+                    }
+                    items.push(entries);
+                }
+            }
+            Self {
+                change_count,
+                items,
+            }
+        }
+    }
+
+    /// Whether something was put on the pasteboard since the snapshot
+    fn changed(&self) -> bool {
+        let pasteboard = NSPasteboard::generalPasteboard();
+        let change_count: isize = unsafe { msg_send![&*pasteboard, changeCount] };
+        change_count != self.change_count
+    }
+}
 
+#[cfg(target_os = "macos")]
+impl Drop for PasteboardSnapshot {
+    fn drop(&mut self) {
+        if !self.changed() {
+            return;
+        }
+        let (Some(item_class), Some(array_class)) = (
+            AnyClass::get(c"NSPasteboardItem"),
+            AnyClass::get(c"NSMutableArray"),
+        ) else {
+            return;
+        };
+
+        // The user's own content goes back as it was, without the markers
+        // `hide_from_clipboard_managers` adds to dictated text
+        let pasteboard = NSPasteboard::generalPasteboard();
+        pasteboard.clearContents();
+        unsafe {
+            let objects: Retained<AnyObject> = msg_send![array_class, new];
+            for entries in &self.items {
+                let item: Retained<AnyObject> = msg_send![item_class, new];
+                for (pasteboard_type, data) in entries {
+                    let _: bool = msg_send![&*item, setData: &**data, forType: &**pasteboard_type];
+                }
+                let _: () = msg_send![&*objects, addObject: &*item];
+            }
+            let restored: bool = msg_send![&*pasteboard, writeObjects: &*objects];
+            if restored || self.items.is_empty() {
+                println!("[Auto-Paste] Restored previous clipboard content");
+            } else {
+                eprintln!("[Auto-Paste] Failed to restore previous clipboard content");
+            }
+        }
+    }
+}
+
+/// Copy the focused app's selection with a simulated Cmd+C and return it.
+/// The clipboard is left alone until the copy lands, and whatever was on it
+/// is put back on every path out. `None` if nothing was copied, i.e. nothing
+/// is selected.
+#[cfg(target_os = "macos")]
+pub fn copy_selection() -> Result<Option<String>, ClipboardPasteError> {
+    // Key code for 'C' key on macOS keyboard
+    const C_KEYCODE: CGKeyCode = 8;
+
+    if is_secure_input_enabled() {
+        return Err(ClipboardPasteError::SecureInputActive);
+    }
+
+    let snapshot = PasteboardSnapshot::take();
+    let mut clipboard =
+        Clipboard::new().map_err(|e| ClipboardPasteError::ClipboardAccessFailed(e.to_string()))?;
+
+    let event_source = CGEventSource::new(CGEventSourceStateID::HIDSystemState)
+        .ok_or(ClipboardPasteError::EventSourceCreationFailed)?;
+    press_key(&event_source, C_KEYCODE, CGEventFlags::MaskCommand)?;
+    println!("[Auto-Paste] Posted Cmd+C");
+
+    // The change count moves once the app has copied, even when the
+    // selection matches what was already on the clipboard
+    let deadline = std::time::Instant::now() + COPY_TIMEOUT;
+    while !snapshot.changed() && std::time::Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(20));
+    }
+    if !snapshot.changed() {
+        return Ok(None);
+    }
+    Ok(clipboard.get_text().ok().filter(|text| !text.is_empty()))
+}
+
+/// Keys pressed after a paste to place the cursor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CursorKey {
+    /// ⌘→, end of the line
+    LineEnd,
+    /// ⇧↩, a line break that doesn't send the message in chat apps
+    ShiftReturn,
+    /// ⇧←, extend the selection one character to the left
+    SelectLeft,
+    /// ⇧⌥←, extend the selection to the start of the previous word
+    SelectWordLeft,
+}
+
+/// Press `keys` in order, e.g. to move the cursor after a paste
+#[cfg(target_os = "macos")]
+pub fn press_cursor_keys(keys: &[CursorKey]) -> Result<(), ClipboardPasteError> {
+    const RETURN_KEYCODE: CGKeyCode = 36;
+    const LEFT_ARROW_KEYCODE: CGKeyCode = 123;
+    const RIGHT_ARROW_KEYCODE: CGKeyCode = 124;
+
+    let event_source = CGEventSource::new(CGEventSourceStateID::HIDSystemState)
+        .ok_or(ClipboardPasteError::EventSourceCreationFailed)?;
+
+    for key in keys {
+        let (keycode, flags) = match key {
+            CursorKey::LineEnd => (RIGHT_ARROW_KEYCODE, CGEventFlags::MaskCommand),
+            CursorKey::ShiftReturn => (RETURN_KEYCODE, CGEventFlags::MaskShift),
+            CursorKey::SelectLeft => (LEFT_ARROW_KEYCODE, CGEventFlags::MaskShift),
+            CursorKey::SelectWordLeft => (
+                LEFT_ARROW_KEYCODE,
+                CGEventFlags::MaskShift | CGEventFlags::MaskAlternate,
+            ),
+        };
+        press_key(&event_source, keycode, flags)?;
+    }
+
+    println!("[Auto-Paste] Pressed {} cursor keys", keys.len());
     Ok(())
 }
 
@@ -127,16 +400,21 @@ pub fn auto_paste_text_cgevent(text: &str) -> Result<(), ClipboardPasteError> {
     }
 
     // Step 3: Set transcribed text to clipboard
-    clipboard
-        .set_text(text.to_string())
-        .map_err(|e| ClipboardPasteError::ClipboardSetFailed(e.to_string()))?;
+    set_clipboard_text(&mut clipboard, text).map_err(ClipboardPasteError::ClipboardSetFailed)?;
 
     println!(
         "[Auto-Paste] Set clipboard to transcribed text ({} chars)",
         text.len()
     );
 
+    // Cmd+V would be swallowed by secure input; leave the text on the clipboard instead
+    if is_secure_input_enabled() {
+        eprintln!("[Auto-Paste] Secure input is enabled, skipping simulated paste");
+        return Err(ClipboardPasteError::SecureInputActive);
+    }
+
     // Step 4: Simulate paste using Core Graphics
+    // On failure the text stays on the clipboard (no restore) so the user can paste manually
     paste_with_cgevent()?;
 
     println!("[Auto-Paste] ✅ CGEvent paste completed successfully");
@@ -153,7 +431,7 @@ pub fn auto_paste_text_cgevent(text: &str) -> Result<(), ClipboardPasteError> {
                 // (avoid overwriting if user copied something else)
                 if let Ok(current_text) = clipboard.get_text() {
                     if current_text == text_for_check {
-                        if clipboard.set_text(previous_text).is_ok() {
+                        if set_clipboard_text(&mut clipboard, &previous_text).is_ok() {
                             println!("[Auto-Paste] Restored previous clipboard content");
                         }
                     } else {
@@ -175,6 +453,21 @@ pub fn auto_paste_text_cgevent(text: &str) -> Result<(), ClipboardPasteError> {
     Err(ClipboardPasteError::UnsupportedPlatform)
 }
 
+#[cfg(not(target_os = "macos"))]
+pub fn is_secure_input_enabled() -> bool {
+    false
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn copy_selection() -> Result<Option<String>, ClipboardPasteError> {
+    Err(ClipboardPasteError::UnsupportedPlatform)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn press_cursor_keys(_keys: &[CursorKey]) -> Result<(), ClipboardPasteError> {
+    Err(ClipboardPasteError::UnsupportedPlatform)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,258 @@
+//! Settings an organization enforces through MDM.
+//!
+//! IT deploys a configuration profile for Dictara's bundle identifier, which
+//! macOS installs as `/Library/Managed Preferences/<identifier>.plist`, or
+//! under the user's folder there for a user profile (which wins). It's read
+//! once at launch. Keys:
+//!
+//! - `provider`: `"open_ai"` or `"azure_open_ai"`, replacing the user's choice
+//! - `azure_endpoint`: Azure OpenAI endpoint, replacing the user's
+//! - `offline`: keep Dictara off the network apart from transcription - no
+//!   update checks, webhook sinks, summaries, LLM cleanup or phone intake
+//! - `blocked_apps`: app names or bundle identifiers dictation won't start in
+//!
+//! [`ManagedConfig::apply`] lays these over the user's settings whenever the
+//! config is loaded, and saving keeps them out of the user's store, so the
+//! user's own settings come back when the profile is removed.
+
+use super::{AppConfig, Provider};
+use crate::output::OutputSinkConfig;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+const MANAGED_PREFERENCES_DIR: &str = "/Library/Managed Preferences";
+
+static MANAGED: OnceLock<ManagedConfig> = OnceLock::new();
+
+/// The managed profile's settings; empty without a profile
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, specta::Type)]
+pub struct ManagedConfig {
+    #[serde(default)]
+    pub provider: Option<Provider>,
+    #[serde(default)]
+    pub azure_endpoint: Option<String>,
+    #[serde(default)]
+    pub offline: bool,
+    #[serde(default)]
+    pub blocked_apps: Vec<String>,
+}
+
+impl ManagedConfig {
+    /// Replace the user's values with the enforced ones
+    pub fn apply(&self, config: &mut AppConfig) {
+        if let Some(provider) = &self.provider {
+            config.active_provider = Some(provider.clone());
+        }
+        if self.offline {
+            config
+                .output_sinks
+                .retain(|sink| !matches!(sink, OutputSinkConfig::Webhook { .. }));
+            config.summary.enabled = false;
+            config.intake.enabled = false;
+        }
+    }
+
+    /// Put the user's own values from `stored` back in place of the enforced
+    /// ones before `config` is saved
+    pub fn keep_user_values(&self, config: &mut AppConfig, stored: &AppConfig) {
+        if self.provider.is_some() {
+            config.active_provider = stored.active_provider.clone();
+        }
+        if self.offline {
+            config.output_sinks = with_webhooks(&stored.output_sinks, &config.output_sinks);
+            config.summary.enabled = stored.summary.enabled;
+            config.intake.enabled = stored.intake.enabled;
+        }
+    }
+
+    /// Whether dictating into the app named `name` with `bundle_id` is blocked
+    pub fn blocks(&self, name: Option<&str>, bundle_id: Option<&str>) -> bool {
+        self.blocked_apps.iter().any(|blocked| {
+            [name, bundle_id]
+                .into_iter()
+                .flatten()
+                .any(|app| app.eq_ignore_ascii_case(blocked))
+        })
+    }
+}
+
+/// The sinks in `edited` with the webhooks of `stored` back where they were.
+/// Each of the other stored sinks is replaced by the next edited one, and
+/// sinks added while editing go last. Webhooks in `edited` are left out,
+/// since the stored ones are the user's.
+fn with_webhooks(
+    stored: &[OutputSinkConfig],
+    edited: &[OutputSinkConfig],
+) -> Vec<OutputSinkConfig> {
+    let mut edited = edited
+        .iter()
+        .filter(|sink| !matches!(sink, OutputSinkConfig::Webhook { .. }));
+    let mut sinks: Vec<OutputSinkConfig> = stored
+        .iter()
+        .filter_map(|sink| match sink {
+            OutputSinkConfig::Webhook { .. } => Some(sink),
+            _ => edited.next(),
+        })
+        .cloned()
+        .collect();
+    sinks.extend(edited.cloned());
+    sinks
+}
+
+/// Profile paths for `identifier`, the one that wins first
+fn profile_paths(identifier: &str, user: Option<&str>) -> Vec<PathBuf> {
+    let file = format!("{}.plist", identifier);
+    let dir = Path::new(MANAGED_PREFERENCES_DIR);
+    let mut paths = Vec::new();
+    if let Some(user) = user {
+        paths.push(dir.join(user).join(&file));
+    }
+    paths.push(dir.join(file));
+    paths
+}
+
+/// Managed profile as JSON, via `plutil` since it may be a binary plist
+fn read_profile(path: &Path) -> Option<serde_json::Value> {
+    let output = std::process::Command::new("plutil")
+        .args(["-convert", "json", "-o", "-"])
+        .arg(path)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    serde_json::from_slice(&output.stdout).ok()
+}
+
+/// Read the managed profile for `identifier`. Call once at launch, before
+/// the config is first loaded.
+pub fn load(identifier: &str) {
+    let user = std::env::var("USER").ok();
+    let profile = profile_paths(identifier, user.as_deref())
+        .into_iter()
+        .filter(|path| path.exists())
+        .find_map(|path| Some((read_profile(&path)?, path)));
+
+    let managed = match profile {
+        Some((json, path)) => match serde_json::from_value::<ManagedConfig>(json) {
+            Ok(managed) => {
+                println!("[Managed Config] Using {:?}: {:?}", path, managed);
+                managed
+            }
+            Err(e) => {
+                eprintln!("[Managed Config] Ignoring unreadable {:?}: {}", path, e);
+                ManagedConfig::default()
+            }
+        },
+        None => ManagedConfig::default(),
+    };
+    let _ = MANAGED.set(managed);
+}
+
+/// The enforced settings
+pub fn current() -> ManagedConfig {
+    MANAGED.get().cloned().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::AfterPaste;
+
+    fn offline() -> ManagedConfig {
+        ManagedConfig {
+            provider: Some(Provider::AzureOpenAI),
+            offline: true,
+            ..ManagedConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_apply_and_keep_user_values() {
+        let webhook = OutputSinkConfig::Webhook {
+            url: "https://example.com/hook".to_string(),
+            body: None,
+        };
+        let mut stored = AppConfig {
+            active_provider: Some(Provider::OpenAI),
+            ..AppConfig::default()
+        };
+        stored.summary.enabled = true;
+        stored.output_sinks.push(webhook.clone());
+
+        let mut config = stored.clone();
+        offline().apply(&mut config);
+        assert_eq!(config.active_provider, Some(Provider::AzureOpenAI));
+        assert!(!config.summary.enabled);
+        assert!(!config.output_sinks.contains(&webhook));
+
+        offline().keep_user_values(&mut config, &stored);
+        assert_eq!(config.active_provider, Some(Provider::OpenAI));
+        assert!(config.summary.enabled);
+        assert_eq!(config.output_sinks, stored.output_sinks);
+    }
+
+    #[test]
+    fn test_keep_user_values_keeps_sink_order() {
+        let webhook = OutputSinkConfig::Webhook {
+            url: "https://example.com/hook".to_string(),
+            body: None,
+        };
+        let file = OutputSinkConfig::FileAppend {
+            path: "/tmp/dictations.txt".to_string(),
+        };
+        let stored = AppConfig {
+            output_sinks: vec![OutputSinkConfig::Clipboard, webhook.clone(), file.clone()],
+            ..AppConfig::default()
+        };
+
+        let mut config = stored.clone();
+        offline().apply(&mut config);
+        assert_eq!(
+            config.output_sinks,
+            [OutputSinkConfig::Clipboard, file.clone()]
+        );
+        offline().keep_user_values(&mut config, &stored);
+        assert_eq!(config.output_sinks, stored.output_sinks);
+        // Saving again keeps the same order
+        offline().keep_user_values(&mut config, &stored);
+        assert_eq!(config.output_sinks, stored.output_sinks);
+
+        // An edited sink keeps its place, an added one goes last
+        let paste = OutputSinkConfig::Paste {
+            after_paste: AfterPaste::Stay,
+        };
+        offline().apply(&mut config);
+        config.output_sinks[0] = paste.clone();
+        config.output_sinks.push(OutputSinkConfig::Clipboard);
+        offline().keep_user_values(&mut config, &stored);
+        assert_eq!(
+            config.output_sinks,
+            [paste, webhook, file, OutputSinkConfig::Clipboard]
+        );
+    }
+
+    #[test]
+    fn test_blocks_by_name_or_bundle_id() {
+        let managed = ManagedConfig {
+            blocked_apps: vec!["com.apple.Terminal".to_string(), "Slack".to_string()],
+            ..ManagedConfig::default()
+        };
+        assert!(managed.blocks(Some("Terminal"), Some("com.apple.terminal")));
+        assert!(managed.blocks(Some("slack"), None));
+        assert!(!managed.blocks(Some("Notes"), Some("com.apple.Notes")));
+        assert!(!managed.blocks(None, None));
+    }
+
+    #[test]
+    fn test_user_profile_wins() {
+        assert_eq!(
+            profile_paths("app.dictara", Some("ana")),
+            [
+                PathBuf::from("/Library/Managed Preferences/ana/app.dictara.plist"),
+                PathBuf::from("/Library/Managed Preferences/app.dictara.plist"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,837 @@
+pub mod managed;
+
+use crate::action_items::ActionItemSinkConfig;
+use crate::batch::SidecarFormat;
+use crate::clients::provider_catalog;
+use crate::output::OutputSinkConfig;
+use crate::pipeline::PipelineStepConfig;
+use crate::plugins::PluginConfig;
+use chrono::{NaiveTime, Timelike};
+use serde::{Deserialize, Serialize};
+
+/// Provider types supported by the application
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, specta::Type)]
+pub enum Provider {
+    #[serde(rename = "open_ai", alias = "openai", alias = "open_a_i")]
+    OpenAI,
+    #[serde(
+        rename = "azure_open_ai",
+        alias = "azure",
+        alias = "azure_openai",
+        alias = "azure_open_a_i"
+    )]
+    AzureOpenAI,
+}
+
+/// App configuration (stored locally)
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct AppConfig {
+    /// Currently active provider (only one can be active)
+    pub active_provider: Option<Provider>,
+    /// Show a brief "No speech detected" notice when the transcript comes back empty
+    #[serde(default = "default_true")]
+    pub show_no_speech_notice: bool,
+    /// Feedback sound settings
+    #[serde(default)]
+    pub sounds: SoundConfig,
+    /// Schedule during which sounds and notices are suppressed
+    #[serde(default)]
+    pub quiet_hours: QuietHoursConfig,
+    /// Meetings looked up in the calendar
+    #[serde(default)]
+    pub calendar: CalendarConfig,
+    /// Return a canned transcript instead of calling the provider (for demos)
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Minimum time between transcription requests in milliseconds (0 = no limit)
+    #[serde(default)]
+    pub min_transcription_interval_ms: u32,
+    /// Let any trigger source stop a session, not just the one that started it
+    #[serde(default)]
+    pub allow_cross_source_stop: bool,
+    /// Where the transcript goes, in order
+    #[serde(default = "crate::output::default_sinks")]
+    pub output_sinks: Vec<OutputSinkConfig>,
+    /// Order and enabled state of transcript processing steps (empty = defaults)
+    #[serde(default)]
+    pub pipeline: Vec<PipelineStepConfig>,
+    /// External executables that can rewrite the transcript
+    #[serde(default)]
+    pub plugins: Vec<PluginConfig>,
+    /// Key (or modifier chord) held for push-to-talk
+    #[serde(default)]
+    pub trigger_key: TriggerKey,
+    /// Menu bar icon style
+    #[serde(default)]
+    pub tray_icon_theme: TrayIconTheme,
+    /// Trade UI smoothness and background polling for battery life
+    #[serde(default)]
+    pub energy_profile: EnergyProfile,
+    /// Maximum audio level updates per second sent to the popup (1-120).
+    /// Peaks between updates are held, so lower rates still show loud bursts.
+    #[serde(default = "default_audio_level_rate_hz")]
+    pub audio_level_rate_hz: u32,
+    /// Sample rate and bit depth of recordings
+    #[serde(default)]
+    pub recorder: RecorderConfig,
+    /// Pick the provider per dictation by price and latency
+    #[serde(default)]
+    pub provider_policy: ProviderPolicyConfig,
+    /// Chat model for post-processing steps such as summaries
+    #[serde(default)]
+    pub llm: LlmConfig,
+    /// Summaries of long hands-free recordings
+    #[serde(default)]
+    pub summary: SummaryConfig,
+    /// Past transcripts kept on this Mac
+    #[serde(default)]
+    pub history: HistoryConfig,
+    /// Latest dictations sent along as context for the next one
+    #[serde(default)]
+    pub recent_context: RecentContextConfig,
+    /// Names and titles of exported transcripts
+    #[serde(default)]
+    pub export_templates: ExportTemplateConfig,
+    /// Mark auto-pasted text as transient so clipboard managers (Paste, Maccy)
+    /// don't keep it in their history
+    #[serde(default)]
+    pub hide_from_clipboard_managers: bool,
+    /// Have VoiceOver speak state changes ("Recording started", "Pasted 42 words")
+    #[serde(default)]
+    pub announcements: bool,
+    /// Transcripts longer than this many characters wait for confirmation
+    /// before they're delivered (0 = no limit)
+    #[serde(default = "default_max_delivery_chars")]
+    pub max_delivery_chars: u32,
+    /// Words dropped by the `remove_filler_words` pipeline step
+    #[serde(default)]
+    pub filler_words: FillerWordsConfig,
+    /// Include per-stage timings in `dictation-completed`, to find out where
+    /// a slow dictation spent its time
+    #[serde(default)]
+    pub show_timing_breakdown: bool,
+    #[serde(default)]
+    pub watch_folder: WatchFolderConfig,
+    #[serde(default)]
+    pub intake: IntakeConfig,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            active_provider: None,
+            show_no_speech_notice: true,
+            sounds: SoundConfig::default(),
+            quiet_hours: QuietHoursConfig::default(),
+            calendar: CalendarConfig::default(),
+            dry_run: false,
+            min_transcription_interval_ms: 0,
+            allow_cross_source_stop: false,
+            output_sinks: crate::output::default_sinks(),
+            pipeline: Vec::new(),
+            plugins: Vec::new(),
+            trigger_key: TriggerKey::default(),
+            tray_icon_theme: TrayIconTheme::default(),
+            energy_profile: EnergyProfile::default(),
+            audio_level_rate_hz: default_audio_level_rate_hz(),
+            recorder: RecorderConfig::default(),
+            provider_policy: ProviderPolicyConfig::default(),
+            llm: LlmConfig::default(),
+            summary: SummaryConfig::default(),
+            history: HistoryConfig::default(),
+            recent_context: RecentContextConfig::default(),
+            export_templates: ExportTemplateConfig::default(),
+            hide_from_clipboard_managers: false,
+            announcements: false,
+            max_delivery_chars: default_max_delivery_chars(),
+            filler_words: FillerWordsConfig::default(),
+            show_timing_breakdown: false,
+            watch_folder: WatchFolderConfig::default(),
+            intake: IntakeConfig::default(),
+        }
+    }
+}
+
+/// Push-to-talk trigger. Modifier triggers help on external keyboards without Fn.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum TriggerKey {
+    #[default]
+    Fn,
+    RightOption,
+    RightCommand,
+    RightControl,
+    RightShift,
+    /// Control and Option held together (either side)
+    ControlOption,
+    /// Raw key captured by `learn_trigger_key`, e.g. a key remapped with
+    /// Karabiner-Elements. `modifier_mask` holds the flag bits that are set while
+    /// a modifier key is held; None for regular keys.
+    Custom {
+        keycode: u32,
+        modifier_mask: Option<u32>,
+    },
+}
+
+/// Menu bar icon style. The idle icon is always a template image that macOS
+/// tints for the menu bar; color badges can't be templates, so those icons are
+/// redrawn for light/dark mode instead.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum TrayIconTheme {
+    /// Template image with a dot badge while recording
+    #[default]
+    Monochrome,
+    /// Red recording dot
+    RedDot,
+    /// Blue ring with a white center, readable without relying on hue
+    Colorblind,
+}
+
+/// Energy profile for background work
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum EnergyProfile {
+    #[default]
+    Balanced,
+    /// Background timers wake less often and the tray icon doesn't animate
+    LowPower,
+}
+
+/// Cost-aware provider selection. When enabled, each dictation goes to the
+/// cheapest configured provider expected to finish within `max_latency_ms`,
+/// instead of always to `active_provider`.
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ProviderPolicyConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Latency target for a transcription in milliseconds
+    #[serde(default = "default_max_latency_ms")]
+    pub max_latency_ms: u32,
+    /// Price per minute of audio. Providers without an entry are tried last.
+    #[serde(default = "default_provider_prices")]
+    pub prices: Vec<ProviderPrice>,
+}
+
+impl Default for ProviderPolicyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_latency_ms: default_max_latency_ms(),
+            prices: default_provider_prices(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ProviderPrice {
+    pub provider: Provider,
+    /// US dollars per minute of audio
+    pub usd_per_minute: f32,
+}
+
+fn default_max_latency_ms() -> u32 {
+    3000
+}
+
+/// List prices from the provider catalog
+fn default_provider_prices() -> Vec<ProviderPrice> {
+    [Provider::OpenAI, Provider::AzureOpenAI]
+        .into_iter()
+        .map(|provider| ProviderPrice {
+            usd_per_minute: provider_catalog::list_price(&provider),
+            provider,
+        })
+        .collect()
+}
+
+/// Sample rate and bit depth of a recording. Recordings are always mono.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+pub struct AudioFormat {
+    pub sample_rate: u32,
+    /// 16 or 24
+    pub bits_per_sample: u16,
+    /// Keep the microphone's own rate when it's at most `sample_rate`, which
+    /// skips local resampling. Only for providers that resample on their side.
+    #[serde(default)]
+    pub native_rate: bool,
+}
+
+impl Default for AudioFormat {
+    /// What Whisper works with internally
+    fn default() -> Self {
+        Self {
+            sample_rate: 16_000,
+            bits_per_sample: 16,
+            native_rate: false,
+        }
+    }
+}
+
+/// Recording format, for providers that do better with higher fidelity
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+pub struct RecorderConfig {
+    #[serde(default)]
+    pub format: AudioFormat,
+    /// Replaces `format` when recording for one of these providers
+    #[serde(default)]
+    pub provider_formats: Vec<ProviderAudioFormat>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ProviderAudioFormat {
+    pub provider: Provider,
+    pub format: AudioFormat,
+}
+
+impl RecorderConfig {
+    /// Format to record in for `provider`, reduced to what the provider accepts
+    pub fn format_for(&self, provider: Option<&Provider>) -> AudioFormat {
+        let Some(provider) = provider else {
+            return self.format;
+        };
+        let format = self
+            .provider_formats
+            .iter()
+            .find(|f| &f.provider == provider)
+            .map(|f| f.format)
+            .unwrap_or(self.format);
+        provider_catalog::supported_format(provider, format)
+    }
+}
+
+/// Folder whose new audio files are transcribed as they arrive, e.g. voice
+/// memos synced from a phone
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+pub struct WatchFolderConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub folder: Option<String>,
+    /// Format of the transcript written next to each file
+    #[serde(default)]
+    pub format: SidecarFormat,
+}
+
+/// Local network endpoint for audio pushed from a phone, e.g. an iOS
+/// Shortcut sharing a voice memo
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct IntakeConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_intake_port")]
+    pub port: u16,
+    /// Format of the transcript written next to each memo
+    #[serde(default)]
+    pub format: SidecarFormat,
+}
+
+impl Default for IntakeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: default_intake_port(),
+            format: SidecarFormat::default(),
+        }
+    }
+}
+
+fn default_intake_port() -> u16 {
+    47821
+}
+
+/// Chat model used with the active provider's key. OpenAI takes a model name;
+/// Azure needs a chat deployment on the same resource as the Whisper deployment.
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct LlmConfig {
+    /// OpenAI model name
+    #[serde(default = "default_llm_model")]
+    pub model: String,
+    /// Azure OpenAI chat deployment name (None = not available on Azure)
+    #[serde(default)]
+    pub azure_deployment: Option<String>,
+    /// Instructions for the `llm_cleanup` step, a template (see `templates`)
+    #[serde(default = "default_cleanup_prompt")]
+    pub cleanup_prompt: String,
+    /// Instructions for rewriting a selection as the user dictates
+    #[serde(default = "default_rewrite_prompt")]
+    pub rewrite_prompt: String,
+}
+
+impl Default for LlmConfig {
+    fn default() -> Self {
+        Self {
+            model: default_llm_model(),
+            azure_deployment: None,
+            cleanup_prompt: default_cleanup_prompt(),
+            rewrite_prompt: default_rewrite_prompt(),
+        }
+    }
+}
+
+fn default_llm_model() -> String {
+    "gpt-4o-mini".to_string()
+}
+
+fn default_cleanup_prompt() -> String {
+    "Clean up this dictated text: fix punctuation, capitalization and obvious transcription mistakes, and drop false starts. Keep the wording and the language. Reply with the cleaned-up text only.".to_string()
+}
+
+fn default_rewrite_prompt() -> String {
+    "Rewrite the selected text as the instruction says. Keep its language and formatting unless the instruction asks otherwise. Reply with the rewritten text only.".to_string()
+}
+
+/// Bullet summary and action items for locked (hands-free) recordings, e.g.
+/// meetings. Both the transcript and the summary are kept in history.
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct SummaryConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Shorter transcripts aren't summarized
+    #[serde(default = "default_summary_min_words")]
+    pub min_words: u32,
+    /// What gets pasted when a summary was made
+    #[serde(default)]
+    pub paste: SummaryPaste,
+    /// Where extracted action items go, e.g. Reminders or a todo.md
+    #[serde(default)]
+    pub action_item_sinks: Vec<ActionItemSinkConfig>,
+}
+
+impl Default for SummaryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_words: default_summary_min_words(),
+            paste: SummaryPaste::default(),
+            action_item_sinks: Vec::new(),
+        }
+    }
+}
+
+fn default_summary_min_words() -> u32 {
+    150
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum SummaryPaste {
+    #[default]
+    Transcript,
+    Summary,
+    /// Summary first, then the full transcript
+    Both,
+}
+
+/// Transcript history settings
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct HistoryConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Oldest entries are dropped beyond this
+    #[serde(default = "default_history_max_entries")]
+    pub max_entries: u32,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_entries: default_history_max_entries(),
+        }
+    }
+}
+
+fn default_history_max_entries() -> u32 {
+    100
+}
+
+/// Context from recent dictations, so one that continues them keeps their
+/// terminology and casing. The transcripts are only kept in memory.
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct RecentContextConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// How many of the latest dictations into the same app to include (1-2)
+    #[serde(default = "default_recent_context_dictations")]
+    pub dictations: u32,
+    /// Longest context in characters, cut from the end
+    #[serde(default = "default_recent_context_max_chars")]
+    pub max_chars: u32,
+    /// Dictations older than this are left out
+    #[serde(default = "default_recent_context_max_age_minutes")]
+    pub max_age_minutes: u32,
+}
+
+impl Default for RecentContextConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dictations: default_recent_context_dictations(),
+            max_chars: default_recent_context_max_chars(),
+            max_age_minutes: default_recent_context_max_age_minutes(),
+        }
+    }
+}
+
+fn default_recent_context_dictations() -> u32 {
+    2
+}
+
+/// About the 224 tokens Whisper reads from its prompt
+fn default_recent_context_max_chars() -> u32 {
+    800
+}
+
+fn default_recent_context_max_age_minutes() -> u32 {
+    10
+}
+
+/// Filler words for the `remove_filler_words` pipeline step
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct FillerWordsConfig {
+    /// Built-in lists to use, by language code: "en", "de", "fr" or "es"
+    #[serde(default = "default_filler_languages")]
+    pub languages: Vec<String>,
+    /// Extra words or phrases to drop, e.g. "like" or "sort of"
+    #[serde(default)]
+    pub custom: Vec<String>,
+}
+
+impl Default for FillerWordsConfig {
+    fn default() -> Self {
+        Self {
+            languages: default_filler_languages(),
+            custom: Vec::new(),
+        }
+    }
+}
+
+fn default_filler_languages() -> Vec<String> {
+    vec!["en".to_string()]
+}
+
+/// Templates for saved transcripts; see `templates` for the variables
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ExportTemplateConfig {
+    /// File name without extension
+    #[serde(default = "default_filename_template")]
+    pub filename: String,
+    /// Heading at the top of the file
+    #[serde(default = "default_title_template")]
+    pub title: String,
+}
+
+impl Default for ExportTemplateConfig {
+    fn default() -> Self {
+        Self {
+            filename: default_filename_template(),
+            title: default_title_template(),
+        }
+    }
+}
+
+fn default_filename_template() -> String {
+    "{date} {time} {first_words}".to_string()
+}
+
+fn default_title_template() -> String {
+    "{first_words}".to_string()
+}
+
+/// Feedback sound settings
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct SoundConfig {
+    /// Master switch for all feedback sounds
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Play a cue when recording starts
+    #[serde(default = "default_true")]
+    pub start: bool,
+    /// Play a cue when Space locks the recording for hands-free dictation
+    #[serde(default = "default_true")]
+    pub locked: bool,
+    /// Play a cue once the transcript has been pasted
+    #[serde(default = "default_true")]
+    pub done: bool,
+    /// Play a cue when recording, transcription or paste fails
+    #[serde(default = "default_true")]
+    pub error: bool,
+    /// Output device name for cues. None follows the system default output.
+    #[serde(default)]
+    pub output_device: Option<String>,
+    /// Cue volume from 0.0 to 1.0, applied on top of the system volume
+    #[serde(default = "default_volume")]
+    pub volume: f32,
+    /// Speak short confirmations ("Locked", "Cancelled") instead of beeps
+    #[serde(default)]
+    pub spoken: bool,
+    /// Speech voice for spoken cues, from `get_voices`. None uses the system voice.
+    #[serde(default)]
+    pub voice: Option<String>,
+    /// Leave the start cue out of the recording: what the microphone picks up
+    /// while it plays is dropped, so a cue leaking from the speakers can't
+    /// end up in the transcript
+    #[serde(default)]
+    pub gate_start_cue: bool,
+}
+
+impl Default for SoundConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            start: true,
+            locked: true,
+            done: true,
+            error: true,
+            output_device: None,
+            volume: default_volume(),
+            spoken: false,
+            voice: None,
+            gate_start_cue: false,
+        }
+    }
+}
+
+fn default_volume() -> f32 {
+    1.0
+}
+
+/// Quiet hours schedule (local time)
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct QuietHoursConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Start time in "HH:MM" format, e.g. "22:00"
+    #[serde(default = "default_quiet_start")]
+    pub start: String,
+    /// End time in "HH:MM" format, e.g. "08:00". May be earlier than start (spans midnight).
+    #[serde(default = "default_quiet_end")]
+    pub end: String,
+}
+
+impl Default for QuietHoursConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            start: default_quiet_start(),
+            end: default_quiet_end(),
+        }
+    }
+}
+
+fn default_quiet_start() -> String {
+    "22:00".to_string()
+}
+
+fn default_quiet_end() -> String {
+    "08:00".to_string()
+}
+
+impl QuietHoursConfig {
+    /// Whether quiet hours are in effect right now
+    pub fn is_active_now(&self) -> bool {
+        self.is_active_at(chrono::Local::now().time())
+    }
+
+    /// Whether quiet hours are in effect at the given local time
+    pub fn is_active_at(&self, now: NaiveTime) -> bool {
+        if !self.enabled {
+            return false;
+        }
+
+        let (Some(start), Some(end)) = (parse_time(&self.start), parse_time(&self.end)) else {
+            eprintln!(
+                "[Config] Invalid quiet hours {}-{}, ignoring",
+                self.start, self.end
+            );
+            return false;
+        };
+
+        let now = now.hour() * 60 + now.minute();
+        if start <= end {
+            now >= start && now < end
+        } else {
+            // Window spans midnight, e.g. 22:00-08:00
+            now >= start || now < end
+        }
+    }
+}
+
+/// What changes while a calendar meeting is in progress (macOS only)
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct CalendarConfig {
+    /// Look up the meeting in progress. Needs calendar access, asked for
+    /// with `request_calendar_access`.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Suppress sounds and notices during a meeting, like quiet hours
+    #[serde(default = "default_true")]
+    pub mute_sounds: bool,
+    /// Tag history entries with the meeting's title
+    #[serde(default = "default_true")]
+    pub tag_history: bool,
+}
+
+impl Default for CalendarConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mute_sounds: true,
+            tag_history: true,
+        }
+    }
+}
+
+/// Parse "HH:MM" into minutes since midnight
+fn parse_time(value: &str) -> Option<u32> {
+    let time = NaiveTime::parse_from_str(value.trim(), "%H:%M").ok()?;
+    Some(time.hour() * 60 + time.minute())
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_audio_level_rate_hz() -> u32 {
+    30
+}
+
+fn default_max_delivery_chars() -> u32 {
+    10_000
+}
+
+/// OpenAI provider configuration (stored in keychain)
+#[derive(Clone, Serialize, Deserialize, specta::Type)]
+pub struct OpenAIConfig {
+    pub api_key: String,
+}
+
+/// Azure OpenAI provider configuration (stored in keychain)
+#[derive(Clone, Serialize, Deserialize, specta::Type)]
+pub struct AzureOpenAIConfig {
+    pub api_key: String,
+    pub endpoint: String,
+}
+
+// Debug is implemented by hand so API keys never end up in logs
+impl std::fmt::Debug for OpenAIConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OpenAIConfig")
+            .field("api_key", &"[REDACTED]")
+            .finish()
+    }
+}
+
+impl std::fmt::Debug for AzureOpenAIConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AzureOpenAIConfig")
+            .field("api_key", &"[REDACTED]")
+            .field("endpoint", &self.endpoint)
+            .finish()
+    }
+}
+
+/// The user's own settings, without the managed ones
+#[cfg(feature = "gui")]
+fn load_user_config(store: &tauri_plugin_store::Store<tauri::Wry>) -> AppConfig {
+    store
+        .get("app_config")
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+/// Load app configuration from store, with the managed settings applied
+#[cfg(feature = "gui")]
+pub fn load_app_config(store: &tauri_plugin_store::Store<tauri::Wry>) -> AppConfig {
+    let mut config = load_user_config(store);
+    managed::current().apply(&mut config);
+    config
+}
+
+/// Save app configuration to store. Managed settings aren't saved over the
+/// user's own.
+#[cfg(feature = "gui")]
+pub fn save_app_config(
+    store: &tauri_plugin_store::Store<tauri::Wry>,
+    config: &AppConfig,
+) -> Result<(), String> {
+    let mut config = config.clone();
+    managed::current().keep_user_values(&mut config, &load_user_config(store));
+    store.set(
+        "app_config",
+        serde_json::to_value(&config).map_err(|e| e.to_string())?,
+    );
+    store.save().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quiet_hours(start: &str, end: &str) -> QuietHoursConfig {
+        QuietHoursConfig {
+            enabled: true,
+            start: start.to_string(),
+            end: end.to_string(),
+        }
+    }
+
+    fn at(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn test_quiet_hours_spanning_midnight() {
+        let config = quiet_hours("22:00", "08:00");
+        assert!(config.is_active_at(at(23, 30)));
+        assert!(config.is_active_at(at(0, 0)));
+        assert!(config.is_active_at(at(7, 59)));
+        assert!(!config.is_active_at(at(8, 0)));
+        assert!(!config.is_active_at(at(12, 0)));
+        assert!(!config.is_active_at(at(21, 59)));
+    }
+
+    #[test]
+    fn test_quiet_hours_same_day_window() {
+        let config = quiet_hours("12:00", "13:30");
+        assert!(config.is_active_at(at(12, 0)));
+        assert!(config.is_active_at(at(13, 29)));
+        assert!(!config.is_active_at(at(13, 30)));
+        assert!(!config.is_active_at(at(11, 59)));
+    }
+
+    #[test]
+    fn test_recorder_format_prefers_provider_override() {
+        let hifi = AudioFormat {
+            sample_rate: 24_000,
+            bits_per_sample: 24,
+            native_rate: false,
+        };
+        let config = RecorderConfig {
+            format: AudioFormat::default(),
+            provider_formats: vec![ProviderAudioFormat {
+                provider: Provider::AzureOpenAI,
+                format: hifi,
+            }],
+        };
+
+        assert_eq!(config.format_for(Some(&Provider::AzureOpenAI)), hifi);
+        assert_eq!(
+            config.format_for(Some(&Provider::OpenAI)),
+            AudioFormat::default()
+        );
+        assert_eq!(config.format_for(None), AudioFormat::default());
+    }
+
+    #[test]
+    fn test_quiet_hours_disabled_or_invalid() {
+        let mut config = quiet_hours("22:00", "08:00");
+        config.enabled = false;
+        assert!(!config.is_active_at(at(23, 0)));
+
+        let config = quiet_hours("late", "08:00");
+        assert!(!config.is_active_at(at(23, 0)));
+    }
+}
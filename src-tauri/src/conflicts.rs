@@ -0,0 +1,234 @@
+//! Detection of other dictation tools that also listen to the Fn key.
+//!
+//! When macOS Dictation or a third-party tool is bound to Fn, a single press is
+//! handled twice and both start recording. We can't unbind them for the user,
+//! so we detect the situation and explain how to fix it. The one exception is
+//! macOS's own "Press 🌐 key to" setting: setup offers once to set it to "Do
+//! Nothing", and Preferences offers it while Fn opens the emoji picker.
+
+use crate::config::{self, TriggerKey};
+use serde::Serialize;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use tauri_plugin_store::StoreExt;
+
+/// Set in the config store once setup offered to change the Fn key setting
+const FN_KEY_OFFERED_KEY: &str = "fn_key_fix_offered";
+
+/// HIToolbox keys that control what the Fn (globe) key does
+#[cfg(target_os = "macos")]
+const HITOOLBOX_DOMAIN: &str = "com.apple.HIToolbox";
+#[cfg(target_os = "macos")]
+const FN_USAGE_KEY: &str = "AppleFnUsageType";
+#[cfg(target_os = "macos")]
+const DICTATION_ENABLED_KEY: &str = "AppleDictationAutoEnable";
+
+/// Third-party dictation apps that bind Fn by default (matched on app name)
+#[cfg(target_os = "macos")]
+const KNOWN_TOOLS: &[(&str, &str)] = &[("superwhisper", "superwhisper"), ("wispr", "Wispr Flow")];
+
+/// What the Fn (globe) key does ("Press 🌐 key to" in System Settings → Keyboard)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum FnKeyUsage {
+    DoNothing,
+    ChangeInputSource,
+    ShowEmoji,
+    StartDictation,
+    /// Not set or not on macOS
+    Unknown,
+}
+
+impl FnKeyUsage {
+    /// `AppleFnUsageType` values as written by System Settings
+    #[cfg(target_os = "macos")]
+    fn from_default(value: &str) -> Self {
+        match value {
+            "0" => FnKeyUsage::DoNothing,
+            "1" => FnKeyUsage::ChangeInputSource,
+            "2" => FnKeyUsage::ShowEmoji,
+            "3" => FnKeyUsage::StartDictation,
+            _ => FnKeyUsage::Unknown,
+        }
+    }
+}
+
+/// A tool that may react to the same trigger as Dictara
+#[derive(Debug, Clone, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DictationConflict {
+    /// Display name of the conflicting tool
+    pub tool: String,
+    /// What the user should change to stop double-handling
+    pub guidance: String,
+}
+
+/// Check for tools configured to the Fn key (macOS only, empty elsewhere)
+pub fn detect_conflicts() -> Vec<DictationConflict> {
+    #[cfg(target_os = "macos")]
+    {
+        let mut conflicts = Vec::new();
+
+        if fn_key_usage() == FnKeyUsage::StartDictation {
+            conflicts.push(DictationConflict {
+                tool: "macOS Dictation".to_string(),
+                guidance: "Fn is set to start Dictation. Open System Settings → Keyboard and set \"Press 🌐 key to\" to \"Do Nothing\".".to_string(),
+            });
+        } else if read_default(HITOOLBOX_DOMAIN, DICTATION_ENABLED_KEY).as_deref() == Some("1") {
+            conflicts.push(DictationConflict {
+                tool: "macOS Dictation".to_string(),
+                guidance: "Dictation is enabled. Open System Settings → Keyboard → Dictation and make sure its shortcut is not \"Press 🌐 Twice\".".to_string(),
+            });
+        }
+
+        for name in running_app_names() {
+            let lower = name.to_lowercase();
+            if let Some((_, tool)) = KNOWN_TOOLS
+                .iter()
+                .find(|(fragment, _)| lower.contains(fragment))
+            {
+                if conflicts.iter().any(|c| c.tool == *tool) {
+                    continue;
+                }
+                conflicts.push(DictationConflict {
+                    tool: tool.to_string(),
+                    guidance: format!(
+                        "{} is running. Change its shortcut away from Fn or quit it while using Dictara.",
+                        tool
+                    ),
+                });
+            }
+        }
+
+        conflicts
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        Vec::new()
+    }
+}
+
+/// Current "Press 🌐 key to" setting
+pub fn fn_key_usage() -> FnKeyUsage {
+    #[cfg(target_os = "macos")]
+    {
+        read_default(HITOOLBOX_DOMAIN, FN_USAGE_KEY)
+            .map(|value| FnKeyUsage::from_default(&value))
+            .unwrap_or(FnKeyUsage::Unknown)
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        FnKeyUsage::Unknown
+    }
+}
+
+/// Set "Press 🌐 key to" to "Do Nothing" so Fn no longer opens the emoji picker
+/// or starts Dictation. Only called after the user agreed, in Preferences or
+/// the setup dialog; macOS picks the change up after the next login.
+pub fn disable_fn_key_action() -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        let status = std::process::Command::new("defaults")
+            .args(["write", HITOOLBOX_DOMAIN, FN_USAGE_KEY, "-int", "0"])
+            .status()
+            .map_err(|e| format!("Failed to run defaults: {}", e))?;
+
+        if !status.success() {
+            return Err(format!("defaults write exited with {}", status));
+        }
+
+        println!("[Conflicts] Set {} to Do Nothing", FN_USAGE_KEY);
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        Ok(())
+    }
+}
+
+/// Ask once whether to set "Press 🌐 key to" to "Do Nothing", when Fn is the
+/// trigger and also opens the emoji picker or starts Dictation
+pub fn offer_fn_key_fix(app_handle: &tauri::AppHandle) {
+    let action = match fn_key_usage() {
+        FnKeyUsage::ShowEmoji => "opens the emoji picker",
+        FnKeyUsage::StartDictation => "starts macOS Dictation",
+        _ => return,
+    };
+    let store = match app_handle.store("config.json") {
+        Ok(store) => store,
+        Err(e) => {
+            eprintln!("[Conflicts] Failed to open config store: {}", e);
+            return;
+        }
+    };
+    if store.get(FN_KEY_OFFERED_KEY).is_some()
+        || config::load_app_config(&store).trigger_key != TriggerKey::Fn
+    {
+        return;
+    }
+
+    app_handle
+        .dialog()
+        .message(format!(
+            "Holding Fn to dictate also {}, because of \"Press 🌐 key to\" in System Settings → Keyboard.\n\nSet it to \"Do Nothing\"? The change applies after you log out and back in.",
+            action
+        ))
+        .title("Change Fn key setting?")
+        .kind(MessageDialogKind::Info)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Set Fn to Do Nothing".to_string(),
+            "Not Now".to_string(),
+        ))
+        .show(move |accepted| {
+            store.set(FN_KEY_OFFERED_KEY, true);
+            if let Err(e) = store.save() {
+                eprintln!("[Conflicts] Failed to save Fn key answer: {}", e);
+            }
+            if !accepted {
+                println!("[Conflicts] User kept the Fn key setting");
+                return;
+            }
+            if let Err(e) = disable_fn_key_action() {
+                eprintln!("[Conflicts] Failed to change Fn key setting: {}", e);
+            }
+        });
+}
+
+/// Log detected conflicts at startup
+pub fn log_conflicts() {
+    for conflict in detect_conflicts() {
+        println!(
+            "⚠️  Possible Fn key conflict with {}: {}",
+            conflict.tool, conflict.guidance
+        );
+    }
+}
+
+/// Read a value with `defaults read`, `None` if the key doesn't exist
+#[cfg(target_os = "macos")]
+pub(crate) fn read_default(domain: &str, key: &str) -> Option<String> {
+    let output = std::process::Command::new("defaults")
+        .args(["read", domain, key])
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn running_app_names() -> Vec<String> {
+    use objc2_app_kit::NSWorkspace;
+
+    NSWorkspace::sharedWorkspace()
+        .runningApplications()
+        .iter()
+        .filter_map(|app| app.localizedName())
+        .map(|name| name.to_string())
+        .collect()
+}
@@ -0,0 +1,89 @@
+//! Transcription of an audio file copied in Finder.
+//!
+//! "Transcribe Copied Audio File" in the menu bar (or
+//! `transcribe_copied_file`) reads the file URL that copying in Finder put on
+//! the pasteboard, transcribes the file with the active provider and the
+//! transcript pipeline, and leaves the text on the clipboard in its place. It's
+//! the quick path for a one-off file; folders go through batch transcription.
+//! The done or error cue tells how it went.
+
+use crate::batch;
+use crate::clients::openai::OpenAIClient;
+use crate::config;
+use crate::output::{self, OutputSinkConfig};
+use crate::pipeline;
+use crate::recording::wav_duration_ms;
+use crate::sound_player::{self, Sound};
+use std::path::PathBuf;
+use tauri_plugin_store::StoreExt;
+
+/// Transcribe the copied audio file and put the transcript on the clipboard.
+/// Blocks until the provider answers.
+pub fn transcribe(app_handle: &tauri::AppHandle) -> Result<String, String> {
+    let result = transcribe_to_clipboard(app_handle);
+    match &result {
+        Ok(text) => {
+            println!("[Copied File] Copied {} chars", text.chars().count());
+            sound_player::play_cue(app_handle, Sound::Done);
+        }
+        Err(e) => {
+            eprintln!("[Copied File] {}", e);
+            sound_player::play_cue(app_handle, Sound::Error);
+        }
+    }
+    result
+}
+
+fn transcribe_to_clipboard(app_handle: &tauri::AppHandle) -> Result<String, String> {
+    let audio = copied_file().ok_or_else(|| "No file on the clipboard".to_string())?;
+    if !batch::is_supported(&audio) {
+        return Err(format!("{:?} isn't an audio file", audio));
+    }
+
+    let store = app_handle
+        .store("config.json")
+        .map_err(|e| format!("Failed to load config: {}", e))?;
+    let config = config::load_app_config(&store);
+
+    println!("[Copied File] Transcribing {:?}", audio);
+    let duration_ms = wav_duration_ms(&audio);
+    let text = OpenAIClient::new()
+        .transcribe_audio_sync(audio, duration_ms, &config)
+        .map(|text| pipeline::run(text, &config))
+        .map_err(|e| e.user_message())?;
+    if text.is_empty() {
+        return Err("No speech detected".to_string());
+    }
+
+    if let Some(failure) = output::deliver(&[OutputSinkConfig::Clipboard], &text).first() {
+        return Err(failure.to_string());
+    }
+    Ok(text)
+}
+
+/// The first file on the general pasteboard
+#[cfg(target_os = "macos")]
+fn copied_file() -> Option<PathBuf> {
+    use objc2::msg_send;
+    use objc2::rc::Retained;
+    use objc2::runtime::{AnyClass, AnyObject};
+    use objc2_app_kit::{NSPasteboard, NSPasteboardTypeFileURL};
+    use objc2_foundation::NSString;
+
+    let pasteboard = NSPasteboard::generalPasteboard();
+    let url = pasteboard.stringForType(unsafe { NSPasteboardTypeFileURL })?;
+    let url_class = AnyClass::get(c"NSURL")?;
+    // Finder copies file reference URLs (file:///.file/id=…), which
+    // filePathURL turns into a regular path
+    let path: Option<Retained<NSString>> = unsafe {
+        let url: Option<Retained<AnyObject>> = msg_send![url_class, URLWithString: &*url];
+        let file_url: Option<Retained<AnyObject>> = msg_send![&*url?, filePathURL];
+        msg_send![&*file_url?, path]
+    };
+    Some(PathBuf::from(path?.to_string()))
+}
+
+#[cfg(not(target_os = "macos"))]
+fn copied_file() -> Option<PathBuf> {
+    None
+}
@@ -0,0 +1,93 @@
+//! System display accessibility options.
+//!
+//! Mirrors Reduce motion and Increase contrast from System Settings →
+//! Accessibility → Display. The frontend reads them with
+//! `get_display_options` and follows changes through the `display-options`
+//! event, so popup transitions and colors can adapt. On the backend, the tray
+//! skips its transcribing animation while reduce motion is on.
+
+use crate::events::DisplayOptions;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static REDUCE_MOTION: AtomicBool = AtomicBool::new(false);
+
+/// Whether backend-driven animations should be skipped
+pub fn reduce_motion() -> bool {
+    REDUCE_MOTION.load(Ordering::Relaxed)
+}
+
+/// Read the options now
+pub fn current() -> DisplayOptions {
+    let options = read_options();
+    REDUCE_MOTION.store(options.reduce_motion, Ordering::Relaxed);
+    options
+}
+
+#[cfg(target_os = "macos")]
+fn read_options() -> DisplayOptions {
+    use objc2_app_kit::NSWorkspace;
+
+    let workspace = NSWorkspace::sharedWorkspace();
+    DisplayOptions {
+        reduce_motion: workspace.accessibilityDisplayShouldReduceMotion(),
+        increase_contrast: workspace.accessibilityDisplayShouldIncreaseContrast(),
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn read_options() -> DisplayOptions {
+    DisplayOptions {
+        reduce_motion: false,
+        increase_contrast: false,
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn emit_changed(app_handle: &tauri::AppHandle) {
+    let options = current();
+    println!("[Display Options] Changed: {:?}", options);
+    crate::events::emit_or_log(app_handle, options);
+}
+
+/// Read the options and emit `display-options` whenever the user changes them
+#[cfg(target_os = "macos")]
+pub fn start_watcher(app_handle: tauri::AppHandle) {
+    use block2::RcBlock;
+    use objc2::msg_send;
+    use objc2::rc::Retained;
+    use objc2::runtime::{AnyClass, AnyObject};
+    use objc2_app_kit::NSWorkspace;
+    use objc2_foundation::NSString;
+
+    #[link(name = "AppKit", kind = "framework")]
+    extern "C" {
+        static NSWorkspaceAccessibilityDisplayOptionsDidChangeNotification: &'static NSString;
+    }
+
+    println!("[Display Options] Initial: {:?}", current());
+
+    let Some(queue_class) = AnyClass::get(c"NSOperationQueue") else {
+        eprintln!("[Display Options] NSOperationQueue is unavailable, not watching for changes");
+        return;
+    };
+
+    let block = RcBlock::new(move |_notification: *mut AnyObject| emit_changed(&app_handle));
+    let center = NSWorkspace::sharedWorkspace().notificationCenter();
+    unsafe {
+        let queue: Retained<AnyObject> = msg_send![queue_class, mainQueue];
+        let observer: Retained<AnyObject> = msg_send![
+            &*center,
+            addObserverForName: NSWorkspaceAccessibilityDisplayOptionsDidChangeNotification,
+            object: std::ptr::null::<AnyObject>(),
+            queue: &*queue,
+            usingBlock: &*block
+        ];
+        // Observe for the lifetime of the app
+        std::mem::forget(observer);
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn start_watcher(_app_handle: tauri::AppHandle) {
+    current();
+}
@@ -0,0 +1,122 @@
+//! Energy accounting and the low-power profile.
+//!
+//! The counters are plain atomics so the hot paths (the keyboard event tap and
+//! the audio callback) can record activity without locking or allocating.
+//! `get_energy_stats` exposes them to help diagnose "high energy" reports.
+
+use crate::config::EnergyProfile;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// Background timers wake this many times less often in low-power mode
+const LOW_POWER_TIMER_FACTOR: u32 = 4;
+
+static PROFILE: AtomicU8 = AtomicU8::new(0);
+static POPUP_VISIBLE: AtomicBool = AtomicBool::new(false);
+
+static TAP_EVENTS: AtomicU64 = AtomicU64::new(0);
+static LEVEL_SENDS: AtomicU64 = AtomicU64::new(0);
+static LEVEL_SENDS_SKIPPED: AtomicU64 = AtomicU64::new(0);
+static TIMER_WAKEUPS: AtomicU64 = AtomicU64::new(0);
+
+fn started_at() -> Instant {
+    static STARTED: OnceLock<Instant> = OnceLock::new();
+    *STARTED.get_or_init(Instant::now)
+}
+
+/// Snapshot of energy-related activity since launch
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct EnergyStats {
+    pub profile: EnergyProfile,
+    pub uptime_secs: u32,
+    /// Keyboard events that went through the event tap
+    pub tap_events: u32,
+    /// Audio level messages sent to the popup
+    pub level_sends: u32,
+    /// Audio buffers whose level wasn't computed because the popup was hidden
+    pub level_sends_skipped: u32,
+    /// Wakeups of background polling timers (permissions, updater)
+    pub timer_wakeups: u32,
+}
+
+/// Apply the configured profile; takes effect on each timer's next wakeup
+pub fn set_profile(profile: EnergyProfile) {
+    let value = match profile {
+        EnergyProfile::Balanced => 0,
+        EnergyProfile::LowPower => 1,
+    };
+    if PROFILE.swap(value, Ordering::Relaxed) != value {
+        println!("[Energy] Profile set to {:?}", profile);
+    }
+}
+
+pub fn profile() -> EnergyProfile {
+    match PROFILE.load(Ordering::Relaxed) {
+        1 => EnergyProfile::LowPower,
+        _ => EnergyProfile::Balanced,
+    }
+}
+
+pub fn is_low_power() -> bool {
+    profile() == EnergyProfile::LowPower
+}
+
+/// Interval for a background timer under the current profile.
+/// Also counts the wakeup, so call it once per timer iteration.
+pub fn timer_interval(base: Duration) -> Duration {
+    TIMER_WAKEUPS.fetch_add(1, Ordering::Relaxed);
+    if is_low_power() {
+        base * LOW_POWER_TIMER_FACTOR
+    } else {
+        base
+    }
+}
+
+/// Track whether the recording popup is on screen
+pub fn set_popup_visible(visible: bool) {
+    POPUP_VISIBLE.store(visible, Ordering::Relaxed);
+}
+
+/// Whether audio levels are worth computing. Nobody sees the meter while the
+/// popup is hidden, so those buffers are skipped.
+pub fn level_meter_visible() -> bool {
+    let visible = POPUP_VISIBLE.load(Ordering::Relaxed);
+    if !visible {
+        LEVEL_SENDS_SKIPPED.fetch_add(1, Ordering::Relaxed);
+    }
+    visible
+}
+
+/// Count an audio level message sent to the popup
+pub fn record_level_send() {
+    LEVEL_SENDS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Count an event seen by the keyboard tap
+pub fn record_tap_event() {
+    TAP_EVENTS.fetch_add(1, Ordering::Relaxed);
+}
+
+fn saturating_u32(value: u64) -> u32 {
+    value.min(u32::MAX as u64) as u32
+}
+
+pub fn stats() -> EnergyStats {
+    EnergyStats {
+        profile: profile(),
+        uptime_secs: saturating_u32(started_at().elapsed().as_secs()),
+        tap_events: saturating_u32(TAP_EVENTS.load(Ordering::Relaxed)),
+        level_sends: saturating_u32(LEVEL_SENDS.load(Ordering::Relaxed)),
+        level_sends_skipped: saturating_u32(LEVEL_SENDS_SKIPPED.load(Ordering::Relaxed)),
+        timer_wakeups: saturating_u32(TIMER_WAKEUPS.load(Ordering::Relaxed)),
+    }
+}
+
+/// Start the uptime clock. Called once from setup.
+pub fn init(profile: EnergyProfile) {
+    started_at();
+    set_profile(profile);
+}
@@ -0,0 +1,434 @@
+//! Catalog of every event the backend emits.
+//!
+//! All payload types live here and are registered with tauri-specta in
+//! `lib.rs`, so the frontend gets matching types and names in `bindings.ts`.
+//! The event name is the kebab-case type name, available as
+//! `<T as tauri_specta::Event>::NAME`. Emit through [`emit_or_log`] when a
+//! failure should only be logged.
+
+use crate::batch::BatchFileOutcome;
+use crate::config::Provider;
+use serde::{Deserialize, Serialize};
+use tauri_specta::Event;
+
+/// Emit an event, logging a failure instead of returning it
+pub fn emit_or_log<E>(app_handle: &tauri::AppHandle, event: E)
+where
+    E: Event + Serialize + Clone,
+{
+    if let Err(e) = event.emit(app_handle) {
+        eprintln!("[Events] Failed to emit {} event: {}", E::NAME, e);
+    }
+}
+
+// ===== RECORDING =====
+
+/// Recording state change event - single event stream for all state transitions
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type, tauri_specta::Event)]
+#[serde(tag = "state", rename_all = "camelCase")]
+pub enum RecordingStateChanged {
+    /// Recording has started
+    #[serde(rename = "started")]
+    Started {
+        /// Name of the input device, e.g. "AirPods Pro"
+        #[serde(rename = "deviceName")]
+        device_name: String,
+        /// Native sample rate of the device in Hz
+        #[serde(rename = "sampleRate")]
+        sample_rate: u32,
+    },
+    /// Recording is being transcribed
+    #[serde(rename = "transcribing")]
+    Transcribing,
+    /// Recording completed successfully
+    #[serde(rename = "stopped")]
+    Stopped {
+        /// The transcribed text
+        text: String,
+    },
+    /// Transcription finished but the provider returned no text
+    #[serde(rename = "noSpeech")]
+    NoSpeech,
+    /// Recording was cancelled by user
+    #[serde(rename = "cancelled")]
+    Cancelled,
+    /// The transcript is longer than `max_delivery_chars` and waits for
+    /// `deliver_held_transcript` or `discard_held_transcript`
+    #[serde(rename = "held")]
+    Held {
+        /// Length of the transcript in characters
+        chars: u32,
+        #[serde(rename = "maxChars")]
+        max_chars: u32,
+        /// Start of the transcript, so the user can judge it
+        preview: String,
+    },
+    /// An error occurred during recording or transcription
+    #[serde(rename = "error")]
+    Error {
+        /// Type of error: "recording" | "transcription"
+        #[serde(rename = "errorType")]
+        error_type: String,
+        /// Technical error message for debugging
+        #[serde(rename = "errorMessage")]
+        error_message: String,
+        /// User-friendly error message
+        #[serde(rename = "userMessage")]
+        user_message: String,
+        /// Path to audio file (for retry functionality)
+        #[serde(rename = "audioFilePath")]
+        audio_file_path: Option<String>,
+    },
+}
+
+/// Space locked the recording for hands-free dictation; it now runs until
+/// stopped
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type, tauri_specta::Event)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingLocked {
+    /// How long the recording had been running when it was locked
+    pub elapsed_ms: u32,
+}
+
+/// Which popup buttons apply, sent whenever the controller state changes
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, specta::Type, tauri_specta::Event)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingControls {
+    /// `stop_recording` would stop a recording
+    pub can_stop: bool,
+    /// `cancel_recording` would discard a recording
+    pub can_cancel: bool,
+    /// Releasing the trigger no longer stops the recording, only Stop does
+    pub locked: bool,
+}
+
+/// Auto-paste failed after a successful transcription
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type, tauri_specta::Event)]
+#[serde(rename_all = "camelCase")]
+pub struct PasteFailed {
+    /// The transcribed text that could not be pasted
+    pub text: String,
+    /// Technical error message for debugging
+    pub reason: String,
+    /// User-friendly, actionable error message
+    pub user_message: String,
+    /// Whether the text was left on the clipboard for a manual paste
+    pub text_on_clipboard: bool,
+}
+
+/// A known reason for a dictation not ending up in the target app
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum PasteProblem {
+    SecureInput,
+    EmptyTranscript,
+    TargetAppChanged,
+    AccessibilityRevoked,
+    ClipboardLocked,
+}
+
+/// How sure the analyzer is about a problem, most likely first
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, specta::Type,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum Likelihood {
+    /// The error or the system state points straight at it
+    Likely,
+    /// It's present but may not be what stopped the paste
+    Possible,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PasteExplanation {
+    pub problem: PasteProblem,
+    pub likelihood: Likelihood,
+    /// What went wrong, in the user's terms
+    pub explanation: String,
+    /// What the user can do about it
+    pub fix: String,
+}
+
+/// Why a paste failed or a dictation came back empty, sent after
+/// `paste-failed` or the no-speech notice
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type, tauri_specta::Event)]
+#[serde(rename_all = "camelCase")]
+pub struct PasteDiagnosed {
+    /// Most likely cause first; empty when none of the known causes apply
+    pub explanations: Vec<PasteExplanation>,
+}
+
+// ===== PROVIDERS =====
+
+/// A provider's key stopped working, or works again
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type, tauri_specta::Event)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderAuthChanged {
+    pub provider: Provider,
+    /// The key was accepted before and is now rejected; re-enter it
+    pub needs_reauth: bool,
+}
+
+// ===== BATCH =====
+
+/// A file of the running batch transcription finished
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type, tauri_specta::Event)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchProgress {
+    /// Path of the audio file
+    pub file: String,
+    pub outcome: BatchFileOutcome,
+    /// Files finished so far, including this one
+    pub completed: u32,
+    pub total: u32,
+}
+
+/// A file dropped into the watch folder was transcribed
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type, tauri_specta::Event)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchFolderTranscribed {
+    /// Path of the audio file
+    pub file: String,
+    pub outcome: BatchFileOutcome,
+}
+
+/// A memo pushed to the local network intake was transcribed
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type, tauri_specta::Event)]
+#[serde(rename_all = "camelCase")]
+pub struct IntakeTranscribed {
+    /// Path of the saved audio file
+    pub file: String,
+    pub outcome: BatchFileOutcome,
+}
+
+/// The batch transcription finished
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type, tauri_specta::Event)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchFinished {
+    pub transcribed: u32,
+    pub skipped: u32,
+    pub failed: u32,
+}
+
+// ===== DICTATION =====
+
+/// How a dictation was recorded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum DictationMode {
+    /// Trigger held for the whole recording
+    PushToTalk,
+    /// Recording locked with Space while holding the trigger
+    HandsFree,
+    /// Saved recording transcribed again after a failure
+    Retry,
+}
+
+/// Summary of a successful dictation, emitted once it has been delivered.
+/// History and stats record the same values.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type, tauri_specta::Event)]
+#[serde(rename_all = "camelCase")]
+pub struct DictationCompleted {
+    /// Local time in RFC 3339 format
+    pub timestamp: String,
+    /// Length of the recording
+    pub duration_ms: u32,
+    /// Words in the transcript after post-processing
+    pub words: u32,
+    pub provider: Option<Provider>,
+    /// Time the provider took to return the transcript
+    pub latency_ms: u32,
+    pub mode: DictationMode,
+    /// App that was in front while dictating
+    pub app: Option<String>,
+    /// Tags this dictation's `[Trace]` log lines
+    pub trace_id: u32,
+    /// Time spent in each stage, with `show_timing_breakdown` on
+    pub timing: Option<Vec<StageTiming>>,
+}
+
+/// Stage of a dictation, from releasing the trigger to delivery
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum TraceStage {
+    /// Stopping the audio stream
+    CaptureStop,
+    /// Writing out the WAV file
+    FileFinalize,
+    /// Sending the audio to the provider
+    Upload,
+    /// Waiting for the provider's response
+    Provider,
+    /// Pipeline steps and summary
+    PostProcess,
+    /// Paste and the other output sinks
+    Delivery,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct StageTiming {
+    pub stage: TraceStage,
+    pub ms: u32,
+}
+
+// ===== ONBOARDING =====
+
+/// Onboarding step practised with the real trigger key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum OnboardingStep {
+    /// Hold Fn while speaking, release to transcribe
+    FnHold,
+    /// Press Space while holding Fn to lock the recording
+    FnSpace,
+}
+
+/// Transcript of a practice dictation. It's only sent to onboarding: never
+/// pasted, and not kept in history or stats.
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type, tauri_specta::Event)]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardingPracticeTranscript {
+    pub step: OnboardingStep,
+    pub text: String,
+    pub mode: DictationMode,
+}
+
+/// The user did what the practised step asks: a push-to-talk dictation for
+/// `fnHold`, locking the recording for `fnSpace`
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type, tauri_specta::Event)]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardingStepCompleted {
+    pub step: OnboardingStep,
+}
+
+// ===== HISTORY =====
+
+/// History was added to or cleared
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type, tauri_specta::Event)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryChanged {
+    pub entries: u32,
+}
+
+// ===== STATS =====
+
+/// Summary for the gamification surface, emitted after each dictation
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type, tauri_specta::Event)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsUpdated {
+    pub total_dictations: u32,
+    pub total_words: u32,
+    pub words_today: u32,
+    /// Consecutive days with at least one dictation, ending today (or yesterday)
+    pub current_streak_days: u32,
+    pub longest_streak_days: u32,
+}
+
+// ===== PERMISSIONS =====
+
+/// State of a single privacy permission
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum PermissionState {
+    Granted,
+    Denied,
+    /// The user hasn't been asked yet
+    NotDetermined,
+}
+
+/// Current status of all permissions, emitted periodically
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type, tauri_specta::Event,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionStatus {
+    pub accessibility: PermissionState,
+    pub microphone: PermissionState,
+    pub input_monitoring: PermissionState,
+}
+
+// ===== DISPLAY =====
+
+/// System display accessibility options, emitted when the user changes them
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type, tauri_specta::Event,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct DisplayOptions {
+    /// System Settings → Accessibility → Display → Reduce motion
+    pub reduce_motion: bool,
+    /// System Settings → Accessibility → Display → Increase contrast
+    pub increase_contrast: bool,
+}
+
+// ===== TRAY =====
+
+/// A dynamic part of the tray menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum MenuSection {
+    /// "Paste Last Recording" and "Read Back Last Recording"
+    LastRecording,
+    /// "Re-enter API Key…"
+    Reauth,
+    /// "Input Capture Not Working…"
+    InputCapture,
+}
+
+/// The tray menu was rebuilt because these sections changed
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type, tauri_specta::Event)]
+#[serde(rename_all = "camelCase")]
+pub struct TrayMenuChanged {
+    pub sections: Vec<MenuSection>,
+}
+
+// ===== SUPERVISOR =====
+
+/// Lifecycle state of a supervised background component
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum ComponentStatus {
+    Running,
+    /// Crashed and waiting for the next restart attempt
+    Restarting,
+    /// Exited normally (e.g. on shutdown)
+    Stopped,
+    /// Gave up after too many restarts - the component is degraded
+    Failed,
+}
+
+/// Health of a supervised component, emitted whenever its status changes
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type, tauri_specta::Event)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentHealth {
+    /// Component name, e.g. "controller" or "key_listener"
+    pub name: String,
+    pub status: ComponentStatus,
+    pub restarts: u32,
+    pub last_error: Option<String>,
+}
+
+// ===== INPUT =====
+
+/// The key listener fell back to rdev because the event tap couldn't be created
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type, tauri_specta::Event)]
+#[serde(rename_all = "camelCase")]
+pub struct InputDegraded {
+    /// What works differently now, for the notice
+    pub limitations: Vec<String>,
+}
+
+/// Whether an event tap can be created, checked at launch and whenever a
+/// permission changes
+#[derive(
+    Debug, Clone, PartialEq, Eq, Serialize, Deserialize, specta::Type, tauri_specta::Event,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct InputCaptureStatus {
+    /// The trigger key will be picked up
+    pub functional: bool,
+    /// Why the event tap couldn't be created
+    pub error: Option<String>,
+}
@@ -0,0 +1,223 @@
+//! Local filler word removal.
+//!
+//! The `remove_filler_words` pipeline step drops hesitations like "um" and
+//! "you know" without a model call, so it works offline and with every
+//! provider. Words are matched whole and case-insensitively: "um" never
+//! touches "umbrella". Commas around a removed filler go with it, sentence
+//! punctuation moves to the previous word, and a capital at the start of a
+//! sentence carries over to the next word.
+//!
+//! Lists come from `filler_words.languages` (built in) plus
+//! `filler_words.custom`.
+
+use crate::config::FillerWordsConfig;
+use crate::pipeline::{PipelineContext, TranscriptMiddleware};
+
+/// Built-in fillers by language code. Only hesitations that are almost never
+/// meant literally are listed; ambiguous ones like "like" are left to `custom`.
+const BUILT_IN: &[(&str, &[&str])] = &[
+    (
+        "en",
+        &[
+            "um", "umm", "uh", "uhh", "uhm", "erm", "hmm", "mm", "you know",
+        ],
+    ),
+    ("de", &["äh", "ähm", "öh", "öhm", "hm", "hmm"]),
+    ("fr", &["euh", "heu", "bah", "hum"]),
+    ("es", &["eh", "em", "ehm", "mmm"]),
+];
+
+/// Fillers for the configured languages and custom entries, each split into
+/// lowercase words, longest phrases first
+fn fillers(config: &FillerWordsConfig) -> Vec<Vec<String>> {
+    let built_in = BUILT_IN
+        .iter()
+        .filter(|(language, _)| config.languages.iter().any(|l| l == language))
+        .flat_map(|(_, words)| words.iter().copied());
+    let custom = config.custom.iter().map(String::as_str);
+
+    let mut fillers: Vec<Vec<String>> = built_in
+        .chain(custom)
+        .map(|filler| filler.split_whitespace().map(str::to_lowercase).collect())
+        .filter(|words: &Vec<String>| !words.is_empty())
+        .collect();
+    fillers.sort_by_key(|words| std::cmp::Reverse(words.len()));
+    fillers
+}
+
+/// Split a token into leading punctuation, the word, and trailing punctuation
+fn split_token(token: &str) -> (&str, &str, &str) {
+    let start = token
+        .find(|c: char| c.is_alphanumeric())
+        .unwrap_or(token.len());
+    let end = token
+        .rfind(|c: char| c.is_alphanumeric())
+        .map(|i| i + token[i..].chars().next().map_or(1, char::len_utf8))
+        .unwrap_or(start);
+    (&token[..start], &token[start..end], &token[end..])
+}
+
+fn ends_sentence(token: &str) -> bool {
+    token.ends_with(['.', '!', '?'])
+}
+
+fn capitalize(token: &str) -> String {
+    let (leading, word, trailing) = split_token(token);
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => format!(
+            "{}{}{}{}",
+            leading,
+            first.to_uppercase(),
+            chars.as_str(),
+            trailing
+        ),
+        None => token.to_string(),
+    }
+}
+
+/// Length in tokens of the filler starting at `tokens[0]`, if any
+fn match_filler(tokens: &[&str], fillers: &[Vec<String>]) -> Option<usize> {
+    fillers
+        .iter()
+        .find(|filler| {
+            filler.len() <= tokens.len()
+                && filler
+                    .iter()
+                    .zip(tokens)
+                    .enumerate()
+                    .all(|(i, (word, token))| {
+                        let (leading, core, trailing) = split_token(token);
+                        // Punctuation inside a phrase ("you, know") means it isn't one
+                        let inner_ok = (i == 0 || leading.is_empty())
+                            && (i + 1 == filler.len() || trailing.is_empty());
+                        inner_ok && core.to_lowercase() == *word
+                    })
+        })
+        .map(Vec::len)
+}
+
+fn remove_from_line(line: &str, fillers: &[Vec<String>]) -> String {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let mut kept: Vec<String> = Vec::with_capacity(tokens.len());
+    let mut capitalize_next = false;
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let Some(len) = match_filler(&tokens[i..], fillers) else {
+            let token = tokens[i];
+            kept.push(if capitalize_next {
+                capitalize(token)
+            } else {
+                token.to_string()
+            });
+            capitalize_next = false;
+            i += 1;
+            continue;
+        };
+
+        let (_, first_word, _) = split_token(tokens[i]);
+        let (_, _, trailing) = split_token(tokens[i + len - 1]);
+        let at_sentence_start = kept.last().is_none_or(|token| ends_sentence(token));
+        if at_sentence_start && first_word.starts_with(char::is_uppercase) {
+            capitalize_next = true;
+        }
+
+        // "Fine, um." keeps its full stop on "Fine"
+        let sentence_end: String = trailing
+            .chars()
+            .filter(|c| matches!(c, '.' | '!' | '?'))
+            .collect();
+        if let Some(previous) = kept.last_mut() {
+            if !sentence_end.is_empty() && !ends_sentence(previous) {
+                let trimmed = previous.trim_end_matches([',', ';', ':']).to_string();
+                *previous = trimmed + &sentence_end;
+            } else if trailing.contains(',') && previous.ends_with(',') {
+                // "we should, you know, go" reads "we should go"
+                previous.pop();
+            }
+        }
+
+        i += len;
+    }
+
+    kept.join(" ")
+}
+
+/// Remove the configured fillers from `text`, keeping line breaks
+pub fn remove_fillers(text: &str, config: &FillerWordsConfig) -> String {
+    let fillers = fillers(config);
+    if fillers.is_empty() {
+        return text.to_string();
+    }
+
+    text.split('\n')
+        .map(|line| remove_from_line(line, &fillers))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Pipeline step wrapping [`remove_fillers`]; off until enabled in the pipeline
+pub struct RemoveFillerWords;
+
+impl TranscriptMiddleware for RemoveFillerWords {
+    fn id(&self) -> String {
+        "remove_filler_words".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Remove filler words like \"um\" and \"you know\"".to_string()
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        false
+    }
+
+    fn process(&self, text: String, ctx: &PipelineContext) -> String {
+        remove_fillers(&text, &ctx.config.filler_words)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn english() -> FillerWordsConfig {
+        FillerWordsConfig {
+            languages: vec!["en".to_string()],
+            custom: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_removes_whole_words_only() {
+        let config = english();
+        assert_eq!(
+            remove_fillers("I think uh we should, you know, bring an umbrella", &config),
+            "I think we should bring an umbrella"
+        );
+        assert_eq!(remove_fillers("So, um, yes", &config), "So yes");
+        assert_eq!(
+            remove_fillers("Um, let's start. Uh so the plan", &config),
+            "Let's start. So the plan"
+        );
+        assert_eq!(remove_fillers("That's fine, um.", &config), "That's fine.");
+        // Punctuation inside a phrase means it's not the filler
+        assert_eq!(
+            remove_fillers("Did you, know him?", &config),
+            "Did you, know him?"
+        );
+    }
+
+    #[test]
+    fn test_languages_and_custom_fillers() {
+        let config = FillerWordsConfig {
+            languages: vec!["de".to_string()],
+            custom: vec!["sort of".to_string()],
+        };
+        assert_eq!(
+            remove_fillers("Ähm, das ist sort of gut.\nUm ok", &config),
+            "Das ist gut.\nUm ok"
+        );
+    }
+}
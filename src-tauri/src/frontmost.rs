@@ -0,0 +1,33 @@
+//! The app in front of Dictara, i.e. the one being dictated into. The
+//! recording popup never takes focus, so this is the target app throughout a
+//! session.
+
+#[cfg(target_os = "macos")]
+pub fn app_name() -> Option<String> {
+    use objc2_app_kit::NSWorkspace;
+
+    NSWorkspace::sharedWorkspace()
+        .frontmostApplication()?
+        .localizedName()
+        .map(|name| name.to_string())
+}
+
+#[cfg(target_os = "macos")]
+pub fn bundle_id() -> Option<String> {
+    use objc2_app_kit::NSWorkspace;
+
+    NSWorkspace::sharedWorkspace()
+        .frontmostApplication()?
+        .bundleIdentifier()
+        .map(|id| id.to_string())
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn app_name() -> Option<String> {
+    None
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn bundle_id() -> Option<String> {
+    None
+}
@@ -0,0 +1,240 @@
+//! Transcript history.
+//!
+//! Each delivered dictation is kept in `history.json`, newest first, up to
+//! `history.max_entries`. Entries hold the transcript after the pipeline and,
+//! for summarized recordings, the summary and action items as well. With
+//! `calendar.tag_history`, they're tagged with the meeting in progress.
+//! `export_history` writes them out as Markdown files named with
+//! `export_templates`.
+//!
+//! Every change emits `HistoryChanged`, which keeps "Paste Last Recording"
+//! pointed at the newest entry, including after a restart.
+
+use crate::action_items::ActionItem;
+use crate::config::{AppConfig, Provider, SummaryPaste};
+use crate::events::{self, DictationCompleted, HistoryChanged};
+use crate::summary::{self, Summary};
+use crate::templates::{self, TemplateVars};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri_plugin_store::StoreExt;
+
+const HISTORY_STORE: &str = "history.json";
+const HISTORY_KEY: &str = "entries";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+pub struct HistoryEntry {
+    /// Local time in RFC 3339 format
+    pub timestamp: String,
+    pub transcript: String,
+    #[serde(default)]
+    pub summary: Option<String>,
+    /// Action items extracted with the summary
+    #[serde(default)]
+    pub action_items: Vec<ActionItem>,
+    /// Provider that transcribed the recording
+    #[serde(default)]
+    pub provider: Option<Provider>,
+    /// App that was in front while dictating
+    #[serde(default)]
+    pub app: Option<String>,
+    /// Title of the calendar meeting in progress while dictating
+    #[serde(default)]
+    pub meeting: Option<String>,
+    #[serde(default)]
+    pub duration_ms: u32,
+}
+
+impl HistoryEntry {
+    pub fn new(
+        transcript: &str,
+        summary: Option<&Summary>,
+        completed: &DictationCompleted,
+    ) -> Self {
+        Self {
+            timestamp: completed.timestamp.clone(),
+            transcript: transcript.to_string(),
+            summary: summary.map(|summary| summary.text.clone()),
+            action_items: summary
+                .map(|summary| summary.action_items.clone())
+                .unwrap_or_default(),
+            provider: completed.provider.clone(),
+            app: completed.app.clone(),
+            meeting: None,
+            duration_ms: completed.duration_ms,
+        }
+    }
+
+    fn template_vars(&self) -> TemplateVars {
+        let timestamp = DateTime::parse_from_rfc3339(&self.timestamp)
+            .map(|t| t.with_timezone(&Local))
+            .unwrap_or_else(|_| Local::now());
+        TemplateVars {
+            app: self.app.clone(),
+            ..TemplateVars::new(timestamp, &self.transcript)
+        }
+    }
+
+    /// The text that was pasted for this entry
+    fn delivered_text(&self, paste: SummaryPaste) -> String {
+        summary::delivered_text(&self.transcript, self.summary.as_deref(), paste)
+    }
+
+    /// Markdown document for `export_history`
+    fn to_markdown(&self, title: &str) -> String {
+        match &self.summary {
+            Some(summary) => format!(
+                "# {}\n\n## Summary\n\n{}\n\n## Transcript\n\n{}\n",
+                title, summary, self.transcript
+            ),
+            None => format!("# {}\n\n{}\n", title, self.transcript),
+        }
+    }
+}
+
+/// Put `entry` first and drop the oldest beyond `max_entries`
+fn push_entry(entries: &mut Vec<HistoryEntry>, entry: HistoryEntry, max_entries: u32) {
+    entries.insert(0, entry);
+    entries.truncate(max_entries as usize);
+}
+
+/// History entries, newest first
+pub fn load_history(app_handle: &tauri::AppHandle) -> Vec<HistoryEntry> {
+    app_handle
+        .store(HISTORY_STORE)
+        .ok()
+        .and_then(|store| store.get(HISTORY_KEY))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+fn save_history(app_handle: &tauri::AppHandle, entries: &[HistoryEntry]) -> Result<(), String> {
+    let store = app_handle
+        .store(HISTORY_STORE)
+        .map_err(|e| format!("Failed to open history store: {}", e))?;
+    let value =
+        serde_json::to_value(entries).map_err(|e| format!("Failed to serialize history: {}", e))?;
+    store.set(HISTORY_KEY, value);
+    store
+        .save()
+        .map_err(|e| format!("Failed to save history: {}", e))?;
+
+    events::emit_or_log(
+        app_handle,
+        HistoryChanged {
+            entries: entries.len() as u32,
+        },
+    );
+    Ok(())
+}
+
+/// What "Paste Last Recording" offers from history: the newest entry, as it
+/// was delivered. None when history is empty or disabled, so turning history
+/// off also stops older transcripts from being pasted.
+pub fn latest_paste_text(app_handle: &tauri::AppHandle, config: &AppConfig) -> Option<String> {
+    paste_text_at(app_handle, config, 0)
+}
+
+/// The text entry `index` (newest first) was delivered as, to paste it again.
+/// None when there's no such entry or history is disabled.
+pub fn paste_text_at(
+    app_handle: &tauri::AppHandle,
+    config: &AppConfig,
+    index: usize,
+) -> Option<String> {
+    if !config.history.enabled {
+        return None;
+    }
+    load_history(app_handle)
+        .get(index)
+        .map(|entry| entry.delivered_text(config.summary.paste))
+}
+
+/// Add a dictation to history, if enabled
+pub fn record(app_handle: &tauri::AppHandle, config: &AppConfig, entry: HistoryEntry) {
+    if !config.history.enabled {
+        return;
+    }
+
+    let mut entries = load_history(app_handle);
+    push_entry(&mut entries, entry, config.history.max_entries);
+    if let Err(e) = save_history(app_handle, &entries) {
+        eprintln!("[History] {}", e);
+    }
+}
+
+/// Write every entry to `folder` as a Markdown file. Returns how many were written.
+pub fn export(
+    app_handle: &tauri::AppHandle,
+    config: &AppConfig,
+    folder: &Path,
+) -> Result<u32, String> {
+    let entries = load_history(app_handle);
+    std::fs::create_dir_all(folder).map_err(|e| format!("Failed to create folder: {}", e))?;
+
+    let mut written = 0;
+    for entry in &entries {
+        let vars = entry.template_vars();
+        let title = templates::render(&config.export_templates.title, &vars);
+        let stem = templates::render_filename(&config.export_templates.filename, &vars);
+        let path = templates::unique_path(folder, &stem, "md");
+
+        std::fs::write(&path, entry.to_markdown(&title))
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        written += 1;
+    }
+
+    println!("[History] Exported {} entries to {:?}", written, folder);
+    Ok(written)
+}
+
+/// Remove all entries
+pub fn clear(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    save_history(app_handle, &[])?;
+    println!("[History] Cleared");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(transcript: &str) -> HistoryEntry {
+        HistoryEntry {
+            timestamp: String::new(),
+            transcript: transcript.to_string(),
+            summary: None,
+            action_items: Vec::new(),
+            provider: None,
+            app: None,
+            meeting: None,
+            duration_ms: 0,
+        }
+    }
+
+    #[test]
+    fn test_push_entry_keeps_newest_first_within_limit() {
+        let mut entries = Vec::new();
+        for text in ["one", "two", "three"] {
+            push_entry(&mut entries, entry(text), 2);
+        }
+        let transcripts: Vec<&str> = entries.iter().map(|e| e.transcript.as_str()).collect();
+        assert_eq!(transcripts, ["three", "two"]);
+    }
+
+    #[test]
+    fn test_markdown_includes_summary_when_present() {
+        let mut entry = entry("Full transcript");
+        assert_eq!(
+            entry.to_markdown("Standup"),
+            "# Standup\n\nFull transcript\n"
+        );
+
+        entry.summary = Some("Summary:\n- Done".to_string());
+        assert_eq!(
+            entry.to_markdown("Standup"),
+            "# Standup\n\n## Summary\n\nSummary:\n- Done\n\n## Transcript\n\nFull transcript\n"
+        );
+    }
+}
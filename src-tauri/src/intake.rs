@@ -0,0 +1,683 @@
+//! Local network intake for audio pushed from a phone.
+//!
+//! With `intake.enabled`, a small HTTP server listens on `intake.port` and is
+//! advertised over Bonjour as `_dictara._tcp`, so an iOS Shortcut can share a
+//! voice memo with `POST /transcribe`, the audio as the body and
+//! `Authorization: Bearer <token>`. `X-Filename` names the file; its extension
+//! must be one the provider accepts.
+//!
+//! The file is saved to `intake` in the app data folder and transcribed like a
+//! batch file, with its transcript written next to it. The outcome is sent
+//! back as the response, emitted as `intake-transcribed` and shown in a
+//! notification. A few uploads are received at a time, each within a size
+//! and time limit, and they're transcribed one after another; once a few are
+//! waiting for their turn, more are turned away.
+//!
+//! The server starts with the app, so changing the settings takes a restart.
+//! The token is generated the first time the intake starts and kept in the
+//! secret store with the API keys; `get_intake_token` shows it.
+
+use crate::batch::BatchFileOutcome;
+use crate::batch::{self, BatchOptions};
+use crate::clients::openai::{OpenAIClient, MAX_FILE_SIZE_BYTES};
+use crate::config::{self, AppConfig};
+use crate::events::{self, IntakeTranscribed};
+use crate::keychain;
+use crate::supervisor::{RestartPolicy, SupervisorState};
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, TrySendError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::Manager;
+use tauri_plugin_store::StoreExt;
+
+const MAX_HEADER_LINES: usize = 64;
+
+/// Longest header line, and all header lines together
+const MAX_HEADER_LINE_BYTES: u64 = 2 * 1024;
+const MAX_HEADER_BYTES: u64 = 16 * 1024;
+
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long a connection may take to send its request line and headers.
+/// Short, since the token isn't checked until they're in.
+const HEAD_DEADLINE: Duration = Duration::from_secs(10);
+
+/// How long an accepted connection may take to send its request, however
+/// steadily it trickles in
+const CONNECTION_DEADLINE: Duration = Duration::from_secs(5 * 60);
+
+/// Connections received at the same time; more are turned away
+const MAX_CONNECTIONS: usize = 4;
+
+/// Received uploads waiting for the transcription worker; more are turned away
+const MAX_WAITING_UPLOADS: usize = 4;
+
+const DEFAULT_FILENAME: &str = "memo.m4a";
+
+#[derive(Debug, PartialEq)]
+struct RequestHead {
+    method: String,
+    path: String,
+    /// Header names are lowercased
+    headers: Vec<(String, String)>,
+}
+
+impl RequestHead {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Why a request was turned away
+#[derive(Debug, PartialEq)]
+enum Rejection {
+    BadRequest,
+    Unauthorized,
+    NotFound,
+    LengthRequired,
+    TooLarge,
+    UnsupportedType,
+    /// Too many uploads are being received or waiting already
+    Busy,
+}
+
+impl Rejection {
+    fn status(&self) -> (u16, &'static str) {
+        match self {
+            Rejection::BadRequest => (400, "Bad Request"),
+            Rejection::Unauthorized => (401, "Unauthorized"),
+            Rejection::NotFound => (404, "Not Found"),
+            Rejection::LengthRequired => (411, "Length Required"),
+            Rejection::TooLarge => (413, "Payload Too Large"),
+            Rejection::UnsupportedType => (415, "Unsupported Media Type"),
+            Rejection::Busy => (503, "Service Unavailable"),
+        }
+    }
+}
+
+/// Parse the request line and headers, without the blank line ending them
+fn parse_head(lines: &[String]) -> Option<RequestHead> {
+    let (request_line, header_lines) = lines.split_first()?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next()?.to_string();
+    let path = parts.next()?.to_string();
+    parts
+        .next()
+        .filter(|version| version.starts_with("HTTP/"))?;
+
+    let headers = header_lines
+        .iter()
+        .map(|line| {
+            let (key, value) = line.split_once(':')?;
+            Some((key.trim().to_ascii_lowercase(), value.trim().to_string()))
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(RequestHead {
+        method,
+        path,
+        headers,
+    })
+}
+
+/// Check what can be checked before reading the body. Returns the body
+/// length and the name to save the audio under.
+fn accept(head: &RequestHead, token: &str) -> Result<(u64, String), Rejection> {
+    if head.method != "POST" || head.path != "/transcribe" {
+        return Err(Rejection::NotFound);
+    }
+    let presented = head
+        .header("authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .unwrap_or_default();
+    if !tokens_match(presented.trim(), token) {
+        return Err(Rejection::Unauthorized);
+    }
+
+    let length: u64 = head
+        .header("content-length")
+        .ok_or(Rejection::LengthRequired)?
+        .parse()
+        .map_err(|_| Rejection::BadRequest)?;
+    if length == 0 {
+        return Err(Rejection::BadRequest);
+    }
+    if length > MAX_FILE_SIZE_BYTES {
+        return Err(Rejection::TooLarge);
+    }
+
+    // Only the last path component, so the name can't leave the intake folder
+    let name = head
+        .header("x-filename")
+        .and_then(|name| Path::new(name).file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .filter(|name| !name.starts_with('.'))
+        .unwrap_or_else(|| DEFAULT_FILENAME.to_string());
+    if !batch::is_supported(Path::new(&name)) {
+        return Err(Rejection::UnsupportedType);
+    }
+
+    Ok((length, name))
+}
+
+/// Compare in constant time, so the token can't be guessed byte by byte
+fn tokens_match(presented: &str, token: &str) -> bool {
+    if token.is_empty() || presented.len() != token.len() {
+        return false;
+    }
+    presented
+        .bytes()
+        .zip(token.bytes())
+        .fold(0, |diff, (a, b)| diff | (a ^ b))
+        == 0
+}
+
+fn generate_token() -> Result<String, getrandom::Error> {
+    let mut bytes = [0u8; 24];
+    getrandom::getrandom(&mut bytes)?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// The intake token from the secret store, generated on first use. A token
+/// older builds kept in `config.json` is moved over.
+fn intake_token(store: &tauri_plugin_store::Store<tauri::Wry>) -> Option<String> {
+    match keychain::load_intake_token() {
+        Ok(Some(token)) => return Some(token),
+        Ok(None) => {}
+        Err(e) => {
+            eprintln!("[Intake] Failed to read the token: {:?}", e);
+            return None;
+        }
+    }
+
+    let legacy = store.get("app_config").and_then(|config| {
+        config
+            .pointer("/intake/token")?
+            .as_str()
+            .map(str::to_string)
+    });
+    let token = match legacy {
+        Some(token) => token,
+        None => match generate_token() {
+            Ok(token) => token,
+            Err(e) => {
+                eprintln!("[Intake] Failed to generate a token: {}", e);
+                return None;
+            }
+        },
+    };
+    if let Err(e) = keychain::save_intake_token(&token) {
+        eprintln!("[Intake] Failed to save the token: {:?}", e);
+        return None;
+    }
+    // Saving rewrites the config without the plaintext token
+    if let Err(e) = config::save_app_config(store, &config::load_app_config(store)) {
+        eprintln!("[Intake] Failed to remove the token from the config: {}", e);
+    }
+    Some(token)
+}
+
+/// Start the intake if it's enabled
+pub fn start(app_handle: &tauri::AppHandle, supervisor: &SupervisorState) {
+    let Ok(store) = app_handle.store("config.json") else {
+        return;
+    };
+    let config = config::load_app_config(&store);
+    if !config.intake.enabled {
+        return;
+    }
+    let Some(token) = intake_token(&store) else {
+        return;
+    };
+
+    let intake_dir = match app_handle.path().app_data_dir() {
+        Ok(dir) => dir.join("intake"),
+        Err(e) => {
+            eprintln!("[Intake] No app data folder: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = fs::create_dir_all(&intake_dir) {
+        eprintln!("[Intake] Failed to create {:?}: {}", intake_dir, e);
+        return;
+    }
+
+    let port = config.intake.port;
+    let listener = match TcpListener::bind(("0.0.0.0", port)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("[Intake] Failed to listen on port {}: {}", port, e);
+            return;
+        }
+    };
+    println!("[Intake] Listening on port {}", port);
+    bonjour::advertise(port);
+
+    let app_handle = app_handle.clone();
+    supervisor.spawn_thread("intake", RestartPolicy::default(), move || {
+        serve(&app_handle, &listener, &token, &intake_dir)
+    });
+}
+
+/// A received memo waiting to be transcribed, and who to answer
+struct Upload {
+    stream: TcpStream,
+    audio: PathBuf,
+}
+
+/// Receive uploads on their own threads and hand them to one transcription
+/// worker, so a slow client or a long transcription doesn't hold up the rest
+fn serve(app_handle: &tauri::AppHandle, listener: &TcpListener, token: &str, intake_dir: &Path) {
+    let (upload_tx, upload_rx) = mpsc::sync_channel(MAX_WAITING_UPLOADS);
+    let worker_handle = app_handle.clone();
+    thread::spawn(move || transcribe_uploads(&worker_handle, upload_rx));
+
+    let connections = Arc::new(AtomicUsize::new(0));
+    for stream in listener.incoming() {
+        let mut stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("[Intake] Failed to accept a connection: {}", e);
+                continue;
+            }
+        };
+        if connections.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+            connections.fetch_sub(1, Ordering::SeqCst);
+            println!("[Intake] Too many connections, turning one away");
+            respond(&mut stream, Rejection::Busy.status(), "");
+            continue;
+        }
+
+        let connections = connections.clone();
+        let upload_tx = upload_tx.clone();
+        let token = token.to_string();
+        let intake_dir = intake_dir.to_path_buf();
+        thread::spawn(move || {
+            match receive(&stream, &token, &intake_dir) {
+                Ok(audio) => match upload_tx.try_send(Upload { stream, audio }) {
+                    Ok(()) => {}
+                    Err(TrySendError::Full(upload) | TrySendError::Disconnected(upload)) => {
+                        println!("[Intake] Too many uploads waiting, turning one away");
+                        let Upload { mut stream, audio } = upload;
+                        let _ = fs::remove_file(&audio);
+                        respond(&mut stream, Rejection::Busy.status(), "");
+                    }
+                },
+                Err(rejection) => {
+                    println!("[Intake] Rejected a request: {:?}", rejection);
+                    respond(&mut stream, rejection.status(), "");
+                }
+            }
+            connections.fetch_sub(1, Ordering::SeqCst);
+        });
+    }
+}
+
+/// Transcribe received uploads in the order they arrived and answer each
+fn transcribe_uploads(app_handle: &tauri::AppHandle, uploads: mpsc::Receiver<Upload>) {
+    let client = OpenAIClient::new();
+    for Upload { mut stream, audio } in uploads {
+        let config = app_handle
+            .store("config.json")
+            .map(|store| config::load_app_config(&store))
+            .unwrap_or_default();
+
+        println!("[Intake] Transcribing {:?}", audio);
+        let outcome = transcribe(&client, &audio, &config);
+        notify_outcome(&outcome);
+        events::emit_or_log(
+            app_handle,
+            IntakeTranscribed {
+                file: audio.to_string_lossy().to_string(),
+                outcome: outcome.clone(),
+            },
+        );
+        let body = serde_json::to_string(&outcome).unwrap_or_default();
+        respond(&mut stream, (200, "OK"), &body);
+    }
+}
+
+fn respond(stream: &mut TcpStream, (status, reason): (u16, &str), body: &str) {
+    if let Err(e) = stream.set_write_timeout(Some(READ_TIMEOUT)) {
+        eprintln!("[Intake] Failed to set a write timeout: {}", e);
+    }
+    let reply = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    );
+    if let Err(e) = stream.write_all(reply.as_bytes()) {
+        eprintln!("[Intake] Failed to respond: {}", e);
+    }
+}
+
+/// Reads from a connection until `deadline`, so trickling bytes can't keep
+/// it open past it
+struct DeadlineReader<'a> {
+    stream: &'a TcpStream,
+    deadline: Instant,
+}
+
+impl Read for DeadlineReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(io::ErrorKind::TimedOut.into());
+        }
+        self.stream
+            .set_read_timeout(Some(remaining.min(READ_TIMEOUT)))?;
+        self.stream.read(buf)
+    }
+}
+
+/// Read the request line and headers, up to the blank line ending them.
+/// Lines and the head as a whole are capped, so it can't grow without bound.
+fn read_head(reader: &mut impl BufRead) -> Result<Vec<String>, Rejection> {
+    let mut budget = MAX_HEADER_BYTES;
+    let mut lines = Vec::new();
+    loop {
+        let mut line = String::new();
+        let limit = budget.min(MAX_HEADER_LINE_BYTES);
+        match reader.by_ref().take(limit).read_line(&mut line) {
+            Ok(0) | Err(_) => return Err(Rejection::BadRequest),
+            Ok(read) => budget -= read as u64,
+        }
+        // Cut off by the cap before the line ended
+        if !line.ends_with('\n') {
+            return Err(Rejection::BadRequest);
+        }
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.is_empty() {
+            return Ok(lines);
+        }
+        if lines.len() == MAX_HEADER_LINES {
+            return Err(Rejection::BadRequest);
+        }
+        lines.push(line.to_string());
+    }
+}
+
+/// Read a request and save its audio. The token is checked before the body
+/// is read, and only then does the connection get the time to upload it. Returns the saved file.
+fn receive(stream: &TcpStream, token: &str, intake_dir: &Path) -> Result<PathBuf, Rejection> {
+    let mut reader = BufReader::new(DeadlineReader {
+        stream,
+        deadline: Instant::now() + HEAD_DEADLINE,
+    });
+    let lines = read_head(&mut reader)?;
+    let head = parse_head(&lines).ok_or(Rejection::BadRequest)?;
+    let (length, name) = accept(&head, token)?;
+    reader.get_mut().deadline = Instant::now() + CONNECTION_DEADLINE;
+
+    let mut body = Vec::with_capacity(length as usize);
+    match reader.take(length).read_to_end(&mut body) {
+        Ok(read) if read as u64 == length => {}
+        _ => return Err(Rejection::BadRequest),
+    }
+
+    save(intake_dir, &name, &body).map_err(|e| {
+        eprintln!("[Intake] Failed to save {}: {}", name, e);
+        Rejection::BadRequest
+    })
+}
+
+/// Save `body` in `dir` as `name` behind a timestamp, numbered when another
+/// upload of that name took it in the same second. Returns the saved file.
+fn save(dir: &Path, name: &str, body: &[u8]) -> io::Result<PathBuf> {
+    let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let mut n = 0;
+    loop {
+        let file_name = match n {
+            0 => format!("{}-{}", timestamp, name),
+            n => format!("{}-{}-{}", timestamp, n, name),
+        };
+        let path = dir.join(file_name);
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                file.write_all(body)?;
+                return Ok(path);
+            }
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => n += 1,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+fn transcribe(client: &OpenAIClient, audio: &Path, config: &AppConfig) -> BatchFileOutcome {
+    let options = BatchOptions {
+        concurrency: 1,
+        format: config.intake.format,
+        recursive: false,
+        overwrite: false,
+        filename_template: None,
+        title_template: None,
+    };
+    batch::transcribe_one(client, audio, &options, config)
+}
+
+fn notify_outcome(outcome: &BatchFileOutcome) {
+    match outcome {
+        BatchFileOutcome::Transcribed { output } => {
+            let text = fs::read_to_string(output).unwrap_or_default();
+            notify("Voice memo transcribed", text.trim());
+        }
+        BatchFileOutcome::Skipped { reason } => notify("Voice memo skipped", reason),
+        BatchFileOutcome::Failed { error } => notify("Voice memo failed", error),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn notify(title: &str, message: &str) {
+    let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+    let script = format!(
+        "display notification {} with title {}",
+        quote(message),
+        quote(title)
+    );
+    if let Err(e) = std::process::Command::new("osascript")
+        .args(["-e", &script])
+        .status()
+    {
+        eprintln!("[Intake] Failed to show a notification: {}", e);
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn notify(_title: &str, _message: &str) {}
+
+#[cfg(target_os = "macos")]
+mod bonjour {
+    use std::ffi::{c_char, c_void, CString};
+    use std::ptr;
+
+    /// Service type the phone looks for
+    const SERVICE_TYPE: &str = "_dictara._tcp";
+
+    type DNSServiceRef = *mut c_void;
+
+    // dns_sd.h, part of libSystem
+    extern "C" {
+        fn DNSServiceRegister(
+            sd_ref: *mut DNSServiceRef,
+            flags: u32,
+            interface_index: u32,
+            name: *const c_char,
+            regtype: *const c_char,
+            domain: *const c_char,
+            host: *const c_char,
+            port: u16,
+            txt_len: u16,
+            txt_record: *const c_void,
+            callback: *const c_void,
+            context: *mut c_void,
+        ) -> i32;
+    }
+
+    /// Advertise the intake under the computer's name until the app quits
+    pub fn advertise(port: u16) {
+        let Ok(regtype) = CString::new(SERVICE_TYPE) else {
+            return;
+        };
+        let mut sd_ref: DNSServiceRef = ptr::null_mut();
+        // The port goes in network byte order. The reference is never
+        // deallocated: the registration ends with the process.
+        let error = unsafe {
+            DNSServiceRegister(
+                &mut sd_ref,
+                0,
+                0,
+                ptr::null(),
+                regtype.as_ptr(),
+                ptr::null(),
+                ptr::null(),
+                port.to_be(),
+                0,
+                ptr::null(),
+                ptr::null(),
+                ptr::null_mut(),
+            )
+        };
+        if error == 0 {
+            println!("[Intake] Advertised as {}", SERVICE_TYPE);
+        } else {
+            eprintln!("[Intake] Bonjour registration failed: {}", error);
+        }
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod bonjour {
+    pub fn advertise(_port: u16) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn head(headers: &[&str]) -> RequestHead {
+        let mut lines = vec!["POST /transcribe HTTP/1.1".to_string()];
+        lines.extend(headers.iter().map(|h| h.to_string()));
+        parse_head(&lines).unwrap()
+    }
+
+    #[test]
+    fn test_parse_head() {
+        let parsed = head(&["Content-Length: 12", "X-Filename:  memo.m4a "]);
+        assert_eq!(parsed.method, "POST");
+        assert_eq!(parsed.header("content-length"), Some("12"));
+        assert_eq!(parsed.header("x-filename"), Some("memo.m4a"));
+
+        assert!(parse_head(&["GET /".to_string()]).is_none());
+        assert!(parse_head(&["POST / HTTP/1.1".to_string(), "no colon".to_string()]).is_none());
+    }
+
+    #[test]
+    fn test_read_head_caps_lines_and_size() {
+        let mut request =
+            io::Cursor::new("POST /transcribe HTTP/1.1\r\nContent-Length: 3\r\n\r\nabc");
+        assert_eq!(
+            read_head(&mut request),
+            Ok(vec![
+                "POST /transcribe HTTP/1.1".to_string(),
+                "Content-Length: 3".to_string()
+            ])
+        );
+
+        let endless = format!(
+            "POST /transcribe HTTP/1.1\r\nX-Pad: {}",
+            "a".repeat(1 << 20)
+        );
+        assert_eq!(
+            read_head(&mut io::Cursor::new(endless)),
+            Err(Rejection::BadRequest)
+        );
+
+        // Under the line count, over the size
+        let line = format!("X-Pad: {}\r\n", "a".repeat(1000));
+        let many = format!("POST / HTTP/1.1\r\n{}", line.repeat(40));
+        assert_eq!(
+            read_head(&mut io::Cursor::new(many)),
+            Err(Rejection::BadRequest)
+        );
+    }
+
+    #[test]
+    fn test_save_keeps_uploads_of_the_same_name() {
+        let dir = std::env::temp_dir().join(format!("dictara-intake-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let first = save(&dir, "memo.m4a", b"first").unwrap();
+        let second = save(&dir, "memo.m4a", b"second").unwrap();
+        let contents = (fs::read(&first).unwrap(), fs::read(&second).unwrap());
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_ne!(first, second);
+        assert!(second.to_string_lossy().ends_with("memo.m4a"));
+        assert_eq!(contents, (b"first".to_vec(), b"second".to_vec()));
+    }
+
+    #[test]
+    fn test_accept_checks_token_length_and_name() {
+        let token = "secret";
+        assert_eq!(
+            accept(&head(&["Content-Length: 10"]), token),
+            Err(Rejection::Unauthorized)
+        );
+        assert_eq!(
+            accept(
+                &head(&["Authorization: Bearer secreT", "Content-Length: 10"]),
+                token
+            ),
+            Err(Rejection::Unauthorized)
+        );
+        assert_eq!(
+            accept(&head(&["Authorization: Bearer secret"]), token),
+            Err(Rejection::LengthRequired)
+        );
+        assert_eq!(
+            accept(
+                &head(&[
+                    "Authorization: Bearer secret",
+                    "Content-Length: 10",
+                    "X-Filename: notes.txt"
+                ]),
+                token
+            ),
+            Err(Rejection::UnsupportedType)
+        );
+        assert_eq!(
+            accept(
+                &head(&[
+                    "Authorization: Bearer secret",
+                    "Content-Length: 10",
+                    "X-Filename: ../../Library/memo.wav"
+                ]),
+                token
+            ),
+            Ok((10, "memo.wav".to_string()))
+        );
+        assert_eq!(
+            accept(
+                &head(&["Authorization: Bearer secret", "Content-Length: 10"]),
+                token
+            ),
+            Ok((10, DEFAULT_FILENAME.to_string()))
+        );
+        // No token configured means nothing is accepted
+        assert_eq!(
+            accept(&head(&["Authorization: Bearer ", "Content-Length: 10"]), ""),
+            Err(Rejection::Unauthorized)
+        );
+    }
+}
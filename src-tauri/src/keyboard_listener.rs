@@ -1,13 +1,17 @@
-use crate::recording::RecordingCommand;
+use crate::config::TriggerKey;
+use crate::recording::{KeyModifiers, RecordingCommand};
+use crate::supervisor::{RestartPolicy, SupervisorState};
 use std::sync::{
-    atomic::{AtomicU8, Ordering},
-    Arc,
+    atomic::{AtomicBool, AtomicU8, Ordering},
+    Arc, Mutex,
 };
-use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 
 #[cfg(target_os = "macos")]
-use objc2_core_foundation::{kCFRunLoopCommonModes, CFMachPort, CFRunLoop};
+use crate::events::{self, InputDegraded};
+#[cfg(target_os = "macos")]
+use objc2_core_foundation::{kCFRunLoopCommonModes, CFMachPort, CFRetained, CFRunLoop};
 #[cfg(target_os = "macos")]
 use objc2_core_graphics::{
     kCGEventMaskForAllEvents, CGEvent, CGEventField, CGEventTapCallBack, CGEventTapLocation,
@@ -16,56 +20,359 @@ use objc2_core_graphics::{
 #[cfg(target_os = "macos")]
 use std::{ffi::c_void, ptr::NonNull};
 
-#[cfg(not(target_os = "macos"))]
-use rdev::{listen, Event, EventType, Key, ListenError};
 #[cfg(target_os = "macos")]
-use rdev::{listen, EventType, Key};
+use rdev::listen;
+#[cfg(not(target_os = "macos"))]
+use rdev::{listen, Event, ListenError};
+use rdev::{EventType, Key};
 
-/// Stateful FN key listener
-pub struct KeyListener {
-    _thread_handle: Option<JoinHandle<()>>,
+/// Name of the listener in supervisor health reports
+const COMPONENT_NAME: &str = "key_listener";
+
+/// How often `learn_next_key` checks for a captured key
+const LEARN_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How long a swallowed Space may go without a repeat or release before its
+/// release is assumed lost
+const SPACE_STUCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Whether the listener runs on the rdev fallback instead of the event tap
+static ON_FALLBACK: AtomicBool = AtomicBool::new(false);
+
+/// Push-to-talk trigger shared with the listener thread.
+/// Updated when the config changes and used for the "capture next key" flow.
+pub struct TriggerSettings {
+    key: Mutex<TriggerKey>,
+    learning: AtomicBool,
+    learned: Mutex<Option<TriggerKey>>,
+}
+
+pub type TriggerKeyState = Arc<TriggerSettings>;
+
+impl TriggerSettings {
+    pub fn new(key: TriggerKey) -> TriggerKeyState {
+        Arc::new(Self {
+            key: Mutex::new(key),
+            learning: AtomicBool::new(false),
+            learned: Mutex::new(None),
+        })
+    }
+
+    pub fn get(&self) -> TriggerKey {
+        self.key.lock().map(|key| *key).unwrap_or_default()
+    }
+
+    pub fn set(&self, key: TriggerKey) {
+        if let Ok(mut current) = self.key.lock() {
+            *current = key;
+        }
+    }
+
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    fn is_learning(&self) -> bool {
+        self.learning.load(Ordering::Relaxed)
+    }
+
+    /// Called by the listener with the key pressed while learning
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    fn finish_learning(&self, key: TriggerKey) {
+        if let Ok(mut learned) = self.learned.lock() {
+            *learned = Some(key);
+        }
+        self.learning.store(false, Ordering::Relaxed);
+    }
+
+    /// Capture the next key press instead of treating it as input.
+    /// Returns None if nothing was pressed within `timeout`. The captured key
+    /// is not applied; the caller saves it to the config.
+    ///
+    /// Only the macOS event tap reports raw keycodes, so on the rdev fallback
+    /// this always times out.
+    pub async fn learn_next_key(&self, timeout: Duration) -> Option<TriggerKey> {
+        if let Ok(mut learned) = self.learned.lock() {
+            *learned = None;
+        }
+        self.learning.store(true, Ordering::Relaxed);
+
+        let started = Instant::now();
+        while started.elapsed() < timeout {
+            tokio::time::sleep(LEARN_POLL_INTERVAL).await;
+            if let Some(key) = self.learned.lock().ok().and_then(|mut l| l.take()) {
+                println!("[FN Key Listener] Learned trigger key: {:?}", key);
+                return Some(key);
+            }
+        }
+
+        self.learning.store(false, Ordering::Relaxed);
+        None
+    }
+}
+
+/// Keys that must all be held for the trigger, as reported by rdev
+fn rdev_trigger_keys(trigger: TriggerKey) -> &'static [Key] {
+    match trigger {
+        TriggerKey::Fn => &[Key::Function],
+        TriggerKey::RightOption => &[Key::AltGr],
+        TriggerKey::RightCommand => &[Key::MetaRight],
+        TriggerKey::RightControl => &[Key::ControlRight],
+        TriggerKey::RightShift => &[Key::ShiftRight],
+        TriggerKey::ControlOption => &[Key::ControlLeft, Key::Alt],
+        // Learned keys are raw CGEvent keycodes, which rdev doesn't expose
+        TriggerKey::Custom { .. } => &[],
+    }
+}
+
+/// What the rdev listener does differently from the event tap with
+/// `trigger`, for the user. rdev only observes events, so it can't swallow
+/// any, and it doesn't report raw keycodes.
+fn fallback_limitations(trigger: TriggerKey) -> Vec<String> {
+    let mut limitations = vec!["Space types a space when it locks a recording".to_string()];
+    match trigger {
+        TriggerKey::Fn => limitations.push("Fn may open the emoji picker".to_string()),
+        TriggerKey::Custom { .. } => limitations.push(
+            "Your learned trigger key doesn't work, pick a built-in one in Preferences".to_string(),
+        ),
+        _ => {}
+    }
+    limitations.push("New trigger keys can't be learned".to_string());
+    limitations.push("Reset Input Handling has no effect".to_string());
+    limitations
+}
+
+/// What works differently right now because the listener fell back to rdev;
+/// empty while the event tap runs
+pub fn input_limitations(trigger_key: &TriggerSettings) -> Vec<String> {
+    if !ON_FALLBACK.load(Ordering::Relaxed) {
+        return Vec::new();
+    }
+    fallback_limitations(trigger_key.get())
+}
+
+/// Tracks held keys for the rdev listener and reports trigger transitions
+struct RdevTrigger {
+    trigger_key: TriggerKeyState,
+    held: Vec<Key>,
+    active: bool,
+    /// Modifiers held, unless they're part of the trigger
+    modifiers: KeyModifiers,
+    /// Space is down; rdev reports its repeats as presses
+    space_held: bool,
+}
+
+impl RdevTrigger {
+    fn new(trigger_key: TriggerKeyState) -> Self {
+        Self {
+            trigger_key,
+            held: Vec::new(),
+            active: false,
+            modifiers: KeyModifiers::default(),
+            space_held: false,
+        }
+    }
+
+    /// Lock when Space goes down while recording, like the event tap. The
+    /// space still reaches the focused app, since rdev can't swallow it.
+    fn handle_space(
+        &mut self,
+        event_type: &EventType,
+        recording: bool,
+    ) -> Option<RecordingCommand> {
+        match event_type {
+            EventType::KeyPress(Key::Space) => {
+                let repeat = std::mem::replace(&mut self.space_held, true);
+                (recording && !repeat).then_some(RecordingCommand::Lock)
+            }
+            EventType::KeyRelease(Key::Space) => {
+                self.space_held = false;
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Command for an rdev event, if any. `recording` is whether a recording
+    /// runs unlocked.
+    fn command(&mut self, event_type: &EventType, recording: bool) -> Option<RecordingCommand> {
+        self.handle(event_type)
+            .or_else(|| self.handle_space(event_type, recording))
+    }
+
+    /// FnDown when the whole trigger becomes held, FnUp when it's released.
+    /// Key repeat doesn't produce duplicate commands.
+    fn handle(&mut self, event_type: &EventType) -> Option<RecordingCommand> {
+        let keys = rdev_trigger_keys(self.trigger_key.get());
+        if keys.is_empty() {
+            return None;
+        }
+
+        if let EventType::KeyPress(key) | EventType::KeyRelease(key) = event_type {
+            let pressed = matches!(event_type, EventType::KeyPress(_));
+            if !keys.contains(key) {
+                match key {
+                    Key::ShiftLeft | Key::ShiftRight => {
+                        self.modifiers.shift = pressed;
+                        return None;
+                    }
+                    Key::Alt | Key::AltGr => {
+                        self.modifiers.option = pressed;
+                        return None;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        match event_type {
+            EventType::KeyPress(key) if keys.contains(key) => {
+                if !self.held.contains(key) {
+                    self.held.push(*key);
+                }
+            }
+            EventType::KeyRelease(key) => self.held.retain(|held| held != key),
+            _ => return None,
+        }
+
+        let active = keys.iter().all(|key| self.held.contains(key));
+        if active == self.active {
+            return None;
+        }
+        self.active = active;
+
+        let modifiers = self.modifiers;
+        Some(if active {
+            RecordingCommand::FnDown { modifiers }
+        } else {
+            RecordingCommand::FnUp { modifiers }
+        })
+    }
 }
 
+/// The Space press swallowed to lock a recording.
+///
+/// Its repeats and release are swallowed as well, so apps never see half a
+/// key press. Nothing waits on the release: a fresh press, or no repeat or
+/// release for `SPACE_STUCK_TIMEOUT` (e.g. it got lost while the tap was off
+/// or the app was restarting), clears the swallowed state.
+#[derive(Debug, Default)]
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+struct SwallowedSpace {
+    /// When the press, or its latest repeat, was swallowed
+    since: Option<Instant>,
+}
+
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+impl SwallowedSpace {
+    /// Space went down; `lock` means it locks the recording. Returns whether
+    /// to swallow the event.
+    fn key_down(&mut self, now: Instant, autorepeat: bool, lock: bool) -> bool {
+        self.expire(now);
+        if autorepeat && self.since.is_some() {
+            self.since = Some(now);
+            return true;
+        }
+        self.since = lock.then_some(now);
+        lock
+    }
+
+    /// Space went up. Returns whether to swallow the event.
+    fn key_up(&mut self, now: Instant) -> bool {
+        self.expire(now);
+        self.since.take().is_some()
+    }
+
+    /// Forget a press whose release never arrived
+    fn expire(&mut self, now: Instant) {
+        if self
+            .since
+            .is_some_and(|since| now.duration_since(since) >= SPACE_STUCK_TIMEOUT)
+        {
+            self.since = None;
+        }
+    }
+
+    fn clear(&mut self) {
+        self.since = None;
+    }
+}
+
+/// Stateful FN key listener
+pub struct KeyListener;
+
 impl KeyListener {
+    /// Start the listener under the supervisor. The listener loop should never
+    /// return, so an exit is treated as a failure and restarted with backoff
+    /// (e.g. until accessibility permission is granted).
     pub fn start(
+        app_handle: tauri::AppHandle,
         command_tx: mpsc::Sender<RecordingCommand>,
         recording_state: Arc<AtomicU8>,
+        trigger_key: TriggerKeyState,
+        supervisor: &SupervisorState,
     ) -> Self {
+        let policy = RestartPolicy {
+            restart_on_exit: true,
+            ..RestartPolicy::default()
+        };
+
         #[cfg(target_os = "macos")]
         {
-            Self::start_macos(command_tx, recording_state)
+            Self::start_macos(
+                app_handle,
+                command_tx,
+                recording_state,
+                trigger_key,
+                supervisor,
+                policy,
+            )
         }
 
         #[cfg(not(target_os = "macos"))]
         {
-            return Self::start_rdev(command_tx);
+            let _ = app_handle;
+            return Self::start_rdev(command_tx, recording_state, trigger_key, supervisor, policy);
         }
     }
 
     #[cfg(target_os = "macos")]
     fn start_macos(
+        app_handle: tauri::AppHandle,
         command_tx: mpsc::Sender<RecordingCommand>,
         recording_state: Arc<AtomicU8>,
+        trigger_key: TriggerKeyState,
+        supervisor: &SupervisorState,
+        policy: RestartPolicy,
     ) -> Self {
-        let thread_handle = thread::spawn(move || {
+        supervisor.spawn_thread(COMPONENT_NAME, policy, move || {
+            let command_tx = command_tx.clone();
             println!("[FN Key Listener] Starting CGEvent tap listener...");
 
-            if let Err(err) = run_event_tap(command_tx.clone(), recording_state.clone()) {
+            if let Err(err) = run_event_tap(
+                command_tx.clone(),
+                recording_state.clone(),
+                trigger_key.clone(),
+            ) {
                 eprintln!(
                     "[FN Key Listener] CGEvent tap failed: {}. Falling back to rdev::listen (emoji picker may appear).",
                     err
                 );
-                if let Err(listen_err) = listen(move |event: rdev::Event| match event.event_type {
-                    EventType::KeyPress(Key::Function) => {
-                        let _ = command_tx.blocking_send(RecordingCommand::FnDown);
-                    }
-                    EventType::KeyRelease(Key::Function) => {
-                        let _ = command_tx.blocking_send(RecordingCommand::FnUp);
+                ON_FALLBACK.store(true, Ordering::Relaxed);
+                events::emit_or_log(
+                    &app_handle,
+                    InputDegraded {
+                        limitations: input_limitations(&trigger_key),
+                    },
+                );
+
+                let mut trigger = RdevTrigger::new(trigger_key.clone());
+                let recording_state = recording_state.clone();
+                if let Err(listen_err) = listen(move |event: rdev::Event| {
+                    if !crate::session::is_active() {
+                        return;
                     }
-                    EventType::KeyPress(Key::Space) => {
-                        let _ = command_tx.blocking_send(RecordingCommand::Lock);
+                    let recording = recording_state.load(Ordering::Relaxed) == 1;
+                    if let Some(command) = trigger.command(&event.event_type, recording) {
+                        let _ = command_tx.blocking_send(command);
                     }
-                    _ => {}
                 }) {
                     eprintln!(
                         "[FN Key Listener] rdev::listen fallback failed: {:?}",
@@ -75,27 +382,31 @@ impl KeyListener {
             }
         });
 
-        Self {
-            _thread_handle: Some(thread_handle),
-        }
+        Self
     }
 
     #[cfg(not(target_os = "macos"))]
-    fn start_rdev(command_tx: mpsc::Sender<RecordingCommand>) -> Self {
-        let thread_handle = thread::spawn(move || {
+    fn start_rdev(
+        command_tx: mpsc::Sender<RecordingCommand>,
+        recording_state: Arc<AtomicU8>,
+        trigger_key: TriggerKeyState,
+        supervisor: &SupervisorState,
+        policy: RestartPolicy,
+    ) -> Self {
+        supervisor.spawn_thread(COMPONENT_NAME, policy, move || {
+            let command_tx = command_tx.clone();
             println!("[FN Key Listener] Starting global keyboard listener...");
 
-            let listen_res = listen(move |event: Event| match event.event_type {
-                EventType::KeyPress(Key::Function) => {
-                    let _ = command_tx.blocking_send(RecordingCommand::FnDown);
-                }
-                EventType::KeyRelease(Key::Function) => {
-                    let _ = command_tx.blocking_send(RecordingCommand::FnUp);
+            let mut trigger = RdevTrigger::new(trigger_key.clone());
+            let recording_state = recording_state.clone();
+            let listen_res = listen(move |event: Event| {
+                if !crate::session::is_active() {
+                    return;
                 }
-                EventType::KeyPress(Key::Space) => {
-                    let _ = command_tx.blocking_send(RecordingCommand::Lock);
+                let recording = recording_state.load(Ordering::Relaxed) == 1;
+                if let Some(command) = trigger.command(&event.event_type, recording) {
+                    let _ = command_tx.blocking_send(command);
                 }
-                _ => {}
             });
 
             if let Err(error) = listen_res {
@@ -111,17 +422,239 @@ impl KeyListener {
             println!("[FN Key Listener] Thread exiting");
         });
 
-        Self {
-            _thread_handle: Some(thread_handle),
+        Self
+    }
+}
+
+// Key codes from <HIToolbox/Events.h>
+#[cfg(target_os = "macos")]
+const KEYCODE_SPACE: i64 = 49;
+#[cfg(target_os = "macos")]
+const KEYCODE_RIGHT_COMMAND: i64 = 54;
+#[cfg(target_os = "macos")]
+const KEYCODE_LEFT_OPTION: i64 = 58;
+#[cfg(target_os = "macos")]
+const KEYCODE_LEFT_CONTROL: i64 = 59;
+#[cfg(target_os = "macos")]
+const KEYCODE_RIGHT_SHIFT: i64 = 60;
+#[cfg(target_os = "macos")]
+const KEYCODE_RIGHT_OPTION: i64 = 61;
+#[cfg(target_os = "macos")]
+const KEYCODE_RIGHT_CONTROL: i64 = 62;
+#[cfg(target_os = "macos")]
+const KEYCODE_FN: i64 = 63;
+
+// Modifier flag bits from <IOKit/hidsystem/IOLLEvent.h>. The device-dependent
+// bits tell the right-hand modifiers apart from the left-hand ones.
+#[cfg(target_os = "macos")]
+const LEARNABLE_FLAGS: u64 = 0x00DF_20FF; // all modifiers, minus NumericPad and NonCoalesced
+#[cfg(target_os = "macos")]
+const FLAG_DEVICE_RIGHT_SHIFT: u64 = 0x0000_0004;
+#[cfg(target_os = "macos")]
+const FLAG_DEVICE_RIGHT_COMMAND: u64 = 0x0000_0010;
+#[cfg(target_os = "macos")]
+const FLAG_DEVICE_RIGHT_OPTION: u64 = 0x0000_0040;
+#[cfg(target_os = "macos")]
+const FLAG_DEVICE_RIGHT_CONTROL: u64 = 0x0000_2000;
+#[cfg(target_os = "macos")]
+const FLAG_SHIFT: u64 = 0x0002_0000;
+#[cfg(target_os = "macos")]
+const FLAG_CONTROL: u64 = 0x0004_0000;
+#[cfg(target_os = "macos")]
+const FLAG_OPTION: u64 = 0x0008_0000;
+
+/// How an event's keycode relates to the configured trigger
+#[cfg(target_os = "macos")]
+enum TapMatch {
+    /// Not the trigger key
+    Other,
+    /// Trigger key without a reliable flag (Fn); FlagsChanged events toggle it
+    Toggle,
+    /// Trigger modifier, held while all of these flag bits are set
+    Flags(u64),
+}
+
+#[cfg(target_os = "macos")]
+fn tap_match(trigger: TriggerKey, keycode: i64) -> TapMatch {
+    let (keycodes, flags): (&[i64], Option<u64>) = match trigger {
+        TriggerKey::Fn => (&[KEYCODE_FN], None),
+        TriggerKey::RightOption => (&[KEYCODE_RIGHT_OPTION], Some(FLAG_DEVICE_RIGHT_OPTION)),
+        TriggerKey::RightCommand => (&[KEYCODE_RIGHT_COMMAND], Some(FLAG_DEVICE_RIGHT_COMMAND)),
+        TriggerKey::RightControl => (&[KEYCODE_RIGHT_CONTROL], Some(FLAG_DEVICE_RIGHT_CONTROL)),
+        TriggerKey::RightShift => (&[KEYCODE_RIGHT_SHIFT], Some(FLAG_DEVICE_RIGHT_SHIFT)),
+        TriggerKey::ControlOption => (
+            &[
+                KEYCODE_LEFT_CONTROL,
+                KEYCODE_RIGHT_CONTROL,
+                KEYCODE_LEFT_OPTION,
+                KEYCODE_RIGHT_OPTION,
+            ],
+            Some(FLAG_CONTROL | FLAG_OPTION),
+        ),
+        TriggerKey::Custom {
+            keycode: learned,
+            modifier_mask,
+        } => {
+            if keycode != learned as i64 {
+                return TapMatch::Other;
+            }
+            return modifier_mask.map_or(TapMatch::Toggle, |mask| TapMatch::Flags(mask as u64));
         }
+    };
+
+    if !keycodes.contains(&keycode) {
+        return TapMatch::Other;
+    }
+    flags.map_or(TapMatch::Toggle, TapMatch::Flags)
+}
+
+/// Modifiers held with the trigger. A modifier doesn't count when the
+/// trigger itself includes it.
+#[cfg(target_os = "macos")]
+fn tap_modifiers(trigger: TriggerKey, flags: u64) -> KeyModifiers {
+    let trigger_flags = match trigger {
+        TriggerKey::RightShift => FLAG_SHIFT,
+        TriggerKey::RightOption => FLAG_OPTION,
+        TriggerKey::ControlOption => FLAG_CONTROL | FLAG_OPTION,
+        TriggerKey::Custom {
+            modifier_mask: Some(mask),
+            ..
+        } => mask as u64,
+        _ => 0,
+    };
+    let held = |flag: u64| flags & flag != 0 && trigger_flags & flag == 0;
+    KeyModifiers {
+        shift: held(FLAG_SHIFT),
+        option: held(FLAG_OPTION),
+    }
+}
+
+/// Trigger for a key captured while learning
+#[cfg(target_os = "macos")]
+fn learned_trigger(keycode: i64, modifier_mask: Option<u64>) -> TriggerKey {
+    if keycode == KEYCODE_FN {
+        return TriggerKey::Fn;
+    }
+    TriggerKey::Custom {
+        keycode: keycode as u32,
+        modifier_mask: modifier_mask.map(|mask| mask as u32),
+    }
+}
+
+/// The running event tap, so it can be switched off or torn down from other
+/// threads
+#[cfg(target_os = "macos")]
+struct TapHandle {
+    tap: CFRetained<CFMachPort>,
+    /// Run loop of the listener thread, which returns once stopped
+    run_loop: CFRetained<CFRunLoop>,
+}
+
+// Safety: CGEventTapEnable, CFMachPortInvalidate and CFRunLoopStop may be
+// called from any thread
+#[cfg(target_os = "macos")]
+unsafe impl Send for TapHandle {}
+
+#[cfg(target_os = "macos")]
+static TAP: Mutex<Option<TapHandle>> = Mutex::new(None);
+
+/// Turn the event tap off while this user session is in the background, and
+/// back on when it returns
+#[cfg(target_os = "macos")]
+pub fn set_tap_enabled(enabled: bool) {
+    if let Some(TapHandle { tap, .. }) = TAP.lock().unwrap().as_ref() {
+        CGEvent::tap_enable(tap, enabled);
+        println!(
+            "[FN Key Listener] Event tap {}",
+            if enabled { "enabled" } else { "disabled" }
+        );
     }
 }
 
+#[cfg(not(target_os = "macos"))]
+pub fn set_tap_enabled(_enabled: bool) {}
+
+/// Tear down the event tap so the supervisor builds a fresh one, dropping any
+/// stuck key state. Backs the "Reset Input Handling" tray action.
+#[cfg(target_os = "macos")]
+pub fn reset_input_handling() {
+    let Some(TapHandle { tap, run_loop }) = TAP.lock().unwrap().take() else {
+        println!("[FN Key Listener] No event tap to reset");
+        return;
+    };
+    println!("[FN Key Listener] Resetting input handling");
+    CGEvent::tap_enable(&tap, false);
+    tap.invalidate();
+    run_loop.stop();
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn reset_input_handling() {
+    println!("[FN Key Listener] Input handling can only be reset with the macOS event tap");
+}
+
+/// Create an event tap like the listener's and tear it down right away,
+/// disabled and never attached to a run loop. `application_is_trusted` can
+/// report a grant that creating the tap still fails with, e.g. after the app
+/// was re-signed.
+#[cfg(target_os = "macos")]
+pub fn probe_event_tap() -> Result<(), String> {
+    unsafe extern "C-unwind" fn pass_through(
+        _proxy: CGEventTapProxy,
+        _event_type: CGEventType,
+        cg_event: NonNull<CGEvent>,
+        _user_info: *mut c_void,
+    ) -> *mut CGEvent {
+        cg_event.as_ptr()
+    }
+
+    let tap = unsafe {
+        CGEvent::tap_create(
+            CGEventTapLocation::HIDEventTap,
+            CGEventTapPlacement::HeadInsertEventTap,
+            CGEventTapOptions::Default,
+            kCGEventMaskForAllEvents.into(),
+            Some(pass_through),
+            std::ptr::null_mut(),
+        )
+    }
+    .ok_or_else(|| "Failed to create CGEvent tap (accessibility permission?)".to_string())?;
+    CGEvent::tap_enable(&tap, false);
+    tap.invalidate();
+    Ok(())
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn probe_event_tap() -> Result<(), String> {
+    Ok(())
+}
+
 #[cfg(target_os = "macos")]
 struct CallbackState {
     command_tx: mpsc::Sender<RecordingCommand>,
     recording_state: Arc<AtomicU8>,
-    fn_down: bool,
+    trigger_key: TriggerKeyState,
+    trigger_down: bool,
+    /// Modifier flags from the previous FlagsChanged event
+    last_flags: u64,
+    swallowed_space: SwallowedSpace,
+}
+
+#[cfg(target_os = "macos")]
+impl CallbackState {
+    /// Send FnDown/FnUp when the trigger's held state changes
+    fn set_trigger_down(&mut self, down: bool, modifiers: KeyModifiers) {
+        if down == self.trigger_down {
+            return;
+        }
+        self.trigger_down = down;
+        let command = if down {
+            RecordingCommand::FnDown { modifiers }
+        } else {
+            RecordingCommand::FnUp { modifiers }
+        };
+        let _ = self.command_tx.blocking_send(command);
+    }
 }
 
 #[cfg(target_os = "macos")]
@@ -131,48 +664,111 @@ unsafe extern "C-unwind" fn tap_callback(
     cg_event: NonNull<CGEvent>,
     user_info: *mut c_void,
 ) -> *mut CGEvent {
-    // Key codes from <HIToolbox/Events.h>
-    const KEYCODE_FN: i64 = 63;
-    const KEYCODE_SPACE: i64 = 49;
-
+    // Runs for every key event system-wide: keep this path free of heap
+    // allocation, logging and blocking work beyond the command send
     let state = &mut *(user_info as *mut CallbackState);
+    crate::energy::record_tap_event();
+
+    // Events that slip through while the tap is being disabled
+    if !crate::session::is_active() {
+        return cg_event.as_ptr();
+    }
+
+    // macOS switches off a tap whose callback was too slow. Keys released in
+    // the meantime were never seen, so start from a clean slate.
+    if matches!(
+        event_type,
+        CGEventType::TapDisabledByTimeout | CGEventType::TapDisabledByUserInput
+    ) {
+        state.swallowed_space.clear();
+        state.set_trigger_down(false, KeyModifiers::default());
+        if let Some(TapHandle { tap, .. }) = TAP.lock().unwrap().as_ref() {
+            CGEvent::tap_enable(tap, true);
+        }
+        return cg_event.as_ptr();
+    }
 
     let keycode =
         CGEvent::integer_value_field(Some(cg_event.as_ref()), CGEventField::KeyboardEventKeycode);
 
+    let flags = CGEvent::flags(Some(cg_event.as_ref())).0;
+    let newly_pressed = flags & !state.last_flags & LEARNABLE_FLAGS;
+    if event_type == CGEventType::FlagsChanged {
+        state.last_flags = flags;
+    }
+
+    // Capture the next key for the hotkey-learning flow, raw keycode and all,
+    // so remapped keys (e.g. from Karabiner-Elements) can be bound
+    if state.trigger_key.is_learning() {
+        let learned = match event_type {
+            CGEventType::KeyDown => Some(learned_trigger(keycode, None)),
+            // Only presses; the release of a modifier sets no new flags
+            CGEventType::FlagsChanged if newly_pressed != 0 || keycode == KEYCODE_FN => Some(
+                learned_trigger(keycode, Some(newly_pressed).filter(|m| *m != 0)),
+            ),
+            _ => None,
+        };
+        if let Some(trigger) = learned {
+            state.trigger_key.finish_learning(trigger);
+            return std::ptr::null_mut();
+        }
+    }
+
+    let trigger = state.trigger_key.get();
+    let trigger_match = tap_match(trigger, keycode);
+    let modifiers = tap_modifiers(trigger, flags);
+    let is_trigger = !matches!(trigger_match, TapMatch::Other);
+    // Fn and learned regular keys are swallowed (Fn would open the emoji
+    // picker). Modifiers pass through so apps keep a consistent modifier state.
+    let swallow = matches!(trigger_match, TapMatch::Toggle);
+
     match event_type {
         CGEventType::KeyDown => {
-            if keycode == KEYCODE_FN {
-                state.fn_down = true;
-                let _ = state.command_tx.blocking_send(RecordingCommand::FnDown);
-                return std::ptr::null_mut(); // Swallow to block emoji picker
+            if is_trigger {
+                state.set_trigger_down(true, modifiers);
+                if swallow {
+                    return std::ptr::null_mut();
+                }
             } else if keycode == KEYCODE_SPACE {
-                let current_state = state.recording_state.load(Ordering::Relaxed);
-                if current_state == 1 {
-                    // Only use Space to lock while actively recording; pass through otherwise
-                    let _ = state.command_tx.blocking_send(RecordingCommand::Lock);
+                let autorepeat = CGEvent::integer_value_field(
+                    Some(cg_event.as_ref()),
+                    CGEventField::KeyboardEventAutorepeat,
+                ) != 0;
+                // Only use Space to lock while actively recording; pass through otherwise
+                let lock = state.recording_state.load(Ordering::Relaxed) == 1;
+                if state
+                    .swallowed_space
+                    .key_down(Instant::now(), autorepeat, lock)
+                {
+                    if lock {
+                        let _ = state.command_tx.blocking_send(RecordingCommand::Lock);
+                    }
                     return std::ptr::null_mut(); // Avoid inserting a space while recording
                 }
             }
         }
         CGEventType::KeyUp => {
-            if keycode == KEYCODE_FN {
-                state.fn_down = false;
-                let _ = state.command_tx.blocking_send(RecordingCommand::FnUp);
-                return std::ptr::null_mut(); // Swallow to block emoji picker
+            if is_trigger {
+                state.set_trigger_down(false, modifiers);
+                if swallow {
+                    return std::ptr::null_mut();
+                }
+            } else if keycode == KEYCODE_SPACE && state.swallowed_space.key_up(Instant::now()) {
+                return std::ptr::null_mut();
             }
         }
         CGEventType::FlagsChanged => {
-            if keycode == KEYCODE_FN {
-                // Fn often arrives as FlagsChanged events; toggle based on last state
-                if state.fn_down {
-                    state.fn_down = false;
-                    let _ = state.command_tx.blocking_send(RecordingCommand::FnUp);
-                } else {
-                    state.fn_down = true;
-                    let _ = state.command_tx.blocking_send(RecordingCommand::FnDown);
+            if is_trigger {
+                let down = match trigger_match {
+                    // Modifier keys: held while all of the trigger's flag bits are set
+                    TapMatch::Flags(mask) => flags & mask == mask,
+                    // Fn often arrives as FlagsChanged events; toggle based on last state
+                    _ => !state.trigger_down,
+                };
+                state.set_trigger_down(down, modifiers);
+                if swallow {
+                    return std::ptr::null_mut();
                 }
-                return std::ptr::null_mut();
             }
         }
         _ => {}
@@ -185,12 +781,16 @@ unsafe extern "C-unwind" fn tap_callback(
 fn run_event_tap(
     command_tx: mpsc::Sender<RecordingCommand>,
     recording_state: Arc<AtomicU8>,
+    trigger_key: TriggerKeyState,
 ) -> Result<(), String> {
     unsafe {
         let callback_state = Box::new(CallbackState {
             command_tx,
             recording_state,
-            fn_down: false,
+            trigger_key,
+            trigger_down: false,
+            last_flags: 0,
+            swallowed_space: SwallowedSpace::default(),
         });
         let user_info = Box::into_raw(callback_state) as *mut c_void;
         let callback: CGEventTapCallBack = Some(tap_callback);
@@ -213,14 +813,192 @@ fn run_event_tap(
 
         current_loop.add_source(Some(&loop_source), kCFRunLoopCommonModes);
 
-        CGEvent::tap_enable(&tap, true);
+        // A restarted listener stays off while another user is on screen
+        CGEvent::tap_enable(&tap, crate::session::is_active());
+        ON_FALLBACK.store(false, Ordering::Relaxed);
+        *TAP.lock().unwrap() = Some(TapHandle {
+            tap: tap.clone(),
+            run_loop: current_loop.clone(),
+        });
 
         // This blocks the thread until the run loop is stopped
         CFRunLoop::run();
 
-        // If the loop ever exits, reclaim the boxed state
-        let _ = Box::from_raw(user_info as *mut CallbackState);
+        // If the loop ever exits, reclaim the boxed state. A trigger still
+        // held now will never report its release, so release it here.
+        let mut callback_state = Box::from_raw(user_info as *mut CallbackState);
+        callback_state.set_trigger_down(false, KeyModifiers::default());
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracker(trigger: TriggerKey) -> RdevTrigger {
+        RdevTrigger::new(TriggerSettings::new(trigger))
+    }
+
+    #[test]
+    fn test_single_key_trigger_ignores_repeat() {
+        let mut trigger = tracker(TriggerKey::RightOption);
+
+        assert!(matches!(
+            trigger.handle(&EventType::KeyPress(Key::AltGr)),
+            Some(RecordingCommand::FnDown { .. })
+        ));
+        assert!(trigger.handle(&EventType::KeyPress(Key::AltGr)).is_none());
+        assert!(matches!(
+            trigger.handle(&EventType::KeyRelease(Key::AltGr)),
+            Some(RecordingCommand::FnUp { .. })
+        ));
+    }
+
+    #[test]
+    fn test_chord_needs_all_keys() {
+        let mut trigger = tracker(TriggerKey::ControlOption);
+
+        assert!(trigger
+            .handle(&EventType::KeyPress(Key::ControlLeft))
+            .is_none());
+        assert!(matches!(
+            trigger.handle(&EventType::KeyPress(Key::Alt)),
+            Some(RecordingCommand::FnDown { .. })
+        ));
+        assert!(matches!(
+            trigger.handle(&EventType::KeyRelease(Key::ControlLeft)),
+            Some(RecordingCommand::FnUp { .. })
+        ));
+        assert!(trigger.handle(&EventType::KeyRelease(Key::Alt)).is_none());
+    }
+
+    #[test]
+    fn test_shift_held_while_releasing_is_reported() {
+        let mut trigger = tracker(TriggerKey::Fn);
+        trigger.handle(&EventType::KeyPress(Key::Function));
+        assert!(trigger
+            .handle(&EventType::KeyPress(Key::ShiftLeft))
+            .is_none());
+        assert!(matches!(
+            trigger.handle(&EventType::KeyRelease(Key::Function)),
+            Some(RecordingCommand::FnUp {
+                modifiers: KeyModifiers {
+                    shift: true,
+                    option: false
+                }
+            })
+        ));
+
+        // Shift that is the trigger itself isn't a modifier
+        let mut trigger = tracker(TriggerKey::RightShift);
+        trigger.handle(&EventType::KeyPress(Key::ShiftRight));
+        assert!(matches!(
+            trigger.handle(&EventType::KeyRelease(Key::ShiftRight)),
+            Some(RecordingCommand::FnUp {
+                modifiers: KeyModifiers { shift: false, .. }
+            })
+        ));
+    }
+
+    #[test]
+    fn test_option_counts_unless_part_of_the_trigger() {
+        let mut trigger = tracker(TriggerKey::Fn);
+        trigger.handle(&EventType::KeyPress(Key::Function));
+        trigger.handle(&EventType::KeyPress(Key::Alt));
+        assert!(matches!(
+            trigger.handle(&EventType::KeyRelease(Key::Function)),
+            Some(RecordingCommand::FnUp {
+                modifiers: KeyModifiers { option: true, .. }
+            })
+        ));
+
+        let mut trigger = tracker(TriggerKey::ControlOption);
+        trigger.handle(&EventType::KeyPress(Key::ControlLeft));
+        trigger.handle(&EventType::KeyPress(Key::Alt));
+        assert!(matches!(
+            trigger.handle(&EventType::KeyRelease(Key::Alt)),
+            Some(RecordingCommand::FnUp {
+                modifiers: KeyModifiers { option: false, .. }
+            })
+        ));
+    }
+
+    #[test]
+    fn test_other_keys_are_ignored() {
+        let mut trigger = tracker(TriggerKey::Fn);
+        assert!(trigger.handle(&EventType::KeyPress(Key::KeyA)).is_none());
+        assert!(trigger.handle(&EventType::KeyPress(Key::AltGr)).is_none());
+    }
+
+    #[test]
+    fn test_swallowed_space_takes_repeats_and_release() {
+        let start = Instant::now();
+        let mut space = SwallowedSpace::default();
+
+        assert!(space.key_down(start, false, true));
+        assert!(space.key_down(start + Duration::from_millis(500), true, false));
+        assert!(space.key_up(start + Duration::from_secs(1)));
+
+        // The next press isn't locking, so it passes through entirely
+        assert!(!space.key_down(start + Duration::from_secs(2), false, false));
+        assert!(!space.key_up(start + Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn test_lost_space_release_is_forgotten() {
+        let start = Instant::now();
+        let mut space = SwallowedSpace::default();
+        assert!(space.key_down(start, false, true));
+
+        // Release never arrived; a later, unrelated release passes through
+        assert!(!space.key_up(start + SPACE_STUCK_TIMEOUT));
+
+        // A new press replaces the stuck one instead of counting as a repeat
+        assert!(space.key_down(start, false, true));
+        assert!(!space.key_down(start + Duration::from_secs(1), false, false));
+        assert!(!space.key_up(start + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_space_locks_once_and_only_while_recording() {
+        let mut trigger = tracker(TriggerKey::Fn);
+        let space = EventType::KeyPress(Key::Space);
+
+        assert!(trigger.command(&space, false).is_none());
+        trigger.command(&EventType::KeyRelease(Key::Space), false);
+
+        assert!(matches!(
+            trigger.command(&space, true),
+            Some(RecordingCommand::Lock)
+        ));
+        // Key repeat
+        assert!(trigger.command(&space, true).is_none());
+        trigger.command(&EventType::KeyRelease(Key::Space), true);
+        assert!(trigger.command(&space, true).is_some());
+    }
+
+    #[test]
+    fn test_fallback_limitations_name_the_trigger() {
+        let fn_key = fallback_limitations(TriggerKey::Fn);
+        assert!(fn_key.iter().any(|l| l.contains("emoji picker")));
+
+        let learned = fallback_limitations(TriggerKey::Custom {
+            keycode: 105,
+            modifier_mask: None,
+        });
+        assert!(learned.iter().any(|l| l.contains("learned trigger key")));
+        assert!(!learned.iter().any(|l| l.contains("emoji picker")));
+    }
+
+    #[test]
+    fn test_learned_key_never_fires_in_rdev() {
+        let mut trigger = tracker(TriggerKey::Custom {
+            keycode: 105,
+            modifier_mask: None,
+        });
+        assert!(trigger.handle(&EventType::KeyPress(Key::F13)).is_none());
+        assert!(trigger.handle(&EventType::KeyRelease(Key::F13)).is_none());
+    }
+}
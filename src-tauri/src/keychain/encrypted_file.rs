@@ -0,0 +1,246 @@
+//! Encrypted-file secret store for systems without a keyring.
+//!
+//! All secrets live in one file, sealed with ChaCha20-Poly1305 under a key
+//! derived with Argon2id from a machine-bound secret (the machine id and user
+//! name) and a random per-file salt. This keeps the file unreadable when it's
+//! copied off the machine, e.g. in a backup; it doesn't protect against other
+//! code running as the same user, which a system keyring wouldn't either
+//! without a login password.
+//!
+//! File layout: `MAGIC | VERSION | salt (16) | nonce (12) | ciphertext`, where
+//! the plaintext is a JSON object of account name → secret.
+
+use argon2::Argon2;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use super::secret_store::SecretStore;
+use super::SERVICE;
+
+const MAGIC: &[u8; 4] = b"DCSS";
+const VERSION: u8 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const HEADER_LEN: usize = MAGIC.len() + 1 + SALT_LEN + NONCE_LEN;
+
+fn invalid(reason: impl Into<String>) -> keyring::Error {
+    keyring::Error::Invalid("secrets file".to_string(), reason.into())
+}
+
+fn random_bytes<const N: usize>() -> Result<[u8; N], keyring::Error> {
+    let mut bytes = [0u8; N];
+    getrandom::getrandom(&mut bytes).map_err(|e| keyring::Error::PlatformFailure(Box::new(e)))?;
+    Ok(bytes)
+}
+
+fn derive_key(secret: &[u8], salt: &[u8]) -> Result<[u8; 32], keyring::Error> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(secret, salt, &mut key)
+        .map_err(|e| invalid(format!("key derivation failed: {}", e)))?;
+    Ok(key)
+}
+
+/// Encrypt `plaintext` into the file layout under a fresh salt and nonce
+fn seal(secret: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, keyring::Error> {
+    let salt = random_bytes::<SALT_LEN>()?;
+    let nonce = random_bytes::<NONCE_LEN>()?;
+    let key = derive_key(secret, &salt)?;
+
+    let ciphertext = ChaCha20Poly1305::new(Key::from_slice(&key))
+        .encrypt(Nonce::from_slice(&nonce), plaintext)
+        .map_err(|_| invalid("encryption failed"))?;
+
+    let mut sealed = Vec::with_capacity(HEADER_LEN + ciphertext.len());
+    sealed.extend_from_slice(MAGIC);
+    sealed.push(VERSION);
+    sealed.extend_from_slice(&salt);
+    sealed.extend_from_slice(&nonce);
+    sealed.extend_from_slice(&ciphertext);
+    Ok(sealed)
+}
+
+/// Decrypt a sealed file, failing if it was tampered with or sealed elsewhere
+fn open(secret: &[u8], sealed: &[u8]) -> Result<Vec<u8>, keyring::Error> {
+    if sealed.len() < HEADER_LEN || &sealed[..MAGIC.len()] != MAGIC {
+        return Err(invalid("not a Dictara secrets file"));
+    }
+    if sealed[MAGIC.len()] != VERSION {
+        return Err(invalid(format!(
+            "unsupported version {}",
+            sealed[MAGIC.len()]
+        )));
+    }
+
+    let salt_start = MAGIC.len() + 1;
+    let nonce_start = salt_start + SALT_LEN;
+    let salt = &sealed[salt_start..nonce_start];
+    let nonce = &sealed[nonce_start..HEADER_LEN];
+    let key = derive_key(secret, salt)?;
+
+    ChaCha20Poly1305::new(Key::from_slice(&key))
+        .decrypt(Nonce::from_slice(nonce), &sealed[HEADER_LEN..])
+        .map_err(|_| invalid("could not be decrypted on this machine"))
+}
+
+/// Secret the file key is derived from: stable for this user on this machine
+fn machine_secret() -> Vec<u8> {
+    let machine_id = ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+        .iter()
+        .find_map(|path| fs::read_to_string(path).ok())
+        .unwrap_or_default();
+    let user = std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_default();
+
+    format!("{}:{}:{}", SERVICE, machine_id.trim(), user).into_bytes()
+}
+
+pub struct EncryptedFileStore {
+    path: PathBuf,
+    secret: Vec<u8>,
+    /// Serializes read-modify-write cycles on the file
+    lock: Mutex<()>,
+}
+
+impl EncryptedFileStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self::with_secret(path, machine_secret())
+    }
+
+    fn with_secret(path: PathBuf, secret: Vec<u8>) -> Self {
+        Self {
+            path,
+            secret,
+            lock: Mutex::new(()),
+        }
+    }
+
+    fn read_all(&self) -> Result<HashMap<String, String>, keyring::Error> {
+        let sealed = match fs::read(&self.path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(HashMap::new()),
+            Err(e) => return Err(keyring::Error::NoStorageAccess(Box::new(e))),
+        };
+        let plaintext = open(&self.secret, &sealed)?;
+        serde_json::from_slice(&plaintext).map_err(|e| invalid(e.to_string()))
+    }
+
+    fn write_all(&self, entries: &HashMap<String, String>) -> Result<(), keyring::Error> {
+        let plaintext = serde_json::to_vec(entries).map_err(|e| invalid(e.to_string()))?;
+        let sealed = seal(&self.secret, &plaintext)?;
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(|e| keyring::Error::NoStorageAccess(Box::new(e)))?;
+        }
+
+        // Write next to the target and rename, so a crash can't truncate it
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, sealed).map_err(|e| keyring::Error::PlatformFailure(Box::new(e)))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let _ = fs::set_permissions(&tmp_path, fs::Permissions::from_mode(0o600));
+        }
+        fs::rename(&tmp_path, &self.path).map_err(|e| keyring::Error::PlatformFailure(Box::new(e)))
+    }
+}
+
+impl SecretStore for EncryptedFileStore {
+    fn name(&self) -> &'static str {
+        "encrypted file"
+    }
+
+    fn get(&self, account: &str) -> Result<String, keyring::Error> {
+        let _guard = self.lock.lock().unwrap();
+        self.read_all()?
+            .remove(account)
+            .ok_or(keyring::Error::NoEntry)
+    }
+
+    fn set(&self, account: &str, secret: &str) -> Result<(), keyring::Error> {
+        let _guard = self.lock.lock().unwrap();
+        let mut entries = self.read_all()?;
+        entries.insert(account.to_string(), secret.to_string());
+        self.write_all(&entries)
+    }
+
+    fn delete(&self, account: &str) -> Result<(), keyring::Error> {
+        let _guard = self.lock.lock().unwrap();
+        let mut entries = self.read_all()?;
+        if entries.remove(account).is_none() {
+            return Err(keyring::Error::NoEntry);
+        }
+        self.write_all(&entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_store(name: &str) -> EncryptedFileStore {
+        let path = std::env::temp_dir().join(format!(
+            "dictara-secrets-{}-{}.bin",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_file(&path);
+        EncryptedFileStore::with_secret(path, b"test-secret".to_vec())
+    }
+
+    #[test]
+    fn test_seal_round_trip() {
+        let sealed = seal(b"secret", b"hello").unwrap();
+        assert_eq!(open(b"secret", &sealed).unwrap(), b"hello");
+    }
+
+    #[test]
+    fn test_open_rejects_wrong_secret_and_tampering() {
+        let mut sealed = seal(b"secret", b"hello").unwrap();
+        assert!(open(b"other", &sealed).is_err());
+
+        let last = sealed.len() - 1;
+        sealed[last] ^= 0x01;
+        assert!(open(b"secret", &sealed).is_err());
+    }
+
+    #[test]
+    fn test_store_set_get_delete() {
+        let store = temp_store("crud");
+
+        assert!(matches!(
+            store.get("provider:openai"),
+            Err(keyring::Error::NoEntry)
+        ));
+
+        store
+            .set("provider:openai", "{\"api_key\":\"sk-1\"}")
+            .unwrap();
+        store.set("provider:azure_openai", "{}").unwrap();
+        assert_eq!(
+            store.get("provider:openai").unwrap(),
+            "{\"api_key\":\"sk-1\"}"
+        );
+
+        store.delete("provider:openai").unwrap();
+        assert!(matches!(
+            store.get("provider:openai"),
+            Err(keyring::Error::NoEntry)
+        ));
+        assert!(matches!(
+            store.delete("provider:openai"),
+            Err(keyring::Error::NoEntry)
+        ));
+        assert_eq!(store.get("provider:azure_openai").unwrap(), "{}");
+
+        // Secrets never hit the disk in plain text
+        let raw = fs::read(&store.path).unwrap();
+        let _ = fs::remove_file(&store.path);
+        assert!(!raw.windows(4).any(|w| w == b"sk-1"));
+    }
+}
@@ -0,0 +1,414 @@
+//! Provider configuration storage.
+//!
+//! Configs are stored as JSON through a [`SecretStore`]: the system keyring
+//! when one is available, otherwise an encrypted file (see `encrypted_file`).
+
+mod encrypted_file;
+mod secret_store;
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+use encrypted_file::EncryptedFileStore;
+use secret_store::{SecretStore, SystemKeyring};
+
+#[cfg(debug_assertions)]
+const SERVICE: &str = "app.dictara.dev";
+
+#[cfg(not(debug_assertions))]
+const SERVICE: &str = "app.dictara";
+
+/// Keyring services of builds released under the Typefree name
+const LEGACY_SERVICES: [&str; 2] = ["app.typefree", "app.typefree.dev"];
+
+// Account names for provider configurations
+const OPENAI_CONFIG_ACCOUNT: &str = "provider:openai";
+const AZURE_OPENAI_CONFIG_ACCOUNT: &str = "provider:azure_openai";
+/// Bearer token of the phone intake
+const INTAKE_TOKEN_ACCOUNT: &str = "intake:token";
+
+// Security framework result codes from <Security/SecBase.h>
+const ERR_SEC_INTERACTION_NOT_ALLOWED: &str = "-25308";
+const ERR_SEC_AUTH_FAILED: &str = "-25293";
+const ERR_SEC_USER_CANCELED: &str = "-128";
+const ERR_SEC_NO_SUCH_KEYCHAIN: &str = "-25294";
+const ERR_SEC_NOT_AVAILABLE: &str = "-25291";
+
+/// Why a keychain operation failed, for targeted user messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum KeychainFailure {
+    /// The keychain is locked, or a prompt to unlock it can't be shown
+    Locked,
+    /// The user (or a managed policy) denied access to the item
+    Denied,
+    /// No keychain is available at all
+    Unavailable,
+    Other,
+}
+
+impl KeychainFailure {
+    /// Returns a user-friendly error message suitable for display in the UI
+    pub fn user_message(&self) -> String {
+        match self {
+            KeychainFailure::Locked => "Your keychain is locked. Unlock it in Keychain Access, or paste an API key for this session in Preferences.".to_string(),
+            KeychainFailure::Denied => "Dictara was denied access to the keychain. Allow access when prompted, or paste an API key for this session in Preferences.".to_string(),
+            KeychainFailure::Unavailable => "No keychain is available. Paste an API key for this session in Preferences.".to_string(),
+            KeychainFailure::Other => "Couldn't read the API key from the keychain. Please try again.".to_string(),
+        }
+    }
+}
+
+/// Classify a keyring error by the underlying Security framework result code
+pub fn failure_kind(err: &keyring::Error) -> KeychainFailure {
+    match err {
+        keyring::Error::NoStorageAccess(inner) | keyring::Error::PlatformFailure(inner) => {
+            classify_platform_error(&format!("{} {:?}", inner, inner))
+        }
+        _ => KeychainFailure::Other,
+    }
+}
+
+/// Error text for a failed keychain command: the targeted message when the
+/// keychain is locked or denied, otherwise `context` with the raw error
+pub fn describe_error(err: &keyring::Error, context: &str) -> String {
+    match failure_kind(err) {
+        KeychainFailure::Other => format!("{}: {}", context, err),
+        failure => failure.user_message(),
+    }
+}
+
+/// Check whether the keychain can be read right now.
+/// Returns None when it's accessible.
+pub fn probe() -> Option<KeychainFailure> {
+    match store().get(OPENAI_CONFIG_ACCOUNT) {
+        Ok(_) | Err(keyring::Error::NoEntry) => None,
+        Err(e) => Some(failure_kind(&e)),
+    }
+}
+
+fn classify_platform_error(description: &str) -> KeychainFailure {
+    let lower = description.to_lowercase();
+    if description.contains(ERR_SEC_INTERACTION_NOT_ALLOWED)
+        || lower.contains("interaction is not allowed")
+    {
+        KeychainFailure::Locked
+    } else if description.contains(ERR_SEC_AUTH_FAILED)
+        || description.contains(ERR_SEC_USER_CANCELED)
+        || lower.contains("canceled")
+    {
+        KeychainFailure::Denied
+    } else if description.contains(ERR_SEC_NO_SUCH_KEYCHAIN)
+        || description.contains(ERR_SEC_NOT_AVAILABLE)
+    {
+        KeychainFailure::Unavailable
+    } else {
+        KeychainFailure::Other
+    }
+}
+
+/// File used by the encrypted fallback store, inside the app data directory
+const FALLBACK_FILE: &str = "secrets.bin";
+
+static STORE: OnceLock<Box<dyn SecretStore>> = OnceLock::new();
+
+/// Choose the secret store: the system keyring if it's reachable, otherwise an
+/// encrypted file in `app_data_dir`. Call once at startup before loading configs.
+pub fn init(app_data_dir: PathBuf) {
+    let store: Box<dyn SecretStore> = if SystemKeyring.is_available() {
+        Box::new(SystemKeyring)
+    } else {
+        println!("[Keychain] No system keyring available, using encrypted file store");
+        Box::new(EncryptedFileStore::new(app_data_dir.join(FALLBACK_FILE)))
+    };
+
+    println!("[Keychain] Storing secrets in {}", store.name());
+    if STORE.set(store).is_err() {
+        eprintln!("[Keychain] Secret store already initialized");
+    }
+}
+
+fn store() -> &'static dyn SecretStore {
+    STORE.get_or_init(|| Box::new(SystemKeyring)).as_ref()
+}
+
+/// Configs pasted for the current run only, keyed by account name. Used when
+/// the keychain can't be read, e.g. a locked keychain on a managed Mac.
+fn session_configs() -> &'static Mutex<HashMap<&'static str, String>> {
+    static SESSION: OnceLock<Mutex<HashMap<&'static str, String>>> = OnceLock::new();
+    SESSION.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum ProviderAccount {
+    OpenAI,
+    AzureOpenAI,
+}
+
+impl ProviderAccount {
+    const ALL: [ProviderAccount; 2] = [ProviderAccount::OpenAI, ProviderAccount::AzureOpenAI];
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            ProviderAccount::OpenAI => OPENAI_CONFIG_ACCOUNT,
+            ProviderAccount::AzureOpenAI => AZURE_OPENAI_CONFIG_ACCOUNT,
+        }
+    }
+}
+
+/// Keep provider configuration in memory for the current run, without touching
+/// the keychain. It takes precedence over the keychain until the app quits.
+pub fn set_session_config<T: Serialize>(
+    account: ProviderAccount,
+    config: &T,
+) -> Result<(), keyring::Error> {
+    let json = serde_json::to_string(config).map_err(|e| {
+        keyring::Error::Invalid("config".to_string(), format!("Failed to serialize: {}", e))
+    })?;
+    session_configs()
+        .lock()
+        .unwrap()
+        .insert(account.as_str(), json);
+    println!("[Keychain] Session config set ({})", account.as_str());
+    Ok(())
+}
+
+/// Save provider configuration as JSON to keychain
+pub fn save_provider_config<T: Serialize>(
+    account: ProviderAccount,
+    config: &T,
+) -> Result<(), keyring::Error> {
+    let account_name = account.as_str();
+
+    let json = serde_json::to_string(config).map_err(|e| {
+        eprintln!(
+            "[Keychain] ❌ Failed to serialize config ({}): {:?}",
+            account_name, e
+        );
+        keyring::Error::Invalid("config".to_string(), format!("Failed to serialize: {}", e))
+    })?;
+
+    match store().set(account_name, &json) {
+        Ok(()) => {
+            // The saved config replaces any key pasted for this session
+            session_configs().lock().unwrap().remove(account_name);
+            println!(
+                "[Keychain] ✅ Config saved successfully to {} ({})",
+                store().name(),
+                account_name
+            );
+            Ok(())
+        }
+        Err(e) => {
+            eprintln!(
+                "[Keychain] ❌ Failed to save config ({}): {:?}",
+                account_name, e
+            );
+            Err(e)
+        }
+    }
+}
+
+/// Load provider configuration from keychain as JSON
+pub fn load_provider_config<T: DeserializeOwned>(
+    account: ProviderAccount,
+) -> Result<Option<T>, keyring::Error> {
+    let account_name = account.as_str();
+    println!("[Keychain] Attempting to load config ({})", account_name);
+
+    if let Some(json) = session_configs().lock().unwrap().get(account_name) {
+        println!("[Keychain] Using session config ({})", account_name);
+        return serde_json::from_str(json).map(Some).map_err(|e| {
+            keyring::Error::Invalid(
+                "config".to_string(),
+                format!("Failed to deserialize: {}", e),
+            )
+        });
+    }
+
+    match store().get(account_name) {
+        Ok(json) => {
+            println!(
+                "[Keychain] ✅ Config loaded successfully (length: {}, account: {})",
+                json.len(),
+                account_name
+            );
+
+            let config: T = serde_json::from_str(&json).map_err(|e| {
+                eprintln!(
+                    "[Keychain] ❌ Failed to deserialize config ({}): {:?}",
+                    account_name, e
+                );
+                keyring::Error::Invalid(
+                    "config".to_string(),
+                    format!("Failed to deserialize: {}", e),
+                )
+            })?;
+
+            Ok(Some(config))
+        }
+        Err(keyring::Error::NoEntry) => {
+            println!(
+                "[Keychain] ℹ️  No config found in keychain ({})",
+                account_name
+            );
+            Ok(None)
+        }
+        Err(e) => {
+            eprintln!(
+                "[Keychain] ❌ Error loading config ({}, {:?}): {:?}",
+                account_name,
+                failure_kind(&e),
+                e
+            );
+            Err(e)
+        }
+    }
+}
+
+/// Move provider configs from Typefree keyring entries into the current store,
+/// keeping any config the current store already has. Legacy entries are
+/// deleted either way. Returns how many configs were imported.
+pub fn import_legacy_configs() -> usize {
+    let mut imported = 0;
+    for service in LEGACY_SERVICES {
+        for account in ProviderAccount::ALL {
+            let account_name = account.as_str();
+            let Ok(entry) = keyring::Entry::new(service, account_name) else {
+                continue;
+            };
+            let json = match entry.get_password() {
+                Ok(json) => json,
+                Err(keyring::Error::NoEntry) => continue,
+                Err(e) => {
+                    eprintln!(
+                        "[Keychain] Failed to read legacy config ({}, {}): {:?}",
+                        service, account_name, e
+                    );
+                    continue;
+                }
+            };
+
+            match store().get(account_name) {
+                Err(keyring::Error::NoEntry) => {
+                    if let Err(e) = store().set(account_name, &json) {
+                        eprintln!(
+                            "[Keychain] Failed to import legacy config ({}): {:?}",
+                            account_name, e
+                        );
+                        continue;
+                    }
+                    println!("[Keychain] Imported legacy config ({})", account_name);
+                    imported += 1;
+                }
+                Ok(_) => println!(
+                    "[Keychain] Keeping existing config over legacy one ({})",
+                    account_name
+                ),
+                Err(e) => {
+                    // Keep the legacy entry when the current store can't be checked
+                    eprintln!(
+                        "[Keychain] Failed to check current config ({}): {:?}",
+                        account_name, e
+                    );
+                    continue;
+                }
+            }
+
+            if let Err(e) = entry.delete_credential() {
+                eprintln!(
+                    "[Keychain] Failed to delete legacy config ({}, {}): {:?}",
+                    service, account_name, e
+                );
+            }
+        }
+    }
+    imported
+}
+
+/// Load the phone intake's bearer token
+pub fn load_intake_token() -> Result<Option<String>, keyring::Error> {
+    match store().get(INTAKE_TOKEN_ACCOUNT) {
+        Ok(token) => Ok(Some(token)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => {
+            eprintln!("[Keychain] ❌ Error loading the intake token: {:?}", e);
+            Err(e)
+        }
+    }
+}
+
+/// Save the phone intake's bearer token
+pub fn save_intake_token(token: &str) -> Result<(), keyring::Error> {
+    store().set(INTAKE_TOKEN_ACCOUNT, token)?;
+    println!("[Keychain] ✅ Intake token saved to {}", store().name());
+    Ok(())
+}
+
+/// Delete provider configuration from keychain (and any session copy)
+pub fn delete_provider_config(account: ProviderAccount) -> Result<(), keyring::Error> {
+    let account_name = account.as_str();
+    session_configs().lock().unwrap().remove(account_name);
+    println!("[Keychain] Attempting to delete config ({})", account_name);
+
+    match store().delete(account_name) {
+        Ok(()) => {
+            println!(
+                "[Keychain] ✅ Config deleted successfully ({})",
+                account_name
+            );
+            Ok(())
+        }
+        Err(keyring::Error::NoEntry) => {
+            println!(
+                "[Keychain] ℹ️  No config to delete (not found, {})",
+                account_name
+            );
+            Ok(())
+        }
+        Err(e) => {
+            eprintln!(
+                "[Keychain] ❌ Error deleting config ({}): {:?}",
+                account_name, e
+            );
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classifies_security_framework_codes() {
+        assert_eq!(
+            classify_platform_error("User interaction is not allowed. Error { code: -25308 }"),
+            KeychainFailure::Locked
+        );
+        assert_eq!(
+            classify_platform_error(
+                "The user name or passphrase you entered is not correct. Error { code: -25293 }"
+            ),
+            KeychainFailure::Denied
+        );
+        assert_eq!(
+            classify_platform_error("Error { code: -25294 }"),
+            KeychainFailure::Unavailable
+        );
+        assert_eq!(
+            classify_platform_error("Something else. Error { code: -50 }"),
+            KeychainFailure::Other
+        );
+    }
+
+    #[test]
+    fn test_non_platform_errors_are_other() {
+        assert_eq!(
+            failure_kind(&keyring::Error::NoEntry),
+            KeychainFailure::Other
+        );
+    }
+}
@@ -0,0 +1,61 @@
+//! Storage backends for provider secrets.
+//!
+//! The system keyring (macOS Keychain, Windows Credential Manager, the Secret
+//! Service on Linux) is used whenever it's reachable. Environments without one,
+//! e.g. minimal Linux installs, fall back to an encrypted file in the app data
+//! directory. Errors use `keyring::Error` for every backend, so callers and
+//! [`super::failure_kind`] don't care which one is active.
+
+use keyring::Entry;
+
+use super::SERVICE;
+
+/// A place to keep secrets, addressed by account name.
+///
+/// Mirrors the keyring crate: missing items are `keyring::Error::NoEntry` for
+/// both `get` and `delete`.
+pub trait SecretStore: Send + Sync {
+    /// Human-readable backend name for logs
+    fn name(&self) -> &'static str;
+    fn get(&self, account: &str) -> Result<String, keyring::Error>;
+    fn set(&self, account: &str, secret: &str) -> Result<(), keyring::Error>;
+    fn delete(&self, account: &str) -> Result<(), keyring::Error>;
+}
+
+/// The platform keyring via the keyring crate
+pub struct SystemKeyring;
+
+impl SystemKeyring {
+    /// Whether the keyring can be reached at all. A missing item still counts
+    /// as available; a locked macOS keychain does too, since it can be unlocked.
+    pub fn is_available(&self) -> bool {
+        if cfg!(target_os = "macos") {
+            return true;
+        }
+        matches!(self.get("probe"), Ok(_) | Err(keyring::Error::NoEntry))
+    }
+}
+
+impl SecretStore for SystemKeyring {
+    fn name(&self) -> &'static str {
+        if cfg!(target_os = "macos") {
+            "macOS Keychain"
+        } else if cfg!(target_os = "windows") {
+            "Windows Credential Manager"
+        } else {
+            "Secret Service"
+        }
+    }
+
+    fn get(&self, account: &str) -> Result<String, keyring::Error> {
+        Entry::new(SERVICE, account)?.get_password()
+    }
+
+    fn set(&self, account: &str, secret: &str) -> Result<(), keyring::Error> {
+        Entry::new(SERVICE, account)?.set_password(secret)
+    }
+
+    fn delete(&self, account: &str) -> Result<(), keyring::Error> {
+        Entry::new(SERVICE, account)?.delete_credential()
+    }
+}
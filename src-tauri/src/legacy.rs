@@ -0,0 +1,402 @@
+//! Import from builds released under the Typefree name.
+//!
+//! Those builds kept their config store, recordings and keychain entries under
+//! the `app.typefree` identifiers. At launch, leftovers are detected on disk
+//! (the keychain isn't touched, since reading another identifier's items can
+//! prompt) and the user is asked once whether to import them:
+//!
+//! - the config store's `app_config`, unless Dictara already has one
+//! - provider keys, unless Dictara already has a key for that provider
+//! - recordings that were never transcribed, moved to `typefree-recordings`
+//!   in the app data folder, ready for batch transcription
+//!
+//! Imported files are removed from the old locations, and a folder goes once
+//! nothing is left in it. A config that was kept out, a recording that
+//! couldn't be moved and anything the import doesn't read stay where they
+//! were. The answer is kept as `legacy_import_offered` in the config store, so
+//! declining isn't asked again.
+
+use crate::config::AppConfig;
+use crate::keychain;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use tauri_plugin_store::StoreExt;
+
+/// Bundle identifiers of release and dev Typefree builds
+const LEGACY_IDENTIFIERS: [&str; 2] = ["app.typefree", "app.typefree.dev"];
+
+const OFFERED_KEY: &str = "legacy_import_offered";
+
+/// Folder in the app data directory that receives legacy recordings
+const IMPORTED_RECORDINGS_DIR: &str = "typefree-recordings";
+
+/// What a Typefree build left on disk
+#[derive(Debug, PartialEq)]
+struct LegacyInstall {
+    data_dir: PathBuf,
+    cache_dir: PathBuf,
+    /// The old config store, if there is one
+    config_file: Option<PathBuf>,
+    recordings: Vec<PathBuf>,
+}
+
+impl LegacyInstall {
+    fn is_empty(&self) -> bool {
+        self.config_file.is_none() && self.recordings.is_empty()
+    }
+}
+
+/// Look for a Typefree install with `identifier` next to Dictara's own
+/// data and cache folders
+fn find_install(data_root: &Path, cache_root: &Path, identifier: &str) -> Option<LegacyInstall> {
+    let data_dir = data_root.join(identifier);
+    let cache_dir = cache_root.join(identifier);
+
+    let config_file = Some(data_dir.join("config.json")).filter(|path| path.is_file());
+    let mut recordings: Vec<PathBuf> = fs::read_dir(cache_dir.join("recordings"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "wav"))
+        .collect();
+    recordings.sort();
+
+    let install = LegacyInstall {
+        data_dir,
+        cache_dir,
+        config_file,
+        recordings,
+    };
+    (!install.is_empty()).then_some(install)
+}
+
+fn describe(installs: &[LegacyInstall]) -> String {
+    let has_config = installs.iter().any(|i| i.config_file.is_some());
+    let recordings: usize = installs.iter().map(|i| i.recordings.len()).sum();
+
+    let mut found = Vec::new();
+    if has_config {
+        found.push("settings and API keys".to_string());
+    }
+    if recordings > 0 {
+        found.push(format!(
+            "{} untranscribed recording{}",
+            recordings,
+            if recordings == 1 { "" } else { "s" }
+        ));
+    }
+    format!(
+        "Dictara found {} from Typefree, its earlier name.\n\nImport them? The old copies are removed afterwards.",
+        found.join(" and ")
+    )
+}
+
+/// Check for Typefree leftovers and ask once whether to import them
+pub fn offer_import(app_handle: &tauri::AppHandle) {
+    let store = match app_handle.store("config.json") {
+        Ok(store) => store,
+        Err(e) => {
+            eprintln!("[Legacy] Failed to open config store: {}", e);
+            return;
+        }
+    };
+    if store.get(OFFERED_KEY).is_some() {
+        return;
+    }
+
+    let path = app_handle.path();
+    let (Some(data_root), Some(cache_root)) = (
+        path.app_data_dir()
+            .ok()
+            .and_then(|d| d.parent().map(Path::to_path_buf)),
+        path.app_cache_dir()
+            .ok()
+            .and_then(|d| d.parent().map(Path::to_path_buf)),
+    ) else {
+        return;
+    };
+
+    let installs: Vec<LegacyInstall> = LEGACY_IDENTIFIERS
+        .iter()
+        .filter_map(|identifier| find_install(&data_root, &cache_root, identifier))
+        .collect();
+    if installs.is_empty() {
+        return;
+    }
+    println!("[Legacy] Found Typefree data: {:?}", installs);
+
+    let app = app_handle.clone();
+    app_handle
+        .dialog()
+        .message(describe(&installs))
+        .title("Import from Typefree")
+        .kind(MessageDialogKind::Info)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Import".to_string(),
+            "Don't Import".to_string(),
+        ))
+        .show(move |accepted| {
+            store.set(OFFERED_KEY, true);
+            if let Err(e) = store.save() {
+                eprintln!("[Legacy] Failed to save import answer: {}", e);
+            }
+            if !accepted {
+                println!("[Legacy] User declined the Typefree import");
+                return;
+            }
+
+            let config_imported = import(&app, &installs);
+            // Settings are read at startup, so restart to apply the imported ones
+            if config_imported {
+                app.restart();
+            }
+        });
+}
+
+/// Import everything and remove what was imported. Returns whether a config
+/// was imported.
+fn import(app_handle: &tauri::AppHandle, installs: &[LegacyInstall]) -> bool {
+    let keys = keychain::import_legacy_configs();
+    println!("[Legacy] Imported {} provider key(s)", keys);
+
+    let mut config_imported = false;
+    for install in installs {
+        let config_done = install
+            .config_file
+            .as_deref()
+            .is_some_and(|config_file| import_config(app_handle, config_file));
+        config_imported |= config_done;
+        import_recordings(app_handle, &install.recordings);
+        remove_imported(install, config_done);
+    }
+    config_imported
+}
+
+/// Remove the old config if `config_imported`, then the folders the import
+/// read from that are left empty. Recordings were moved already.
+fn remove_imported(install: &LegacyInstall, config_imported: bool) {
+    if let Some(config_file) = &install.config_file {
+        if config_imported {
+            if let Err(e) = fs::remove_file(config_file) {
+                eprintln!("[Legacy] Failed to remove {:?}: {}", config_file, e);
+            }
+        }
+        remove_if_empty(&install.data_dir);
+    }
+    if !install.recordings.is_empty() {
+        remove_if_empty(&install.cache_dir.join("recordings"));
+        remove_if_empty(&install.cache_dir);
+    }
+}
+
+/// Remove `dir` if nothing is left in it
+fn remove_if_empty(dir: &Path) {
+    let empty = fs::read_dir(dir).is_ok_and(|mut entries| entries.next().is_none());
+    if !empty {
+        println!(
+            "[Legacy] Keeping {:?}, not everything in it was imported",
+            dir
+        );
+    } else if let Err(e) = fs::remove_dir(dir) {
+        eprintln!("[Legacy] Failed to remove {:?}: {}", dir, e);
+    }
+}
+
+/// Copy `app_config` from the old store unless Dictara has its own. Returns
+/// whether it was imported.
+fn import_config(app_handle: &tauri::AppHandle, config_file: &Path) -> bool {
+    let Ok(store) = app_handle.store("config.json") else {
+        return false;
+    };
+    if store.get("app_config").is_some() {
+        println!("[Legacy] Keeping the existing config instead of Typefree's");
+        return false;
+    }
+
+    let legacy: Option<AppConfig> = fs::read_to_string(config_file)
+        .ok()
+        .and_then(|json| serde_json::from_str::<serde_json::Value>(&json).ok())
+        .and_then(|mut value| value.get_mut("app_config").map(serde_json::Value::take))
+        .and_then(|value| serde_json::from_value(value).ok());
+    let Some(legacy) = legacy else {
+        eprintln!("[Legacy] No readable config in {:?}", config_file);
+        return false;
+    };
+
+    match crate::config::save_app_config(&store, &legacy) {
+        Ok(()) => {
+            println!("[Legacy] Imported Typefree config");
+            true
+        }
+        Err(e) => {
+            eprintln!("[Legacy] Failed to save imported config: {}", e);
+            false
+        }
+    }
+}
+
+/// A path in `dir` for `name` that doesn't exist yet, numbered on a collision
+fn free_path(dir: &Path, name: &Path) -> PathBuf {
+    let stem = name.file_stem().unwrap_or_default().to_string_lossy();
+    let extension = name
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+    let mut path = dir.join(name);
+    let mut n = 1;
+    while path.exists() {
+        path = dir.join(format!("{}-{}{}", stem, n, extension));
+        n += 1;
+    }
+    path
+}
+
+/// Move recordings to `typefree-recordings`. A recording that couldn't be
+/// moved stays where it was.
+fn import_recordings(app_handle: &tauri::AppHandle, recordings: &[PathBuf]) {
+    if recordings.is_empty() {
+        return;
+    }
+    let Ok(target) = app_handle
+        .path()
+        .app_data_dir()
+        .map(|dir| dir.join(IMPORTED_RECORDINGS_DIR))
+    else {
+        return;
+    };
+    if let Err(e) = fs::create_dir_all(&target) {
+        eprintln!("[Legacy] Failed to create {:?}: {}", target, e);
+        return;
+    }
+
+    let mut moved = 0;
+    for recording in recordings {
+        let Some(name) = recording.file_name() else {
+            continue;
+        };
+        let destination = free_path(&target, Path::new(name));
+        // Fall back to copying when the cache is on another volume
+        let result = fs::rename(recording, &destination).or_else(|_| {
+            fs::copy(recording, &destination)?;
+            fs::remove_file(recording)
+        });
+        match result {
+            Ok(()) => moved += 1,
+            Err(e) => eprintln!("[Legacy] Failed to import {:?}: {}", recording, e),
+        }
+    }
+    println!(
+        "[Legacy] Moved {} of {} recording(s) to {:?}",
+        moved,
+        recordings.len(),
+        target
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_finds_config_and_recordings() {
+        let root = std::env::temp_dir().join(format!("dictara-legacy-{}", std::process::id()));
+        let (data_root, cache_root) = (root.join("data"), root.join("cache"));
+        let recordings = cache_root.join("app.typefree/recordings");
+        fs::create_dir_all(data_root.join("app.typefree")).unwrap();
+        fs::create_dir_all(&recordings).unwrap();
+        fs::write(data_root.join("app.typefree/config.json"), "{}").unwrap();
+        fs::write(recordings.join("recording_2.wav"), b"").unwrap();
+        fs::write(recordings.join("recording_1.wav"), b"").unwrap();
+        fs::write(recordings.join("in-progress.json"), b"").unwrap();
+
+        let install = find_install(&data_root, &cache_root, "app.typefree");
+        let missing = find_install(&data_root, &cache_root, "app.typefree.dev");
+        let _ = fs::remove_dir_all(&root);
+
+        let install = install.unwrap();
+        assert_eq!(
+            install.config_file,
+            Some(data_root.join("app.typefree/config.json"))
+        );
+        assert_eq!(
+            install.recordings,
+            [
+                recordings.join("recording_1.wav"),
+                recordings.join("recording_2.wav")
+            ]
+        );
+        assert_eq!(missing, None);
+        assert_eq!(
+            describe(&[install]),
+            "Dictara found settings and API keys and 2 untranscribed recordings from Typefree, its earlier name.\n\nImport them? The old copies are removed afterwards."
+        );
+    }
+
+    #[test]
+    fn test_removes_only_what_was_imported() {
+        let root = std::env::temp_dir().join(format!("dictara-legacy-rm-{}", std::process::id()));
+        let install = LegacyInstall {
+            data_dir: root.join("data/app.typefree"),
+            cache_dir: root.join("cache/app.typefree"),
+            config_file: Some(root.join("data/app.typefree/config.json")),
+            recordings: vec![root.join("cache/app.typefree/recordings/recording_1.wav")],
+        };
+        fs::create_dir_all(install.data_dir.join("logs")).unwrap();
+        fs::create_dir_all(install.cache_dir.join("recordings")).unwrap();
+        fs::write(install.data_dir.join("config.json"), "{}").unwrap();
+        fs::write(install.data_dir.join("logs/typefree.log"), b"").unwrap();
+
+        remove_imported(&install, true);
+        let config_left = install.data_dir.join("config.json").exists();
+        let logs_left = install.data_dir.join("logs/typefree.log").exists();
+        let cache_left = install.cache_dir.exists();
+        let _ = fs::remove_dir_all(&root);
+
+        assert!(!config_left);
+        assert!(logs_left);
+        assert!(!cache_left);
+    }
+
+    #[test]
+    fn test_keeps_data_folder_without_a_config() {
+        let root = std::env::temp_dir().join(format!("dictara-legacy-keep-{}", std::process::id()));
+        let install = LegacyInstall {
+            data_dir: root.join("data/app.typefree"),
+            cache_dir: root.join("cache/app.typefree"),
+            config_file: None,
+            recordings: vec![root.join("cache/app.typefree/recordings/recording_1.wav")],
+        };
+        fs::create_dir_all(&install.data_dir).unwrap();
+        fs::create_dir_all(install.cache_dir.join("recordings")).unwrap();
+        fs::write(install.cache_dir.join("recordings/in-progress.json"), b"").unwrap();
+
+        remove_imported(&install, false);
+        let data_left = install.data_dir.exists();
+        let unknown_left = install
+            .cache_dir
+            .join("recordings/in-progress.json")
+            .exists();
+        let _ = fs::remove_dir_all(&root);
+
+        assert!(data_left);
+        assert!(unknown_left);
+    }
+
+    #[test]
+    fn test_free_path_never_overwrites() {
+        let dir = std::env::temp_dir().join(format!("dictara-legacy-free-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("recording_1.wav"), b"").unwrap();
+        fs::write(dir.join("recording_1-1.wav"), b"").unwrap();
+
+        let taken = free_path(&dir, Path::new("recording_1.wav"));
+        let free = free_path(&dir, Path::new("recording_2.wav"));
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(taken, dir.join("recording_1-2.wav"));
+        assert_eq!(free, dir.join("recording_2.wav"));
+    }
+}
@@ -1,23 +1,102 @@
+// Without the app, parts of the shared modules that only the app calls go
+// unused. Building with `--no-default-features` leaves them in as they are.
+#![cfg_attr(not(feature = "gui"), allow(dead_code))]
+
+mod action_items;
+#[cfg(feature = "gui")]
+mod actions;
+#[cfg(feature = "gui")]
+mod announcements;
+pub mod api;
+mod batch;
+#[cfg(feature = "gui")]
+mod calendar;
 mod clients;
 mod clipboard_paste;
 mod config;
+#[cfg(feature = "gui")]
+mod conflicts;
+#[cfg(feature = "gui")]
+mod copied_file;
+#[cfg(feature = "gui")]
+mod display_options;
+#[cfg(feature = "gui")]
+mod energy;
+#[cfg(feature = "gui")]
 mod error;
+#[cfg(feature = "gui")]
+mod events;
+mod filler_words;
+mod frontmost;
+#[cfg(feature = "gui")]
+mod history;
+#[cfg(feature = "gui")]
+mod intake;
+#[cfg(feature = "gui")]
 mod keyboard_listener;
 mod keychain;
+#[cfg(feature = "gui")]
+mod legacy;
+mod llm_cleanup;
+mod logging;
+#[cfg(feature = "gui")]
+mod onboarding;
+mod output;
+#[cfg(feature = "gui")]
+mod paste_diagnosis;
+#[cfg(feature = "gui")]
+mod permissions;
+mod pipeline;
+mod plugins;
+mod recent_context;
+#[cfg(feature = "gui")]
 mod recording;
+#[cfg(feature = "gui")]
+mod rewrite;
+#[cfg(feature = "gui")]
+mod rule_packs;
+#[cfg(feature = "gui")]
+mod session;
+#[cfg(feature = "gui")]
 mod setup;
+#[cfg(feature = "gui")]
+mod sound_player;
+#[cfg(feature = "gui")]
+mod speech;
+#[cfg(feature = "gui")]
+mod stats;
+mod subtitles;
+#[cfg(feature = "gui")]
+mod summary;
+#[cfg(feature = "gui")]
+mod supervisor;
+mod system_context;
+#[cfg(feature = "gui")]
 mod tauri_commands;
+mod templates;
+#[cfg(feature = "gui")]
 mod ui;
+#[cfg(feature = "gui")]
 mod updater;
+#[cfg(feature = "gui")]
+mod validation;
+#[cfg(feature = "gui")]
+mod watch_folder;
+mod wav;
 
 /// Build the tauri-specta builder for type-safe commands and events
+#[cfg(feature = "gui")]
 fn build_specta_builder() -> tauri_specta::Builder<tauri::Wry> {
     tauri_specta::Builder::<tauri::Wry>::new()
         // Commands with specta support (type-safe bindings will be generated)
         .commands(tauri_specta::collect_commands![
             // App configuration
             tauri_commands::load_app_config,
+            tauri_commands::get_managed_config,
             tauri_commands::save_app_config,
+            tauri_commands::update_app_config,
+            tauri_commands::learn_trigger_key,
+            tauri_commands::describe_pipeline,
             // OpenAI provider
             tauri_commands::load_openai_config,
             tauri_commands::save_openai_config,
@@ -28,22 +107,98 @@ fn build_specta_builder() -> tauri_specta::Builder<tauri::Wry> {
             tauri_commands::save_azure_openai_config,
             tauri_commands::delete_azure_openai_config,
             tauri_commands::test_azure_openai_config,
+            tauri_commands::validate_provider_config,
+            tauri_commands::get_provider_catalog,
+            // Keychain
+            tauri_commands::get_keychain_status,
+            tauri_commands::get_intake_token,
+            tauri_commands::set_session_api_key,
+            tauri_commands::get_reauth_providers,
             // Recording
+            tauri_commands::start_recording,
             tauri_commands::stop_recording,
+            tauri_commands::toggle_recording,
             tauri_commands::cancel_recording,
+            tauri_commands::start_rewrite_selection,
             tauri_commands::retry_transcription,
+            tauri_commands::deliver_held_transcript,
+            tauri_commands::discard_held_transcript,
             tauri_commands::dismiss_error,
             tauri_commands::resize_popup_for_error,
+            tauri_commands::set_popup_layout,
             tauri_commands::register_audio_level_channel,
+            // Onboarding
+            tauri_commands::start_onboarding_practice,
+            tauri_commands::stop_onboarding_practice,
+            tauri_commands::restart_for_onboarding,
+            tauri_commands::get_onboarding_resume,
+            tauri_commands::validate_provider_config_live,
+            tauri_commands::cancel_live_validation,
+            // Batch
+            tauri_commands::transcribe_folder,
+            tauri_commands::transcribe_copied_file,
+            tauri_commands::list_actions,
+            tauri_commands::invoke_action,
+            tauri_commands::open_command_palette,
+            // Stats
+            tauri_commands::get_usage_stats,
+            tauri_commands::get_stats_summary,
+            tauri_commands::export_usage_stats,
+            tauri_commands::export_rule_pack,
+            tauri_commands::preview_rule_pack,
+            tauri_commands::import_rule_pack,
+            // History
+            tauri_commands::get_history,
+            tauri_commands::clear_history,
+            tauri_commands::export_history,
+            // Calendar
+            tauri_commands::request_calendar_access,
+            // Sounds
+            tauri_commands::get_output_devices,
+            tauri_commands::get_voices,
+            tauri_commands::read_back_last_recording,
+            // Diagnostics
+            tauri_commands::detect_dictation_conflicts,
+            tauri_commands::get_fn_key_usage,
+            tauri_commands::disable_fn_key_action,
+            tauri_commands::get_permission_status,
+            tauri_commands::get_display_options,
+            tauri_commands::get_state_journal,
+            tauri_commands::cancel_all_transcriptions,
+            tauri_commands::get_component_health,
+            tauri_commands::get_input_limitations,
+            tauri_commands::get_input_capture_status,
+            tauri_commands::get_energy_stats,
             // Updater
             updater::check_for_updates,
         ])
         // Events with specta support (type-safe bindings will be generated)
         .events(tauri_specta::collect_events![
-            recording::events::RecordingStateChanged,
+            events::RecordingStateChanged,
+            events::RecordingLocked,
+            events::RecordingControls,
+            events::PasteFailed,
+            events::PasteDiagnosed,
+            events::BatchProgress,
+            events::WatchFolderTranscribed,
+            events::IntakeTranscribed,
+            events::BatchFinished,
+            events::ProviderAuthChanged,
+            events::DictationCompleted,
+            events::OnboardingPracticeTranscript,
+            events::OnboardingStepCompleted,
+            events::HistoryChanged,
+            events::StatsUpdated,
+            events::PermissionStatus,
+            events::DisplayOptions,
+            events::TrayMenuChanged,
+            events::ComponentHealth,
+            events::InputDegraded,
+            events::InputCaptureStatus,
         ])
 }
 
+#[cfg(feature = "gui")]
 pub fn run() {
     // Load environment variables from .env file
     dotenvy::dotenv().ok();
@@ -76,11 +231,18 @@ pub fn run() {
             tauri_commands::check_accessibility_permission,
             tauri_commands::request_accessibility_permission,
             tauri_commands::restart_app,
+            tauri_commands::start_recording,
             tauri_commands::stop_recording,
+            tauri_commands::toggle_recording,
             tauri_commands::cancel_recording,
+            tauri_commands::start_rewrite_selection,
             // App configuration
             tauri_commands::load_app_config,
+            tauri_commands::get_managed_config,
             tauri_commands::save_app_config,
+            tauri_commands::update_app_config,
+            tauri_commands::learn_trigger_key,
+            tauri_commands::describe_pipeline,
             // OpenAI provider
             tauri_commands::load_openai_config,
             tauri_commands::save_openai_config,
@@ -91,12 +253,64 @@ pub fn run() {
             tauri_commands::save_azure_openai_config,
             tauri_commands::delete_azure_openai_config,
             tauri_commands::test_azure_openai_config,
+            tauri_commands::validate_provider_config,
+            tauri_commands::get_provider_catalog,
+            // Keychain
+            tauri_commands::get_keychain_status,
+            tauri_commands::get_intake_token,
+            tauri_commands::set_session_api_key,
+            tauri_commands::get_reauth_providers,
             // Audio
             tauri_commands::register_audio_level_channel,
+            // Batch
+            tauri_commands::transcribe_folder,
+            tauri_commands::transcribe_copied_file,
+            tauri_commands::list_actions,
+            tauri_commands::invoke_action,
+            tauri_commands::open_command_palette,
             // Error handling
             tauri_commands::retry_transcription,
+            tauri_commands::deliver_held_transcript,
+            tauri_commands::discard_held_transcript,
             tauri_commands::dismiss_error,
             tauri_commands::resize_popup_for_error,
+            tauri_commands::set_popup_layout,
+            // Onboarding
+            tauri_commands::start_onboarding_practice,
+            tauri_commands::stop_onboarding_practice,
+            tauri_commands::restart_for_onboarding,
+            tauri_commands::get_onboarding_resume,
+            tauri_commands::validate_provider_config_live,
+            tauri_commands::cancel_live_validation,
+            // Stats
+            tauri_commands::get_usage_stats,
+            tauri_commands::get_stats_summary,
+            tauri_commands::export_usage_stats,
+            tauri_commands::export_rule_pack,
+            tauri_commands::preview_rule_pack,
+            tauri_commands::import_rule_pack,
+            // History
+            tauri_commands::get_history,
+            tauri_commands::clear_history,
+            tauri_commands::export_history,
+            // Calendar
+            tauri_commands::request_calendar_access,
+            // Sounds
+            tauri_commands::get_output_devices,
+            tauri_commands::get_voices,
+            tauri_commands::read_back_last_recording,
+            // Diagnostics
+            tauri_commands::detect_dictation_conflicts,
+            tauri_commands::get_fn_key_usage,
+            tauri_commands::disable_fn_key_action,
+            tauri_commands::get_permission_status,
+            tauri_commands::get_display_options,
+            tauri_commands::get_state_journal,
+            tauri_commands::cancel_all_transcriptions,
+            tauri_commands::get_component_health,
+            tauri_commands::get_input_limitations,
+            tauri_commands::get_input_capture_status,
+            tauri_commands::get_energy_stats,
             // Updater
             updater::check_for_updates
         ])
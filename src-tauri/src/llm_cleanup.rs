@@ -0,0 +1,71 @@
+//! Transcript cleanup by the chat model.
+//!
+//! The `llm_cleanup` pipeline step sends the transcript with
+//! `llm.cleanup_prompt` as instructions and uses the model's reply instead.
+//! The prompt is a template, so it can bring in what's on screen: "Turn this
+//! into a reply to the email below.\n{selection}" makes a dictated answer a
+//! finished reply. With `recent_context` on, the model also sees the latest
+//! dictations into the same app, to keep their terminology. Off by default,
+//! and skipped in the managed offline mode. If the request fails the
+//! transcript goes on unchanged.
+
+use crate::clients::{dry_run, llm};
+use crate::config;
+use crate::frontmost;
+use crate::pipeline::{PipelineContext, TranscriptMiddleware};
+use crate::recent_context;
+use crate::system_context;
+use crate::templates::{self, TemplateVars};
+
+pub struct LlmCleanup;
+
+impl TranscriptMiddleware for LlmCleanup {
+    fn id(&self) -> String {
+        "llm_cleanup".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Have the chat model clean up the transcript with your prompt".to_string()
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        false
+    }
+
+    fn process(&self, text: String, ctx: &PipelineContext) -> String {
+        if text.trim().is_empty() || dry_run::is_enabled(ctx.config) {
+            return text;
+        }
+        if config::managed::current().offline {
+            println!("[LLM Cleanup] Offline mode is enforced, skipping");
+            return text;
+        }
+
+        let template = &ctx.config.llm.cleanup_prompt;
+        let mut vars = TemplateVars::new(chrono::Local::now(), &text);
+        system_context::capture_for(template, &mut vars);
+        let mut prompt = templates::render_text(template, &vars);
+        let app = vars.app.clone().or_else(frontmost::app_name);
+        if let Some(recent) = recent_context::context(ctx.config, app.as_deref()) {
+            prompt.push_str(
+                "\n\nThe text continues this earlier dictation. Keep its terminology and casing, and don't repeat it:\n",
+            );
+            prompt.push_str(&recent);
+        }
+
+        match llm::complete(ctx.config, &prompt, &text) {
+            Ok(cleaned) => {
+                println!(
+                    "[LLM Cleanup] Cleaned up {} chars into {}",
+                    text.chars().count(),
+                    cleaned.chars().count()
+                );
+                cleaned
+            }
+            Err(e) => {
+                eprintln!("[LLM Cleanup] Failed, keeping the transcript: {}", e);
+                text
+            }
+        }
+    }
+}
@@ -0,0 +1,154 @@
+//! Privacy-aware logging helpers.
+//!
+//! Transcribed text and raw API response bodies are user content and must not
+//! reach stdout by default. Call sites wrap such values with [`content`] or
+//! [`response_body`], which only reveal the payload in debug builds when
+//! `DICTARA_LOG_CONTENT=1` is set. Anything that ends up in an error message is
+//! passed through [`mask_secrets`] so API keys can never leak into logs or the UI.
+
+use std::sync::OnceLock;
+
+/// Environment variable that enables content logging (debug builds only)
+const LOG_CONTENT_ENV: &str = "DICTARA_LOG_CONTENT";
+
+/// Tokens at least this long made of key-like characters are treated as secrets
+const MIN_SECRET_TOKEN_LEN: usize = 32;
+
+/// Known API key prefixes that are always masked regardless of length
+const SECRET_PREFIXES: &[&str] = &["sk-", "sk_", "gsk_"];
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Whether user content may be logged. Off by default, cached after first call.
+pub fn content_logging_enabled() -> bool {
+    static ENABLED: OnceLock<bool> = OnceLock::new();
+    *ENABLED.get_or_init(|| {
+        cfg!(debug_assertions)
+            && std::env::var(LOG_CONTENT_ENV)
+                .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+                .unwrap_or(false)
+    })
+}
+
+/// Format transcribed text (or any user content) for logging
+pub fn content(text: &str) -> String {
+    format_content(text, content_logging_enabled())
+}
+
+/// Format a raw API response body for logging
+pub fn response_body(body: &str) -> String {
+    format_response_body(body, content_logging_enabled())
+}
+
+fn format_content(text: &str, enabled: bool) -> String {
+    if enabled {
+        mask_secrets(text)
+    } else {
+        format!("<redacted {} chars>", text.chars().count())
+    }
+}
+
+fn format_response_body(body: &str, enabled: bool) -> String {
+    if enabled {
+        mask_secrets(body)
+    } else {
+        format!("<redacted response body, {} bytes>", body.len())
+    }
+}
+
+/// Replace anything that looks like an API key with `[REDACTED]`
+///
+/// A token is a run of alphanumerics, `-` and `_`. It is masked when it starts
+/// with a known key prefix or is long enough to be a key (Azure keys are 32 hex
+/// characters, OpenAI keys are longer).
+pub fn mask_secrets(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut token = String::new();
+
+    for ch in input.chars() {
+        if ch.is_ascii_alphanumeric() || ch == '-' || ch == '_' {
+            token.push(ch);
+        } else {
+            push_token(&mut output, &token);
+            token.clear();
+            output.push(ch);
+        }
+    }
+    push_token(&mut output, &token);
+
+    output
+}
+
+fn push_token(output: &mut String, token: &str) {
+    if is_secret_token(token) {
+        output.push_str(REDACTED);
+    } else {
+        output.push_str(token);
+    }
+}
+
+fn is_secret_token(token: &str) -> bool {
+    let has_prefix = SECRET_PREFIXES
+        .iter()
+        .any(|prefix| token.len() > prefix.len() + 4 && token.starts_with(prefix));
+    let looks_like_key =
+        token.len() >= MIN_SECRET_TOKEN_LEN && token.chars().any(|c| c.is_ascii_digit());
+
+    has_prefix || looks_like_key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AzureOpenAIConfig, OpenAIConfig};
+
+    const OPENAI_KEY: &str = "sk-proj-Abc123Def456Ghi789Jkl012Mno345Pqr678";
+    const AZURE_KEY: &str = "0123456789abcdef0123456789abcdef";
+
+    #[test]
+    fn test_content_hidden_by_default() {
+        let logged = format_content("my secret dictation", false);
+        assert!(!logged.contains("dictation"));
+        assert_eq!(logged, "<redacted 19 chars>");
+    }
+
+    #[test]
+    fn test_response_body_hidden_by_default() {
+        let body = format!(
+            r#"{{"error": "Incorrect API key provided: {}"}}"#,
+            OPENAI_KEY
+        );
+        let logged = format_response_body(&body, false);
+        assert!(!logged.contains(OPENAI_KEY));
+        assert!(!logged.contains("Incorrect"));
+    }
+
+    #[test]
+    fn test_keys_masked_even_when_content_enabled() {
+        let body = format!("key={} azure={} done", OPENAI_KEY, AZURE_KEY);
+        let logged = format_response_body(&body, true);
+        assert!(!logged.contains(OPENAI_KEY));
+        assert!(!logged.contains(AZURE_KEY));
+        assert_eq!(logged, "key=[REDACTED] azure=[REDACTED] done");
+    }
+
+    #[test]
+    fn test_mask_secrets_keeps_ordinary_text() {
+        let message = "API returned status 429 Too Many Requests: rate_limit_exceeded";
+        assert_eq!(mask_secrets(message), message);
+    }
+
+    #[test]
+    fn test_provider_configs_do_not_expose_keys_in_debug() {
+        let openai = OpenAIConfig {
+            api_key: OPENAI_KEY.to_string(),
+        };
+        let azure = AzureOpenAIConfig {
+            api_key: AZURE_KEY.to_string(),
+            endpoint: "https://example.openai.azure.com".to_string(),
+        };
+
+        assert!(!format!("{:?}", openai).contains(OPENAI_KEY));
+        assert!(!format!("{:?}", azure).contains(AZURE_KEY));
+    }
+}
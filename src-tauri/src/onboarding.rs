@@ -0,0 +1,99 @@
+//! Onboarding across permission restarts.
+//!
+//! macOS only applies a newly granted Accessibility or Input Monitoring
+//! permission once the app restarts, which would drop the user out of
+//! onboarding. `restart_for_onboarding` marks the step to come back to as
+//! `pending_restart` in the config store, then restarts. On the next launch,
+//! setup consumes the mark, re-checks permissions and reopens onboarding at
+//! that step; the window reads the details with `get_onboarding_resume`.
+
+use crate::events::PermissionStatus;
+use crate::permissions;
+use crate::ui::window;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri_plugin_store::StoreExt;
+
+const PENDING_RESTART_KEY: &str = "pending_restart";
+
+/// Onboarding step that needs a restart to take effect
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum RestartStep {
+    Accessibility,
+    InputMonitoring,
+}
+
+impl RestartStep {
+    /// Onboarding page for the step
+    fn page(&self) -> &'static str {
+        match self {
+            RestartStep::Accessibility => "onboarding/accessibility",
+            RestartStep::InputMonitoring => "onboarding/input-monitoring",
+        }
+    }
+}
+
+/// Onboarding picking up where it left off before a restart
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardingResume {
+    pub step: RestartStep,
+    /// Checked after the restart, so the step shows whether the grant took
+    pub permissions: PermissionStatus,
+}
+
+/// Resume details for this launch, None unless it followed an onboarding restart
+pub struct OnboardingResumeState(pub Mutex<Option<OnboardingResume>>);
+
+/// Persist the step to return to after the next restart
+pub fn mark_pending_restart(
+    app_handle: &tauri::AppHandle,
+    step: RestartStep,
+) -> Result<(), String> {
+    let store = app_handle
+        .store("config.json")
+        .map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set(
+        PENDING_RESTART_KEY,
+        serde_json::to_value(step).map_err(|e| e.to_string())?,
+    );
+    store.save().map_err(|e| e.to_string())
+}
+
+/// Remove and return the step marked before the restart, if any
+fn take_pending_restart(app_handle: &tauri::AppHandle) -> Option<RestartStep> {
+    let store = match app_handle.store("config.json") {
+        Ok(store) => store,
+        Err(e) => {
+            eprintln!("[Onboarding] Failed to open store: {}", e);
+            return None;
+        }
+    };
+
+    let step = store
+        .get(PENDING_RESTART_KEY)
+        .and_then(|value| serde_json::from_value(value).ok());
+    if store.delete(PENDING_RESTART_KEY) {
+        if let Err(e) = store.save() {
+            eprintln!("[Onboarding] Failed to clear pending restart: {}", e);
+        }
+    }
+    step
+}
+
+/// Called at launch: when the previous run restarted for onboarding, reopen
+/// onboarding at the marked step and return what it needs to resume
+pub fn resume_after_restart(app_handle: &tauri::AppHandle) -> Option<OnboardingResume> {
+    let step = take_pending_restart(app_handle)?;
+    let resume = OnboardingResume {
+        step,
+        permissions: permissions::check_permissions(),
+    };
+    println!("[Onboarding] Resuming after restart: {:?}", resume);
+
+    if let Err(e) = window::open_onboarding_window(app_handle, step.page()) {
+        eprintln!("[Onboarding] Failed to open onboarding window: {}", e);
+    }
+    Some(resume)
+}
@@ -0,0 +1,367 @@
+//! Output sinks for delivering a finished transcript.
+//!
+//! Every delivery method (paste, copy-only, file append, webhook) implements
+//! [`OutputSink`]. The controller runs the ordered list from
+//! `AppConfig::output_sinks`, so combinations like "paste and append to a daily
+//! note" need no special cases.
+
+use crate::clipboard_paste::{self, ClipboardPasteError, CursorKey};
+use crate::system_context;
+use crate::templates::{self, TemplateVars};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::time::Duration;
+use unicode_segmentation::UnicodeSegmentation;
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Time for the target app to insert the pasted text before the cursor moves
+const AFTER_PASTE_DELAY: Duration = Duration::from_millis(50);
+
+/// Most key presses `AfterPaste::SelectPasted` sends. Longer transcripts
+/// would take a noticeable burst of key presses, so the cursor stays.
+const MAX_SELECT_KEYS: usize = 150;
+
+/// Whether `text` is over the `max_delivery_chars` cap (0 = no limit)
+pub fn exceeds_length_cap(text: &str, max_chars: u32) -> bool {
+    max_chars > 0 && text.chars().count() > max_chars as usize
+}
+
+/// Configured sink, in the order it should run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum OutputSinkConfig {
+    /// Put the text on the clipboard and press ⌘V
+    Paste {
+        #[serde(default)]
+        after_paste: AfterPaste,
+    },
+    /// Only copy the text to the clipboard
+    Clipboard,
+    /// Append the text as a new line to a file
+    FileAppend { path: String },
+    /// POST `{"text": ...}` as JSON to a URL, or `body` rendered as a JSON
+    /// template (see `templates`), e.g. `{"text": "{text}", "app": "{app}"}`
+    Webhook {
+        url: String,
+        #[serde(default)]
+        body: Option<String>,
+    },
+}
+
+/// Where the cursor goes once the paste sink has pasted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum AfterPaste {
+    /// Leave it right after the pasted text
+    #[default]
+    Stay,
+    /// Move to the end of the line (⌘→)
+    LineEnd,
+    /// Add a line break (⇧↩, so chat apps don't send) and continue on it
+    Newline,
+    /// Select the pasted text for a quick review or retype
+    SelectPasted,
+}
+
+/// Keys that carry out `after` for a paste of `text`
+fn after_paste_keys(after: AfterPaste, text: &str) -> Vec<CursorKey> {
+    match after {
+        AfterPaste::Stay => Vec::new(),
+        AfterPaste::LineEnd => vec![CursorKey::LineEnd],
+        AfterPaste::Newline => vec![CursorKey::ShiftReturn],
+        AfterPaste::SelectPasted => {
+            // Word by word, skipping the spaces and punctuation between words,
+            // then by user-perceived character for anything before the first
+            let words = text.unicode_words().count();
+            let leading = text
+                .split_word_bounds()
+                .take_while(|segment| !segment.chars().any(char::is_alphanumeric))
+                .map(|segment| segment.graphemes(true).count())
+                .sum::<usize>();
+            if words + leading > MAX_SELECT_KEYS {
+                println!(
+                    "[Output] {} words is too long to select, leaving the cursor",
+                    words
+                );
+                return Vec::new();
+            }
+            let mut keys = vec![CursorKey::SelectWordLeft; words];
+            keys.extend(std::iter::repeat(CursorKey::SelectLeft).take(leading));
+            keys
+        }
+    }
+}
+
+pub fn default_sinks() -> Vec<OutputSinkConfig> {
+    vec![OutputSinkConfig::Paste {
+        after_paste: AfterPaste::Stay,
+    }]
+}
+
+#[derive(Debug)]
+pub enum SinkError {
+    Paste(ClipboardPasteError),
+    Clipboard(String),
+    Io(std::io::Error),
+    Webhook(String),
+}
+
+impl std::fmt::Display for SinkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SinkError::Paste(err) => write!(f, "Paste failed: {}", err),
+            SinkError::Clipboard(msg) => write!(f, "Failed to copy to clipboard: {}", msg),
+            SinkError::Io(err) => write!(f, "Failed to write file: {}", err),
+            SinkError::Webhook(msg) => write!(f, "Webhook failed: {}", msg),
+        }
+    }
+}
+
+impl From<std::io::Error> for SinkError {
+    fn from(err: std::io::Error) -> Self {
+        SinkError::Io(err)
+    }
+}
+
+/// A destination for the finished transcript
+pub trait OutputSink {
+    /// Short name used in logs
+    fn name(&self) -> &'static str;
+
+    /// `context` is the system context captured before the first sink ran,
+    /// for sinks that render templates
+    fn deliver(&self, text: &str, context: &TemplateVars) -> Result<(), SinkError>;
+}
+
+pub struct PasteSink {
+    pub after_paste: AfterPaste,
+}
+
+impl OutputSink for PasteSink {
+    fn name(&self) -> &'static str {
+        "paste"
+    }
+
+    fn deliver(&self, text: &str, _context: &TemplateVars) -> Result<(), SinkError> {
+        clipboard_paste::auto_paste_text_cgevent(text).map_err(SinkError::Paste)?;
+
+        let keys = after_paste_keys(self.after_paste, text);
+        if !keys.is_empty() {
+            std::thread::sleep(AFTER_PASTE_DELAY);
+            // The text is in place either way, so this isn't a sink failure
+            if let Err(e) = clipboard_paste::press_cursor_keys(&keys) {
+                eprintln!("[Output] Failed to move cursor after paste: {}", e);
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct ClipboardSink;
+
+impl OutputSink for ClipboardSink {
+    fn name(&self) -> &'static str {
+        "clipboard"
+    }
+
+    fn deliver(&self, text: &str, _context: &TemplateVars) -> Result<(), SinkError> {
+        let mut clipboard =
+            arboard::Clipboard::new().map_err(|e| SinkError::Clipboard(e.to_string()))?;
+        clipboard
+            .set_text(text.to_string())
+            .map_err(|e| SinkError::Clipboard(e.to_string()))
+    }
+}
+
+pub struct FileAppendSink {
+    pub path: String,
+}
+
+impl OutputSink for FileAppendSink {
+    fn name(&self) -> &'static str {
+        "file"
+    }
+
+    fn deliver(&self, text: &str, _context: &TemplateVars) -> Result<(), SinkError> {
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", text)?;
+        Ok(())
+    }
+}
+
+pub struct WebhookSink {
+    pub url: String,
+    pub body: Option<String>,
+}
+
+impl WebhookSink {
+    fn body(&self, text: &str, context: &TemplateVars) -> Result<String, SinkError> {
+        let Some(template) = &self.body else {
+            return Ok(serde_json::json!({ "text": text }).to_string());
+        };
+        templates::render_json(template, context)
+            .map(|body| body.to_string())
+            .map_err(SinkError::Webhook)
+    }
+}
+
+impl OutputSink for WebhookSink {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    fn deliver(&self, text: &str, context: &TemplateVars) -> Result<(), SinkError> {
+        let body = self.body(text, context)?;
+
+        let response = reqwest::blocking::Client::new()
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .timeout(WEBHOOK_TIMEOUT)
+            .body(body)
+            .send()
+            .map_err(|e| SinkError::Webhook(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(SinkError::Webhook(format!(
+                "server returned {}",
+                response.status()
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+pub fn build_sink(config: &OutputSinkConfig) -> Box<dyn OutputSink> {
+    match config {
+        OutputSinkConfig::Paste { after_paste } => Box::new(PasteSink {
+            after_paste: *after_paste,
+        }),
+        OutputSinkConfig::Clipboard => Box::new(ClipboardSink),
+        OutputSinkConfig::FileAppend { path } => Box::new(FileAppendSink { path: path.clone() }),
+        OutputSinkConfig::Webhook { url, body } => Box::new(WebhookSink {
+            url: url.clone(),
+            body: body.clone(),
+        }),
+    }
+}
+
+/// Template variables for the sinks' templates. Captured once, before any
+/// sink runs, since pasting or copying changes the clipboard and selection
+/// the templates read.
+fn capture_context(configs: &[OutputSinkConfig], text: &str) -> TemplateVars {
+    let mut vars = TemplateVars::new(chrono::Local::now(), text);
+    let templates: Vec<&str> = configs
+        .iter()
+        .filter_map(|config| match config {
+            OutputSinkConfig::Webhook {
+                body: Some(body), ..
+            } => Some(body.as_str()),
+            _ => None,
+        })
+        .collect();
+    if !templates.is_empty() {
+        system_context::capture_for(&templates.join("\n"), &mut vars);
+    }
+    vars
+}
+
+/// Run every configured sink in order. A failing sink doesn't stop the rest.
+/// Returns the errors of the sinks that failed.
+pub fn deliver(configs: &[OutputSinkConfig], text: &str) -> Vec<SinkError> {
+    let context = capture_context(configs, text);
+    let mut failures = Vec::new();
+
+    for config in configs {
+        let sink = build_sink(config);
+        match sink.deliver(text, &context) {
+            Ok(()) => println!("[Output] Delivered to {} sink", sink.name()),
+            Err(e) => {
+                eprintln!("[Output] {} sink failed: {}", sink.name(), e);
+                failures.push(e);
+            }
+        }
+    }
+
+    failures
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_after_paste_keys() {
+        assert!(after_paste_keys(AfterPaste::Stay, "hello").is_empty());
+        assert_eq!(
+            after_paste_keys(AfterPaste::Newline, "hello"),
+            [CursorKey::ShiftReturn]
+        );
+        // One press per word, and per character before the first word
+        assert_eq!(
+            after_paste_keys(AfterPaste::SelectPasted, "Hello, big world."),
+            [CursorKey::SelectWordLeft; 3]
+        );
+        assert_eq!(
+            after_paste_keys(AfterPaste::SelectPasted, "👍🏽 (ok)"),
+            [
+                CursorKey::SelectWordLeft,
+                CursorKey::SelectLeft,
+                CursorKey::SelectLeft,
+                CursorKey::SelectLeft
+            ]
+        );
+        assert!(after_paste_keys(
+            AfterPaste::SelectPasted,
+            &"word ".repeat(MAX_SELECT_KEYS + 1)
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_length_cap_counts_characters() {
+        assert!(!exceeds_length_cap("héllo", 5));
+        assert!(exceeds_length_cap("héllo!", 5));
+        assert!(!exceeds_length_cap(&"a".repeat(50_000), 0));
+    }
+
+    #[test]
+    fn test_file_append_sink_appends_lines() {
+        let path = std::env::temp_dir().join(format!("dictara-sink-{}.txt", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let configs = vec![OutputSinkConfig::FileAppend {
+            path: path.to_string_lossy().to_string(),
+        }];
+        assert!(deliver(&configs, "first").is_empty());
+        assert!(deliver(&configs, "second").is_empty());
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents, "first\nsecond\n");
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_failing_sink_does_not_stop_the_rest() {
+        let path = std::env::temp_dir().join(format!("dictara-sink-ok-{}.txt", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let configs = vec![
+            OutputSinkConfig::FileAppend {
+                path: "/nonexistent-dir/dictara/out.txt".to_string(),
+            },
+            OutputSinkConfig::FileAppend {
+                path: path.to_string_lossy().to_string(),
+            },
+        ];
+        let failures = deliver(&configs, "hello");
+        assert_eq!(failures.len(), 1);
+        assert!(matches!(failures[0], SinkError::Io(_)));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "hello\n");
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,240 @@
+//! "Why didn't it paste?"
+//!
+//! After a failed paste or an empty result the controller asks [`diagnose`]
+//! what went wrong. It checks the known causes - secure keyboard input, an
+//! empty transcript, focus moving to another app while dictating, a revoked
+//! Accessibility permission and a clipboard another app holds on to - against
+//! the error and the system state right now, and ranks the ones that apply
+//! with a fix for each, for the popup to show.
+
+use crate::clipboard_paste::{self, ClipboardPasteError};
+use crate::events::{Likelihood, PasteExplanation, PasteProblem, PermissionState};
+use crate::frontmost;
+use crate::permissions;
+
+/// What happened to the dictation
+#[derive(Clone, Copy)]
+pub enum Outcome<'a> {
+    /// The transcript couldn't be pasted
+    PasteFailed(&'a ClipboardPasteError),
+    /// Nothing was left to paste
+    Empty {
+        /// The provider returned text that post-processing then removed
+        removed_by_pipeline: bool,
+    },
+}
+
+/// What the analyzer goes by
+struct Evidence<'a> {
+    outcome: Outcome<'a>,
+    secure_input: bool,
+    accessibility: PermissionState,
+    /// App in front when the dictation started
+    target_app: Option<String>,
+    /// App in front now
+    front_app: Option<String>,
+}
+
+fn explanation(
+    problem: PasteProblem,
+    likelihood: Likelihood,
+    explanation: &str,
+    fix: &str,
+) -> PasteExplanation {
+    PasteExplanation {
+        problem,
+        likelihood,
+        explanation: explanation.to_string(),
+        fix: fix.to_string(),
+    }
+}
+
+fn rank(evidence: &Evidence) -> Vec<PasteExplanation> {
+    let mut explanations = Vec::new();
+    let error = match evidence.outcome {
+        Outcome::PasteFailed(error) => Some(error),
+        Outcome::Empty { .. } => None,
+    };
+
+    let secure_input_error = matches!(error, Some(ClipboardPasteError::SecureInputActive));
+    if secure_input_error || (error.is_some() && evidence.secure_input) {
+        explanations.push(explanation(
+            PasteProblem::SecureInput,
+            if secure_input_error {
+                Likelihood::Likely
+            } else {
+                Likelihood::Possible
+            },
+            "Secure keyboard input is on, so macOS drops the ⌘V Dictara sends. Password fields and terminals with Secure Keyboard Entry turn it on.",
+            "Press ⌘V yourself - the text is on your clipboard. If it keeps happening, close password prompts or turn off Secure Keyboard Entry in your terminal.",
+        ));
+    }
+
+    let key_events_failed = matches!(
+        error,
+        Some(
+            ClipboardPasteError::EventSourceCreationFailed
+                | ClipboardPasteError::KeyEventCreationFailed
+        )
+    );
+    let accessibility_denied = evidence.accessibility != PermissionState::Granted;
+    if error.is_some() && (accessibility_denied || key_events_failed) {
+        explanations.push(explanation(
+            PasteProblem::AccessibilityRevoked,
+            if accessibility_denied {
+                Likelihood::Likely
+            } else {
+                Likelihood::Possible
+            },
+            "Dictara can't send keystrokes without the Accessibility permission. macOS may have dropped it after an update.",
+            "Open System Settings → Privacy & Security → Accessibility, remove Dictara and add it again.",
+        ));
+    }
+
+    if matches!(
+        error,
+        Some(
+            ClipboardPasteError::ClipboardAccessFailed(_)
+                | ClipboardPasteError::ClipboardSetFailed(_)
+        )
+    ) {
+        explanations.push(explanation(
+            PasteProblem::ClipboardLocked,
+            Likelihood::Likely,
+            "Another app held on to the clipboard, so the text couldn't be put on it. Clipboard managers and remote desktop apps do this.",
+            "Use \"Paste Last Recording\" from the menu, or quit the clipboard manager and try again.",
+        ));
+    }
+
+    if let (Some(target), Some(front)) = (&evidence.target_app, &evidence.front_app) {
+        if error.is_some() && target != front {
+            explanations.push(explanation(
+                PasteProblem::TargetAppChanged,
+                Likelihood::Possible,
+                &format!(
+                    "You started dictating in {} but {} is in front now, so the text went there or nowhere.",
+                    target, front
+                ),
+                &format!(
+                    "Switch back to {} and use \"Paste Last Recording\" from the menu.",
+                    target
+                ),
+            ));
+        }
+    }
+
+    match evidence.outcome {
+        Outcome::Empty {
+            removed_by_pipeline: true,
+        } => explanations.push(explanation(
+            PasteProblem::EmptyTranscript,
+            Likelihood::Likely,
+            "Speech was transcribed, but post-processing (filler word removal or a plugin) removed all of it.",
+            "Check the processing steps in Preferences.",
+        )),
+        Outcome::Empty {
+            removed_by_pipeline: false,
+        }
+        | Outcome::PasteFailed(ClipboardPasteError::EmptyText) => {
+            explanations.push(explanation(
+                PasteProblem::EmptyTranscript,
+                Likelihood::Likely,
+                "The provider didn't hear any speech in the recording.",
+                "Check that the right microphone is selected in Preferences and that its level moves while you speak.",
+            ))
+        }
+        Outcome::PasteFailed(_) => {}
+    }
+
+    explanations.sort_by_key(|explanation| explanation.likelihood);
+    explanations
+}
+
+/// Explain `outcome` of a dictation started in `target_app`, most likely cause first
+pub fn diagnose(outcome: Outcome, target_app: Option<String>) -> Vec<PasteExplanation> {
+    let evidence = Evidence {
+        outcome,
+        secure_input: clipboard_paste::is_secure_input_enabled(),
+        accessibility: permissions::check_permissions().accessibility,
+        target_app,
+        front_app: frontmost::app_name(),
+    };
+    let explanations = rank(&evidence);
+    println!(
+        "[Paste Diagnosis] {:?}",
+        explanations
+            .iter()
+            .map(|explanation| (explanation.problem, explanation.likelihood))
+            .collect::<Vec<_>>()
+    );
+    explanations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn evidence(outcome: Outcome) -> Evidence {
+        Evidence {
+            outcome,
+            secure_input: false,
+            accessibility: PermissionState::Granted,
+            target_app: Some("Notes".to_string()),
+            front_app: Some("Notes".to_string()),
+        }
+    }
+
+    fn problems(evidence: &Evidence) -> Vec<(PasteProblem, Likelihood)> {
+        rank(evidence)
+            .into_iter()
+            .map(|explanation| (explanation.problem, explanation.likelihood))
+            .collect()
+    }
+
+    #[test]
+    fn test_direct_cause_ranks_first() {
+        let error = ClipboardPasteError::KeyEventCreationFailed;
+        let mut evidence = evidence(Outcome::PasteFailed(&error));
+        evidence.secure_input = true;
+        evidence.accessibility = PermissionState::Denied;
+        evidence.front_app = Some("Safari".to_string());
+
+        assert_eq!(
+            problems(&evidence),
+            [
+                (PasteProblem::AccessibilityRevoked, Likelihood::Likely),
+                (PasteProblem::SecureInput, Likelihood::Possible),
+                (PasteProblem::TargetAppChanged, Likelihood::Possible),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_clipboard_and_secure_input_errors() {
+        let error = ClipboardPasteError::ClipboardSetFailed("busy".to_string());
+        assert_eq!(
+            problems(&evidence(Outcome::PasteFailed(&error))),
+            [(PasteProblem::ClipboardLocked, Likelihood::Likely)]
+        );
+
+        let error = ClipboardPasteError::SecureInputActive;
+        assert_eq!(
+            problems(&evidence(Outcome::PasteFailed(&error))),
+            [(PasteProblem::SecureInput, Likelihood::Likely)]
+        );
+    }
+
+    #[test]
+    fn test_empty_result_ignores_paste_state() {
+        let mut evidence = evidence(Outcome::Empty {
+            removed_by_pipeline: true,
+        });
+        evidence.secure_input = true;
+        evidence.front_app = Some("Safari".to_string());
+
+        let explanations = rank(&evidence);
+        assert_eq!(explanations.len(), 1);
+        assert_eq!(explanations[0].problem, PasteProblem::EmptyTranscript);
+        assert!(explanations[0].explanation.contains("post-processing"));
+    }
+}
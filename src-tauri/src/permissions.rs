@@ -0,0 +1,157 @@
+//! Permission watcher.
+//!
+//! Polls the macOS privacy permissions Dictara depends on and emits a
+//! `permission-status` event so preferences and onboarding can show live status
+//! without polling from the frontend.
+//!
+//! A granted permission doesn't guarantee the key listener works, so an event
+//! tap is also probed at launch and on every permission change. When it can't
+//! be created, the tray menu and `input-capture-status` say so before the user
+//! first presses the trigger.
+
+use crate::energy;
+use crate::events::{self, InputCaptureStatus, PermissionState, PermissionStatus};
+use crate::keyboard_listener;
+use crate::ui::menu;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// How often permissions are re-checked
+const PERMISSION_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Result of the last event tap probe
+static INPUT_CAPTURE: Mutex<Option<InputCaptureStatus>> = Mutex::new(None);
+
+/// Check all permissions now
+pub fn check_permissions() -> PermissionStatus {
+    PermissionStatus {
+        accessibility: accessibility_state(),
+        microphone: microphone_state(),
+        input_monitoring: input_monitoring_state(),
+    }
+}
+
+/// Start the watcher task, emitting the status on every check
+pub fn start_permission_watcher(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut last_status: Option<PermissionStatus> = None;
+
+        loop {
+            let status = check_permissions();
+
+            if last_status != Some(status) {
+                println!("[Permissions] Status changed: {:?}", status);
+                // The launch check runs from setup, once the tray exists
+                if last_status.is_some() {
+                    check_input_capture(&app_handle);
+                }
+                last_status = Some(status);
+            }
+
+            events::emit_or_log(&app_handle, status);
+
+            tokio::time::sleep(energy::timer_interval(PERMISSION_CHECK_INTERVAL)).await;
+        }
+    });
+}
+
+/// Probe the event tap and show the result in the tray menu and preferences
+pub fn check_input_capture(app_handle: &tauri::AppHandle) -> InputCaptureStatus {
+    let error = keyboard_listener::probe_event_tap().err();
+    match &error {
+        Some(e) => eprintln!("[Permissions] Input capture not functional: {}", e),
+        None => println!("[Permissions] Event tap probe succeeded"),
+    }
+
+    let status = InputCaptureStatus {
+        functional: error.is_none(),
+        error,
+    };
+    *INPUT_CAPTURE.lock().unwrap_or_else(|e| e.into_inner()) = Some(status.clone());
+    if let Err(e) = menu::update(app_handle, |model| {
+        model.input_capture_broken = !status.functional
+    }) {
+        eprintln!("[Permissions] Failed to update tray menu: {}", e);
+    }
+    events::emit_or_log(app_handle, status.clone());
+    status
+}
+
+/// Result of the last event tap probe; functional until the first one ran
+pub fn input_capture_status() -> InputCaptureStatus {
+    INPUT_CAPTURE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or(InputCaptureStatus {
+            functional: true,
+            error: None,
+        })
+}
+
+#[cfg(target_os = "macos")]
+fn accessibility_state() -> PermissionState {
+    if macos_accessibility_client::accessibility::application_is_trusted() {
+        PermissionState::Granted
+    } else {
+        PermissionState::Denied
+    }
+}
+
+/// Microphone authorization via `+[AVCaptureDevice authorizationStatusForMediaType:]`
+#[cfg(target_os = "macos")]
+fn microphone_state() -> PermissionState {
+    use objc2::msg_send;
+    use objc2::runtime::{AnyClass, AnyObject};
+
+    #[link(name = "AVFoundation", kind = "framework")]
+    extern "C" {
+        static AVMediaTypeAudio: *const AnyObject;
+    }
+
+    let Some(class) = AnyClass::get(c"AVCaptureDevice") else {
+        return PermissionState::NotDetermined;
+    };
+
+    // AVAuthorizationStatus: 0 = notDetermined, 1 = restricted, 2 = denied, 3 = authorized
+    let status: isize =
+        unsafe { msg_send![class, authorizationStatusForMediaType: AVMediaTypeAudio] };
+
+    match status {
+        3 => PermissionState::Granted,
+        0 => PermissionState::NotDetermined,
+        _ => PermissionState::Denied,
+    }
+}
+
+/// Input monitoring via `IOHIDCheckAccess(kIOHIDRequestTypeListenEvent)`
+#[cfg(target_os = "macos")]
+fn input_monitoring_state() -> PermissionState {
+    #[link(name = "IOKit", kind = "framework")]
+    extern "C" {
+        fn IOHIDCheckAccess(request_type: u32) -> u32;
+    }
+
+    // kIOHIDRequestTypeListenEvent = 1
+    // IOHIDAccessType: 0 = granted, 1 = denied, 2 = unknown
+    match unsafe { IOHIDCheckAccess(1) } {
+        0 => PermissionState::Granted,
+        1 => PermissionState::Denied,
+        _ => PermissionState::NotDetermined,
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn accessibility_state() -> PermissionState {
+    PermissionState::Granted // Other platforms don't need this permission
+}
+
+#[cfg(not(target_os = "macos"))]
+fn microphone_state() -> PermissionState {
+    PermissionState::Granted
+}
+
+#[cfg(not(target_os = "macos"))]
+fn input_monitoring_state() -> PermissionState {
+    PermissionState::Granted
+}
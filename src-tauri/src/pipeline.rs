@@ -0,0 +1,208 @@
+//! Transcript post-processing pipeline.
+//!
+//! Each processing step is a [`TranscriptMiddleware`] registered in
+//! [`registry`]. `AppConfig::pipeline` controls which steps run and in what
+//! order; steps missing from the config (e.g. added in a newer version) run at
+//! the end with their default setting. External plugins from
+//! `AppConfig::plugins` are registered as steps too. `describe_pipeline` exposes the resolved
+//! order to the frontend.
+
+use crate::config::AppConfig;
+use crate::filler_words::RemoveFillerWords;
+use crate::llm_cleanup::LlmCleanup;
+use crate::plugins;
+use serde::{Deserialize, Serialize};
+
+/// Everything a step may need besides the text itself
+pub struct PipelineContext<'a> {
+    pub config: &'a AppConfig,
+}
+
+/// A single transcript processing step
+pub trait TranscriptMiddleware {
+    /// Stable identifier used in the config
+    fn id(&self) -> String;
+
+    /// One-line description shown in the UI
+    fn description(&self) -> String;
+
+    /// Whether the step runs when the config doesn't mention it
+    fn enabled_by_default(&self) -> bool {
+        true
+    }
+
+    fn process(&self, text: String, ctx: &PipelineContext) -> String;
+}
+
+/// Step entry in the config
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+pub struct PipelineStepConfig {
+    pub id: String,
+    pub enabled: bool,
+}
+
+/// Resolved step, as returned by `describe_pipeline`
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct PipelineStepInfo {
+    pub id: String,
+    pub description: String,
+    pub enabled: bool,
+}
+
+/// Collapse runs of whitespace and trim the ends
+pub struct NormalizeWhitespace;
+
+impl TranscriptMiddleware for NormalizeWhitespace {
+    fn id(&self) -> String {
+        "normalize_whitespace".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Collapse repeated spaces and trim the transcript".to_string()
+    }
+
+    fn process(&self, text: String, _ctx: &PipelineContext) -> String {
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+}
+
+/// All known steps in their default order, followed by user plugins
+pub fn registry(config: &AppConfig) -> Vec<Box<dyn TranscriptMiddleware>> {
+    let mut steps: Vec<Box<dyn TranscriptMiddleware>> = vec![
+        Box::new(NormalizeWhitespace),
+        Box::new(RemoveFillerWords),
+        Box::new(LlmCleanup),
+    ];
+    steps.extend(plugins::middleware(&config.plugins));
+    steps
+}
+
+/// Steps in execution order with their enabled flag
+fn resolve(config: &AppConfig) -> Vec<(Box<dyn TranscriptMiddleware>, bool)> {
+    let mut available = registry(config);
+    let mut resolved = Vec::with_capacity(available.len());
+
+    for step in &config.pipeline {
+        match available.iter().position(|m| m.id() == step.id) {
+            Some(index) => resolved.push((available.remove(index), step.enabled)),
+            None => eprintln!("[Pipeline] Unknown step '{}' in config, skipping", step.id),
+        }
+    }
+
+    // Steps the config doesn't know about yet
+    for middleware in available {
+        let enabled = middleware.enabled_by_default();
+        resolved.push((middleware, enabled));
+    }
+
+    resolved
+}
+
+/// Run the transcript through every enabled step
+pub fn run(text: String, config: &AppConfig) -> String {
+    let ctx = PipelineContext { config };
+
+    resolve(config)
+        .into_iter()
+        .filter(|(_, enabled)| *enabled)
+        .fold(text, |text, (middleware, _)| middleware.process(text, &ctx))
+}
+
+/// Per-dictation changes to the configured processing and delivery, from
+/// modifier keys held while stopping
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DictationOverrides {
+    /// Deliver the provider's transcript as is: no steps and no summary
+    pub raw: bool,
+    /// Only copy the result to the clipboard instead of using the configured
+    /// output sinks
+    pub clipboard_only: bool,
+}
+
+/// Run the transcript of one dictation through the pipeline, unless its
+/// overrides skip it
+pub fn run_with_overrides(
+    text: String,
+    config: &AppConfig,
+    overrides: DictationOverrides,
+) -> String {
+    if overrides.raw {
+        println!("[Pipeline] Raw dictation, skipping post-processing");
+        return text.trim().to_string();
+    }
+    run(text, config)
+}
+
+/// Resolved pipeline for display
+pub fn describe(config: &AppConfig) -> Vec<PipelineStepInfo> {
+    resolve(config)
+        .into_iter()
+        .map(|(middleware, enabled)| PipelineStepInfo {
+            id: middleware.id(),
+            description: middleware.description(),
+            enabled,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_pipeline_normalizes_whitespace() {
+        let config = AppConfig::default();
+        assert_eq!(
+            run("  hello   world \n".to_string(), &config),
+            "hello world"
+        );
+    }
+
+    #[test]
+    fn test_disabled_step_is_skipped() {
+        let config = AppConfig {
+            pipeline: vec![PipelineStepConfig {
+                id: "normalize_whitespace".to_string(),
+                enabled: false,
+            }],
+            ..AppConfig::default()
+        };
+        assert_eq!(run(" hello ".to_string(), &config), " hello ");
+    }
+
+    #[test]
+    fn test_raw_override_skips_every_step() {
+        let config = AppConfig::default();
+        let raw = DictationOverrides {
+            raw: true,
+            ..DictationOverrides::default()
+        };
+        assert_eq!(
+            run_with_overrides(" um  hello   world \n".to_string(), &config, raw),
+            "um  hello   world"
+        );
+        assert_eq!(
+            run_with_overrides(
+                " hello   world ".to_string(),
+                &config,
+                DictationOverrides::default()
+            ),
+            run(" hello   world ".to_string(), &config)
+        );
+    }
+
+    #[test]
+    fn test_unknown_steps_are_ignored_and_missing_steps_appended() {
+        let config = AppConfig {
+            pipeline: vec![PipelineStepConfig {
+                id: "removed_step".to_string(),
+                enabled: true,
+            }],
+            ..AppConfig::default()
+        };
+        let steps = describe(&config);
+        assert_eq!(steps.len(), registry(&config).len());
+        assert_eq!(steps[0].id, "normalize_whitespace");
+        assert!(steps[0].enabled);
+    }
+}
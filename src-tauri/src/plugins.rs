@@ -0,0 +1,384 @@
+//! External-process transcript plugins.
+//!
+//! A plugin is an executable declared in `AppConfig::plugins`. It receives a
+//! JSON object on stdin:
+//!
+//! ```json
+//! { "text": "...", "metadata": { "app_version": "0.1.13", "plugin_api_version": 1 } }
+//! ```
+//!
+//! and prints the modified transcript on stdout. Plugins run with a cleared
+//! environment (only `PATH` is kept), in the temp directory, in their own
+//! process group, which is killed when they exceed their timeout, including
+//! when processes they leave behind keep their output open. Any failure
+//! leaves the text unchanged. Plugins show up in the pipeline as
+//! `plugin:<name>` steps.
+//!
+//! On macOS a plugin runs under `sandbox-exec` with `SANDBOX_PROFILE`: no
+//! network, no writes outside the temp directory and no reads from the home
+//! folder apart from the plugin's own folder. Elsewhere there is no sandbox
+//! and a plugin can do whatever the user can.
+
+use crate::logging;
+use crate::pipeline::{PipelineContext, TranscriptMiddleware};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+/// Version of the stdin/stdout contract
+const PLUGIN_API_VERSION: u32 = 1;
+
+/// How often a running plugin is polled for completion
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Seatbelt profile plugins run under. Later rules take precedence.
+#[cfg(target_os = "macos")]
+const SANDBOX_PROFILE: &str = r#"(version 1)
+(allow default)
+(deny network*)
+(deny file-write*)
+(allow file-write* (subpath (param "TMP")) (literal "/dev/null"))
+(deny file-read* (subpath (param "HOME")))
+(allow file-read* (subpath (param "PLUGIN_DIR")))
+"#;
+
+/// Kill a process group, like `kill -KILL -<pgid>`
+#[cfg(unix)]
+fn kill_group(pgid: u32) {
+    extern "C" {
+        fn kill(pid: i32, sig: i32) -> i32;
+    }
+    const SIGKILL: i32 = 9;
+    // SAFETY: kill only takes plain integers
+    unsafe {
+        kill(-(pgid as i32), SIGKILL);
+    }
+}
+
+/// External executable that rewrites the transcript
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+pub struct PluginConfig {
+    /// Unique name, used as the `plugin:<name>` pipeline step id
+    pub name: String,
+    /// Absolute path to the executable
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Kill the plugin if it runs longer than this
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u32,
+}
+
+fn default_timeout_ms() -> u32 {
+    5000
+}
+
+#[derive(Debug)]
+pub enum PluginError {
+    Spawn(std::io::Error),
+    Io(std::io::Error),
+    Timeout,
+    /// Exited unsuccessfully. `stderr` can echo the transcript, so it is kept
+    /// out of `Display`.
+    Exited {
+        status: std::process::ExitStatus,
+        stderr: String,
+    },
+    Failed(String),
+}
+
+impl std::fmt::Display for PluginError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PluginError::Spawn(err) => write!(f, "Failed to start plugin: {}", err),
+            PluginError::Io(err) => write!(f, "Plugin IO error: {}", err),
+            PluginError::Timeout => write!(f, "Plugin timed out"),
+            PluginError::Exited { status, .. } => write!(f, "Plugin exited with {}", status),
+            PluginError::Failed(msg) => write!(f, "Plugin failed: {}", msg),
+        }
+    }
+}
+
+impl From<std::io::Error> for PluginError {
+    fn from(err: std::io::Error) -> Self {
+        PluginError::Io(err)
+    }
+}
+
+#[derive(Serialize)]
+struct PluginInput<'a> {
+    text: &'a str,
+    metadata: PluginMetadata,
+}
+
+#[derive(Serialize)]
+struct PluginMetadata {
+    app_version: &'static str,
+    plugin_api_version: u32,
+}
+
+pub struct ExternalProcessPlugin {
+    config: PluginConfig,
+}
+
+impl ExternalProcessPlugin {
+    pub fn new(config: PluginConfig) -> Self {
+        Self { config }
+    }
+
+    /// The plugin's command line, inside the sandbox where there is one
+    #[cfg(target_os = "macos")]
+    fn command(&self) -> Command {
+        let dir = |path: &std::path::Path| {
+            let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+            path.to_string_lossy().to_string()
+        };
+        let executable = std::path::Path::new(&self.config.command);
+        let plugin_dir = executable
+            .parent()
+            .map(dir)
+            .unwrap_or_else(|| "/".to_string());
+        let home = std::env::var("HOME").unwrap_or_else(|_| "/Users".to_string());
+
+        let mut command = Command::new("/usr/bin/sandbox-exec");
+        command
+            .arg("-D")
+            .arg(format!("TMP={}", dir(&std::env::temp_dir())))
+            .arg("-D")
+            .arg(format!("HOME={}", dir(std::path::Path::new(&home))))
+            .arg("-D")
+            .arg(format!("PLUGIN_DIR={}", plugin_dir))
+            .arg("-p")
+            .arg(SANDBOX_PROFILE)
+            .arg(&self.config.command)
+            .args(&self.config.args);
+        command
+    }
+
+    /// The plugin's command line, inside the sandbox where there is one
+    #[cfg(not(target_os = "macos"))]
+    fn command(&self) -> Command {
+        let mut command = Command::new(&self.config.command);
+        command.args(&self.config.args);
+        command
+    }
+
+    /// Run the plugin once and return its stdout
+    pub fn invoke(&self, text: &str) -> Result<String, PluginError> {
+        let input = serde_json::to_vec(&PluginInput {
+            text,
+            metadata: PluginMetadata {
+                app_version: env!("CARGO_PKG_VERSION"),
+                plugin_api_version: PLUGIN_API_VERSION,
+            },
+        })
+        .map_err(|e| PluginError::Failed(e.to_string()))?;
+
+        let mut command = self.command();
+        command
+            .env_clear()
+            .current_dir(std::env::temp_dir())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        if let Ok(path) = std::env::var("PATH") {
+            command.env("PATH", path);
+        }
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            command.process_group(0);
+        }
+
+        let mut child = command.spawn().map_err(PluginError::Spawn)?;
+
+        // Write stdin and read stdout and stderr on helper threads so a plugin
+        // that doesn't drain its input or fills a pipe can't block us past the
+        // timeout
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        std::thread::spawn(move || stdin.write_all(&input));
+
+        let mut stdout = child.stdout.take().expect("stdout is piped");
+        let (stdout_tx, stdout_rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut output = String::new();
+            let _ = stdout_tx.send(stdout.read_to_string(&mut output).map(|_| output));
+        });
+
+        let mut stderr = child.stderr.take().expect("stderr is piped");
+        let (stderr_tx, stderr_rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut output = String::new();
+            let _ = stderr.read_to_string(&mut output);
+            let _ = stderr_tx.send(output);
+        });
+
+        let timeout = Duration::from_millis(self.config.timeout_ms as u64);
+        let started = Instant::now();
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if started.elapsed() >= timeout {
+                // The whole group, so processes the plugin started don't keep
+                // running, or keep the pipes and our reader threads open
+                #[cfg(unix)]
+                kill_group(child.id());
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(PluginError::Timeout);
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        };
+
+        // Processes the plugin started in the background can keep the pipes
+        // open after it exits, so reading them shares the same time budget
+        let remaining = || timeout.saturating_sub(started.elapsed());
+        let output = match stdout_rx.recv_timeout(remaining()) {
+            Ok(output) => output?,
+            Err(RecvTimeoutError::Timeout) => {
+                // Still open, so a process of the group is alive and the
+                // group id can't have been reused
+                #[cfg(unix)]
+                kill_group(child.id());
+                return Err(PluginError::Timeout);
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                return Err(PluginError::Failed("stdout reader panicked".to_string()));
+            }
+        };
+        let stderr = match stderr_rx.recv_timeout(remaining()) {
+            Ok(stderr) => stderr,
+            Err(RecvTimeoutError::Timeout) => {
+                #[cfg(unix)]
+                kill_group(child.id());
+                String::new()
+            }
+            Err(RecvTimeoutError::Disconnected) => String::new(),
+        };
+
+        if !status.success() {
+            return Err(PluginError::Exited {
+                status,
+                stderr: stderr.trim().to_string(),
+            });
+        }
+
+        Ok(output.trim_end_matches('\n').to_string())
+    }
+}
+
+impl TranscriptMiddleware for ExternalProcessPlugin {
+    fn id(&self) -> String {
+        format!("plugin:{}", self.config.name)
+    }
+
+    fn description(&self) -> String {
+        format!("External plugin ({})", self.config.command)
+    }
+
+    fn process(&self, text: String, _ctx: &PipelineContext) -> String {
+        match self.invoke(&text) {
+            Ok(output) => output,
+            Err(e) => {
+                eprintln!("[Plugins] {} failed, keeping text: {}", self.config.name, e);
+                if let PluginError::Exited { stderr, .. } = &e {
+                    if !stderr.is_empty() {
+                        eprintln!(
+                            "[Plugins] {} stderr: {}",
+                            self.config.name,
+                            logging::content(stderr)
+                        );
+                    }
+                }
+                text
+            }
+        }
+    }
+}
+
+/// Pipeline steps for the configured plugins
+pub fn middleware(plugins: &[PluginConfig]) -> Vec<Box<dyn TranscriptMiddleware>> {
+    plugins
+        .iter()
+        .cloned()
+        .map(|config| Box::new(ExternalProcessPlugin::new(config)) as Box<dyn TranscriptMiddleware>)
+        .collect()
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn plugin(script: &str, timeout_ms: u32) -> ExternalProcessPlugin {
+        ExternalProcessPlugin::new(PluginConfig {
+            name: "test".to_string(),
+            command: "/bin/sh".to_string(),
+            args: vec!["-c".to_string(), script.to_string()],
+            timeout_ms,
+        })
+    }
+
+    #[test]
+    fn test_plugin_receives_json_and_returns_stdout() {
+        let output = plugin("grep -o '\"text\":\"[^\"]*\"'", 2000)
+            .invoke("hello")
+            .unwrap();
+        assert_eq!(output, "\"text\":\"hello\"");
+    }
+
+    #[test]
+    fn test_plugin_timeout_is_enforced() {
+        let result = plugin("sleep 5", 100).invoke("hello");
+        assert!(matches!(result, Err(PluginError::Timeout)));
+    }
+
+    #[test]
+    fn test_timeout_kills_processes_the_plugin_started() {
+        let marker = std::env::temp_dir().join(format!("dictara-plugin-{}", std::process::id()));
+        let _ = std::fs::remove_file(&marker);
+        let script = format!("(sleep 1; touch {}) & wait", marker.display());
+
+        let result = plugin(&script, 100).invoke("hello");
+        assert!(matches!(result, Err(PluginError::Timeout)));
+        std::thread::sleep(Duration::from_millis(1500));
+        assert!(!marker.exists());
+    }
+
+    #[test]
+    fn test_background_process_cannot_hold_the_result_past_timeout() {
+        let started = Instant::now();
+        let result = plugin("(sleep 5) & echo done", 300).invoke("hello");
+        assert!(matches!(result, Err(PluginError::Timeout)));
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_stderr_is_reported() {
+        let result = plugin("echo oops >&2; exit 3", 2000).invoke("hello");
+        let err = result.unwrap_err();
+        assert!(matches!(&err, PluginError::Exited { stderr, .. } if stderr == "oops"));
+        // Stderr may echo the transcript, so it stays out of the message
+        assert!(!err.to_string().contains("oops"));
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn test_sandbox_blocks_writes_outside_temp() {
+        let home = std::env::var("HOME").unwrap();
+        let target = std::path::Path::new(&home).join(".dictara-plugin-test");
+        let result = plugin(&format!("echo x > {}", target.display()), 2000).invoke("hello");
+        assert!(result.is_err());
+        assert!(!target.exists());
+    }
+
+    #[test]
+    fn test_failing_plugin_keeps_text() {
+        let config = crate::config::AppConfig::default();
+        let ctx = PipelineContext { config: &config };
+        let output = plugin("exit 3", 2000).process("hello".to_string(), &ctx);
+        assert_eq!(output, "hello");
+    }
+}
@@ -0,0 +1,162 @@
+//! Recent dictations as context for the next one.
+//!
+//! With `recent_context.enabled`, the latest one or two transcripts dictated
+//! into the same app go along with the next dictation into it: as the
+//! transcription prompt, which steers Whisper's spelling and casing, and to
+//! the `llm_cleanup` step. Only dictations from the last `max_age_minutes`
+//! count, and the context is cut to its last `max_chars`. Transcripts are
+//! kept in memory only, and dropped once the setting is off.
+
+use crate::config::{AppConfig, RecentContextConfig};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Most dictations ever included
+const MAX_DICTATIONS: usize = 2;
+
+struct Dictation {
+    text: String,
+    /// App it went to
+    app: Option<String>,
+    at: Instant,
+}
+
+/// Latest dictations, oldest first
+static RECENT: Mutex<VecDeque<Dictation>> = Mutex::new(VecDeque::new());
+
+/// Keep `text`, just dictated into `app`, for the next dictations
+pub fn remember(config: &AppConfig, text: &str, app: Option<&str>) {
+    let Ok(mut recent) = RECENT.lock() else {
+        return;
+    };
+    if !config.recent_context.enabled {
+        recent.clear();
+        return;
+    }
+
+    recent.push_back(Dictation {
+        text: text.to_string(),
+        app: app.map(str::to_string),
+        at: Instant::now(),
+    });
+    while recent.len() > MAX_DICTATIONS {
+        recent.pop_front();
+    }
+}
+
+/// Context for the next dictation into `app`, if enabled and there is any
+pub fn context(config: &AppConfig, app: Option<&str>) -> Option<String> {
+    if !config.recent_context.enabled {
+        return None;
+    }
+    let recent = RECENT.lock().ok()?;
+    let context = select(&recent, &config.recent_context, app, Instant::now())?;
+    println!(
+        "[Recent Context] Using {} chars of recent dictation",
+        context.chars().count()
+    );
+    Some(context)
+}
+
+fn select(
+    recent: &VecDeque<Dictation>,
+    config: &RecentContextConfig,
+    app: Option<&str>,
+    now: Instant,
+) -> Option<String> {
+    let max_age = Duration::from_secs(config.max_age_minutes as u64 * 60);
+    let count = (config.dictations as usize).clamp(1, MAX_DICTATIONS);
+    let mut texts: Vec<&str> = recent
+        .iter()
+        .rev()
+        .filter(|dictation| now.duration_since(dictation.at) <= max_age)
+        .filter(|dictation| dictation.app.as_deref() == app)
+        .take(count)
+        .map(|dictation| dictation.text.as_str())
+        .collect();
+    texts.reverse();
+
+    let context = tail(&texts.join(" "), config.max_chars as usize);
+    (!context.is_empty()).then_some(context)
+}
+
+/// The last `max_chars` characters of `text`, not starting mid-word
+fn tail(text: &str, max_chars: usize) -> String {
+    let chars = text.chars().count();
+    if chars <= max_chars {
+        return text.trim().to_string();
+    }
+
+    let start = text
+        .char_indices()
+        .nth(chars - max_chars)
+        .map_or(text.len(), |(i, _)| i);
+    let tail = &text[start..];
+    if text[..start].ends_with(char::is_whitespace) {
+        return tail.trim().to_string();
+    }
+    match tail.find(char::is_whitespace) {
+        Some(i) => tail[i..].trim().to_string(),
+        None => tail.trim().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dictation(text: &str, app: &str, at: Instant) -> Dictation {
+        Dictation {
+            text: text.to_string(),
+            app: Some(app.to_string()),
+            at,
+        }
+    }
+
+    #[test]
+    fn test_select_takes_latest_from_the_same_app() {
+        let now = Instant::now();
+        let config = RecentContextConfig::default();
+        let recent = VecDeque::from([
+            dictation("The Kubernetes cluster", "Notes", now),
+            dictation("Lunch at noon?", "Slack", now),
+            dictation("runs on GKE.", "Notes", now),
+        ]);
+
+        assert_eq!(
+            select(&recent, &config, Some("Notes"), now).as_deref(),
+            Some("The Kubernetes cluster runs on GKE.")
+        );
+
+        let one = RecentContextConfig {
+            dictations: 1,
+            ..config.clone()
+        };
+        assert_eq!(
+            select(&recent, &one, Some("Notes"), now).as_deref(),
+            Some("runs on GKE.")
+        );
+        assert_eq!(select(&recent, &config, Some("Mail"), now), None);
+    }
+
+    #[test]
+    fn test_select_skips_old_dictations() {
+        let then = Instant::now();
+        let now = then + Duration::from_secs(11 * 60);
+        let recent = VecDeque::from([dictation("Old", "Notes", then)]);
+        assert_eq!(
+            select(&recent, &RecentContextConfig::default(), Some("Notes"), now),
+            None
+        );
+    }
+
+    #[test]
+    fn test_tail_starts_at_a_word() {
+        assert_eq!(tail("short", 10), "short");
+        assert_eq!(tail("one two three", 7), "three");
+        assert_eq!(tail("one two three", 8), "three");
+        assert_eq!(tail("one two three", 9), "two three");
+        assert_eq!(tail("unbroken", 3), "ken");
+    }
+}
@@ -6,19 +6,37 @@ use std::fs::{self, File};
 use std::io::BufWriter;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
-use std::time::SystemTime;
+use std::time::{Duration, Instant, SystemTime};
 use tauri::ipc::Channel;
 use tauri::Manager;
 
+use super::level_meter::LevelMeter;
+use super::{pending_retry, recovery};
+use crate::config::AudioFormat;
+
+/// Flush the WAV writer (buffered samples and header sizes) every 2s of audio
+const FLUSH_INTERVAL_SECS: u32 = 2;
+
 #[derive(Debug, Clone)]
 pub struct RecordingResult {
     pub file_path: String,
     pub duration_ms: u64,
+    /// Time spent writing out the WAV file
+    pub finalize: Duration,
+}
+
+/// Input device a recording is captured from
+#[derive(Debug, Clone)]
+pub struct InputSource {
+    pub device_name: String,
+    /// Native device sample rate (before resampling)
+    pub sample_rate: u32,
 }
 
 /// Active recording session - owns all recording state and lifecycle
 pub struct Recording {
     stream: cpal::Stream,
+    source: InputSource,
     writer: Arc<Mutex<WavWriter<BufWriter<File>>>>,
     start_timestamp: SystemTime,
     filename: String,
@@ -26,6 +44,18 @@ pub struct Recording {
 }
 
 impl Recording {
+    /// Device this recording is captured from
+    pub fn source(&self) -> &InputSource {
+        &self.source
+    }
+
+    /// Time since the recording started
+    pub fn elapsed(&self) -> Duration {
+        SystemTime::now()
+            .duration_since(self.start_timestamp)
+            .unwrap_or_default()
+    }
+
     /// Stop the recording and return the result
     pub fn stop(self) -> Result<RecordingResult, RecorderError> {
         use cpal::traits::StreamTrait;
@@ -41,6 +71,7 @@ impl Recording {
         let file_path = audio_dir.join(&self.filename);
 
         // Finalize WAV file
+        let finalizing = Instant::now();
         if let Ok(writer_mutex) = Arc::try_unwrap(self.writer) {
             if let Ok(writer) = writer_mutex.into_inner() {
                 let result = writer.finalize();
@@ -51,6 +82,8 @@ impl Recording {
                 }
             }
         }
+        recovery::clear_in_progress(&audio_dir);
+        let finalize = finalizing.elapsed();
 
         // Calculate duration
         let duration_ms = SystemTime::now()
@@ -77,6 +110,7 @@ impl Recording {
         Ok(RecordingResult {
             file_path: file_path.to_string_lossy().to_string(),
             duration_ms,
+            finalize,
         })
     }
 }
@@ -137,8 +171,16 @@ impl AudioRecorder {
         AudioRecorder { app_handle }
     }
 
-    /// Start a new recording session
-    pub fn start(&self, level_channel: Option<Channel<f32>>) -> Result<Recording, RecorderError> {
+    /// Start a new recording session written in `format`. Levels are sent to
+    /// `level_channel` at most `level_rate_hz` times per second. Audio that
+    /// arrives before `gate_until` is left out of the file.
+    pub fn start(
+        &self,
+        level_channel: Option<Channel<f32>>,
+        level_rate_hz: u32,
+        format: AudioFormat,
+        gate_until: Option<Instant>,
+    ) -> Result<Recording, RecorderError> {
         println!("[AudioRecorder] Starting recording...");
 
         // Ensure audio directory exists
@@ -150,12 +192,10 @@ impl AudioRecorder {
             .default_input_device()
             .ok_or(RecorderError::NoInputDevice)?;
 
-        println!(
-            "[Audio Recorder] Using input device: {}",
-            device.name().unwrap_or_else(|_| "Unknown".to_string())
-        );
+        let device_name = device.name().unwrap_or_else(|_| "Unknown".to_string());
+        println!("[Audio Recorder] Using input device: {}", device_name);
 
-        // Get default device config - we'll always resample to 16kHz
+        // Get default device config - we'll always resample to the output rate
         let config = device
             .default_input_config()
             .map_err(|_| RecorderError::DeviceError)?;
@@ -172,20 +212,29 @@ impl AudioRecorder {
         let file_path = audio_dir.join(&filename);
         println!("[Audio Recorder] Recording to: {:?}", file_path);
 
-        // Always write 16kHz mono to file (optimal for speech transcription)
+        // Always write mono, at the rate and depth the provider is configured for.
+        // With `native_rate`, a device that's slow enough is recorded as is.
+        let device_rate = config.sample_rate().0;
+        let native = format.native_rate && device_rate <= format.sample_rate;
         let spec = WavSpec {
-            channels: 1,        // Always mono
-            sample_rate: 16000, // Always 16kHz
-            bits_per_sample: 16,
+            channels: 1,
+            sample_rate: if native {
+                device_rate
+            } else {
+                format.sample_rate
+            },
+            bits_per_sample: format.bits_per_sample,
             sample_format: hound::SampleFormat::Int,
         };
 
         let needs_channel_conversion = config.channels() != 1;
 
         println!(
-            "[Audio Recorder] Output: {} Hz mono → resampling from {} Hz {}",
+            "[Audio Recorder] Output: {} Hz {}-bit mono → {} from {} Hz {}",
             spec.sample_rate,
-            config.sample_rate().0,
+            spec.bits_per_sample,
+            if native { "native rate" } else { "resampling" },
+            device_rate,
             if needs_channel_conversion {
                 "stereo"
             } else {
@@ -196,26 +245,30 @@ impl AudioRecorder {
         let writer = WavWriter::create(file_path, spec).map_err(|_| RecorderError::IoError)?;
         let writer = Arc::new(Mutex::new(writer));
 
-        // Always create resampler (device sample rate → 16kHz)
-        let input_rate = config.sample_rate().0 as usize;
-        let output_rate = 16000;
+        let source = InputSource {
+            device_name,
+            sample_rate: config.sample_rate().0,
+        };
+
+        // Create resampler (device sample rate → output rate) unless recording natively
+        let input_rate = device_rate as usize;
+        let output_rate = spec.sample_rate as usize;
         let channels = config.channels() as usize;
 
-        let (resampler, required_chunk_size) = match FftFixedInOut::<f32>::new(
-            input_rate,
-            output_rate,
-            1024,
-            channels,
-        ) {
-            Ok(r) => {
-                // Query the actual input chunk size the resampler needs
-                let input_frames = r.input_frames_next();
-                println!("[Audio Recorder] Created FFT resampler: {}Hz {}ch → 16kHz mono (needs {} input samples per chunk)", input_rate, channels, input_frames);
-                (Arc::new(Mutex::new(r)), input_frames)
-            }
-            Err(e) => {
-                eprintln!("[Audio Recorder] Failed to create resampler: {:?}", e);
-                return Err(RecorderError::DeviceError);
+        let (resampler, required_chunk_size) = if native {
+            (None, 0)
+        } else {
+            match FftFixedInOut::<f32>::new(input_rate, output_rate, 1024, channels) {
+                Ok(r) => {
+                    // Query the actual input chunk size the resampler needs
+                    let input_frames = r.input_frames_next();
+                    println!("[Audio Recorder] Created FFT resampler: {}Hz {}ch → {}Hz mono (needs {} input samples per chunk)", input_rate, channels, output_rate, input_frames);
+                    (Some(Arc::new(Mutex::new(r))), input_frames)
+                }
+                Err(e) => {
+                    eprintln!("[Audio Recorder] Failed to create resampler: {:?}", e);
+                    return Err(RecorderError::DeviceError);
+                }
             }
         };
 
@@ -224,6 +277,8 @@ impl AudioRecorder {
         let sample_buffer: Arc<Mutex<Vec<Vec<f32>>>> =
             Arc::new(Mutex::new(vec![Vec::new(); channels]));
 
+        let level_meter = level_channel.map(|channel| LevelMeter::new(channel, level_rate_hz));
+
         // Build input stream
         let writer_clone = Arc::clone(&writer);
         let err_writer_clone = Arc::clone(&writer);
@@ -233,41 +288,45 @@ impl AudioRecorder {
                 &device,
                 &config.into(),
                 writer_clone,
-                level_channel,
+                level_meter,
                 resampler.clone(),
                 sample_buffer.clone(),
                 required_chunk_size,
                 needs_channel_conversion,
+                gate_until,
             )?,
             cpal::SampleFormat::I16 => build_input_stream::<i16>(
                 &device,
                 &config.into(),
                 writer_clone,
-                level_channel,
+                level_meter,
                 resampler.clone(),
                 sample_buffer.clone(),
                 required_chunk_size,
                 needs_channel_conversion,
+                gate_until,
             )?,
             cpal::SampleFormat::I32 => build_input_stream::<i32>(
                 &device,
                 &config.into(),
                 writer_clone,
-                level_channel,
+                level_meter,
                 resampler.clone(),
                 sample_buffer.clone(),
                 required_chunk_size,
                 needs_channel_conversion,
+                gate_until,
             )?,
             cpal::SampleFormat::F32 => build_input_stream::<f32>(
                 &device,
                 &config.into(),
                 writer_clone,
-                level_channel,
+                level_meter,
                 resampler.clone(),
                 sample_buffer.clone(),
                 required_chunk_size,
                 needs_channel_conversion,
+                gate_until,
             )?,
             _ => return Err(RecorderError::DeviceError),
         };
@@ -275,6 +334,15 @@ impl AudioRecorder {
         // Start the stream
         stream.play()?;
         println!("[AudioRecorder] Stream started successfully");
+        if let Some(until) = gate_until {
+            println!(
+                "[Audio Recorder] Leaving out the next {}ms while the start cue plays",
+                until.saturating_duration_since(Instant::now()).as_millis()
+            );
+        }
+
+        // Lets the next launch recover this file if the app crashes mid-recording
+        recovery::mark_in_progress(&audio_dir, &filename);
 
         // Record start timestamp
         let start_timestamp = SystemTime::now();
@@ -282,6 +350,7 @@ impl AudioRecorder {
         // Return Recording session
         Ok(Recording {
             stream,
+            source,
             writer: err_writer_clone,
             start_timestamp,
             filename,
@@ -320,7 +389,8 @@ pub fn cleanup_recording_file(file_path: &str) {
 }
 
 /// Clean up old recording files on app startup
-/// Only deletes files matching pattern: recording_*.wav
+/// Only deletes files matching pattern: recording_*.wav, and keeps a recording
+/// interrupted by a crash so it can be recovered
 pub fn cleanup_old_recordings(app_handle: &tauri::AppHandle) {
     let recordings_dir = match app_handle.path().app_cache_dir() {
         Ok(cache_dir) => cache_dir.join("recordings"),
@@ -332,13 +402,19 @@ pub fn cleanup_old_recordings(app_handle: &tauri::AppHandle) {
         Err(_) => return, // Directory doesn't exist yet, nothing to clean
     };
 
+    // Kept for crash recovery or a retry
+    let kept = [
+        recovery::interrupted_filename(&recordings_dir),
+        pending_retry::pending_filename(&recordings_dir),
+    ];
+
     let mut cleaned = 0;
     for entry in entries.flatten() {
         let path = entry.path();
         let filename = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
 
         let is_old_recording = filename.starts_with("recording_") && filename.ends_with(".wav");
-        if !is_old_recording {
+        if !is_old_recording || kept.iter().any(|kept| kept.as_deref() == Some(filename)) {
             continue;
         }
 
@@ -365,11 +441,12 @@ fn build_input_stream<T>(
     device: &cpal::Device,
     config: &cpal::StreamConfig,
     writer: Arc<Mutex<WavWriter<BufWriter<File>>>>,
-    level_channel: Option<Channel<f32>>,
-    resampler: Arc<Mutex<FftFixedInOut<f32>>>,
+    mut level_meter: Option<LevelMeter>,
+    resampler: Option<Arc<Mutex<FftFixedInOut<f32>>>>,
     sample_buffer: Arc<Mutex<Vec<Vec<f32>>>>,
     required_chunk_size: usize,
     needs_channel_conversion: bool,
+    gate_until: Option<Instant>,
 ) -> Result<cpal::Stream, RecorderError>
 where
     T: Sample + FromSample<i16> + FromSample<f32> + std::fmt::Debug + cpal::SizedSample,
@@ -386,11 +463,12 @@ where
             write_input_data::<T>(
                 data,
                 &writer,
-                &level_channel,
+                &mut level_meter,
                 &resampler,
                 &sample_buffer,
                 required_chunk_size,
                 needs_channel_conversion,
+                gate_until,
             );
         },
         err_fn,
@@ -400,22 +478,24 @@ where
     Ok(stream)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn write_input_data<T>(
     input: &[T],
     writer: &Arc<Mutex<WavWriter<BufWriter<File>>>>,
-    level_channel: &Option<Channel<f32>>,
-    resampler: &Arc<Mutex<FftFixedInOut<f32>>>,
+    level_meter: &mut Option<LevelMeter>,
+    resampler: &Option<Arc<Mutex<FftFixedInOut<f32>>>>,
     sample_buffer: &Arc<Mutex<Vec<Vec<f32>>>>,
     required_chunk_size: usize,
     needs_channel_conversion: bool,
+    gate_until: Option<Instant>,
 ) where
     T: Sample,
     i16: FromSample<T>,
     f32: FromSample<T>,
 {
     // Calculate RMS (Root Mean Square) for audio level visualization (use original samples)
-    if let Some(channel) = level_channel {
-        if !input.is_empty() {
+    if let Some(meter) = level_meter {
+        if !input.is_empty() && meter.is_active() {
             let sum_of_squares: f32 = input
                 .iter()
                 .map(|&sample| {
@@ -425,10 +505,15 @@ fn write_input_data<T>(
                 .sum();
             let rms = (sum_of_squares / input.len() as f32).sqrt();
             let level = (rms * 100.0).min(1.0);
-            let _ = channel.send(level);
+            meter.push(level);
         }
     }
 
+    // Still within the start cue: the meter shows it, the file doesn't get it
+    if gate_until.is_some_and(|until| Instant::now() < until) {
+        return;
+    }
+
     // Convert samples to f32 and organize by channel, then append to buffer
     let num_channels = if needs_channel_conversion { 2 } else { 1 };
 
@@ -447,6 +532,20 @@ fn write_input_data<T>(
         buffer_guard[channel_idx].push(sample_f32);
     }
 
+    // At the native rate, write whatever arrived
+    let Some(resampler) = resampler else {
+        let channel_chunks: Vec<Vec<f32>> = buffer_guard
+            .iter_mut()
+            .map(|ch| ch.drain(..).collect())
+            .collect();
+        drop(buffer_guard);
+        write_samples(
+            writer,
+            &mix_to_mono(channel_chunks, needs_channel_conversion),
+        );
+        return;
+    };
+
     // Process complete chunks of required_chunk_size samples
     while buffer_guard[0].len() >= required_chunk_size {
         // Extract required_chunk_size samples from each channel
@@ -479,27 +578,7 @@ fn write_input_data<T>(
             }
         };
 
-        // Convert to mono if needed (average stereo channels)
-        let mono_samples = if needs_channel_conversion && resampled.len() >= 2 {
-            let mut mono = Vec::with_capacity(resampled[0].len());
-            for (left, right) in resampled[0].iter().zip(resampled[1].iter()) {
-                let mixed = (left + right) / 2.0;
-                mono.push(mixed);
-            }
-            mono
-        } else {
-            // Already mono, just use first channel
-            resampled[0].clone()
-        };
-
-        // Write to WAV file as i16
-        if let Ok(mut guard) = writer.lock() {
-            for sample_f32 in mono_samples.iter() {
-                let clamped = sample_f32.clamp(-1.0, 1.0);
-                let sample_i16 = (clamped * 32767.0) as i16;
-                guard.write_sample(sample_i16).ok();
-            }
-        }
+        write_samples(writer, &mix_to_mono(resampled, needs_channel_conversion));
 
         // Re-acquire buffer lock for next iteration
         buffer_guard = match sample_buffer.lock() {
@@ -512,3 +591,64 @@ fn write_input_data<T>(
     }
     // Remaining samples (< required_chunk_size) stay in buffer for next call
 }
+
+/// Convert to mono if needed (average stereo channels)
+fn mix_to_mono(mut channels: Vec<Vec<f32>>, needs_channel_conversion: bool) -> Vec<f32> {
+    if needs_channel_conversion && channels.len() >= 2 {
+        channels[0]
+            .iter()
+            .zip(channels[1].iter())
+            .map(|(left, right)| (left + right) / 2.0)
+            .collect()
+    } else {
+        // Already mono, just use first channel
+        channels.swap_remove(0)
+    }
+}
+
+/// Write to WAV file as integers of the file's bit depth
+fn write_samples(writer: &Arc<Mutex<WavWriter<BufWriter<File>>>>, mono_samples: &[f32]) {
+    if let Ok(mut guard) = writer.lock() {
+        let spec = guard.spec();
+        let flush_interval = spec.sample_rate * FLUSH_INTERVAL_SECS;
+        let flushes_before = guard.len() / flush_interval;
+        for &sample_f32 in mono_samples {
+            guard
+                .write_sample(quantize(sample_f32, spec.bits_per_sample))
+                .ok();
+        }
+
+        // Keep the header valid on disk so a crash loses at most the last interval
+        if guard.len() / flush_interval > flushes_before {
+            if let Err(e) = guard.flush() {
+                eprintln!("[Audio Recorder] Failed to flush WAV file: {}", e);
+            }
+        }
+    }
+}
+
+/// Integer sample of `bits_per_sample` bits for a sample in -1.0..=1.0
+fn quantize(sample: f32, bits_per_sample: u16) -> i32 {
+    let max = ((1i64 << (bits_per_sample - 1)) - 1) as f32;
+    (sample.clamp(-1.0, 1.0) * max) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quantize_uses_full_range_of_bit_depth() {
+        assert_eq!(quantize(1.0, 16), 32767);
+        assert_eq!(quantize(-2.0, 16), -32767);
+        assert_eq!(quantize(1.0, 24), 8_388_607);
+        assert_eq!(quantize(0.5, 24), 4_194_303);
+    }
+
+    #[test]
+    fn test_mix_to_mono_averages_stereo() {
+        let stereo = vec![vec![0.5, -1.0], vec![0.0, 1.0]];
+        assert_eq!(mix_to_mono(stereo, true), [0.25, 0.0]);
+        assert_eq!(mix_to_mono(vec![vec![0.5]], false), [0.5]);
+    }
+}
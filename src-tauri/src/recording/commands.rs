@@ -1,15 +1,49 @@
+use crate::events::OnboardingStep;
+
+/// Where a command came from, used to decide who may stop a session
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerSource {
+    /// Fn key listener
+    Keyboard,
+    /// Recording popup buttons (always act on the session they show)
+    Popup,
+}
+
+/// Modifier keys held with the trigger, besides the trigger's own
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeyModifiers {
+    /// Shift: deliver the raw transcript, skipping post-processing
+    pub shift: bool,
+    /// Option: copy to the clipboard instead of pasting
+    pub option: bool,
+}
+
 /// Commands for controlling audio recording
 /// These are sent through channels (NOT Tauri events) for zero-overhead internal communication
 #[derive(Debug, Clone)]
 pub enum RecordingCommand {
     /// Fn key pressed
-    FnDown,
+    FnDown { modifiers: KeyModifiers },
     /// Fn key released
-    FnUp,
+    FnUp { modifiers: KeyModifiers },
     /// Space key pressed - lock the recording
     Lock,
+    /// Start a recording; ignored if one is already in progress
+    Start { source: TriggerSource },
+    /// Capture the selection and start a recording of how to rewrite it
+    StartRewrite { source: TriggerSource },
+    /// Stop the current recording and transcribe it; never starts a new one
+    Stop { source: TriggerSource },
+    /// Start a recording when idle, otherwise stop the current one
+    Toggle { source: TriggerSource },
     /// Cancel the current recording without transcribing
     Cancel,
     /// Retry transcription of the last failed recording
     RetryTranscription,
+    /// Deliver the transcript held for exceeding the length cap
+    DeliverHeld,
+    /// Drop the transcript held for exceeding the length cap
+    DiscardHeld,
+    /// Enter onboarding practice for a step, or leave it with `None`
+    SetPractice { step: Option<OnboardingStep> },
 }
@@ -1,25 +1,64 @@
-use std::path::PathBuf;
+use std::cell::{Cell, RefCell};
+use std::path::{Path, PathBuf};
 use std::sync::{
     atomic::{AtomicU8, Ordering},
     Arc, Mutex,
 };
+use std::time::{Duration, Instant};
 use tauri::ipc::Channel;
 use tauri_plugin_store::StoreExt;
 use tauri_specta::Event;
 use tokio::sync::mpsc::Receiver;
 
-use crate::clients::openai::OpenAIClient;
-use crate::config;
+use crate::action_items;
+use crate::announcements::{self, Announcement};
+use crate::calendar;
+use crate::clients::openai::{OpenAIClient, TranscriptionError, TranscriptionTiming};
+use crate::clients::upload::{self, AbortToken};
+use crate::clients::{provider_policy, reauth};
+use crate::clipboard_paste::ClipboardPasteError;
+use crate::config::{self, AppConfig};
 use crate::error::Error;
+use crate::events::{
+    self, DictationCompleted, DictationMode, OnboardingPracticeTranscript, OnboardingStep,
+    OnboardingStepCompleted, PasteDiagnosed, PasteFailed, RecordingControls, RecordingLocked,
+    RecordingStateChanged, TraceStage,
+};
+use crate::frontmost;
+use crate::history::{self, HistoryEntry};
+use crate::output::{self, AfterPaste, OutputSinkConfig, SinkError};
+use crate::paste_diagnosis::{self, Outcome};
+use crate::pipeline::{self, DictationOverrides};
+use crate::recent_context;
 use crate::recording::{
     audio_recorder::{cleanup_recording_file, AudioRecorder},
-    commands::RecordingCommand,
-    events::RecordingStateChanged,
-    LastRecordingState, Recording,
+    commands::{KeyModifiers, RecordingCommand, TriggerSource},
+    journal::{JournalEvent, StateJournalState},
+    queue::TranscriptionQueueState,
+    restore_paste_from_history,
+    trace::DictationTrace,
+    wav_duration_ms, LastRecordingState, Recording,
+};
+use crate::rewrite;
+use crate::session;
+use crate::sound_player::{self, Sound};
+use crate::stats;
+use crate::summary;
+use crate::ui::window::{
+    close_recording_popup, open_recording_popup, resize_recording_popup_for_error,
+    set_popup_layout, PopupMode,
 };
-use crate::ui::window::{close_recording_popup, open_recording_popup};
 use crate::updater;
 
+/// How long the "No speech detected" notice stays visible
+const NO_SPEECH_NOTICE_DURATION: Duration = Duration::from_millis(1500);
+
+/// Characters of a held transcript shown in the confirmation popup
+const HELD_PREVIEW_CHARS: usize = 200;
+
+/// Gated on top of the start cue, for output latency and the room's echo
+const CUE_ECHO_TAIL: Duration = Duration::from_millis(120);
+
 #[derive(PartialEq, Debug, Copy, Clone)]
 enum ControllerState {
     /// Controller is ready to start recording
@@ -30,6 +69,13 @@ enum ControllerState {
     RecordingLocked,
 }
 
+/// Ownership token for the active session
+#[derive(Debug, Copy, Clone)]
+struct SessionToken {
+    id: u32,
+    owner: TriggerSource,
+}
+
 pub struct Controller {
     command_rx: Receiver<RecordingCommand>,
     audio_recorder: AudioRecorder,
@@ -39,6 +85,22 @@ pub struct Controller {
     shared_state: Arc<AtomicU8>,
     audio_level_channel: Arc<Mutex<Option<Channel<f32>>>>,
     last_recording_state: LastRecordingState,
+    journal: StateJournalState,
+    queue: TranscriptionQueueState,
+    /// When the last transcription request was sent (for rate limiting)
+    last_transcription_at: Cell<Option<Instant>>,
+    /// Transcript over the length cap, waiting for the user to confirm or discard it
+    held_transcript: RefCell<Option<String>>,
+    /// Onboarding step being practised; transcripts go to onboarding instead of the sinks
+    practice: Option<OnboardingStep>,
+    /// App in front when the current dictation started, for explaining a failed paste
+    target_app: RefCell<Option<String>>,
+    /// Selection the current recording says how to rewrite; kept with a
+    /// failed recording for its retry
+    rewrite_selection: RefCell<Option<String>>,
+    /// Active session and the source that started it
+    session: Option<SessionToken>,
+    next_session_id: u32,
 }
 
 impl Controller {
@@ -49,6 +111,8 @@ impl Controller {
         shared_state: Arc<AtomicU8>,
         audio_level_channel: Arc<Mutex<Option<Channel<f32>>>>,
         last_recording_state: LastRecordingState,
+        journal: StateJournalState,
+        queue: TranscriptionQueueState,
     ) -> Self {
         let audio_recorder = AudioRecorder::new(app_handle.clone());
 
@@ -63,59 +127,59 @@ impl Controller {
             shared_state,
             audio_level_channel,
             last_recording_state,
+            journal,
+            queue,
+            last_transcription_at: Cell::new(None),
+            held_transcript: RefCell::new(None),
+            practice: None,
+            target_app: RefCell::new(None),
+            rewrite_selection: RefCell::new(None),
+            session: None,
+            next_session_id: 1,
         }
     }
 
-    /// Main control loop - consumes self, runs in blocking thread
-    pub fn run(mut self) {
-        // Recording session lives here (not Send, so stays in this thread)
+    /// Main control loop - runs in blocking thread under the supervisor.
+    /// Returns when the command channel closes; after a panic the supervisor
+    /// calls it again on the same Controller.
+    pub fn run(&mut self) {
+        // Recording session lives here (not Send, so stays in this thread).
+        // If the loop panics it is dropped during unwinding, which stops the stream.
         let mut current_recording: Option<Recording> = None;
 
+        self.recover_after_restart();
+
         println!("[Controller] Starting command processing loop");
 
         while let Some(command) = self.command_rx.blocking_recv() {
+            self.journal.record(JournalEvent::Command {
+                command: format!("{:?}", command),
+            });
+
             match command {
-                RecordingCommand::FnDown => {
+                RecordingCommand::FnDown { modifiers } => {
                     match self.state {
                         ControllerState::Ready => {
-                            // Start recording
-                            self.set_state(ControllerState::Recording);
-                            match self.handle_start() {
-                                Ok(recording) => current_recording = Some(recording),
-                                Err(e) => {
-                                    eprintln!("[Controller] Error starting recording: {:?}", e);
-                                    self.set_state(ControllerState::Ready);
-                                }
-                            }
+                            self.start_session(TriggerSource::Keyboard, &mut current_recording);
                         }
                         ControllerState::RecordingLocked => {
                             // Stop locked recording
-                            if let Some(rec) = current_recording.take() {
-                                if let Err(e) = self.handle_stop(rec) {
-                                    eprintln!("[Controller] Error stopping recording: {:?}", e);
-                                }
+                            if self.may_stop(TriggerSource::Keyboard) {
+                                self.stop_session(&mut current_recording, overrides(modifiers));
                             }
-                            self.set_state(ControllerState::Ready);
-                            // Notify updater that recording/transcription finished
-                            updater::on_recording_finished(&self.app_handle);
                         }
                         _ => {
                             println!("[Controller] FnDown ignored in Recording state");
                         }
                     }
                 }
-                RecordingCommand::FnUp => {
+                RecordingCommand::FnUp { modifiers } => {
                     match self.state {
                         ControllerState::Recording => {
                             // Stop recording normally
-                            if let Some(rec) = current_recording.take() {
-                                if let Err(e) = self.handle_stop(rec) {
-                                    eprintln!("[Controller] Error stopping recording: {:?}", e);
-                                }
+                            if self.may_stop(TriggerSource::Keyboard) {
+                                self.stop_session(&mut current_recording, overrides(modifiers));
                             }
-                            self.set_state(ControllerState::Ready);
-                            // Notify updater that recording/transcription finished
-                            updater::on_recording_finished(&self.app_handle);
                         }
                         _ => {
                             println!("[Controller] FnUp ignored (Ready or RecordingLocked state)");
@@ -128,12 +192,59 @@ impl Controller {
                             // Lock the recording
                             self.set_state(ControllerState::RecordingLocked);
                             println!("[Controller] Recording locked - FnUp will be ignored");
+                            sound_player::play_cue(&self.app_handle, Sound::Locked);
+                            let elapsed = current_recording
+                                .as_ref()
+                                .map(Recording::elapsed)
+                                .unwrap_or_default();
+                            events::emit_or_log(
+                                &self.app_handle,
+                                RecordingLocked {
+                                    elapsed_ms: elapsed.as_millis() as u32,
+                                },
+                            );
+                            if self.practice == Some(OnboardingStep::FnSpace) {
+                                events::emit_or_log(
+                                    &self.app_handle,
+                                    OnboardingStepCompleted {
+                                        step: OnboardingStep::FnSpace,
+                                    },
+                                );
+                            }
                         }
                         _ => {
                             println!("[Controller] Lock ignored (not in Recording state)");
                         }
                     }
                 }
+                RecordingCommand::Start { source } => {
+                    if self.state == ControllerState::Ready {
+                        self.start_session(source, &mut current_recording);
+                    } else {
+                        println!("[Controller] Start ignored (already recording)");
+                    }
+                }
+                RecordingCommand::StartRewrite { source } => {
+                    if self.state == ControllerState::Ready {
+                        self.start_rewrite_session(source, &mut current_recording);
+                    } else {
+                        println!("[Controller] StartRewrite ignored (already recording)");
+                    }
+                }
+                RecordingCommand::Stop { source } => {
+                    if self.state == ControllerState::Ready {
+                        println!("[Controller] Stop ignored (not recording)");
+                    } else if self.may_stop(source) {
+                        self.stop_session(&mut current_recording, DictationOverrides::default());
+                    }
+                }
+                RecordingCommand::Toggle { source } => {
+                    if self.state == ControllerState::Ready {
+                        self.start_session(source, &mut current_recording);
+                    } else if self.may_stop(source) {
+                        self.stop_session(&mut current_recording, DictationOverrides::default());
+                    }
+                }
                 RecordingCommand::Cancel => {
                     // Cancel works in both Recording and RecordingLocked states
                     if self.state != ControllerState::Ready {
@@ -153,65 +264,262 @@ impl Controller {
                     // Notify updater that transcription finished (success or failure)
                     updater::on_recording_finished(&self.app_handle);
                 }
+                RecordingCommand::DeliverHeld => {
+                    if let Err(e) = self.handle_deliver_held() {
+                        eprintln!("[Controller] Error delivering held transcript: {:?}", e);
+                    }
+                }
+                RecordingCommand::DiscardHeld => self.handle_discard_held(),
+                RecordingCommand::SetPractice { step } => {
+                    println!("[Controller] Onboarding practice: {:?}", step);
+                    self.practice = step;
+                }
             }
         }
 
         println!("[Controller] Channel closed, shutting down");
     }
 
+    /// Reset a session that was interrupted by a panic so the app isn't stuck recording
+    fn recover_after_restart(&mut self) {
+        if self.state == ControllerState::Ready {
+            return;
+        }
+
+        eprintln!(
+            "[Controller] Restarted while in {:?} state, resetting to Ready",
+            self.state
+        );
+        self.set_state(ControllerState::Ready);
+
+        if let Err(e) = close_recording_popup(&self.app_handle) {
+            eprintln!("[Controller] Failed to close recording popup: {}", e);
+        }
+        events::emit_or_log(&self.app_handle, RecordingStateChanged::Cancelled);
+    }
+
+    /// Start recording and hand the session to `owner`
+    fn start_session(&mut self, owner: TriggerSource, current_recording: &mut Option<Recording>) {
+        if !session::is_active() {
+            println!("[Controller] Start ignored, user session is in the background");
+            return;
+        }
+        let app_name = frontmost::app_name();
+        if config::managed::current().blocks(app_name.as_deref(), frontmost::bundle_id().as_deref())
+        {
+            let app_name = app_name.unwrap_or_else(|| "this app".to_string());
+            println!(
+                "[Controller] Start refused, {} is blocked by the managed config",
+                app_name
+            );
+            sound_player::play_cue(&self.app_handle, Sound::Error);
+            announcements::announce(
+                &self.app_handle,
+                Announcement::Failed(format!(
+                    "Your organization turned off dictation in {}",
+                    app_name
+                )),
+            );
+            return;
+        }
+        self.set_state(ControllerState::Recording);
+        match self.handle_start() {
+            Ok(recording) => {
+                *current_recording = Some(recording);
+                self.begin_session(owner);
+            }
+            Err(e) => {
+                eprintln!("[Controller] Error starting recording: {:?}", e);
+                self.set_state(ControllerState::Ready);
+            }
+        }
+    }
+
+    /// Start a recording of how to rewrite the current selection, refusing
+    /// when there's nothing selected or the chat model is off limits
+    fn start_rewrite_session(
+        &mut self,
+        owner: TriggerSource,
+        current_recording: &mut Option<Recording>,
+    ) {
+        let selection = if config::managed::current().offline {
+            Err("Rewriting needs the chat model, which your organization turned off")
+        } else {
+            rewrite::capture_selection().ok_or("Select the text to rewrite first")
+        };
+
+        match selection {
+            Ok(selection) => {
+                self.start_session(owner, current_recording);
+                if self.state != ControllerState::Ready {
+                    println!("[Controller] Recording a rewrite instruction");
+                    *self.rewrite_selection.borrow_mut() = Some(selection);
+                }
+            }
+            Err(message) => {
+                println!("[Controller] Rewrite refused: {}", message);
+                sound_player::play_cue(&self.app_handle, Sound::Error);
+                announcements::announce(
+                    &self.app_handle,
+                    Announcement::Failed(message.to_string()),
+                );
+            }
+        }
+    }
+
+    /// Hand out an ownership token for the session that just started
+    fn begin_session(&mut self, owner: TriggerSource) {
+        let token = SessionToken {
+            id: self.next_session_id,
+            owner,
+        };
+        self.next_session_id = self.next_session_id.wrapping_add(1);
+        println!("[Controller] Session {} started by {:?}", token.id, owner);
+        self.session = Some(token);
+    }
+
+    /// Whether `source` may stop the active session. The owner and the popup
+    /// always can; other sources only when cross-source stop is enabled.
+    fn may_stop(&self, source: TriggerSource) -> bool {
+        let Some(session) = self.session else {
+            return true;
+        };
+
+        if session.owner == source || source == TriggerSource::Popup {
+            return true;
+        }
+
+        let allow_cross_source_stop = self
+            .app_handle
+            .store("config.json")
+            .map(|store| config::load_app_config(&store).allow_cross_source_stop)
+            .unwrap_or(false);
+
+        if !allow_cross_source_stop {
+            println!(
+                "[Controller] Stop from {:?} ignored, session {} is owned by {:?}",
+                source, session.id, session.owner
+            );
+        }
+        allow_cross_source_stop
+    }
+
+    /// Stop the active recording, transcribe it and return to Ready
+    fn stop_session(
+        &mut self,
+        current_recording: &mut Option<Recording>,
+        overrides: DictationOverrides,
+    ) {
+        // Nothing to stop or cancel while transcribing
+        self.emit_controls(false);
+        if let Some(rec) = current_recording.take() {
+            if let Err(e) = self.handle_stop(rec, overrides) {
+                eprintln!("[Controller] Error stopping recording: {:?}", e);
+            }
+        }
+        self.set_state(ControllerState::Ready);
+        // Notify updater that recording/transcription finished
+        updater::on_recording_finished(&self.app_handle);
+    }
+
     fn handle_start(&self) -> Result<Recording, Error> {
         println!("[Controller] Received Start command");
 
+        // A new dictation replaces a held transcript the user never confirmed
+        if self.held_transcript.borrow_mut().take().is_some() {
+            println!("[Controller] Dropped unconfirmed held transcript");
+        }
+        self.rewrite_selection.borrow_mut().take();
+
+        *self.target_app.borrow_mut() = frontmost::app_name();
+
         // Show recording popup window
         if let Err(e) = open_recording_popup(&self.app_handle) {
             eprintln!("[Controller] Failed to open recording popup: {}", e);
         }
 
-        RecordingStateChanged::Started.emit(&self.app_handle)?;
+        let start_cue = sound_player::play_cue(&self.app_handle, Sound::Start);
+        let cue_started = Instant::now();
+        announcements::announce(&self.app_handle, Announcement::RecordingStarted);
 
         // Get the audio level channel if one is registered
         let level_channel = self.audio_level_channel.lock().unwrap().clone();
+        let app_config = match self.app_handle.store("config.json") {
+            Ok(store) => config::load_app_config(&store),
+            Err(_) => AppConfig::default(),
+        };
+        let level_rate_hz = app_config.audio_level_rate_hz;
+        let format = app_config
+            .recorder
+            .format_for(app_config.active_provider.as_ref());
+        let gate_until = start_cue
+            .filter(|_| app_config.sounds.gate_start_cue)
+            .map(|cue| cue_started + cue + CUE_ECHO_TAIL);
 
-        let recording = match self.audio_recorder.start(level_channel) {
-            Ok(rec) => rec,
-            Err(e) => {
-                eprintln!("[Controller] Error starting recording: {:?}", e);
+        let recording =
+            match self
+                .audio_recorder
+                .start(level_channel, level_rate_hz, format, gate_until)
+            {
+                Ok(rec) => rec,
+                Err(e) => {
+                    eprintln!("[Controller] Error starting recording: {:?}", e);
+                    sound_player::play_cue(&self.app_handle, Sound::Error);
+                    announcements::announce(
+                        &self.app_handle,
+                        Announcement::Failed(e.user_message()),
+                    );
 
-                // Emit error event to frontend
-                let error_event = RecordingStateChanged::Error {
-                    error_type: "recording".to_string(),
-                    error_message: format!("{:?}", e),
-                    user_message: e.user_message(),
-                    audio_file_path: None,
-                };
+                    // Emit error event to frontend
+                    let error_event = RecordingStateChanged::Error {
+                        error_type: "recording".to_string(),
+                        error_message: format!("{:?}", e),
+                        user_message: e.user_message(),
+                        audio_file_path: None,
+                    };
 
-                if let Err(emit_err) = error_event.emit(&self.app_handle) {
-                    eprintln!(
-                        "[Controller] Failed to emit recording-error event: {}",
-                        emit_err
-                    );
+                    events::emit_or_log(&self.app_handle, error_event);
+
+                    return Err(Error::from(e));
                 }
+            };
 
-                return Err(Error::from(e));
-            }
-        };
+        // Emitted once the device is open so the popup can show where audio comes from
+        let source = recording.source();
+        RecordingStateChanged::Started {
+            device_name: source.device_name.clone(),
+            sample_rate: source.sample_rate,
+        }
+        .emit(&self.app_handle)?;
 
         Ok(recording)
     }
 
-    fn handle_stop(&self, recording: Recording) -> Result<(), Error> {
+    fn handle_stop(
+        &self,
+        recording: Recording,
+        overrides: DictationOverrides,
+    ) -> Result<(), Error> {
         println!("[Controller] Received Stop command");
 
+        let rewrite_selection = self.rewrite_selection.borrow_mut().take();
+        let mut trace = DictationTrace::start();
+        let stopping = Instant::now();
         let recording_result = recording.stop()?;
+        trace.record(
+            TraceStage::CaptureStop,
+            stopping.elapsed().saturating_sub(recording_result.finalize),
+        );
+        trace.record(TraceStage::FileFinalize, recording_result.finalize);
+        let job = self.queue.enqueue();
+        let mode = if self.state == ControllerState::RecordingLocked {
+            DictationMode::HandsFree
+        } else {
+            DictationMode::PushToTalk
+        };
 
-        println!("[Controller] Emitting recording-transcribing event");
-        match RecordingStateChanged::Transcribing.emit(&self.app_handle) {
-            Ok(_) => println!("[Controller] Successfully emitted recording-transcribing event"),
-            Err(e) => eprintln!(
-                "[Controller] Failed to emit recording-transcribing event: {:?}",
-                e
-            ),
-        }
+        events::emit_or_log(&self.app_handle, RecordingStateChanged::Transcribing);
+        announcements::announce(&self.app_handle, Announcement::Transcribing);
 
         // Load provider config
         let store = match self.app_handle.store("config.json") {
@@ -226,76 +534,101 @@ impl Controller {
                 ));
             }
         };
-        let app_config = config::load_app_config(&store);
-
-        // Transcribe with loaded config
-        let transcription_result = self.openai_client.transcribe_audio_sync(
-            PathBuf::from(&recording_result.file_path),
+        let app_config = self.choose_provider(
+            config::load_app_config(&store),
             recording_result.duration_ms,
-            &app_config,
         );
 
+        // A rewrite instruction doesn't continue earlier dictations
+        let prompt = if rewrite_selection.is_some() {
+            None
+        } else {
+            recent_context::context(&app_config, self.target_app.borrow().as_deref())
+        };
+
+        // Transcribe with loaded config
+        let started = Instant::now();
+        let transcription_result = self.check_rate_limit(&app_config).and_then(|_| {
+            self.transcribe_abortable(
+                PathBuf::from(&recording_result.file_path),
+                recording_result.duration_ms,
+                &app_config,
+                prompt,
+                job.abort_token(),
+            )
+        });
+        let latency = started.elapsed();
+        let transcription_result = transcription_result.map(|(text, timing)| {
+            trace.record_request(timing, latency);
+            text
+        });
+        if job.is_aborted() {
+            return self.discard_aborted(&recording_result.file_path);
+        }
+        if transcription_result.is_ok() {
+            provider_policy::record_latency(
+                &self.app_handle,
+                &app_config,
+                recording_result.duration_ms,
+                latency,
+            );
+        }
+
+        let needs_reauth =
+            reauth::record_transcription(&self.app_handle, &app_config, &transcription_result);
+
         match transcription_result {
             Ok(text) => {
                 // Clean up recording file after successful transcription
                 cleanup_recording_file(&recording_result.file_path);
 
-                if !text.is_empty() {
-                    crate::clipboard_paste::auto_paste_text_cgevent(&text)?;
-                }
-
-                // Update last recording state with successful transcription
-                if let Ok(mut last_recording) = self.last_recording_state.lock() {
-                    last_recording.text = Some(text.clone());
-                    last_recording.timestamp = Some(std::time::SystemTime::now());
-                    last_recording.audio_file_path = None;
+                if let Some(selection) = rewrite_selection {
+                    return self.finish_rewrite(text, selection, &app_config);
                 }
-
-                // Enable the paste menu item
-                if let Err(e) = crate::ui::tray::update_paste_menu_item(&self.app_handle, true) {
-                    eprintln!("[Controller] Failed to enable paste menu item: {}", e);
-                }
-
-                // Hide recording popup window
-                if let Err(e) = close_recording_popup(&self.app_handle) {
-                    eprintln!("[Controller] Failed to close recording popup: {}", e);
-                }
-
-                RecordingStateChanged::Stopped { text: text.clone() }.emit(&self.app_handle)?;
-
-                Ok(())
+                self.finish_transcription(
+                    text,
+                    &app_config,
+                    recording_result.duration_ms,
+                    latency,
+                    mode,
+                    overrides,
+                    trace,
+                )
             }
             Err(e) => {
                 eprintln!("[Controller] Transcription error: {}", e);
+                sound_player::play_cue(&self.app_handle, Sound::Error);
 
                 // Update last recording state with failed transcription
                 // Keep the audio file for retry
                 if let Ok(mut last_recording) = self.last_recording_state.lock() {
-                    last_recording.text = None;
                     last_recording.timestamp = None;
-                    last_recording.audio_file_path = Some(recording_result.file_path.clone());
+                    last_recording.keep_for_retry(recording_result.file_path.clone());
                 }
+                *self.rewrite_selection.borrow_mut() = rewrite_selection;
 
-                // Disable the paste menu item since there's no text to paste
-                if let Err(err) = crate::ui::tray::update_paste_menu_item(&self.app_handle, false) {
-                    eprintln!("[Controller] Failed to disable paste menu item: {}", err);
-                }
+                // Nothing new to paste, fall back to the newest history entry
+                restore_paste_from_history(
+                    &self.app_handle,
+                    &app_config,
+                    &self.last_recording_state,
+                );
 
                 // DON'T close popup - keep it open to show error
                 // Emit error event to frontend
+                let user_message = reauth_aware_message(&e, needs_reauth);
+                announcements::announce(
+                    &self.app_handle,
+                    Announcement::Failed(user_message.clone()),
+                );
                 let error_event = RecordingStateChanged::Error {
                     error_type: "transcription".to_string(),
                     error_message: format!("{}", e),
-                    user_message: e.user_message(),
+                    user_message,
                     audio_file_path: Some(recording_result.file_path.clone()),
                 };
 
-                if let Err(emit_err) = error_event.emit(&self.app_handle) {
-                    eprintln!(
-                        "[Controller] Failed to emit recording-error event: {}",
-                        emit_err
-                    );
-                }
+                events::emit_or_log(&self.app_handle, error_event);
 
                 Err(Error::from(e))
             }
@@ -304,6 +637,7 @@ impl Controller {
 
     fn handle_cancel(&self, recording: Recording) -> Result<(), Error> {
         println!("[Controller] Received Cancel command");
+        self.rewrite_selection.borrow_mut().take();
 
         // Stop recording (creates file but we don't use it)
         let recording_result = recording.stop()?;
@@ -316,8 +650,11 @@ impl Controller {
             eprintln!("[Controller] Failed to close recording popup: {}", e);
         }
 
+        sound_player::play_cue(&self.app_handle, Sound::Cancelled);
+
         // Emit cancellation event for frontend awareness
         RecordingStateChanged::Cancelled.emit(&self.app_handle)?;
+        announcements::announce(&self.app_handle, Announcement::Cancelled);
 
         println!("[Controller] Recording cancelled successfully");
         Ok(())
@@ -325,6 +662,9 @@ impl Controller {
 
     fn handle_retry_transcription(&self) -> Result<(), Error> {
         println!("[Controller] Retrying transcription");
+        let mut trace = DictationTrace::start();
+        *self.target_app.borrow_mut() = frontmost::app_name();
+        let rewrite_selection = self.rewrite_selection.borrow_mut().take();
 
         // Get audio file path from last recording state
         let (audio_file_path, duration_ms) = {
@@ -340,19 +680,19 @@ impl Controller {
                 ))
             })?;
 
-            // Estimate duration from file size: ~32KB per second for 16kHz mono 16-bit
-            let metadata = std::fs::metadata(&path).map_err(|e| {
+            // Recordings can be in any configured format, so ask the header
+            let duration_ms = wav_duration_ms(Path::new(&path)).ok_or_else(|| {
                 Error::from(crate::clients::openai::TranscriptionError::FileNotFound(
-                    format!("File not found: {}", e),
+                    format!("File not found or unreadable: {}", path),
                 ))
             })?;
-            let duration_ms = (metadata.len() * 1000) / 32000;
 
             (path, duration_ms)
         };
 
+        let job = self.queue.enqueue();
+
         // Emit transcribing event
-        println!("[Controller] Emitting recording-transcribing event for retry");
         RecordingStateChanged::Transcribing.emit(&self.app_handle)?;
 
         // Load provider config
@@ -368,88 +708,569 @@ impl Controller {
                 ));
             }
         };
-        let app_config = config::load_app_config(&store);
+        let app_config = self.choose_provider(config::load_app_config(&store), duration_ms);
+        let prompt = if rewrite_selection.is_some() {
+            None
+        } else {
+            recent_context::context(&app_config, self.target_app.borrow().as_deref())
+        };
 
         // Transcribe with loaded config
-        let transcription_result = self.openai_client.transcribe_audio_sync(
-            PathBuf::from(&audio_file_path),
-            duration_ms,
-            &app_config,
-        );
+        let started = Instant::now();
+        let transcription_result = self.check_rate_limit(&app_config).and_then(|_| {
+            self.transcribe_abortable(
+                PathBuf::from(&audio_file_path),
+                duration_ms,
+                &app_config,
+                prompt,
+                job.abort_token(),
+            )
+        });
+        let latency = started.elapsed();
+        let transcription_result = transcription_result.map(|(text, timing)| {
+            trace.record_request(timing, latency);
+            text
+        });
+        if job.is_aborted() {
+            return self.discard_aborted(&audio_file_path);
+        }
+        if transcription_result.is_ok() {
+            provider_policy::record_latency(&self.app_handle, &app_config, duration_ms, latency);
+        }
+
+        let needs_reauth =
+            reauth::record_transcription(&self.app_handle, &app_config, &transcription_result);
 
         match transcription_result {
             Ok(text) => {
                 // Clean up recording file after successful transcription
                 cleanup_recording_file(&audio_file_path);
 
-                if !text.is_empty() {
-                    crate::clipboard_paste::auto_paste_text_cgevent(&text)?;
-                }
-
-                // Update last recording state with successful transcription
-                if let Ok(mut last_recording) = self.last_recording_state.lock() {
-                    last_recording.text = Some(text.clone());
-                    last_recording.timestamp = Some(std::time::SystemTime::now());
-                    last_recording.audio_file_path = None;
-                }
-
-                // Enable the paste menu item
-                if let Err(e) = crate::ui::tray::update_paste_menu_item(&self.app_handle, true) {
-                    eprintln!("[Controller] Failed to enable paste menu item: {}", e);
+                if let Some(selection) = rewrite_selection {
+                    return self.finish_rewrite(text, selection, &app_config);
                 }
-
-                // Hide recording popup window
-                if let Err(e) = close_recording_popup(&self.app_handle) {
-                    eprintln!("[Controller] Failed to close recording popup: {}", e);
-                }
-
-                RecordingStateChanged::Stopped { text: text.clone() }.emit(&self.app_handle)?;
-
-                Ok(())
+                self.finish_transcription(
+                    text,
+                    &app_config,
+                    duration_ms,
+                    latency,
+                    DictationMode::Retry,
+                    DictationOverrides::default(),
+                    trace,
+                )
             }
             Err(e) => {
                 eprintln!("[Controller] Retry transcription error: {}", e);
+                sound_player::play_cue(&self.app_handle, Sound::Error);
 
                 // Update last recording state - keep audio file for another retry
                 if let Ok(mut last_recording) = self.last_recording_state.lock() {
-                    last_recording.text = None;
                     last_recording.timestamp = None;
-                    last_recording.audio_file_path = Some(audio_file_path.clone());
+                    last_recording.keep_for_retry(audio_file_path.clone());
                 }
+                *self.rewrite_selection.borrow_mut() = rewrite_selection;
 
-                // Disable the paste menu item since there's no text to paste
-                if let Err(err) = crate::ui::tray::update_paste_menu_item(&self.app_handle, false) {
-                    eprintln!("[Controller] Failed to disable paste menu item: {}", err);
-                }
+                // Nothing new to paste, fall back to the newest history entry
+                restore_paste_from_history(
+                    &self.app_handle,
+                    &app_config,
+                    &self.last_recording_state,
+                );
 
                 // DON'T close popup - keep it open to show error
                 // Emit error event to frontend
+                let user_message = reauth_aware_message(&e, needs_reauth);
+                announcements::announce(
+                    &self.app_handle,
+                    Announcement::Failed(user_message.clone()),
+                );
                 let error_event = RecordingStateChanged::Error {
                     error_type: "transcription".to_string(),
                     error_message: format!("{}", e),
-                    user_message: e.user_message(),
+                    user_message,
                     audio_file_path: Some(audio_file_path),
                 };
 
-                if let Err(emit_err) = error_event.emit(&self.app_handle) {
-                    eprintln!(
-                        "[Controller] Failed to emit recording-error event: {}",
-                        emit_err
-                    );
-                }
+                events::emit_or_log(&self.app_handle, error_event);
 
                 Err(Error::from(e))
             }
         }
     }
 
+    /// Send the recording to the provider on a thread of its own, so
+    /// cancelling the transcription through `abort` stops the wait right away
+    /// instead of once the provider answers
+    fn transcribe_abortable(
+        &self,
+        file_path: PathBuf,
+        duration_ms: u64,
+        app_config: &AppConfig,
+        prompt: Option<String>,
+        abort: &AbortToken,
+    ) -> Result<(String, TranscriptionTiming), TranscriptionError> {
+        let client = self.openai_client.clone();
+        let config = app_config.clone();
+        let upload_abort = abort.clone();
+        upload::run_abortable(abort, move || {
+            client.transcribe_audio_timed(
+                file_path,
+                Some(duration_ms),
+                &config,
+                prompt.as_deref(),
+                &upload_abort,
+            )
+        })
+        .unwrap_or_else(|| {
+            Err(TranscriptionError::ApiError(
+                "Transcription request was stopped".to_string(),
+            ))
+        })
+    }
+
+    /// Drop a transcription cancelled while it ran, whatever the provider
+    /// returned. The recording goes the way of a cancelled one.
+    fn discard_aborted(&self, audio_file_path: &str) -> Result<(), Error> {
+        println!("[Controller] Transcription was cancelled, discarding the result");
+        cleanup_recording_file(audio_file_path);
+        if let Ok(mut last_recording) = self.last_recording_state.lock() {
+            if last_recording.audio_file_path.as_deref() == Some(audio_file_path) {
+                last_recording.take_retry();
+            }
+        }
+
+        if let Err(e) = close_recording_popup(&self.app_handle) {
+            eprintln!("[Controller] Failed to close recording popup: {}", e);
+        }
+        RecordingStateChanged::Cancelled.emit(&self.app_handle)?;
+        announcements::announce(&self.app_handle, Announcement::Cancelled);
+        Ok(())
+    }
+
+    /// Apply the provider policy, if enabled, and journal the decision
+    fn choose_provider(&self, mut app_config: AppConfig, duration_ms: u64) -> AppConfig {
+        if let Some(selection) = provider_policy::select(&self.app_handle, &app_config, duration_ms)
+        {
+            self.journal.record(JournalEvent::ProviderSelected {
+                provider: format!("{:?}", selection.provider),
+                reason: selection.reason,
+            });
+            app_config.active_provider = Some(selection.provider);
+        }
+        app_config
+    }
+
+    /// Reject a transcription that follows the previous one too closely.
+    /// The recording is kept, so the user can retry once the interval has passed.
+    fn check_rate_limit(&self, app_config: &AppConfig) -> Result<(), TranscriptionError> {
+        let min_interval = Duration::from_millis(app_config.min_transcription_interval_ms as u64);
+        let now = Instant::now();
+
+        if let Some(last) = self.last_transcription_at.get() {
+            let elapsed = now.duration_since(last);
+            if elapsed < min_interval {
+                let retry_in = min_interval - elapsed;
+                println!(
+                    "[Controller] Transcription rate limited, {}ms since last request",
+                    elapsed.as_millis()
+                );
+                return Err(TranscriptionError::RateLimited {
+                    retry_in_ms: retry_in.as_millis() as u64,
+                });
+            }
+        }
+
+        self.last_transcription_at.set(Some(now));
+        Ok(())
+    }
+
+    /// Post-process the provider's text, summarize long locked recordings and
+    /// keep them in history and stats, then deliver the result and emit
+    /// `dictation-completed`, or show the no-speech notice
+    #[allow(clippy::too_many_arguments)]
+    fn finish_transcription(
+        &self,
+        text: String,
+        app_config: &AppConfig,
+        duration_ms: u64,
+        latency: Duration,
+        mode: DictationMode,
+        overrides: DictationOverrides,
+        mut trace: DictationTrace,
+    ) -> Result<(), Error> {
+        let provider_text = !text.trim().is_empty();
+        let text = pipeline::run_with_overrides(text, app_config, overrides);
+
+        if text.is_empty() {
+            return self.handle_empty_transcript(app_config, provider_text);
+        }
+
+        if let Some(step) = self.practice {
+            return self.finish_practice(step, text, mode);
+        }
+        recent_context::remember(app_config, &text, self.target_app.borrow().as_deref());
+
+        let mut completed = DictationCompleted {
+            timestamp: chrono::Local::now().to_rfc3339(),
+            duration_ms: duration_ms.min(u32::MAX as u64) as u32,
+            words: text.split_whitespace().count() as u32,
+            provider: app_config.active_provider.clone(),
+            latency_ms: latency.as_millis().min(u32::MAX as u128) as u32,
+            mode,
+            app: frontmost::app_name(),
+            trace_id: trace.id(),
+            timing: None,
+        };
+
+        let locked = mode == DictationMode::HandsFree;
+        let summary = if overrides.raw {
+            None
+        } else {
+            summary::summarize(app_config, &text, locked)
+        };
+        let delivered = summary::delivered_text(
+            &text,
+            summary.as_ref().map(|summary| summary.text.as_str()),
+            app_config.summary.paste,
+        );
+        trace.mark(TraceStage::PostProcess);
+        if let Some(summary) = &summary {
+            action_items::deliver_in_background(
+                &app_config.summary.action_item_sinks,
+                &summary.action_items,
+            );
+        }
+        history::record(
+            &self.app_handle,
+            app_config,
+            HistoryEntry {
+                meeting: calendar::history_tag(&app_config.calendar),
+                ..HistoryEntry::new(&text, summary.as_ref(), &completed)
+            },
+        );
+        stats::record_dictation(&self.app_handle, &completed);
+
+        // Copying to the clipboard is always safe, so it skips the length cap
+        if overrides.clipboard_only {
+            println!("[Controller] Copying to the clipboard only, as requested");
+            self.handle_transcript(delivered, &[OutputSinkConfig::Clipboard])?;
+        } else if output::exceeds_length_cap(&delivered, app_config.max_delivery_chars) {
+            self.hold_transcript(delivered, app_config.max_delivery_chars)?;
+        } else {
+            self.handle_transcript(delivered, &app_config.output_sinks)?;
+        }
+        trace.mark(TraceStage::Delivery);
+
+        if app_config.show_timing_breakdown {
+            completed.timing = Some(trace.stages().to_vec());
+        }
+        events::emit_or_log(&self.app_handle, completed);
+        Ok(())
+    }
+
+    /// Rewrite `selection` as the transcribed `instruction` says and paste the
+    /// result over it
+    fn finish_rewrite(
+        &self,
+        instruction: String,
+        selection: String,
+        app_config: &AppConfig,
+    ) -> Result<(), Error> {
+        if instruction.trim().is_empty() {
+            return self.handle_empty_transcript(app_config, false);
+        }
+
+        match rewrite::rewrite(app_config, &instruction, &selection) {
+            // The selection is still there, so the paste replaces it
+            Ok(text) => self.handle_transcript(
+                text,
+                &[OutputSinkConfig::Paste {
+                    after_paste: AfterPaste::Stay,
+                }],
+            ),
+            Err(e) => {
+                eprintln!("[Controller] Rewrite error: {}", e);
+                sound_player::play_cue(&self.app_handle, Sound::Error);
+                announcements::announce(&self.app_handle, Announcement::Failed(e.user_message()));
+                events::emit_or_log(
+                    &self.app_handle,
+                    RecordingStateChanged::Error {
+                        error_type: "rewrite".to_string(),
+                        error_message: format!("{}", e),
+                        user_message: e.user_message(),
+                        audio_file_path: None,
+                    },
+                );
+                Err(Error::from(e))
+            }
+        }
+    }
+
+    /// Deliver the transcript to `sinks` and update last recording, tray and popup
+    fn handle_transcript(&self, text: String, sinks: &[OutputSinkConfig]) -> Result<(), Error> {
+        let failures = output::deliver(sinks, &text);
+
+        // Update last recording state with successful transcription
+        if let Ok(mut last_recording) = self.last_recording_state.lock() {
+            last_recording.text = Some(text.clone());
+            last_recording.timestamp = Some(std::time::SystemTime::now());
+            last_recording.take_retry();
+        }
+
+        // Enable the paste menu item
+        if let Err(e) = crate::ui::tray::update_paste_menu_item(&self.app_handle, true) {
+            eprintln!("[Controller] Failed to enable paste menu item: {}", e);
+        }
+
+        RecordingStateChanged::Stopped { text: text.clone() }.emit(&self.app_handle)?;
+
+        if failures.is_empty() {
+            sound_player::play_cue(&self.app_handle, Sound::Done);
+        } else {
+            sound_player::play_cue(&self.app_handle, Sound::Error);
+        }
+
+        // A failed paste keeps the popup open with guidance, other sink failures are only logged
+        let paste_error = failures.into_iter().find_map(|failure| match failure {
+            SinkError::Paste(e) => Some(e),
+            _ => None,
+        });
+
+        match paste_error {
+            Some(e) => self.handle_paste_failure(text, e),
+            None => {
+                let announcement = Announcement::Delivered {
+                    words: text.split_whitespace().count(),
+                    pasted: sinks
+                        .iter()
+                        .any(|sink| matches!(sink, OutputSinkConfig::Paste { .. })),
+                };
+                announcements::announce(&self.app_handle, announcement);
+
+                // Hide recording popup window
+                if let Err(e) = close_recording_popup(&self.app_handle) {
+                    eprintln!("[Controller] Failed to close recording popup: {}", e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Send a practice transcript to onboarding only, completing the step if
+    /// it was dictated the way the step asks
+    fn finish_practice(
+        &self,
+        step: OnboardingStep,
+        text: String,
+        mode: DictationMode,
+    ) -> Result<(), Error> {
+        println!(
+            "[Controller] Practice transcript for {:?} ({:?})",
+            step, mode
+        );
+
+        RecordingStateChanged::Stopped { text: text.clone() }.emit(&self.app_handle)?;
+        sound_player::play_cue(&self.app_handle, Sound::Done);
+        if let Err(e) = close_recording_popup(&self.app_handle) {
+            eprintln!("[Controller] Failed to close recording popup: {}", e);
+        }
+
+        events::emit_or_log(
+            &self.app_handle,
+            OnboardingPracticeTranscript { step, text, mode },
+        );
+        if step == OnboardingStep::FnHold && mode == DictationMode::PushToTalk {
+            events::emit_or_log(&self.app_handle, OnboardingStepCompleted { step });
+        }
+        Ok(())
+    }
+
+    /// Keep an over-long transcript back and ask the user to confirm it, in
+    /// case it's a hallucination that would flood the focused text field
+    fn hold_transcript(&self, text: String, max_chars: u32) -> Result<(), Error> {
+        let chars = text.chars().count();
+        println!(
+            "[Controller] Holding {} char transcript (cap is {})",
+            chars, max_chars
+        );
+
+        let mut preview: String = text.chars().take(HELD_PREVIEW_CHARS).collect();
+        if chars > HELD_PREVIEW_CHARS {
+            preview.push('…');
+        }
+        *self.held_transcript.borrow_mut() = Some(text);
+
+        if let Err(e) = set_popup_layout(&self.app_handle, PopupMode::Confirm, None) {
+            eprintln!("[Controller] Failed to resize recording popup: {}", e);
+        }
+        sound_player::play_cue(&self.app_handle, Sound::Error);
+        announcements::announce(&self.app_handle, Announcement::Held { chars });
+
+        RecordingStateChanged::Held {
+            chars: chars.min(u32::MAX as usize) as u32,
+            max_chars,
+            preview,
+        }
+        .emit(&self.app_handle)?;
+        Ok(())
+    }
+
+    /// Deliver the held transcript after the user confirmed it
+    fn handle_deliver_held(&self) -> Result<(), Error> {
+        let Some(text) = self.held_transcript.borrow_mut().take() else {
+            println!("[Controller] No held transcript to deliver");
+            return Ok(());
+        };
+        println!("[Controller] Delivering held transcript");
+
+        let app_config = match self.app_handle.store("config.json") {
+            Ok(store) => config::load_app_config(&store),
+            Err(_) => AppConfig::default(),
+        };
+        self.handle_transcript(text, &app_config.output_sinks)
+    }
+
+    /// Drop the held transcript; it stays in history if that's enabled
+    fn handle_discard_held(&self) {
+        if self.held_transcript.borrow_mut().take().is_some() {
+            println!("[Controller] Discarded held transcript");
+        }
+        if let Err(e) = close_recording_popup(&self.app_handle) {
+            eprintln!("[Controller] Failed to close recording popup: {}", e);
+        }
+    }
+
+    /// Keep the popup open with an actionable message when auto-paste fails
+    fn handle_paste_failure(&self, text: String, error: ClipboardPasteError) {
+        eprintln!("[Controller] Auto-paste failed: {}", error);
+        announcements::announce(&self.app_handle, Announcement::Failed(error.user_message()));
+
+        if let Err(e) = resize_recording_popup_for_error(&self.app_handle) {
+            eprintln!("[Controller] Failed to resize recording popup: {}", e);
+        }
+
+        let event = PasteFailed {
+            text,
+            reason: error.to_string(),
+            user_message: error.user_message(),
+            text_on_clipboard: error.text_on_clipboard(),
+        };
+
+        events::emit_or_log(&self.app_handle, event);
+        self.emit_diagnosis(Outcome::PasteFailed(&error));
+    }
+
+    /// Explain a failed paste or an empty result in the popup
+    fn emit_diagnosis(&self, outcome: Outcome) {
+        let target_app = self.target_app.borrow().clone();
+        let explanations = paste_diagnosis::diagnose(outcome, target_app);
+        events::emit_or_log(&self.app_handle, PasteDiagnosed { explanations });
+    }
+
+    /// Handle a transcription that came back without any text. `provider_text`
+    /// is whether the provider returned any before post-processing.
+    fn handle_empty_transcript(
+        &self,
+        app_config: &AppConfig,
+        provider_text: bool,
+    ) -> Result<(), Error> {
+        println!("[Controller] No speech detected in recording");
+
+        stats::record_empty_transcript(&self.app_handle);
+        announcements::announce(&self.app_handle, Announcement::NoSpeech);
+
+        // The notice is suppressed during quiet hours and meetings just like sounds
+        if !app_config.show_no_speech_notice
+            || app_config.quiet_hours.is_active_now()
+            || calendar::mutes_cues(&app_config.calendar)
+        {
+            if let Err(e) = close_recording_popup(&self.app_handle) {
+                eprintln!("[Controller] Failed to close recording popup: {}", e);
+            }
+            RecordingStateChanged::Stopped {
+                text: String::new(),
+            }
+            .emit(&self.app_handle)?;
+            return Ok(());
+        }
+
+        RecordingStateChanged::NoSpeech.emit(&self.app_handle)?;
+        self.emit_diagnosis(Outcome::Empty {
+            removed_by_pipeline: provider_text,
+        });
+
+        if let Err(e) = resize_recording_popup_for_error(&self.app_handle) {
+            eprintln!("[Controller] Failed to resize recording popup: {}", e);
+        }
+
+        // Hide the notice after a moment, unless a new recording has started meanwhile
+        let app_handle = self.app_handle.clone();
+        let shared_state = self.shared_state.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(NO_SPEECH_NOTICE_DURATION);
+            if shared_state.load(Ordering::Relaxed) == 0 {
+                if let Err(e) = close_recording_popup(&app_handle) {
+                    eprintln!("[Controller] Failed to close recording popup: {}", e);
+                }
+            }
+        });
+
+        Ok(())
+    }
+
     fn set_state(&mut self, new_state: ControllerState) {
+        let changed = self.state != new_state;
+        if changed {
+            self.journal.record(JournalEvent::Transition {
+                from: format!("{:?}", self.state),
+                to: format!("{:?}", new_state),
+            });
+        }
+
         self.state = new_state;
+        if new_state == ControllerState::Ready {
+            self.session = None;
+        }
         let state_value = match new_state {
             ControllerState::Ready => 0,
             ControllerState::Recording => 1,
             ControllerState::RecordingLocked => 2,
         };
         self.shared_state.store(state_value, Ordering::Relaxed);
+
+        if changed {
+            self.emit_controls(new_state != ControllerState::Ready);
+        }
+    }
+
+    /// Tell the popup which buttons apply; `active` while a recording runs
+    fn emit_controls(&self, active: bool) {
+        events::emit_or_log(
+            &self.app_handle,
+            RecordingControls {
+                can_stop: active,
+                can_cancel: active,
+                locked: active && self.state == ControllerState::RecordingLocked,
+            },
+        );
+    }
+}
+
+/// What the modifiers held while stopping change about the dictation
+fn overrides(modifiers: KeyModifiers) -> DictationOverrides {
+    DictationOverrides {
+        raw: modifiers.shift,
+        clipboard_only: modifiers.option,
+    }
+}
+
+/// Point at the API keys page when the key stopped working, rather than the
+/// generic invalid-key message that suggests a typo
+fn reauth_aware_message(error: &TranscriptionError, needs_reauth: bool) -> String {
+    if needs_reauth && error.is_auth_rejected() {
+        reauth::REAUTH_USER_MESSAGE.to_string()
+    } else {
+        error.user_message()
     }
 }
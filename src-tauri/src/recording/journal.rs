@@ -0,0 +1,124 @@
+//! Session journal of Controller activity for debugging.
+//!
+//! Keeps the last few hundred commands and state transitions in memory so a
+//! "stuck recording" report can be diagnosed with `get_state_journal`. Entries
+//! only carry timestamps and names, never transcribed content. Setting
+//! `DICTARA_STATE_JOURNAL=1` also appends every entry to `state-journal.log`
+//! in the app log directory.
+
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use tauri::Manager;
+
+/// Number of entries kept in memory
+const JOURNAL_CAPACITY: usize = 500;
+
+/// Environment variable that enables writing the journal to a file
+const JOURNAL_FILE_ENV: &str = "DICTARA_STATE_JOURNAL";
+const JOURNAL_FILE_NAME: &str = "state-journal.log";
+
+/// What happened
+#[derive(Debug, Clone, Serialize, specta::Type)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum JournalEvent {
+    /// A command reached the Controller (key events arrive as commands)
+    Command { command: String },
+    /// The Controller changed state
+    Transition { from: String, to: String },
+    /// The provider policy picked a provider for a dictation
+    ProviderSelected { provider: String, reason: String },
+}
+
+/// A single journal entry
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct JournalEntry {
+    /// Local time in RFC 3339 with milliseconds
+    pub timestamp: String,
+    pub event: JournalEvent,
+}
+
+pub struct StateJournal {
+    entries: Mutex<VecDeque<JournalEntry>>,
+    file: Option<Mutex<File>>,
+}
+
+pub type StateJournalState = Arc<StateJournal>;
+
+impl StateJournal {
+    pub fn new(app_handle: &tauri::AppHandle) -> Self {
+        let file_enabled = std::env::var(JOURNAL_FILE_ENV)
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+
+        let file = if file_enabled {
+            open_journal_file(app_handle).map(Mutex::new)
+        } else {
+            None
+        };
+
+        Self {
+            entries: Mutex::new(VecDeque::with_capacity(JOURNAL_CAPACITY)),
+            file,
+        }
+    }
+
+    pub fn record(&self, event: JournalEvent) {
+        let entry = JournalEntry {
+            timestamp: chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, false),
+            event,
+        };
+
+        if let Some(file) = &self.file {
+            if let (Ok(mut file), Ok(line)) = (file.lock(), serde_json::to_string(&entry)) {
+                if let Err(e) = writeln!(file, "{}", line) {
+                    eprintln!("[Journal] Failed to write journal file: {}", e);
+                }
+            }
+        }
+
+        if let Ok(mut entries) = self.entries.lock() {
+            if entries.len() == JOURNAL_CAPACITY {
+                entries.pop_front();
+            }
+            entries.push_back(entry);
+        }
+    }
+
+    /// Oldest first
+    pub fn entries(&self) -> Vec<JournalEntry> {
+        self.entries
+            .lock()
+            .map(|entries| entries.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+fn open_journal_file(app_handle: &tauri::AppHandle) -> Option<File> {
+    let dir = match app_handle.path().app_log_dir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            eprintln!("[Journal] Failed to resolve log directory: {}", e);
+            return None;
+        }
+    };
+
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        eprintln!("[Journal] Failed to create log directory: {}", e);
+        return None;
+    }
+
+    let path = dir.join(JOURNAL_FILE_NAME);
+    match OpenOptions::new().create(true).append(true).open(&path) {
+        Ok(file) => {
+            println!("[Journal] Writing state journal to {:?}", path);
+            Some(file)
+        }
+        Err(e) => {
+            eprintln!("[Journal] Failed to open {:?}: {}", path, e);
+            None
+        }
+    }
+}
@@ -0,0 +1,123 @@
+//! Rate-limited audio level updates for the recording popup.
+//!
+//! The audio callback runs for every device buffer, which can be hundreds of
+//! times per second. Levels are folded into a max-hold peak and only sent over
+//! IPC at the configured rate, so short peaks still reach the visualizer.
+
+use std::time::{Duration, Instant};
+use tauri::ipc::Channel;
+
+use crate::energy;
+
+/// Highest accepted send rate; above this the popup can't render any faster
+const MAX_RATE_HZ: u32 = 120;
+
+/// Aggregates levels between sends, keeping the loudest one
+#[derive(Debug)]
+pub struct LevelThrottle {
+    interval: Duration,
+    last_sent: Option<Instant>,
+    peak: f32,
+}
+
+impl LevelThrottle {
+    /// Allow at most `rate_hz` sends per second (clamped to 1-120)
+    pub fn new(rate_hz: u32) -> Self {
+        Self {
+            interval: Duration::from_secs(1) / rate_hz.clamp(1, MAX_RATE_HZ),
+            last_sent: None,
+            peak: 0.0,
+        }
+    }
+
+    /// Fold `level` into the pending peak. Returns the peak to send once an
+    /// interval has passed since the last send, and starts a new window.
+    pub fn push(&mut self, level: f32, now: Instant) -> Option<f32> {
+        self.peak = self.peak.max(level);
+
+        if let Some(last_sent) = self.last_sent {
+            if now.duration_since(last_sent) < self.interval {
+                return None;
+            }
+        }
+
+        self.last_sent = Some(now);
+        Some(std::mem::take(&mut self.peak))
+    }
+}
+
+/// Level channel from the popup paired with its throttle
+pub struct LevelMeter {
+    channel: Channel<f32>,
+    throttle: LevelThrottle,
+}
+
+impl LevelMeter {
+    pub fn new(channel: Channel<f32>, rate_hz: u32) -> Self {
+        Self {
+            channel,
+            throttle: LevelThrottle::new(rate_hz),
+        }
+    }
+
+    /// Whether levels should be computed at all right now
+    pub fn is_active(&self) -> bool {
+        energy::level_meter_visible()
+    }
+
+    /// Record a level, sending the window's peak if it's due
+    pub fn push(&mut self, level: f32) {
+        if let Some(peak) = self.throttle.push(level, Instant::now()) {
+            energy::record_level_send();
+            let _ = self.channel.send(peak);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MS: Duration = Duration::from_millis(1);
+
+    #[test]
+    fn test_first_level_is_sent_immediately() {
+        let mut throttle = LevelThrottle::new(30);
+        assert_eq!(throttle.push(0.4, Instant::now()), Some(0.4));
+    }
+
+    #[test]
+    fn test_levels_within_interval_are_held() {
+        let mut throttle = LevelThrottle::new(30);
+        let start = Instant::now();
+
+        assert_eq!(throttle.push(0.1, start), Some(0.1));
+        assert_eq!(throttle.push(0.2, start + 5 * MS), None);
+        assert_eq!(throttle.push(0.3, start + 20 * MS), None);
+    }
+
+    #[test]
+    fn test_peak_between_sends_is_kept() {
+        let mut throttle = LevelThrottle::new(30);
+        let start = Instant::now();
+
+        throttle.push(0.1, start);
+        throttle.push(0.9, start + 10 * MS);
+        throttle.push(0.2, start + 20 * MS);
+
+        // The loud buffer in the middle of the window wins
+        assert_eq!(throttle.push(0.1, start + 40 * MS), Some(0.9));
+        // And the window resets after a send
+        assert_eq!(throttle.push(0.3, start + 80 * MS), Some(0.3));
+    }
+
+    #[test]
+    fn test_rate_is_clamped() {
+        let mut throttle = LevelThrottle::new(0);
+        let start = Instant::now();
+
+        throttle.push(0.5, start);
+        assert_eq!(throttle.push(0.5, start + 500 * MS), None);
+        assert_eq!(throttle.push(0.5, start + 1000 * MS), Some(0.5));
+    }
+}
@@ -1,17 +1,30 @@
 mod audio_recorder;
 mod commands;
 mod controller;
-pub mod events;
+mod journal;
+mod level_meter;
+mod pending_retry;
+mod queue;
+mod recovery;
+mod trace;
 
+use crate::config::AppConfig;
+use crate::history;
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 use std::time::SystemTime;
 
 // Public exports
+pub use crate::wav::wav_duration_ms;
 pub use audio_recorder::{
     cleanup_old_recordings, cleanup_recording_file, RecorderError, Recording,
 };
-pub use commands::RecordingCommand;
+pub use commands::{KeyModifiers, RecordingCommand, TriggerSource};
 pub use controller::Controller;
+pub use journal::{JournalEntry, StateJournal, StateJournalState};
+pub use pending_retry::offer_retry;
+pub use queue::{TranscriptionQueue, TranscriptionQueueState};
+pub use recovery::offer_recovery;
 
 /// Stores the last recording attempt for paste retry functionality
 #[derive(Debug, Clone)]
@@ -33,6 +46,21 @@ impl LastRecording {
         }
     }
 
+    /// Keep the recording at `path` for a retry, also across a restart
+    pub fn keep_for_retry(&mut self, path: String) {
+        pending_retry::save(Path::new(&path));
+        self.audio_file_path = Some(path);
+    }
+
+    /// Give up the recording kept for a retry, returning its path
+    pub fn take_retry(&mut self) -> Option<String> {
+        let path = self.audio_file_path.take();
+        if let Some(path) = &path {
+            pending_retry::clear(Path::new(path));
+        }
+        path
+    }
+
     #[allow(dead_code)]
     pub fn can_paste(&self) -> bool {
         self.text.is_some()
@@ -40,3 +68,21 @@ impl LastRecording {
 }
 
 pub type LastRecordingState = Arc<Mutex<LastRecording>>;
+
+/// Point "Paste Last Recording" at the newest history entry, disabling it when
+/// history has nothing to offer
+pub fn restore_paste_from_history(
+    app_handle: &tauri::AppHandle,
+    config: &AppConfig,
+    last_recording_state: &LastRecordingState,
+) {
+    let text = history::latest_paste_text(app_handle, config);
+    let enabled = text.is_some();
+    if let Ok(mut last_recording) = last_recording_state.lock() {
+        last_recording.text = text;
+    }
+
+    if let Err(e) = crate::ui::tray::update_paste_menu_item(app_handle, enabled) {
+        eprintln!("[Recording] Failed to update paste menu item: {}", e);
+    }
+}
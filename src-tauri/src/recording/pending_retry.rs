@@ -0,0 +1,186 @@
+//! Failed transcriptions kept for a retry across restarts.
+//!
+//! While a failed recording waits for a retry, a marker file next to it names
+//! the WAV (see `LastRecording::keep_for_retry`). The marker goes when the
+//! retry is taken, succeeds or is dismissed. If the app quits in between, the
+//! next launch finds the marker, keeps the recording from the startup cleanup
+//! and asks whether to retry it, as crash recovery does.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use tauri::Manager;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use tokio::sync::mpsc;
+
+use super::{cleanup_recording_file, wav_duration_ms, LastRecordingState, RecordingCommand};
+use crate::ui::window::open_recording_popup;
+
+/// Marker written next to the recording waiting for a retry
+const MARKER_FILE: &str = "pending-retry.json";
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct PendingRetry {
+    /// File name of the WAV inside the recordings directory
+    filename: String,
+    /// Unix timestamp (seconds) of when the transcription failed
+    failed_at: u64,
+}
+
+/// Remember that the recording at `path` waits for a retry. Best-effort -
+/// logs on failure.
+pub fn save(path: &Path) {
+    let (Some(audio_dir), Some(filename)) = (path.parent(), path.file_name()) else {
+        return;
+    };
+    let marker = PendingRetry {
+        filename: filename.to_string_lossy().to_string(),
+        failed_at: SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+    };
+
+    let result = serde_json::to_vec(&marker)
+        .map_err(io::Error::other)
+        .and_then(|bytes| fs::write(audio_dir.join(MARKER_FILE), bytes));
+    if let Err(e) = result {
+        eprintln!("[Pending Retry] Failed to write marker: {}", e);
+    }
+}
+
+/// Forget the recording at `path` waited for a retry
+pub fn clear(path: &Path) {
+    let Some(audio_dir) = path.parent() else {
+        return;
+    };
+    if let Err(e) = fs::remove_file(audio_dir.join(MARKER_FILE)) {
+        if e.kind() != io::ErrorKind::NotFound {
+            eprintln!("[Pending Retry] Failed to remove marker: {}", e);
+        }
+    }
+}
+
+fn read_marker(audio_dir: &Path) -> Option<PendingRetry> {
+    let bytes = fs::read(audio_dir.join(MARKER_FILE)).ok()?;
+    match serde_json::from_slice(&bytes) {
+        Ok(marker) => Some(marker),
+        Err(e) => {
+            eprintln!("[Pending Retry] Ignoring unreadable marker: {}", e);
+            None
+        }
+    }
+}
+
+/// File name of the recording waiting for a retry, if any.
+/// Startup cleanup skips it so it can still be retried.
+pub fn pending_filename(audio_dir: &Path) -> Option<String> {
+    read_marker(audio_dir).map(|marker| marker.filename)
+}
+
+/// Check for a recording whose transcription failed in the last session and
+/// ask whether to retry it.
+///
+/// Retrying hands the file to the retry path, which pastes the transcript like
+/// any other dictation and keeps the file for another retry if it fails again.
+/// Discarding deletes it.
+pub fn offer_retry(
+    app_handle: &tauri::AppHandle,
+    command_tx: mpsc::Sender<RecordingCommand>,
+    last_recording_state: LastRecordingState,
+) {
+    let Ok(audio_dir) = app_handle
+        .path()
+        .app_cache_dir()
+        .map(|dir| dir.join("recordings"))
+    else {
+        return;
+    };
+    let Some(marker) = read_marker(&audio_dir) else {
+        return;
+    };
+    let path = audio_dir.join(&marker.filename);
+    let Some(duration_ms) = wav_duration_ms(&path) else {
+        println!("[Pending Retry] Recording is gone, forgetting it");
+        clear(&path);
+        return;
+    };
+    println!("[Pending Retry] Found {:?} ({}ms)", path, duration_ms);
+
+    let failed_at = SystemTime::UNIX_EPOCH + Duration::from_secs(marker.failed_at);
+    let ago = SystemTime::now()
+        .duration_since(failed_at)
+        .map(|d| d.as_secs() / 60)
+        .unwrap_or(0);
+    let message = format!(
+        "A {}s recording from {} couldn't be transcribed before Dictara quit.\n\nRetry it now? The text will be pasted into the active app.",
+        (duration_ms / 1000).max(1),
+        if ago == 0 {
+            "just now".to_string()
+        } else {
+            format!("{} min ago", ago)
+        }
+    );
+
+    let app = app_handle.clone();
+    app_handle
+        .dialog()
+        .message(message)
+        .title("Retry Transcription")
+        .kind(MessageDialogKind::Info)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Retry".to_string(),
+            "Discard".to_string(),
+        ))
+        .show(move |accepted| {
+            let path_string = path.to_string_lossy().to_string();
+            if !accepted {
+                println!("[Pending Retry] User discarded the recording");
+                clear(&path);
+                cleanup_recording_file(&path_string);
+                return;
+            }
+
+            match last_recording_state.lock() {
+                Ok(mut last) => {
+                    last.audio_file_path = Some(path_string);
+                    last.timestamp = Some(failed_at);
+                }
+                Err(e) => {
+                    eprintln!("[Pending Retry] Failed to lock last recording state: {}", e);
+                    return;
+                }
+            }
+
+            if let Err(e) = open_recording_popup(&app) {
+                eprintln!("[Pending Retry] Failed to open recording popup: {}", e);
+            }
+            if let Err(e) = command_tx.try_send(RecordingCommand::RetryTranscription) {
+                eprintln!("[Pending Retry] Failed to queue transcription: {}", e);
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_marker_names_the_recording_until_cleared() {
+        let dir =
+            std::env::temp_dir().join(format!("dictara-pending-retry-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let recording = dir.join("recording_1.wav");
+
+        assert_eq!(pending_filename(&dir), None);
+        save(&recording);
+        assert_eq!(pending_filename(&dir).as_deref(), Some("recording_1.wav"));
+        clear(&recording);
+        assert_eq!(pending_filename(&dir), None);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
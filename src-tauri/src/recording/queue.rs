@@ -0,0 +1,137 @@
+//! The transcription in flight, so it can be cancelled.
+//!
+//! Transcription runs on the controller thread, so at most one runs at a time
+//! and nothing waits behind it; a dictation started meanwhile is recorded once
+//! the controller is free again. There is no queue to show the user until
+//! transcription moves off that thread.
+//!
+//! Each transcription gets an abort token of its own. `abort_all` aborts the
+//! tokens of the transcriptions started here, which ends their uploads; the
+//! controller stops waiting for the provider right away, sees `is_aborted`
+//! and drops whatever came back. Uploads started elsewhere, like batch or
+//! intake transcriptions, keep going.
+
+use crate::clients::upload::AbortToken;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+#[derive(Debug, Default)]
+pub struct TranscriptionQueue {
+    /// Tokens of the transcriptions started and not finished yet
+    running: Mutex<Vec<(u32, AbortToken)>>,
+    next_id: AtomicU32,
+}
+
+pub type TranscriptionQueueState = Arc<TranscriptionQueue>;
+
+impl TranscriptionQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Track a transcription that is starting, until the returned job is
+    /// dropped. Its upload should use the job's `abort_token`.
+    pub fn enqueue(self: &Arc<Self>) -> QueuedTranscription {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let abort = AbortToken::default();
+        self.running().push((id, abort.clone()));
+        QueuedTranscription {
+            queue: self.clone(),
+            id,
+            abort,
+        }
+    }
+
+    /// Cancel the transcriptions in progress and abort their uploads. Returns
+    /// how many were cancelled.
+    pub fn abort_all(&self) -> u32 {
+        let mut cancelled = 0;
+        for (_, abort) in self.running().iter() {
+            if !abort.is_aborted() {
+                abort.abort();
+                cancelled += 1;
+            }
+        }
+        println!("[Queue] Cancelled {} transcription(s)", cancelled);
+        cancelled
+    }
+
+    fn running(&self) -> MutexGuard<'_, Vec<(u32, AbortToken)>> {
+        self.running.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// A running transcription, tracked until it is dropped
+pub struct QueuedTranscription {
+    queue: TranscriptionQueueState,
+    id: u32,
+    abort: AbortToken,
+}
+
+impl QueuedTranscription {
+    /// Token that aborts this transcription's upload
+    pub fn abort_token(&self) -> &AbortToken {
+        &self.abort
+    }
+
+    /// Whether `abort_all` cancelled this transcription. Its result, if any,
+    /// should be discarded.
+    pub fn is_aborted(&self) -> bool {
+        self.abort.is_aborted()
+    }
+}
+
+impl Drop for QueuedTranscription {
+    fn drop(&mut self) {
+        self.queue.running().retain(|(id, _)| *id != self.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clients::upload;
+    use std::time::Duration;
+
+    #[test]
+    fn test_abort_all_leaves_other_uploads_running() {
+        let queue = Arc::new(TranscriptionQueue::new());
+        let job = queue.enqueue();
+
+        let abort = job.abort_token().clone();
+        let dictation = std::thread::spawn(move || {
+            upload::run_abortable(&abort, || {
+                std::thread::sleep(Duration::from_secs(5));
+                7
+            })
+        });
+        // E.g. a batch transcription, which the queue doesn't own
+        let batch = std::thread::spawn(|| {
+            upload::run_abortable(&AbortToken::default(), || {
+                std::thread::sleep(Duration::from_millis(300));
+                7
+            })
+        });
+        std::thread::sleep(Duration::from_millis(50));
+
+        assert_eq!(queue.abort_all(), 1);
+        assert!(job.is_aborted());
+        assert_eq!(dictation.join().unwrap(), None);
+        assert_eq!(batch.join().unwrap(), Some(7));
+    }
+
+    #[test]
+    fn test_abort_all_skips_finished_jobs() {
+        let queue = Arc::new(TranscriptionQueue::new());
+        let finished = queue.enqueue();
+        let abort = finished.abort_token().clone();
+        drop(finished);
+        let running = queue.enqueue();
+
+        assert_eq!(queue.abort_all(), 1);
+        assert!(!abort.is_aborted());
+        assert!(running.is_aborted());
+        // Already cancelled, so not counted again
+        assert_eq!(queue.abort_all(), 0);
+    }
+}
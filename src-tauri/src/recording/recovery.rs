@@ -0,0 +1,362 @@
+//! Crash recovery for in-progress recordings.
+//!
+//! While a recording is running, a marker file in the recordings directory
+//! names the WAV being written. If the app crashes, the marker survives and the
+//! WAV is left with a stale header. On the next launch the header is repaired
+//! from the file length and the user is offered a transcription of it. A
+//! recording whose header can't be repaired is moved to a `recovered` folder
+//! next to the recordings instead of being deleted, so the audio isn't lost.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tauri::Manager;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use tokio::sync::mpsc;
+
+use super::{cleanup_recording_file, wav_duration_ms, LastRecordingState, RecordingCommand};
+use crate::ui::window::open_recording_popup;
+
+/// Marker written next to the recording while it's in progress
+const MARKER_FILE: &str = "in-progress.json";
+
+/// Recordings shorter than this aren't worth offering
+const MIN_RECOVERABLE_MS: u64 = 500;
+
+/// Folder inside the recordings directory for recordings that couldn't be
+/// repaired. Old-recording cleanup doesn't look inside it.
+const RECOVERED_DIR: &str = "recovered";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct InProgressMarker {
+    /// File name of the WAV inside the recordings directory
+    filename: String,
+    /// Unix timestamp (seconds) of when the recording started
+    started_at: u64,
+}
+
+/// A recording left behind by a crash, with its header repaired
+#[derive(Debug, Clone)]
+pub struct InterruptedRecording {
+    pub path: PathBuf,
+    pub duration_ms: u64,
+    pub started_at: SystemTime,
+}
+
+/// Record that `filename` is being written. Best-effort - logs on failure.
+pub fn mark_in_progress(audio_dir: &Path, filename: &str) {
+    let marker = InProgressMarker {
+        filename: filename.to_string(),
+        started_at: SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+    };
+
+    let result = serde_json::to_vec(&marker)
+        .map_err(io::Error::other)
+        .and_then(|bytes| fs::write(audio_dir.join(MARKER_FILE), bytes));
+    if let Err(e) = result {
+        eprintln!("[Recovery] Failed to write in-progress marker: {}", e);
+    }
+}
+
+/// Remove the marker once the recording has been finalized
+pub fn clear_in_progress(audio_dir: &Path) {
+    let marker_path = audio_dir.join(MARKER_FILE);
+    if let Err(e) = fs::remove_file(&marker_path) {
+        if e.kind() != io::ErrorKind::NotFound {
+            eprintln!("[Recovery] Failed to remove in-progress marker: {}", e);
+        }
+    }
+}
+
+fn read_marker(audio_dir: &Path) -> Option<InProgressMarker> {
+    let bytes = fs::read(audio_dir.join(MARKER_FILE)).ok()?;
+    match serde_json::from_slice(&bytes) {
+        Ok(marker) => Some(marker),
+        Err(e) => {
+            eprintln!("[Recovery] Ignoring unreadable in-progress marker: {}", e);
+            None
+        }
+    }
+}
+
+/// File name of the recording that was interrupted, if any.
+/// Startup cleanup skips it so it can still be recovered.
+pub fn interrupted_filename(audio_dir: &Path) -> Option<String> {
+    read_marker(audio_dir).map(|marker| marker.filename)
+}
+
+/// Rewrite the RIFF and data chunk sizes of a WAV file from its length on disk.
+///
+/// A crashed writer leaves the sizes from its last flush (often 0). The data
+/// chunk is assumed to run to the end of the file; a trailing partial frame is
+/// truncated. Returns the length of the repaired data chunk in bytes.
+pub fn repair_wav_header(path: &Path) -> io::Result<u64> {
+    let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+    let file_len = file.metadata()?.len();
+
+    let mut riff = [0u8; 12];
+    file.read_exact(&mut riff)?;
+    if &riff[0..4] != b"RIFF" || &riff[8..12] != b"WAVE" {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "not a WAV file"));
+    }
+
+    let mut block_align: u64 = 1;
+    let mut pos: u64 = 12;
+    loop {
+        if pos + 8 > file_len {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "no data chunk found",
+            ));
+        }
+
+        let mut chunk = [0u8; 8];
+        file.seek(SeekFrom::Start(pos))?;
+        file.read_exact(&mut chunk)?;
+        let id = &chunk[0..4];
+        let size = u32::from_le_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]) as u64;
+
+        if id == b"fmt " {
+            let mut fmt = [0u8; 16];
+            file.read_exact(&mut fmt)?;
+            block_align = u16::from_le_bytes([fmt[12], fmt[13]]).max(1) as u64;
+        } else if id == b"data" {
+            let data_start = pos + 8;
+            let available = (file_len - data_start).min(u32::MAX as u64 - data_start);
+            let data_len = available - available % block_align;
+
+            file.set_len(data_start + data_len)?;
+            file.seek(SeekFrom::Start(4))?;
+            file.write_all(&((data_start + data_len - 8) as u32).to_le_bytes())?;
+            file.seek(SeekFrom::Start(pos + 4))?;
+            file.write_all(&(data_len as u32).to_le_bytes())?;
+            file.sync_all()?;
+            return Ok(data_len);
+        }
+
+        // Chunks are word-aligned
+        pos += 8 + size + (size & 1);
+    }
+}
+
+/// Move a recording that couldn't be repaired into [`RECOVERED_DIR`].
+/// Returns its new path.
+fn keep_unrepaired(audio_dir: &Path, path: &Path) -> io::Result<PathBuf> {
+    let dir = audio_dir.join(RECOVERED_DIR);
+    fs::create_dir_all(&dir)?;
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::other("recording has no file name"))?;
+    let kept = dir.join(file_name);
+    fs::rename(path, &kept)?;
+    Ok(kept)
+}
+
+/// Find and repair a recording interrupted by a crash.
+/// Consumes the marker, so each interrupted recording is offered only once.
+pub fn take_interrupted(audio_dir: &Path) -> Option<InterruptedRecording> {
+    let marker = read_marker(audio_dir)?;
+    clear_in_progress(audio_dir);
+
+    let path = audio_dir.join(&marker.filename);
+    if !path.exists() {
+        return None;
+    }
+
+    if let Err(e) = repair_wav_header(&path) {
+        eprintln!("[Recovery] Could not repair {:?}: {}", path, e);
+        match keep_unrepaired(audio_dir, &path) {
+            Ok(kept) => println!("[Recovery] Kept the interrupted recording at {:?}", kept),
+            Err(e) => eprintln!("[Recovery] Failed to move {:?} aside: {}", path, e),
+        }
+        return None;
+    }
+
+    // The header is valid again, and says which format the recording is in
+    let duration_ms = wav_duration_ms(&path).unwrap_or(0);
+    if duration_ms < MIN_RECOVERABLE_MS {
+        println!("[Recovery] Interrupted recording too short to recover, discarding");
+        cleanup_recording_file(&path.to_string_lossy());
+        return None;
+    }
+
+    println!(
+        "[Recovery] Repaired interrupted recording {:?} ({}ms)",
+        path, duration_ms
+    );
+    Some(InterruptedRecording {
+        path,
+        duration_ms,
+        started_at: SystemTime::UNIX_EPOCH + Duration::from_secs(marker.started_at),
+    })
+}
+
+/// Check for a recording interrupted by a crash and ask whether to transcribe it.
+///
+/// Accepting hands the file to the retry path, which pastes the transcript like
+/// any other dictation. Declining deletes it.
+pub fn offer_recovery(
+    app_handle: &tauri::AppHandle,
+    command_tx: mpsc::Sender<RecordingCommand>,
+    last_recording_state: LastRecordingState,
+) {
+    let Ok(audio_dir) = app_handle
+        .path()
+        .app_cache_dir()
+        .map(|dir| dir.join("recordings"))
+    else {
+        return;
+    };
+    let Some(recording) = take_interrupted(&audio_dir) else {
+        return;
+    };
+
+    let seconds = recording.duration_ms / 1000;
+    let ago = SystemTime::now()
+        .duration_since(recording.started_at)
+        .map(|d| d.as_secs() / 60)
+        .unwrap_or(0);
+    let message = format!(
+        "Dictara closed unexpectedly while recording. A {}s recording from {} was recovered.\n\nTranscribe it now? The text will be pasted into the active app.",
+        seconds.max(1),
+        if ago == 0 {
+            "just now".to_string()
+        } else {
+            format!("{} min ago", ago)
+        }
+    );
+
+    let app = app_handle.clone();
+    app_handle
+        .dialog()
+        .message(message)
+        .title("Recover Recording")
+        .kind(MessageDialogKind::Info)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Transcribe".to_string(),
+            "Discard".to_string(),
+        ))
+        .show(move |accepted| {
+            let path = recording.path.to_string_lossy().to_string();
+            if !accepted {
+                println!("[Recovery] User discarded interrupted recording");
+                cleanup_recording_file(&path);
+                return;
+            }
+
+            match last_recording_state.lock() {
+                Ok(mut last) => {
+                    last.audio_file_path = Some(path);
+                    last.timestamp = Some(recording.started_at);
+                }
+                Err(e) => {
+                    eprintln!("[Recovery] Failed to lock last recording state: {}", e);
+                    return;
+                }
+            }
+
+            if let Err(e) = open_recording_popup(&app) {
+                eprintln!("[Recovery] Failed to open recording popup: {}", e);
+            }
+            if let Err(e) = command_tx.try_send(RecordingCommand::RetryTranscription) {
+                eprintln!("[Recovery] Failed to queue transcription: {}", e);
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 16kHz mono 16-bit header with the given (stale) sizes
+    fn wav_header(riff_size: u32, data_size: u32) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&riff_size.to_le_bytes());
+        bytes.extend_from_slice(b"WAVE");
+        bytes.extend_from_slice(b"fmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        bytes.extend_from_slice(&1u16.to_le_bytes()); // mono
+        bytes.extend_from_slice(&16_000u32.to_le_bytes());
+        bytes.extend_from_slice(&32_000u32.to_le_bytes());
+        bytes.extend_from_slice(&2u16.to_le_bytes()); // block align
+        bytes.extend_from_slice(&16u16.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&data_size.to_le_bytes());
+        bytes
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("dictara-{}-{}.wav", name, std::process::id()))
+    }
+
+    fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_repair_sets_sizes_from_file_length() {
+        let path = temp_path("repair");
+        let mut bytes = wav_header(36, 0);
+        bytes.extend_from_slice(&[0x11; 3200]);
+        fs::write(&path, &bytes).unwrap();
+
+        let data_len = repair_wav_header(&path).unwrap();
+        let repaired = fs::read(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(data_len, 3200);
+        assert_eq!(u32_at(&repaired, 4), 36 + 3200);
+        assert_eq!(u32_at(&repaired, 40), 3200);
+    }
+
+    #[test]
+    fn test_repair_truncates_partial_sample() {
+        let path = temp_path("partial");
+        let mut bytes = wav_header(36, 0);
+        bytes.extend_from_slice(&[0x22; 101]);
+        fs::write(&path, &bytes).unwrap();
+
+        let data_len = repair_wav_header(&path).unwrap();
+        let len = fs::metadata(&path).unwrap().len();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(data_len, 100);
+        assert_eq!(len, 44 + 100);
+    }
+
+    #[test]
+    fn test_unrepairable_recording_is_kept() {
+        let audio_dir =
+            std::env::temp_dir().join(format!("dictara-recovery-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&audio_dir);
+        fs::create_dir_all(&audio_dir).unwrap();
+        let filename = "recording_1.wav";
+        fs::write(audio_dir.join(filename), b"not a wav file at all").unwrap();
+        mark_in_progress(&audio_dir, filename);
+
+        assert!(take_interrupted(&audio_dir).is_none());
+        let kept = audio_dir.join(RECOVERED_DIR).join(filename);
+        let contents = fs::read(&kept).unwrap();
+        let _ = fs::remove_dir_all(&audio_dir);
+
+        assert_eq!(contents, b"not a wav file at all");
+    }
+
+    #[test]
+    fn test_repair_rejects_non_wav() {
+        let path = temp_path("garbage");
+        fs::write(&path, b"not a wav file at all").unwrap();
+
+        let result = repair_wav_header(&path);
+        let _ = fs::remove_file(&path);
+
+        assert!(result.is_err());
+    }
+}
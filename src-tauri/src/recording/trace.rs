@@ -0,0 +1,89 @@
+//! Where the time of a dictation goes.
+//!
+//! Each dictation gets a trace id, and every stage logs how long it took as
+//! `[Trace <id>] <stage>: <ms>ms`, so a slow dictation can be followed through
+//! the log on a user's machine. With `show_timing_breakdown`, the stages are
+//! also sent along with `dictation-completed`.
+
+use crate::clients::openai::TranscriptionTiming;
+use crate::events::{StageTiming, TraceStage};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, Instant};
+
+static NEXT_TRACE_ID: AtomicU32 = AtomicU32::new(1);
+
+#[derive(Debug)]
+pub struct DictationTrace {
+    id: u32,
+    stages: Vec<StageTiming>,
+    /// End of the last recorded stage
+    last: Instant,
+}
+
+impl DictationTrace {
+    pub fn start() -> Self {
+        Self {
+            id: NEXT_TRACE_ID.fetch_add(1, Ordering::Relaxed),
+            stages: Vec::new(),
+            last: Instant::now(),
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Record a stage that just ended and took `duration`
+    pub fn record(&mut self, stage: TraceStage, duration: Duration) {
+        let ms = duration.as_millis().min(u32::MAX as u128) as u32;
+        println!("[Trace {}] {:?}: {}ms", self.id, stage, ms);
+        self.stages.push(StageTiming { stage, ms });
+        self.last = Instant::now();
+    }
+
+    /// Record a stage that ran from the end of the previous one until now
+    pub fn mark(&mut self, stage: TraceStage) {
+        self.record(stage, self.last.elapsed());
+    }
+
+    /// Record a transcription request that took `total`. Without a split
+    /// (dry run), all of it counts as the provider's.
+    pub fn record_request(&mut self, timing: TranscriptionTiming, total: Duration) {
+        match (timing.upload, timing.provider) {
+            (Some(upload), Some(provider)) => {
+                self.record(TraceStage::Upload, upload);
+                self.record(TraceStage::Provider, provider);
+            }
+            _ => self.record(TraceStage::Provider, total),
+        }
+    }
+
+    pub fn stages(&self) -> &[StageTiming] {
+        &self.stages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stages_are_kept_in_order() {
+        let mut trace = DictationTrace::start();
+        trace.record(TraceStage::Upload, Duration::from_millis(120));
+        trace.record(TraceStage::Provider, Duration::from_millis(480));
+        trace.mark(TraceStage::PostProcess);
+
+        let stages: Vec<TraceStage> = trace.stages().iter().map(|s| s.stage).collect();
+        assert_eq!(
+            stages,
+            [
+                TraceStage::Upload,
+                TraceStage::Provider,
+                TraceStage::PostProcess
+            ]
+        );
+        assert_eq!(trace.stages()[1].ms, 480);
+        assert_ne!(DictationTrace::start().id(), trace.id());
+    }
+}
@@ -0,0 +1,75 @@
+//! "Rewrite my selection" mode.
+//!
+//! Starting a rewrite captures the text selected in the focused app, from the
+//! Accessibility API or, for apps that don't expose it there, by copying it
+//! with a simulated Cmd+C. The recording is then an instruction rather than
+//! text to insert ("make this more formal", "translate to German"): the chat
+//! model gets both with `llm.rewrite_prompt`, and its reply is pasted over the
+//! selection, which is still selected.
+
+use crate::clients::openai::TranscriptionError;
+use crate::clients::{dry_run, llm};
+use crate::clipboard_paste;
+use crate::config::AppConfig;
+use crate::system_context;
+
+/// The text selected in the focused app, `None` if there's none or it can't
+/// be read (secure input is on)
+pub fn capture_selection() -> Option<String> {
+    if clipboard_paste::is_secure_input_enabled() {
+        println!("[Rewrite] Secure input is on, not reading the selection");
+        return None;
+    }
+    if let Some(selection) = system_context::selected_text() {
+        println!("[Rewrite] Read the selection from Accessibility");
+        return Some(selection);
+    }
+    match clipboard_paste::copy_selection() {
+        Ok(selection) => selection.filter(|text| !text.trim().is_empty()),
+        Err(e) => {
+            eprintln!("[Rewrite] Failed to copy the selection: {}", e);
+            None
+        }
+    }
+}
+
+/// The message sent to the model
+fn request(instruction: &str, selection: &str) -> String {
+    format!(
+        "Instruction: {}\n\nSelected text:\n{}",
+        instruction.trim(),
+        selection
+    )
+}
+
+/// `selection` rewritten as the spoken `instruction` says
+pub fn rewrite(
+    config: &AppConfig,
+    instruction: &str,
+    selection: &str,
+) -> Result<String, TranscriptionError> {
+    if dry_run::is_enabled(config) {
+        println!("[Rewrite] Dry run, keeping the selection as it is");
+        return Ok(selection.to_string());
+    }
+
+    println!("[Rewrite] Rewriting {} chars", selection.chars().count());
+    llm::complete(
+        config,
+        &config.llm.rewrite_prompt,
+        &request(instruction, selection),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_keeps_the_selection_verbatim() {
+        assert_eq!(
+            request(" Make it formal. ", "hey,\n  see you\n"),
+            "Instruction: Make it formal.\n\nSelected text:\nhey,\n  see you\n"
+        );
+    }
+}
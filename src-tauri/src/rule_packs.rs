@@ -0,0 +1,326 @@
+//! Shareable packs of text-processing rules.
+//!
+//! A pack is a JSON file with the settings that shape a transcript: the
+//! filler word lists and which pipeline steps run. Teammates export theirs and
+//! import each other's. Word lists merge, skipping words already there. A step
+//! the pack turns on or off the other way than the current config is a
+//! conflict, settled by [`Resolution`]; import previews first, so the user can
+//! see the conflicts before choosing. Plugin steps stay out of packs, since
+//! they name executables on the exporting machine, and steps this version
+//! doesn't know are skipped.
+//!
+//! Packs carry a format version. Ones from a newer version are refused rather
+//! than half-applied.
+
+use crate::config::AppConfig;
+use crate::pipeline::{self, PipelineStepConfig};
+use serde::{Deserialize, Serialize};
+
+/// Format version written by this build
+const PACK_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+pub struct RulePack {
+    /// Format version, see `PACK_VERSION`
+    pub version: u32,
+    pub name: String,
+    /// Dictara version that exported the pack
+    #[serde(default)]
+    pub exported_by: String,
+    /// Built-in filler word lists, by language code
+    #[serde(default)]
+    pub filler_languages: Vec<String>,
+    /// Extra filler words or phrases
+    #[serde(default)]
+    pub filler_words: Vec<String>,
+    /// Pipeline steps and whether they run
+    #[serde(default)]
+    pub pipeline: Vec<PipelineStepConfig>,
+}
+
+/// How to settle a step the pack sets differently from the current config
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum Resolution {
+    KeepMine,
+    UseTheirs,
+}
+
+/// A pipeline step the pack and the current config disagree on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+pub struct StepConflict {
+    pub id: String,
+    /// Whether the step runs now
+    pub mine: bool,
+    /// Whether the pack runs it
+    pub theirs: bool,
+}
+
+/// What importing a pack changes, or would change when previewing
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+pub struct PackImport {
+    pub name: String,
+    /// Filler words and languages that weren't there yet
+    pub added_words: u32,
+    /// Filler words and languages already there
+    pub duplicate_words: u32,
+    pub conflicts: Vec<StepConflict>,
+    /// Plugin steps and steps this version doesn't know
+    pub skipped_steps: Vec<String>,
+}
+
+/// The current rules as a pack named `name`
+pub fn export(config: &AppConfig, name: &str) -> RulePack {
+    RulePack {
+        version: PACK_VERSION,
+        name: name.to_string(),
+        exported_by: env!("CARGO_PKG_VERSION").to_string(),
+        filler_languages: config.filler_words.languages.clone(),
+        filler_words: config.filler_words.custom.clone(),
+        pipeline: config
+            .pipeline
+            .iter()
+            .filter(|step| !step.id.starts_with("plugin:"))
+            .cloned()
+            .collect(),
+    }
+}
+
+/// Read a pack, refusing formats this version doesn't understand
+pub fn parse(json: &str) -> Result<RulePack, String> {
+    let pack: RulePack =
+        serde_json::from_str(json).map_err(|e| format!("Not a rule pack: {}", e))?;
+    if pack.version == 0 || pack.version > PACK_VERSION {
+        return Err(format!(
+            "\"{}\" is in pack format {}, this version of Dictara reads up to {}. Update Dictara to import it.",
+            pack.name, pack.version, PACK_VERSION
+        ));
+    }
+    Ok(pack)
+}
+
+/// Add the words of `theirs` missing from `mine`, ignoring case.
+/// Returns (added, duplicates).
+fn merge_words(mine: &mut Vec<String>, theirs: &[String]) -> (u32, u32) {
+    let (mut added, mut duplicates) = (0, 0);
+    for word in theirs {
+        let word = word.trim();
+        if word.is_empty() {
+            continue;
+        }
+        if mine.iter().any(|w| w.eq_ignore_ascii_case(word)) {
+            duplicates += 1;
+        } else {
+            mine.push(word.to_string());
+            added += 1;
+        }
+    }
+    (added, duplicates)
+}
+
+/// Add the `defaults` steps missing from `pipeline`, in order and with their
+/// default setting, so it lists the steps in the order they run
+fn materialize(pipeline: &mut Vec<PipelineStepConfig>, defaults: &[(String, bool)]) {
+    for (id, enabled) in defaults {
+        if !pipeline.iter().any(|step| step.id == *id) {
+            pipeline.push(PipelineStepConfig {
+                id: id.clone(),
+                enabled: *enabled,
+            });
+        }
+    }
+}
+
+/// Merge `pack` into `config`, settling step conflicts with `resolution`
+pub fn merge(config: &mut AppConfig, pack: &RulePack, resolution: Resolution) -> PackImport {
+    let mut import = PackImport {
+        name: pack.name.clone(),
+        ..PackImport::default()
+    };
+
+    for (mine, theirs) in [
+        (&mut config.filler_words.languages, &pack.filler_languages),
+        (&mut config.filler_words.custom, &pack.filler_words),
+    ] {
+        let (added, duplicates) = merge_words(mine, theirs);
+        import.added_words += added;
+        import.duplicate_words += duplicates;
+    }
+
+    // Steps the config doesn't mention run with their default setting
+    let defaults: Vec<(String, bool)> = pipeline::registry(config)
+        .iter()
+        .map(|step| (step.id(), step.enabled_by_default()))
+        .collect();
+    for step in &pack.pipeline {
+        let default = match defaults.iter().find(|(id, _)| *id == step.id) {
+            Some((_, default)) if !step.id.starts_with("plugin:") => *default,
+            _ => {
+                import.skipped_steps.push(step.id.clone());
+                continue;
+            }
+        };
+        let position = config.pipeline.iter().position(|mine| mine.id == step.id);
+        let mine = position.map_or(default, |i| config.pipeline[i].enabled);
+        if mine == step.enabled {
+            continue;
+        }
+
+        import.conflicts.push(StepConflict {
+            id: step.id.clone(),
+            mine,
+            theirs: step.enabled,
+        });
+        if resolution == Resolution::UseTheirs {
+            // A step the config doesn't mention yet: spell out the resolved
+            // pipeline first, so setting this one doesn't move it ahead of
+            // the steps that run before it
+            let position = position.or_else(|| {
+                materialize(&mut config.pipeline, &defaults);
+                config.pipeline.iter().position(|mine| mine.id == step.id)
+            });
+            match position {
+                Some(i) => config.pipeline[i].enabled = step.enabled,
+                None => config.pipeline.push(step.clone()),
+            }
+        }
+    }
+
+    import
+}
+
+/// Write the current rules to `path` as a pack named `name`
+pub fn export_to_file(config: &AppConfig, name: &str, path: &str) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(&export(config, name)).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+    println!("[Rule Packs] Exported \"{}\" to {}", name, path);
+    Ok(())
+}
+
+/// Read the pack at `path`
+pub fn read_file(path: &str) -> Result<RulePack, String> {
+    let json =
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    parse(&json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(id: &str, enabled: bool) -> PipelineStepConfig {
+        PipelineStepConfig {
+            id: id.to_string(),
+            enabled,
+        }
+    }
+
+    fn pack() -> RulePack {
+        RulePack {
+            version: PACK_VERSION,
+            name: "Team".to_string(),
+            exported_by: String::new(),
+            filler_languages: vec!["en".to_string(), "de".to_string()],
+            filler_words: vec!["Basically".to_string(), "you know".to_string()],
+            pipeline: vec![
+                step("normalize_whitespace", false),
+                step("remove_filler_words", true),
+                step("plugin:spellcheck", true),
+                step("from_the_future", true),
+            ],
+        }
+    }
+
+    fn config() -> AppConfig {
+        let mut config = AppConfig::default();
+        config.filler_words.custom = vec!["basically".to_string()];
+        config.pipeline = vec![step("normalize_whitespace", true)];
+        config
+    }
+
+    #[test]
+    fn test_merge_reports_conflicts_and_skips() {
+        let mut config = config();
+        let import = merge(&mut config, &pack(), Resolution::KeepMine);
+
+        assert_eq!(import.added_words, 2);
+        assert_eq!(import.duplicate_words, 2);
+        assert_eq!(
+            import.conflicts,
+            [
+                StepConflict {
+                    id: "normalize_whitespace".to_string(),
+                    mine: true,
+                    theirs: false,
+                },
+                // Off by default
+                StepConflict {
+                    id: "remove_filler_words".to_string(),
+                    mine: false,
+                    theirs: true,
+                },
+            ]
+        );
+        assert_eq!(
+            import.skipped_steps,
+            ["plugin:spellcheck", "from_the_future"]
+        );
+        assert_eq!(config.pipeline, [step("normalize_whitespace", true)]);
+        assert_eq!(config.filler_words.custom, ["basically", "you know"]);
+    }
+
+    #[test]
+    fn test_use_theirs_takes_the_pack_setting() {
+        let mut config = config();
+        merge(&mut config, &pack(), Resolution::UseTheirs);
+        assert_eq!(
+            config.pipeline,
+            [
+                step("normalize_whitespace", false),
+                step("remove_filler_words", true),
+                step("llm_cleanup", false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_use_theirs_on_a_default_config_keeps_the_default_steps() {
+        let mut config = AppConfig::default();
+        let mut pack = pack();
+        pack.pipeline = vec![step("remove_filler_words", true)];
+        merge(&mut config, &pack, Resolution::UseTheirs);
+
+        assert_eq!(
+            config.pipeline,
+            [
+                step("normalize_whitespace", true),
+                step("remove_filler_words", true),
+                step("llm_cleanup", false),
+            ]
+        );
+        let order: Vec<String> = pipeline::describe(&config)
+            .into_iter()
+            .map(|info| info.id)
+            .collect();
+        assert_eq!(
+            order,
+            ["normalize_whitespace", "remove_filler_words", "llm_cleanup"]
+        );
+    }
+
+    #[test]
+    fn test_export_round_trips_without_plugins() {
+        let mut config = config();
+        config.pipeline.push(step("plugin:spellcheck", true));
+        let json = serde_json::to_string(&export(&config, "Mine")).unwrap();
+        let pack = parse(&json).unwrap();
+        assert_eq!(pack.pipeline, [step("normalize_whitespace", true)]);
+    }
+
+    #[test]
+    fn test_parse_refuses_newer_versions() {
+        let json = r#"{"version": 99, "name": "Future"}"#;
+        assert!(parse(json).unwrap_err().contains("Update Dictara"));
+        assert!(parse(r#"{"name": "No version"}"#).is_err());
+    }
+}
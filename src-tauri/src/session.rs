@@ -0,0 +1,83 @@
+//! Fast user switching.
+//!
+//! When another user takes over the screen, this session keeps running in the
+//! background. Its event tap would still see the trigger key and its
+//! recordings would still capture the microphone, so both stop: the key
+//! listener ignores input and the controller cancels the current recording
+//! and refuses new ones. Everything resumes once the session is active again.
+
+use crate::recording::RecordingCommand;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::mpsc;
+
+static ACTIVE: AtomicBool = AtomicBool::new(true);
+
+/// Whether this user session is the one on screen
+pub fn is_active() -> bool {
+    ACTIVE.load(Ordering::Relaxed)
+}
+
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn set_active(active: bool, command_tx: &mpsc::Sender<RecordingCommand>) {
+    if ACTIVE.swap(active, Ordering::Relaxed) == active {
+        return;
+    }
+    println!(
+        "[Session] Session became {}",
+        if active { "active" } else { "inactive" }
+    );
+
+    crate::keyboard_listener::set_tap_enabled(active);
+    if !active {
+        if let Err(e) = command_tx.try_send(RecordingCommand::Cancel) {
+            eprintln!("[Session] Failed to cancel recording: {}", e);
+        }
+    }
+}
+
+/// Follow session switches for the lifetime of the app
+#[cfg(target_os = "macos")]
+pub fn start_watcher(command_tx: mpsc::Sender<RecordingCommand>) {
+    use block2::RcBlock;
+    use objc2::msg_send;
+    use objc2::rc::Retained;
+    use objc2::runtime::{AnyClass, AnyObject};
+    use objc2_app_kit::NSWorkspace;
+    use objc2_foundation::NSString;
+
+    #[link(name = "AppKit", kind = "framework")]
+    extern "C" {
+        static NSWorkspaceSessionDidResignActiveNotification: &'static NSString;
+        static NSWorkspaceSessionDidBecomeActiveNotification: &'static NSString;
+    }
+
+    let Some(queue_class) = AnyClass::get(c"NSOperationQueue") else {
+        eprintln!("[Session] NSOperationQueue is unavailable, not watching for user switches");
+        return;
+    };
+
+    let center = NSWorkspace::sharedWorkspace().notificationCenter();
+    unsafe {
+        let queue: Retained<AnyObject> = msg_send![queue_class, mainQueue];
+        for (name, active) in [
+            (NSWorkspaceSessionDidResignActiveNotification, false),
+            (NSWorkspaceSessionDidBecomeActiveNotification, true),
+        ] {
+            let command_tx = command_tx.clone();
+            let block =
+                RcBlock::new(move |_notification: *mut AnyObject| set_active(active, &command_tx));
+            let observer: Retained<AnyObject> = msg_send![
+                &*center,
+                addObserverForName: name,
+                object: std::ptr::null::<AnyObject>(),
+                queue: &*queue,
+                usingBlock: &*block
+            ];
+            // Observe for the lifetime of the app
+            std::mem::forget(observer);
+        }
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn start_watcher(_command_tx: mpsc::Sender<RecordingCommand>) {}
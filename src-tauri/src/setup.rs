@@ -1,19 +1,31 @@
 #[cfg(not(debug_assertions))]
 use crate::updater::{self, UpdaterState};
 use crate::{
-    clients::openai::OpenAIClient,
+    clients::{openai::OpenAIClient, reauth},
+    clipboard_paste,
     config::{self, AzureOpenAIConfig, OpenAIConfig, Provider},
-    keyboard_listener::KeyListener,
+    energy,
+    events::{HistoryChanged, RecordingStateChanged},
+    keyboard_listener::{self, KeyListener, TriggerKeyState, TriggerSettings},
     keychain::{self, ProviderAccount},
+    onboarding::{self, OnboardingResumeState},
     recording::{
-        cleanup_old_recordings, Controller, LastRecording, LastRecordingState, RecordingCommand,
+        cleanup_old_recordings, offer_recovery, offer_retry, restore_paste_from_history,
+        Controller, LastRecording, LastRecordingState, RecordingCommand, StateJournal,
+        StateJournalState, TranscriptionQueue, TranscriptionQueueState,
+    },
+    supervisor::{RestartPolicy, Supervisor, SupervisorState},
+    ui::{
+        menu::{build_menu, MenuModel, MenuState},
+        tray::{self, TrayState, TrayStatus},
+        window,
     },
-    ui::{menu::build_menu, tray::PasteMenuItemState, window},
 };
 use std::sync::{atomic::AtomicU8, Arc, Mutex};
 use tauri::ipc::Channel;
 use tauri::Manager;
 use tauri_plugin_store::StoreExt;
+use tauri_specta::Event;
 use tokio::sync::mpsc;
 
 pub struct RecordingCommandSender {
@@ -27,6 +39,9 @@ pub struct AudioLevelChannel {
 pub fn setup_app(app: &mut tauri::App<tauri::Wry>) -> Result<(), Box<dyn std::error::Error>> {
     println!("Dictara v{}", env!("CARGO_PKG_VERSION"));
 
+    // Settings enforced through MDM override whatever config is loaded later
+    config::managed::load(&app.config().identifier);
+
     // Clean up old recordings from previous sessions
     cleanup_old_recordings(app.app_handle());
 
@@ -42,6 +57,15 @@ pub fn setup_app(app: &mut tauri::App<tauri::Wry>) -> Result<(), Box<dyn std::er
         }
     }
 
+    // Keep the frontend updated with live permission status
+    crate::permissions::start_permission_watcher(app.app_handle().clone());
+    crate::display_options::start_watcher(app.app_handle().clone());
+
+    // Warn about other tools that also react to the Fn key, and offer to
+    // stop macOS's own use of it
+    crate::conflicts::log_conflicts();
+    crate::conflicts::offer_fn_key_fix(app.app_handle());
+
     #[cfg(target_os = "macos")]
     {
         // Keep the app running in the background
@@ -51,12 +75,21 @@ pub fn setup_app(app: &mut tauri::App<tauri::Wry>) -> Result<(), Box<dyn std::er
     // Initialize OpenAI client (always succeeds, key checked at transcription time)
     let openai_client = OpenAIClient::new();
 
+    // Pick where provider secrets live before any config is loaded
+    keychain::init(app.path().app_data_dir()?);
+
+    // Offer to bring over settings from builds released as Typefree
+    crate::legacy::offer_import(app.app_handle());
+
     // Load app config and check if properly configured
     let store = app.store("config.json")?;
     let app_config = config::load_app_config(&store);
+    energy::init(app_config.energy_profile);
+    clipboard_paste::set_hide_from_clipboard_managers(app_config.hide_from_clipboard_managers);
 
-    // Check if any provider is properly configured
+    // Check if any provider is properly configured (dry-run mode needs none)
     let needs_configuration = match &app_config.active_provider {
+        _ if crate::clients::dry_run::is_enabled(&app_config) => false,
         Some(Provider::OpenAI) => {
             keychain::load_provider_config::<OpenAIConfig>(ProviderAccount::OpenAI)
                 .ok()
@@ -100,18 +133,29 @@ pub fn setup_app(app: &mut tauri::App<tauri::Wry>) -> Result<(), Box<dyn std::er
     // Create last recording state for paste retry functionality
     let last_recording_state: LastRecordingState = Arc::new(Mutex::new(LastRecording::new()));
 
+    // Supervisor restarts background workers that crash
+    let supervisor: SupervisorState = Supervisor::new(app.app_handle().clone());
+
+    // Create state journal for diagnosing stuck sessions
+    let state_journal: StateJournalState = Arc::new(StateJournal::new(app.app_handle()));
+
+    // Transcription in flight, so it can be cancelled
+    let transcription_queue: TranscriptionQueueState = Arc::new(TranscriptionQueue::new());
+
     // Initialize controller with OpenAI client
-    let controller = Controller::new(
+    let mut controller = Controller::new(
         command_rx,
         app.app_handle().clone(),
         openai_client,
         recording_state.clone(),
         audio_level_channel.channel.clone(),
         last_recording_state.clone(),
+        state_journal.clone(),
+        transcription_queue.clone(),
     );
 
-    // Spawn controller in blocking thread (cpal::Stream is not Send)
-    std::thread::spawn(move || {
+    // Spawn controller in a supervised blocking thread (cpal::Stream is not Send)
+    supervisor.spawn_thread("controller", RestartPolicy::default(), move || {
         controller.run();
     });
 
@@ -119,27 +163,54 @@ pub fn setup_app(app: &mut tauri::App<tauri::Wry>) -> Result<(), Box<dyn std::er
     app.manage(command_sender_state);
     app.manage(audio_level_channel);
     app.manage(last_recording_state.clone());
+    app.manage(state_journal);
+    app.manage(transcription_queue);
+    app.manage(supervisor.clone());
+
+    // Trigger key is shared with the listener so config changes apply without a restart
+    let trigger_key: TriggerKeyState = TriggerSettings::new(app_config.trigger_key);
+    app.manage(trigger_key.clone());
+
+    // Offer to transcribe a recording interrupted by a crash in the last session
+    offer_recovery(
+        app.app_handle(),
+        command_tx.clone(),
+        last_recording_state.clone(),
+    );
+
+    // Offer to retry a transcription that failed in the last session
+    offer_retry(
+        app.app_handle(),
+        command_tx.clone(),
+        last_recording_state.clone(),
+    );
+
+    // Stop listening and recording while another user is on screen
+    crate::session::start_watcher(command_tx.clone());
 
     // Start keyboard listener with command sender
-    let _listener = KeyListener::start(command_tx, recording_state.clone());
+    let _listener = KeyListener::start(
+        app.app_handle().clone(),
+        command_tx,
+        recording_state.clone(),
+        trigger_key,
+        &supervisor,
+    );
 
-    let menu_with_items = build_menu(app)?;
-    let paste_menu_item_state = PasteMenuItemState {
-        item: menu_with_items.paste_last_item,
-    };
+    // Transcribe audio files dropped into the watch folder
+    crate::watch_folder::start(app.app_handle(), &supervisor);
+    // Receive voice memos pushed from a phone
+    crate::intake::start(app.app_handle(), &supervisor);
+
+    // Rebuilt from the model by `menu::update` as its items change
+    app.manage(MenuState::default());
+    let menu = build_menu(app, &MenuModel::default())?;
 
     // Build tray icon with template image for menu bar
-    const TRAY_ICON_BYTES: &[u8] = include_bytes!("../icons/tray-icon.png");
-    let tray_icon_image = image::load_from_memory(TRAY_ICON_BYTES)
-        .expect("Failed to load tray icon")
-        .to_rgba8();
-    let (width, height) = tray_icon_image.dimensions();
-    let tray_icon = tauri::image::Image::new_owned(tray_icon_image.into_raw(), width, height);
-
-    let _tray = tauri::tray::TrayIconBuilder::new()
-        .icon(tray_icon)
+    let _tray = tauri::tray::TrayIconBuilder::with_id(tray::TRAY_ID)
+        .icon(tray::base_icon())
         .icon_as_template(true) // macOS template image - auto-adapts to light/dark mode
-        .menu(&menu_with_items.menu)
+        .menu(&menu)
         .show_menu_on_left_click(true)
         .on_menu_event(|app, event| {
             match event.id().as_ref() {
@@ -153,6 +224,24 @@ pub fn setup_app(app: &mut tauri::App<tauri::Wry>) -> Result<(), Box<dyn std::er
                         eprintln!("Failed to open preferences window: {}", e);
                     }
                 }
+                "command_palette" => {
+                    println!("Command Palette clicked");
+                    if let Err(e) = window::open_command_palette(app) {
+                        eprintln!("Failed to open command palette: {}", e);
+                    }
+                }
+                "input_capture" => {
+                    println!("Input Capture Not Working clicked");
+                    if let Err(e) = window::open_preferences_window(app) {
+                        eprintln!("Failed to open preferences window: {}", e);
+                    }
+                }
+                "reauth" => {
+                    println!("Re-enter API Key clicked");
+                    if let Err(e) = window::open_preferences_page(app, window::API_KEYS_PAGE) {
+                        eprintln!("Failed to open API keys page: {}", e);
+                    }
+                }
                 "paste_last_recording" => {
                     println!("Paste Last Recording clicked");
                     // Get the last recording state
@@ -175,6 +264,28 @@ pub fn setup_app(app: &mut tauri::App<tauri::Wry>) -> Result<(), Box<dyn std::er
                         eprintln!("Last recording state not available");
                     }
                 }
+                "read_back_last_recording" => {
+                    println!("Read Back Last Recording clicked");
+                    if let Err(e) = crate::speech::read_back_last_recording(app) {
+                        eprintln!("Failed to read back last recording: {}", e);
+                    }
+                }
+                "transcribe_copied_file" => {
+                    println!("Transcribe Copied Audio File clicked");
+                    // Off the main thread: it waits for the provider
+                    let app = app.clone();
+                    std::thread::spawn(move || {
+                        let _ = crate::copied_file::transcribe(&app);
+                    });
+                }
+                "cancel_transcriptions" => {
+                    println!("Cancel Transcription clicked");
+                    app.state::<TranscriptionQueueState>().abort_all();
+                }
+                "reset_input" => {
+                    println!("Reset Input Handling clicked");
+                    keyboard_listener::reset_input_handling();
+                }
                 "quit" => {
                     println!("Quit clicked");
                     app.exit(0);
@@ -184,18 +295,46 @@ pub fn setup_app(app: &mut tauri::App<tauri::Wry>) -> Result<(), Box<dyn std::er
         })
         .build(app)?;
 
-    app.manage(paste_menu_item_state);
+    // Warn now, rather than at the first trigger press, when input can't be captured
+    crate::permissions::check_input_capture(app.app_handle());
+
+    // Offer the newest history entry for pasting, also after a restart
+    restore_paste_from_history(app.app_handle(), &app_config, &last_recording_state);
+    let history_handle = app.app_handle().clone();
+    let history_last_recording = last_recording_state.clone();
+    HistoryChanged::listen(app.app_handle(), move |_event| {
+        let app_config = history_handle
+            .store("config.json")
+            .map(|store| config::load_app_config(&store))
+            .unwrap_or_default();
+        restore_paste_from_history(&history_handle, &app_config, &history_last_recording);
+    });
+
+    // Keep the menu bar icon in sync with the recording state
+    app.manage(TrayState::default());
+    reauth::init(app.app_handle());
+    let tray_handle = app.app_handle().clone();
+    RecordingStateChanged::listen(app.app_handle(), move |event| {
+        if let Err(e) = tray::set_status(&tray_handle, TrayStatus::from_event(&event.payload)) {
+            eprintln!("[Setup] Failed to update tray icon: {}", e);
+        }
+    });
 
     // Initialize and start the updater (only in release builds)
     #[cfg(not(debug_assertions))]
     {
         let updater_state = Arc::new(UpdaterState::new(recording_state));
         app.manage(updater_state.clone());
-        updater::start_periodic_update_check(app.app_handle().clone(), updater_state);
+        updater::start_periodic_update_check(app.app_handle().clone(), updater_state, &supervisor);
     }
 
+    // Pick onboarding back up if the last run restarted for a permission
+    let onboarding_resume = onboarding::resume_after_restart(app.app_handle());
+    let resuming_onboarding = onboarding_resume.is_some();
+    app.manage(OnboardingResumeState(Mutex::new(onboarding_resume)));
+
     // Open preferences window if configuration needed
-    if needs_configuration {
+    if needs_configuration && !resuming_onboarding {
         if let Err(e) = window::open_preferences_window(app.app_handle()) {
             eprintln!("Failed to open preferences window: {}", e);
         }
@@ -0,0 +1,461 @@
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, Sample, SizedSample};
+use std::io::Cursor;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    mpsc, Arc, Mutex, OnceLock,
+};
+use std::thread;
+use std::time::Duration;
+use tauri_plugin_store::StoreExt;
+
+use crate::calendar;
+use crate::config::{self, SoundConfig};
+use crate::speech;
+
+const START_SOUND_BYTES: &[u8] = include_bytes!("../sounds/start.wav");
+const LOCK_SOUND_BYTES: &[u8] = include_bytes!("../sounds/lock.wav");
+const DONE_SOUND_BYTES: &[u8] = include_bytes!("../sounds/done.wav");
+const ERROR_SOUND_BYTES: &[u8] = include_bytes!("../sounds/error.wav");
+
+/// Pause the shared output stream after this long without sounds
+const IDLE_PAUSE_AFTER: Duration = Duration::from_secs(2);
+
+/// Feedback cues played during a dictation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Sound {
+    /// Recording started
+    Start,
+    /// Recording locked for hands-free dictation
+    Locked,
+    /// Recording cancelled (spoken only)
+    Cancelled,
+    /// Transcript was transcribed and pasted
+    Done,
+    /// Recording, transcription or paste failed
+    Error,
+}
+
+impl Sound {
+    /// Beep asset; spoken-only cues have none
+    fn bytes(&self) -> Option<&'static [u8]> {
+        match self {
+            Sound::Start => Some(START_SOUND_BYTES),
+            Sound::Locked => Some(LOCK_SOUND_BYTES),
+            Sound::Done => Some(DONE_SOUND_BYTES),
+            Sound::Error => Some(ERROR_SOUND_BYTES),
+            Sound::Cancelled => None,
+        }
+    }
+
+    /// What `sounds.spoken` says instead of the beep
+    fn phrase(&self) -> &'static str {
+        match self {
+            Sound::Start => "Recording",
+            Sound::Locked => "Locked",
+            Sound::Cancelled => "Cancelled",
+            Sound::Done => "Done",
+            Sound::Error => "Error",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SoundError {
+    NoOutputDevice,
+    DeviceError,
+    DecodeError,
+}
+
+impl From<cpal::DefaultStreamConfigError> for SoundError {
+    fn from(_err: cpal::DefaultStreamConfigError) -> Self {
+        SoundError::DeviceError
+    }
+}
+
+impl From<cpal::BuildStreamError> for SoundError {
+    fn from(_err: cpal::BuildStreamError) -> Self {
+        SoundError::DeviceError
+    }
+}
+
+impl From<cpal::PlayStreamError> for SoundError {
+    fn from(_err: cpal::PlayStreamError) -> Self {
+        SoundError::DeviceError
+    }
+}
+
+impl From<hound::Error> for SoundError {
+    fn from(_err: hound::Error) -> Self {
+        SoundError::DecodeError
+    }
+}
+
+/// Decoded mono samples of a sound asset
+struct DecodedSound {
+    samples: Vec<f32>,
+    sample_rate: u32,
+}
+
+/// Play a feedback cue if it's enabled in the app config (fire and forget).
+/// Returns how long the beep plays, `None` if it's muted or spoken.
+pub fn play_cue(app_handle: &tauri::AppHandle, sound: Sound) -> Option<Duration> {
+    let app_config = match app_handle.store("config.json") {
+        Ok(store) => config::load_app_config(&store),
+        Err(e) => {
+            eprintln!("[Sound Player] Failed to load config store: {}", e);
+            return None;
+        }
+    };
+
+    if app_config.quiet_hours.is_active_now() {
+        println!("[Sound Player] Quiet hours active, muting {:?}", sound);
+        return None;
+    }
+    if calendar::mutes_cues(&app_config.calendar) {
+        println!("[Sound Player] Meeting in progress, muting {:?}", sound);
+        return None;
+    }
+
+    let sounds = app_config.sounds;
+
+    let enabled = sounds.enabled
+        && match sound {
+            Sound::Start => sounds.start,
+            Sound::Locked => sounds.locked,
+            Sound::Cancelled => sounds.spoken,
+            Sound::Done => sounds.done,
+            Sound::Error => sounds.error,
+        };
+
+    if !enabled {
+        return None;
+    }
+    if sounds.spoken {
+        speech::say(sound.phrase(), &sounds);
+        return None;
+    }
+    play_sound(sound, &sounds);
+    decoded(sound).ok().map(|decoded| {
+        Duration::from_secs_f64(decoded.samples.len() as f64 / decoded.sample_rate as f64)
+    })
+}
+
+/// Queue a sound on the shared player thread
+pub fn play_sound(sound: Sound, sounds: &SoundConfig) {
+    let request = PlayRequest {
+        sound,
+        output_device: sounds.output_device.clone(),
+        volume: sounds.volume.clamp(0.0, 1.0),
+    };
+
+    if player().send(request).is_err() {
+        eprintln!("[Sound Player] Player thread is gone, dropping {:?}", sound);
+    }
+}
+
+/// Names of all available output devices, for the preferences device picker
+pub fn list_output_devices() -> Vec<String> {
+    let host = cpal::default_host();
+    match host.output_devices() {
+        Ok(devices) => devices.filter_map(|d| d.name().ok()).collect(),
+        Err(e) => {
+            eprintln!("[Sound Player] Failed to enumerate output devices: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// A sound to play and where to play it
+struct PlayRequest {
+    sound: Sound,
+    output_device: Option<String>,
+    volume: f32,
+}
+
+/// Lazily start the player thread that owns the output stream
+fn player() -> &'static mpsc::Sender<PlayRequest> {
+    static PLAYER: OnceLock<mpsc::Sender<PlayRequest>> = OnceLock::new();
+    PLAYER.get_or_init(|| {
+        let (tx, rx) = mpsc::channel();
+        // cpal::Stream is not Send, so it lives on this thread for the app's lifetime
+        thread::spawn(move || run_player(rx));
+        tx
+    })
+}
+
+/// A sound being mixed into the output, already at the device sample rate
+struct Voice {
+    samples: Vec<f32>,
+    position: usize,
+}
+
+/// The shared output stream and what it was built for
+struct Output {
+    stream: cpal::Stream,
+    device_name: String,
+    sample_rate: u32,
+    voices: Arc<Mutex<Vec<Voice>>>,
+    /// Set by the stream error callback (e.g. device unplugged)
+    failed: Arc<AtomicBool>,
+    playing: bool,
+}
+
+impl Output {
+    fn is_active(&self) -> bool {
+        self.voices.lock().map(|v| !v.is_empty()).unwrap_or(false)
+    }
+}
+
+fn run_player(rx: mpsc::Receiver<PlayRequest>) {
+    println!("[Sound Player] Player thread started");
+
+    let mut output: Option<Output> = None;
+
+    loop {
+        match rx.recv_timeout(IDLE_PAUSE_AFTER) {
+            Ok(request) => {
+                let sound = request.sound;
+                if let Err(e) = play_on_output(&mut output, request) {
+                    eprintln!("[Sound Player] Failed to play {:?}: {:?}", sound, e);
+                    // Force a rebuild on the next sound
+                    output = None;
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                // Pause when idle so the stream doesn't keep the audio device busy
+                if let Some(out) = output.as_mut() {
+                    if out.playing && !out.is_active() {
+                        out.stream.pause().ok();
+                        out.playing = false;
+                    }
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+    }
+
+    println!("[Sound Player] Player thread exiting");
+}
+
+/// Pick the configured output device, falling back to the system default
+/// when it isn't connected
+fn resolve_device(host: &cpal::Host, preferred: Option<&str>) -> Option<cpal::Device> {
+    if let Some(name) = preferred {
+        let found = host
+            .output_devices()
+            .ok()
+            .and_then(|mut devices| devices.find(|d| d.name().ok().as_deref() == Some(name)));
+        if found.is_some() {
+            return found;
+        }
+        println!(
+            "[Sound Player] Output device '{}' not available, using system default",
+            name
+        );
+    }
+
+    host.default_output_device()
+}
+
+fn play_on_output(output: &mut Option<Output>, request: PlayRequest) -> Result<(), SoundError> {
+    let decoded = decoded(request.sound)?;
+
+    let host = cpal::default_host();
+    let device = resolve_device(&host, request.output_device.as_deref())
+        .ok_or(SoundError::NoOutputDevice)?;
+    let device_name = device.name().unwrap_or_else(|_| "Unknown".to_string());
+
+    // Rebuild when the stream died, the system default changed or the user picked another device
+    let needs_rebuild = match output {
+        Some(out) => out.failed.load(Ordering::Relaxed) || out.device_name != device_name,
+        None => true,
+    };
+
+    if needs_rebuild {
+        if output.is_some() {
+            println!("[Sound Player] Output device changed, rebuilding stream");
+        }
+        *output = Some(build_output(&device, device_name)?);
+    }
+
+    let Some(out) = output.as_mut() else {
+        return Err(SoundError::DeviceError);
+    };
+
+    let samples = resample_linear(&decoded.samples, decoded.sample_rate, out.sample_rate)
+        .into_iter()
+        .map(|sample| sample * request.volume)
+        .collect();
+    if let Ok(mut voices) = out.voices.lock() {
+        voices.push(Voice {
+            samples,
+            position: 0,
+        });
+    }
+
+    if !out.playing {
+        out.stream.play()?;
+        out.playing = true;
+    }
+
+    Ok(())
+}
+
+fn build_output(device: &cpal::Device, device_name: String) -> Result<Output, SoundError> {
+    let config = device.default_output_config()?;
+    let sample_rate = config.sample_rate().0;
+    let channels = config.channels() as usize;
+
+    println!(
+        "[Sound Player] Opening output stream on {} ({} Hz, {} channels)",
+        device_name, sample_rate, channels
+    );
+
+    let voices: Arc<Mutex<Vec<Voice>>> = Arc::new(Mutex::new(Vec::new()));
+    let failed = Arc::new(AtomicBool::new(false));
+
+    let stream = match config.sample_format() {
+        cpal::SampleFormat::F32 => build_output_stream::<f32>(
+            device,
+            &config.into(),
+            voices.clone(),
+            failed.clone(),
+            channels,
+        )?,
+        cpal::SampleFormat::I16 => build_output_stream::<i16>(
+            device,
+            &config.into(),
+            voices.clone(),
+            failed.clone(),
+            channels,
+        )?,
+        cpal::SampleFormat::I32 => build_output_stream::<i32>(
+            device,
+            &config.into(),
+            voices.clone(),
+            failed.clone(),
+            channels,
+        )?,
+        _ => return Err(SoundError::DeviceError),
+    };
+
+    Ok(Output {
+        stream,
+        device_name,
+        sample_rate,
+        voices,
+        failed,
+        playing: false,
+    })
+}
+
+fn decoded(sound: Sound) -> Result<Arc<DecodedSound>, SoundError> {
+    static START: OnceLock<Arc<DecodedSound>> = OnceLock::new();
+    static LOCKED: OnceLock<Arc<DecodedSound>> = OnceLock::new();
+    static DONE: OnceLock<Arc<DecodedSound>> = OnceLock::new();
+    static ERROR: OnceLock<Arc<DecodedSound>> = OnceLock::new();
+
+    let (cell, bytes) = match (sound, sound.bytes()) {
+        (Sound::Start, Some(bytes)) => (&START, bytes),
+        (Sound::Locked, Some(bytes)) => (&LOCKED, bytes),
+        (Sound::Done, Some(bytes)) => (&DONE, bytes),
+        (Sound::Error, Some(bytes)) => (&ERROR, bytes),
+        _ => return Err(SoundError::DecodeError),
+    };
+
+    if let Some(decoded) = cell.get() {
+        return Ok(decoded.clone());
+    }
+
+    let decoded = Arc::new(decode_wav(bytes)?);
+    Ok(cell.get_or_init(|| decoded).clone())
+}
+
+fn decode_wav(bytes: &[u8]) -> Result<DecodedSound, SoundError> {
+    let mut reader = hound::WavReader::new(Cursor::new(bytes))?;
+    let spec = reader.spec();
+    let channels = spec.channels.max(1) as usize;
+
+    let interleaved: Vec<f32> = reader
+        .samples::<i16>()
+        .map(|s| s.map(|v| v as f32 / i16::MAX as f32))
+        .collect::<Result<_, _>>()?;
+
+    // Downmix to mono, output channels are filled from it at playback time
+    let samples = interleaved
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+        .collect();
+
+    Ok(DecodedSound {
+        samples,
+        sample_rate: spec.sample_rate,
+    })
+}
+
+/// Linear resampling - plenty for short UI cues
+fn resample_linear(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    if from_rate == to_rate || samples.is_empty() {
+        return samples.to_vec();
+    }
+
+    let ratio = from_rate as f64 / to_rate as f64;
+    let out_len = (samples.len() as f64 / ratio) as usize;
+
+    (0..out_len)
+        .map(|i| {
+            let pos = i as f64 * ratio;
+            let idx = pos as usize;
+            let frac = (pos - idx as f64) as f32;
+            let a = samples[idx.min(samples.len() - 1)];
+            let b = samples[(idx + 1).min(samples.len() - 1)];
+            a + (b - a) * frac
+        })
+        .collect()
+}
+
+fn build_output_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    voices: Arc<Mutex<Vec<Voice>>>,
+    failed: Arc<AtomicBool>,
+    channels: usize,
+) -> Result<cpal::Stream, SoundError>
+where
+    T: SizedSample + FromSample<f32>,
+{
+    let stream = device.build_output_stream(
+        config,
+        move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+            let Ok(mut voices) = voices.try_lock() else {
+                // Never block the audio thread; output silence for this buffer
+                data.fill(0.0f32.to_sample::<T>());
+                return;
+            };
+
+            for frame in data.chunks_mut(channels) {
+                let mut mixed = 0.0f32;
+                for voice in voices.iter_mut() {
+                    if let Some(sample) = voice.samples.get(voice.position) {
+                        mixed += sample;
+                        voice.position += 1;
+                    }
+                }
+                let value = mixed.clamp(-1.0, 1.0).to_sample::<T>();
+                for out in frame.iter_mut() {
+                    *out = value;
+                }
+            }
+
+            voices.retain(|voice| voice.position < voice.samples.len());
+        },
+        move |err| {
+            eprintln!("[Sound Player] Stream error: {}", err);
+            failed.store(true, Ordering::Relaxed);
+        },
+        None,
+    )?;
+
+    Ok(stream)
+}
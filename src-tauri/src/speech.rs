@@ -0,0 +1,167 @@
+//! Spoken feedback cues.
+//!
+//! With `sounds.spoken`, cues are spoken by macOS speech synthesis ("Locked",
+//! "Cancelled") instead of played as beeps, for dictating eyes-free. Speech
+//! runs through the `say` tool, which honors the cue output device and lets
+//! the user pick any installed voice.
+//!
+//! `read_back` speaks a whole transcript the same way, to check it without
+//! looking at the screen. Starting another read-back stops the one speaking.
+
+use crate::config::{self, SoundConfig};
+use crate::recording::LastRecordingState;
+#[cfg(target_os = "macos")]
+use std::process::{Child, Command, Stdio};
+#[cfg(target_os = "macos")]
+use std::sync::Mutex;
+use tauri::Manager;
+use tauri_plugin_store::StoreExt;
+
+/// The read-back speaking, if any
+#[cfg(target_os = "macos")]
+static READ_BACK: Mutex<Option<Child>> = Mutex::new(None);
+
+/// `say` with the cue voice and output device
+#[cfg(target_os = "macos")]
+fn say_command(sounds: &SoundConfig) -> Command {
+    let mut command = Command::new("say");
+    if let Some(voice) = &sounds.voice {
+        command.args(["-v", voice]);
+    }
+    if let Some(device) = &sounds.output_device {
+        command.args(["-a", device]);
+    }
+    command
+}
+
+/// Embedded speech command for the cue volume, 0.0 to 1.0
+#[cfg(target_os = "macos")]
+fn with_volume(text: &str, sounds: &SoundConfig) -> String {
+    format!("[[volm {:.2}]] {}", sounds.volume.clamp(0.0, 1.0), text)
+}
+
+/// Speak `phrase` with the cue voice, volume and output device (fire and forget)
+#[cfg(target_os = "macos")]
+pub fn say(phrase: &str, sounds: &SoundConfig) {
+    let mut command = say_command(sounds);
+    command.arg(with_volume(phrase, sounds));
+
+    match command.spawn() {
+        Ok(mut child) => {
+            // Reap the process without holding up the caller
+            std::thread::spawn(move || {
+                if let Ok(status) = child.wait() {
+                    if !status.success() {
+                        eprintln!("[Speech] say exited with {}", status);
+                    }
+                }
+            });
+        }
+        Err(e) => eprintln!("[Speech] Failed to run say: {}", e),
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn say(phrase: &str, _sounds: &SoundConfig) {
+    println!(
+        "[Speech] Speech is only available on macOS, skipping \"{}\"",
+        phrase
+    );
+}
+
+/// Speak `text` with the cue voice, volume and output device, stopping the
+/// read-back already speaking
+#[cfg(target_os = "macos")]
+pub fn read_back(text: &str, sounds: &SoundConfig) -> Result<(), String> {
+    use std::io::Write;
+
+    let mut speaking = READ_BACK.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(mut previous) = speaking.take() {
+        // Already finished if this fails; waiting reaps it either way
+        let _ = previous.kill();
+        let _ = previous.wait();
+    }
+
+    // The text goes through stdin, so a transcript starting with "-" isn't
+    // taken for an option
+    let mut child = say_command(sounds)
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run say: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(with_volume(text, sounds).as_bytes())
+            .map_err(|e| format!("Failed to send text to say: {}", e))?;
+    }
+    *speaking = Some(child);
+    Ok(())
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn read_back(_text: &str, _sounds: &SoundConfig) -> Result<(), String> {
+    Err("Read-back is only available on macOS".to_string())
+}
+
+/// Read back the last transcription
+pub fn read_back_last_recording(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    let text = app_handle
+        .try_state::<LastRecordingState>()
+        .and_then(|state| state.lock().ok().and_then(|last| last.text.clone()))
+        .ok_or_else(|| "No transcription to read back".to_string())?;
+    let sounds = app_handle
+        .store("config.json")
+        .map(|store| config::load_app_config(&store).sounds)
+        .map_err(|e| format!("Failed to load config: {}", e))?;
+    println!("[Speech] Reading back {} chars", text.chars().count());
+    read_back(&text, &sounds)
+}
+
+/// Voice names from `say -v ?` output, e.g.
+/// `Bad News            en_US    # The light you see...`
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_voices(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let description = line.split('#').next()?.trim_end();
+            // The last column is the locale; names may contain spaces
+            let (name, _locale) = description.rsplit_once(char::is_whitespace)?;
+            let name = name.trim();
+            (!name.is_empty()).then(|| name.to_string())
+        })
+        .collect()
+}
+
+/// Installed speech voices
+#[cfg(target_os = "macos")]
+pub fn list_voices() -> Vec<String> {
+    match std::process::Command::new("say").args(["-v", "?"]).output() {
+        Ok(output) => parse_voices(&String::from_utf8_lossy(&output.stdout)),
+        Err(e) => {
+            eprintln!("[Speech] Failed to list voices: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn list_voices() -> Vec<String> {
+    Vec::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_voices() {
+        let output = "Albert              en_US    # Hello! My name is Albert.\n\
+                      Bad News            en_US    # The light you see at the end of the tunnel.\n\
+                      Anna (Premium)      de_DE    # Hallo, ich heiße Anna.\n\
+                      \n";
+        assert_eq!(
+            parse_voices(output),
+            ["Albert", "Bad News", "Anna (Premium)"]
+        );
+    }
+}
@@ -0,0 +1,241 @@
+use crate::events::{self, DictationCompleted, StatsUpdated};
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tauri_plugin_store::StoreExt;
+
+const STATS_STORE: &str = "stats.json";
+const STATS_KEY: &str = "usage_stats";
+
+/// Date format used for the daily stats keys
+const DAY_FORMAT: &str = "%Y-%m-%d";
+
+/// Aggregate usage statistics (stored locally)
+#[derive(Debug, Clone, Serialize, Deserialize, Default, specta::Type)]
+pub struct UsageStats {
+    /// Number of dictations where the provider returned no text
+    #[serde(default)]
+    pub empty_transcripts: u32,
+    /// Number of dictations that produced text
+    #[serde(default)]
+    pub total_dictations: u32,
+    /// Number of words across all dictations
+    #[serde(default)]
+    pub total_words: u32,
+    /// Per-day totals keyed by local date ("YYYY-MM-DD")
+    #[serde(default)]
+    pub daily: BTreeMap<String, DailyStats>,
+}
+
+/// Totals for a single day
+#[derive(Debug, Clone, Serialize, Deserialize, Default, specta::Type)]
+pub struct DailyStats {
+    pub dictations: u32,
+    pub words: u32,
+}
+
+impl UsageStats {
+    /// Build the summary relative to the given day
+    pub fn summary(&self, today: NaiveDate) -> StatsUpdated {
+        let days: Vec<NaiveDate> = self
+            .daily
+            .iter()
+            .filter(|(_, day)| day.dictations > 0)
+            .filter_map(|(key, _)| NaiveDate::parse_from_str(key, DAY_FORMAT).ok())
+            .collect();
+
+        StatsUpdated {
+            total_dictations: self.total_dictations,
+            total_words: self.total_words,
+            words_today: self
+                .daily
+                .get(&today.format(DAY_FORMAT).to_string())
+                .map(|day| day.words)
+                .unwrap_or(0),
+            current_streak_days: current_streak(&days, today),
+            longest_streak_days: longest_streak(&days),
+        }
+    }
+}
+
+/// Streak ending today, or yesterday if nothing was dictated yet today.
+/// `days` must be sorted ascending (BTreeMap keys are).
+fn current_streak(days: &[NaiveDate], today: NaiveDate) -> u32 {
+    let Some(&last) = days.last() else {
+        return 0;
+    };
+
+    if (today - last).num_days() > 1 {
+        return 0;
+    }
+
+    let mut streak = 1;
+    for pair in days.windows(2).rev() {
+        if (pair[1] - pair[0]).num_days() == 1 {
+            streak += 1;
+        } else {
+            break;
+        }
+    }
+    streak
+}
+
+fn longest_streak(days: &[NaiveDate]) -> u32 {
+    if days.is_empty() {
+        return 0;
+    }
+
+    let mut longest = 1;
+    let mut run = 1;
+    for pair in days.windows(2) {
+        if (pair[1] - pair[0]).num_days() == 1 {
+            run += 1;
+            longest = longest.max(run);
+        } else {
+            run = 1;
+        }
+    }
+    longest
+}
+
+/// Load usage statistics from store
+pub fn load_stats(app_handle: &tauri::AppHandle) -> UsageStats {
+    app_handle
+        .store(STATS_STORE)
+        .ok()
+        .and_then(|store| store.get(STATS_KEY))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+/// Apply a change to the stored statistics and persist them
+fn update_stats(
+    app_handle: &tauri::AppHandle,
+    update: impl FnOnce(&mut UsageStats),
+) -> Option<UsageStats> {
+    let store = match app_handle.store(STATS_STORE) {
+        Ok(store) => store,
+        Err(e) => {
+            eprintln!("[Stats] Failed to open stats store: {}", e);
+            return None;
+        }
+    };
+
+    let mut stats: UsageStats = store
+        .get(STATS_KEY)
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default();
+
+    update(&mut stats);
+
+    match serde_json::to_value(&stats) {
+        Ok(value) => store.set(STATS_KEY, value),
+        Err(e) => {
+            eprintln!("[Stats] Failed to serialize stats: {}", e);
+            return None;
+        }
+    }
+
+    if let Err(e) = store.save() {
+        eprintln!("[Stats] Failed to save stats: {}", e);
+    }
+
+    Some(stats)
+}
+
+/// Count a dictation that produced no text
+pub fn record_empty_transcript(app_handle: &tauri::AppHandle) {
+    update_stats(app_handle, |stats| stats.empty_transcripts += 1);
+}
+
+/// Count a successful dictation and notify the frontend
+pub fn record_dictation(app_handle: &tauri::AppHandle, completed: &DictationCompleted) {
+    let words = completed.words;
+    let today = Local::now().date_naive();
+
+    let Some(stats) = update_stats(app_handle, |stats| {
+        stats.total_dictations += 1;
+        stats.total_words += words;
+        let day = stats
+            .daily
+            .entry(today.format(DAY_FORMAT).to_string())
+            .or_default();
+        day.dictations += 1;
+        day.words += words;
+    }) else {
+        return;
+    };
+
+    events::emit_or_log(app_handle, stats.summary(today));
+}
+
+/// Current summary (words today, streaks)
+pub fn load_summary(app_handle: &tauri::AppHandle) -> StatsUpdated {
+    load_stats(app_handle).summary(Local::now().date_naive())
+}
+
+/// Write the aggregate stats as pretty JSON to the given path
+pub fn export_stats(app_handle: &tauri::AppHandle, path: &str) -> Result<(), String> {
+    let stats = load_stats(app_handle);
+    let json = serde_json::to_string_pretty(&stats).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+    println!("[Stats] Exported usage stats to {}", path);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, DAY_FORMAT).unwrap()
+    }
+
+    fn stats_for(days: &[(&str, u32)]) -> UsageStats {
+        UsageStats {
+            daily: days
+                .iter()
+                .map(|(day, words)| {
+                    (
+                        day.to_string(),
+                        DailyStats {
+                            dictations: 1,
+                            words: *words,
+                        },
+                    )
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_streak_ending_today() {
+        let stats = stats_for(&[
+            ("2026-10-01", 5),
+            ("2026-10-02", 5),
+            ("2026-10-04", 5),
+            ("2026-10-05", 5),
+            ("2026-10-06", 7),
+        ]);
+        let summary = stats.summary(date("2026-10-06"));
+        assert_eq!(summary.current_streak_days, 3);
+        assert_eq!(summary.longest_streak_days, 3);
+        assert_eq!(summary.words_today, 7);
+    }
+
+    #[test]
+    fn test_streak_survives_until_end_of_next_day() {
+        let stats = stats_for(&[("2026-10-05", 5), ("2026-10-06", 5)]);
+        assert_eq!(stats.summary(date("2026-10-07")).current_streak_days, 2);
+        assert_eq!(stats.summary(date("2026-10-08")).current_streak_days, 0);
+        assert_eq!(stats.summary(date("2026-10-07")).words_today, 0);
+    }
+
+    #[test]
+    fn test_no_dictations() {
+        let summary = UsageStats::default().summary(date("2026-10-06"));
+        assert_eq!(summary.current_streak_days, 0);
+        assert_eq!(summary.longest_streak_days, 0);
+    }
+}
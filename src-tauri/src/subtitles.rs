@@ -0,0 +1,212 @@
+//! SRT and WebVTT export.
+//!
+//! Provider segments are often too long to read as one subtitle, so they are
+//! split into cues of at most two lines of [`MAX_LINE_CHARS`] and
+//! [`MAX_CUE_MS`]. A split segment's time is shared between its cues in
+//! proportion to their length, which is close enough for speech.
+
+use crate::clients::openai::TranscriptSegment;
+
+/// Common broadcast limit for subtitle line length
+const MAX_LINE_CHARS: usize = 42;
+const MAX_CUE_CHARS: usize = MAX_LINE_CHARS * 2;
+const MAX_CUE_MS: u64 = 7000;
+/// Cues shorter than this are extended when the next cue leaves room
+const MIN_CUE_MS: u64 = 1000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Cue {
+    start_ms: u64,
+    end_ms: u64,
+    lines: Vec<String>,
+}
+
+/// Split `words` into `parts` runs of roughly equal length
+fn split_words(words: &[&str], parts: usize) -> Vec<String> {
+    let total: usize = words.iter().map(|w| w.len() + 1).sum();
+    let target = total as f64 / parts as f64;
+
+    let mut chunks: Vec<Vec<&str>> = vec![Vec::new()];
+    let mut consumed = 0;
+    for word in words {
+        let boundary = target * chunks.len() as f64;
+        if consumed as f64 >= boundary && chunks.len() < parts {
+            chunks.push(Vec::new());
+        }
+        chunks.last_mut().unwrap().push(word);
+        consumed += word.len() + 1;
+    }
+
+    chunks
+        .into_iter()
+        .filter(|chunk| !chunk.is_empty())
+        .map(|chunk| chunk.join(" "))
+        .collect()
+}
+
+/// Break a cue's text into one line, or two lines split near the middle
+fn wrap(text: &str) -> Vec<String> {
+    if text.len() <= MAX_LINE_CHARS {
+        return vec![text.to_string()];
+    }
+
+    let middle = text.len() / 2;
+    let split = text
+        .match_indices(' ')
+        .map(|(i, _)| i)
+        .min_by_key(|&i| i.abs_diff(middle));
+
+    match split {
+        Some(i) => vec![text[..i].to_string(), text[i + 1..].to_string()],
+        None => vec![text.to_string()],
+    }
+}
+
+fn build_cues(segments: &[TranscriptSegment]) -> Vec<Cue> {
+    let mut cues = Vec::new();
+
+    for segment in segments {
+        let words: Vec<&str> = segment.text.split_whitespace().collect();
+        if words.is_empty() {
+            continue;
+        }
+
+        let duration = segment.end_ms.saturating_sub(segment.start_ms);
+        let chars = segment.text.len();
+        let parts = chars
+            .div_ceil(MAX_CUE_CHARS)
+            .max(duration.div_ceil(MAX_CUE_MS) as usize)
+            .clamp(1, words.len());
+
+        let chunks = split_words(&words, parts);
+        let total: usize = chunks.iter().map(|c| c.len()).sum();
+        let mut offset = 0;
+        for chunk in chunks {
+            let start = segment.start_ms + duration * offset as u64 / total as u64;
+            offset += chunk.len();
+            let end = segment.start_ms + duration * offset as u64 / total as u64;
+            cues.push(Cue {
+                start_ms: start,
+                end_ms: end,
+                lines: wrap(&chunk),
+            });
+        }
+    }
+
+    // Give very short cues time to be read, without overlapping the next one
+    for i in 0..cues.len() {
+        let limit = cues.get(i + 1).map_or(u64::MAX, |next| next.start_ms);
+        let cue = &mut cues[i];
+        if cue.end_ms - cue.start_ms < MIN_CUE_MS {
+            cue.end_ms = (cue.start_ms + MIN_CUE_MS).min(limit).max(cue.end_ms);
+        }
+    }
+
+    cues
+}
+
+fn timestamp(ms: u64, separator: char) -> String {
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        separator,
+        ms % 1000
+    )
+}
+
+/// SubRip subtitles
+pub fn to_srt(segments: &[TranscriptSegment]) -> String {
+    build_cues(segments)
+        .iter()
+        .enumerate()
+        .map(|(i, cue)| {
+            format!(
+                "{}\n{} --> {}\n{}\n",
+                i + 1,
+                timestamp(cue.start_ms, ','),
+                timestamp(cue.end_ms, ','),
+                cue.lines.join("\n")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// WebVTT subtitles
+pub fn to_vtt(segments: &[TranscriptSegment]) -> String {
+    let cues: Vec<String> = build_cues(segments)
+        .iter()
+        .map(|cue| {
+            format!(
+                "{} --> {}\n{}\n",
+                timestamp(cue.start_ms, '.'),
+                timestamp(cue.end_ms, '.'),
+                cue.lines.join("\n")
+            )
+        })
+        .collect();
+    format!("WEBVTT\n\n{}", cues.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(start_ms: u64, end_ms: u64, text: &str) -> TranscriptSegment {
+        TranscriptSegment {
+            start_ms,
+            end_ms,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_srt_and_vtt_formatting() {
+        let segments = [
+            segment(0, 2500, "Hello there."),
+            segment(3_723_004, 3_725_000, "Later on."),
+        ];
+
+        assert_eq!(
+            to_srt(&segments),
+            "1\n00:00:00,000 --> 00:00:02,500\nHello there.\n\n\
+             2\n01:02:03,004 --> 01:02:05,000\nLater on.\n"
+        );
+        assert_eq!(
+            to_vtt(&segments),
+            "WEBVTT\n\n00:00:00.000 --> 00:00:02.500\nHello there.\n\n\
+             01:02:03.004 --> 01:02:05.000\nLater on.\n"
+        );
+    }
+
+    #[test]
+    fn test_long_segments_split_into_readable_cues() {
+        let text = "This is a rather long segment that keeps going well past what \
+                    fits comfortably on two subtitle lines, so it has to be split up";
+        let cues = build_cues(&[segment(1000, 11_000, text)]);
+
+        assert!(cues.len() >= 2);
+        assert_eq!(cues.first().unwrap().start_ms, 1000);
+        assert_eq!(cues.last().unwrap().end_ms, 11_000);
+        for pair in cues.windows(2) {
+            assert_eq!(pair[0].end_ms, pair[1].start_ms);
+        }
+        for cue in &cues {
+            assert!(cue.lines.len() <= 2);
+            assert!(cue.lines.iter().all(|l| l.len() <= MAX_LINE_CHARS));
+            assert!(cue.end_ms - cue.start_ms <= MAX_CUE_MS);
+        }
+
+        let words: Vec<String> = cues.iter().flat_map(|c| c.lines.clone()).collect();
+        assert_eq!(words.join(" "), text);
+    }
+
+    #[test]
+    fn test_short_cues_extended_without_overlap() {
+        let cues = build_cues(&[segment(0, 200, "Hi."), segment(600, 900, "Yes.")]);
+        assert_eq!((cues[0].start_ms, cues[0].end_ms), (0, 600));
+        assert_eq!((cues[1].start_ms, cues[1].end_ms), (600, 1600));
+    }
+}
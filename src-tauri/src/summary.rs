@@ -0,0 +1,198 @@
+//! Summaries of long hands-free recordings.
+//!
+//! With `summary.enabled`, a locked recording whose transcript (after the
+//! pipeline) has at least `summary.min_words` words is sent to the chat model
+//! for a bullet summary and structured action items. If that fails the
+//! transcript is used as usual. `summary.paste` picks what gets delivered;
+//! history keeps both, and the action items also go to
+//! `summary.action_item_sinks`.
+
+use crate::action_items::ActionItem;
+use crate::clients::openai::TranscriptionError;
+use crate::clients::{dry_run, llm};
+use crate::config::{AppConfig, SummaryConfig, SummaryPaste};
+use serde::Deserialize;
+
+const SYSTEM_PROMPT: &str = "You summarize transcripts of meetings and voice notes. \
+Reply with a JSON object only, written in the language of the transcript:\n\
+{\"summary\": [\"3 to 7 bullets with the key points and decisions\"], \
+\"action_items\": [{\"task\": \"what needs to be done\", \
+\"owner\": \"who should do it, or null\", \
+\"due\": \"when, as said in the transcript, or null\"}]}\n\
+Use an empty action_items list when there are none.";
+
+/// The model's reply, as requested in [`SYSTEM_PROMPT`]
+#[derive(Debug, Deserialize)]
+struct SummaryReply {
+    summary: Vec<String>,
+    #[serde(default)]
+    action_items: Vec<ActionItem>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    /// Bullets and action items rendered as plain text, for pasting
+    pub text: String,
+    pub action_items: Vec<ActionItem>,
+}
+
+impl Summary {
+    fn from_reply(reply: SummaryReply) -> Option<Self> {
+        let bullets: Vec<String> = reply
+            .summary
+            .iter()
+            .map(|bullet| bullet.trim().to_string())
+            .filter(|bullet| !bullet.is_empty())
+            .collect();
+        let action_items: Vec<ActionItem> = reply
+            .action_items
+            .into_iter()
+            .filter(|item| !item.task.trim().is_empty())
+            .collect();
+        if bullets.is_empty() {
+            return None;
+        }
+
+        let mut text = String::from("Summary:\n");
+        for bullet in &bullets {
+            text.push_str(&format!("- {}\n", bullet));
+        }
+        text.push_str("\nAction items:\n");
+        if action_items.is_empty() {
+            text.push_str("- None\n");
+        }
+        for item in &action_items {
+            text.push_str(&format!("- {}\n", item.describe()));
+        }
+
+        Some(Self {
+            text: text.trim_end().to_string(),
+            action_items,
+        })
+    }
+}
+
+fn should_summarize(config: &SummaryConfig, locked: bool, transcript: &str) -> bool {
+    config.enabled && locked && transcript.split_whitespace().count() >= config.min_words as usize
+}
+
+/// Summary of `transcript`, or None when summaries don't apply or failed
+pub fn summarize(config: &AppConfig, transcript: &str, locked: bool) -> Option<Summary> {
+    if !should_summarize(&config.summary, locked, transcript) {
+        return None;
+    }
+    if dry_run::is_enabled(config) {
+        println!("[Summary] Dry run, skipping summary");
+        return None;
+    }
+
+    println!("[Summary] Summarizing {} chars", transcript.len());
+    let reply = llm::complete_json(config, SYSTEM_PROMPT, transcript).and_then(|value| {
+        serde_json::from_value::<SummaryReply>(value)
+            .map_err(|e| TranscriptionError::ApiError(format!("Unexpected summary format: {}", e)))
+    });
+
+    match reply.map(Summary::from_reply) {
+        Ok(Some(summary)) => {
+            println!(
+                "[Summary] Summarized with {} action items",
+                summary.action_items.len()
+            );
+            Some(summary)
+        }
+        Ok(None) => {
+            eprintln!("[Summary] Model returned an empty summary, using transcript");
+            None
+        }
+        Err(e) => {
+            eprintln!("[Summary] Failed to summarize, using transcript: {}", e);
+            None
+        }
+    }
+}
+
+/// Text to deliver for a transcript and its optional summary
+pub fn delivered_text(transcript: &str, summary: Option<&str>, paste: SummaryPaste) -> String {
+    match (summary, paste) {
+        (None, _) | (Some(_), SummaryPaste::Transcript) => transcript.to_string(),
+        (Some(summary), SummaryPaste::Summary) => summary.to_string(),
+        (Some(summary), SummaryPaste::Both) => {
+            format!("{}\n\nTranscript:\n{}", summary, transcript)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_long_locked_recordings_are_summarized() {
+        let config = SummaryConfig {
+            enabled: true,
+            min_words: 3,
+            paste: SummaryPaste::Summary,
+            action_item_sinks: Vec::new(),
+        };
+        assert!(should_summarize(&config, true, "one two three"));
+        assert!(!should_summarize(&config, true, "one two"));
+        assert!(!should_summarize(&config, false, "one two three"));
+
+        let disabled = SummaryConfig {
+            enabled: false,
+            ..config
+        };
+        assert!(!should_summarize(&disabled, true, "one two three"));
+    }
+
+    #[test]
+    fn test_summary_text_from_reply() {
+        let reply: SummaryReply = serde_json::from_value(serde_json::json!({
+            "summary": ["Launch moves to May", " "],
+            "action_items": [
+                { "task": "Update the roadmap", "owner": "Anna", "due": "Friday" },
+                { "task": "" }
+            ]
+        }))
+        .unwrap();
+        let summary = Summary::from_reply(reply).unwrap();
+        assert_eq!(
+            summary.text,
+            "Summary:\n- Launch moves to May\n\nAction items:\n- Update the roadmap (Anna, Friday)"
+        );
+        assert_eq!(summary.action_items.len(), 1);
+
+        let no_items: SummaryReply =
+            serde_json::from_value(serde_json::json!({ "summary": ["Quick sync"] })).unwrap();
+        assert!(Summary::from_reply(no_items)
+            .unwrap()
+            .text
+            .ends_with("Action items:\n- None"));
+
+        let empty: SummaryReply =
+            serde_json::from_value(serde_json::json!({ "summary": [] })).unwrap();
+        assert_eq!(Summary::from_reply(empty), None);
+    }
+
+    #[test]
+    fn test_delivered_text_follows_paste_setting() {
+        let summary = Some("Summary:\n- Ship it");
+        assert_eq!(
+            delivered_text("Full text", summary, SummaryPaste::Transcript),
+            "Full text"
+        );
+        assert_eq!(
+            delivered_text("Full text", summary, SummaryPaste::Summary),
+            "Summary:\n- Ship it"
+        );
+        assert_eq!(
+            delivered_text("Full text", summary, SummaryPaste::Both),
+            "Summary:\n- Ship it\n\nTranscript:\nFull text"
+        );
+        // Without a summary the transcript is always used
+        assert_eq!(
+            delivered_text("Full text", None, SummaryPaste::Summary),
+            "Full text"
+        );
+    }
+}
@@ -0,0 +1,257 @@
+//! Supervisor for long-running background workers.
+//!
+//! The Controller, key listener and updater loops run under the supervisor,
+//! which catches panics (and unexpected exits), restarts the worker with
+//! exponential backoff and emits a `component-health` event whenever a
+//! component's status changes. After too many consecutive failures a component
+//! is marked failed so the UI can report it as degraded.
+
+use crate::events::{self, ComponentHealth, ComponentStatus};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// A worker that ran at least this long is considered healthy again
+const STABLE_AFTER: Duration = Duration::from_secs(60);
+
+/// How a component is restarted when it dies
+#[derive(Debug, Clone, Copy)]
+pub struct RestartPolicy {
+    /// Consecutive failures before giving up
+    pub max_restarts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Whether a normal return counts as a failure (for loops that never end)
+    pub restart_on_exit: bool,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            max_restarts: 5,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            restart_on_exit: false,
+        }
+    }
+}
+
+/// Tracks restart attempts for a single component
+struct Backoff {
+    policy: RestartPolicy,
+    failures: u32,
+}
+
+impl Backoff {
+    fn new(policy: RestartPolicy) -> Self {
+        Self {
+            policy,
+            failures: 0,
+        }
+    }
+
+    /// Register a failure after the worker ran for `uptime`.
+    /// Returns the delay before the next attempt, or None to give up.
+    fn next_delay(&mut self, uptime: Duration) -> Option<Duration> {
+        if uptime >= STABLE_AFTER {
+            self.failures = 0;
+        }
+
+        self.failures += 1;
+        if self.failures > self.policy.max_restarts {
+            return None;
+        }
+
+        let factor = 2u32.saturating_pow(self.failures - 1);
+        Some(
+            self.policy
+                .initial_backoff
+                .saturating_mul(factor)
+                .min(self.policy.max_backoff),
+        )
+    }
+}
+
+pub struct Supervisor {
+    app_handle: tauri::AppHandle,
+    components: Mutex<BTreeMap<&'static str, ComponentHealth>>,
+}
+
+pub type SupervisorState = Arc<Supervisor>;
+
+impl Supervisor {
+    pub fn new(app_handle: tauri::AppHandle) -> SupervisorState {
+        Arc::new(Self {
+            app_handle,
+            components: Mutex::new(BTreeMap::new()),
+        })
+    }
+
+    /// Run a blocking worker on its own thread, restarting it per `policy`
+    pub fn spawn_thread<F>(
+        self: &Arc<Self>,
+        name: &'static str,
+        policy: RestartPolicy,
+        mut worker: F,
+    ) where
+        F: FnMut() + Send + 'static,
+    {
+        let supervisor = self.clone();
+        std::thread::spawn(move || {
+            let mut backoff = Backoff::new(policy);
+
+            loop {
+                supervisor.update(name, ComponentStatus::Running, None);
+                let started = Instant::now();
+
+                let error = match panic::catch_unwind(AssertUnwindSafe(&mut worker)) {
+                    Ok(()) if !policy.restart_on_exit => {
+                        supervisor.update(name, ComponentStatus::Stopped, None);
+                        return;
+                    }
+                    Ok(()) => "exited unexpectedly".to_string(),
+                    Err(payload) => panic_message(payload.as_ref()),
+                };
+
+                match backoff.next_delay(started.elapsed()) {
+                    Some(delay) => {
+                        eprintln!("[Supervisor] {} {}, restarting in {:?}", name, error, delay);
+                        supervisor.update(name, ComponentStatus::Restarting, Some(error));
+                        std::thread::sleep(delay);
+                    }
+                    None => {
+                        eprintln!("[Supervisor] {} {}, giving up", name, error);
+                        supervisor.update(name, ComponentStatus::Failed, Some(error));
+                        return;
+                    }
+                }
+            }
+        });
+    }
+
+    /// Run an async worker on the Tauri runtime, restarting it per `policy`
+    pub fn spawn_async<F, Fut>(
+        self: &Arc<Self>,
+        name: &'static str,
+        policy: RestartPolicy,
+        mut factory: F,
+    ) where
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let supervisor = self.clone();
+        tauri::async_runtime::spawn(async move {
+            let mut backoff = Backoff::new(policy);
+
+            loop {
+                supervisor.update(name, ComponentStatus::Running, None);
+                let started = Instant::now();
+
+                let error = match tauri::async_runtime::spawn(factory()).await {
+                    Ok(()) if !policy.restart_on_exit => {
+                        supervisor.update(name, ComponentStatus::Stopped, None);
+                        return;
+                    }
+                    Ok(()) => "exited unexpectedly".to_string(),
+                    Err(e) => e.to_string(),
+                };
+
+                match backoff.next_delay(started.elapsed()) {
+                    Some(delay) => {
+                        eprintln!("[Supervisor] {} {}, restarting in {:?}", name, error, delay);
+                        supervisor.update(name, ComponentStatus::Restarting, Some(error));
+                        tokio::time::sleep(delay).await;
+                    }
+                    None => {
+                        eprintln!("[Supervisor] {} {}, giving up", name, error);
+                        supervisor.update(name, ComponentStatus::Failed, Some(error));
+                        return;
+                    }
+                }
+            }
+        });
+    }
+
+    /// Current health of every supervised component
+    pub fn health(&self) -> Vec<ComponentHealth> {
+        self.components
+            .lock()
+            .map(|components| components.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    fn update(&self, name: &'static str, status: ComponentStatus, error: Option<String>) {
+        let health = {
+            let Ok(mut components) = self.components.lock() else {
+                return;
+            };
+
+            let health = components.entry(name).or_insert_with(|| ComponentHealth {
+                name: name.to_string(),
+                status,
+                restarts: 0,
+                last_error: None,
+            });
+
+            if status == ComponentStatus::Restarting {
+                health.restarts += 1;
+            }
+            if error.is_some() {
+                health.last_error = error;
+            }
+            health.status = status;
+            health.clone()
+        };
+
+        events::emit_or_log(&self.app_handle, health);
+    }
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    let message = payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string());
+    format!("panicked: {}", message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> RestartPolicy {
+        RestartPolicy {
+            max_restarts: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(250),
+            restart_on_exit: false,
+        }
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_max_then_gives_up() {
+        let mut backoff = Backoff::new(policy());
+        let quick = Duration::from_millis(1);
+
+        assert_eq!(backoff.next_delay(quick), Some(Duration::from_millis(100)));
+        assert_eq!(backoff.next_delay(quick), Some(Duration::from_millis(200)));
+        assert_eq!(backoff.next_delay(quick), Some(Duration::from_millis(250)));
+        assert_eq!(backoff.next_delay(quick), None);
+    }
+
+    #[test]
+    fn test_backoff_resets_after_stable_run() {
+        let mut backoff = Backoff::new(policy());
+        let quick = Duration::from_millis(1);
+
+        backoff.next_delay(quick);
+        backoff.next_delay(quick);
+        assert_eq!(
+            backoff.next_delay(STABLE_AFTER),
+            Some(Duration::from_millis(100))
+        );
+    }
+}
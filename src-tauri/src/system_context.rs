@@ -0,0 +1,121 @@
+//! What's around a dictation, for templates.
+//!
+//! Webhook bodies and LLM prompts can use `{window_title}`, `{selection}` and
+//! `{clipboard}` besides the usual template variables. They're read only when
+//! the template asks for them, right before it's rendered. The window title
+//! and selection come from the Accessibility API of the app in front, so they
+//! need the Accessibility permission and stay empty while secure input is on
+//! (a password field has focus).
+
+use crate::clipboard_paste;
+use crate::frontmost;
+use crate::templates::{self, TemplateVars};
+
+/// Fill in the context variables `template` uses
+pub fn capture_for(template: &str, vars: &mut TemplateVars) {
+    if templates::uses(template, "app") && vars.app.is_none() {
+        vars.app = frontmost::app_name();
+    }
+    if templates::uses(template, "clipboard") {
+        vars.clipboard = arboard::Clipboard::new()
+            .and_then(|mut clipboard| clipboard.get_text())
+            .ok();
+    }
+
+    let window_title = templates::uses(template, "window_title");
+    let selection = templates::uses(template, "selection");
+    if !(window_title || selection) {
+        return;
+    }
+    if clipboard_paste::is_secure_input_enabled() {
+        println!("[System Context] Secure input is on, leaving window and selection empty");
+        return;
+    }
+    if window_title {
+        vars.window_title = focused_window_title();
+    }
+    if selection {
+        vars.selection = selected_text();
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod accessibility {
+    use objc2_core_foundation::{CFRetained, CFString, CFType};
+    use std::ffi::c_void;
+    use std::ptr::NonNull;
+
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn AXIsProcessTrusted() -> bool;
+        fn AXUIElementCreateSystemWide() -> *mut c_void;
+        fn AXUIElementCopyAttributeValue(
+            element: *const c_void,
+            attribute: *const c_void,
+            value: *mut *mut c_void,
+        ) -> i32;
+    }
+
+    /// kAXErrorSuccess
+    const AX_SUCCESS: i32 = 0;
+
+    fn retained(ptr: *mut c_void) -> Option<CFRetained<CFType>> {
+        // Create and Copy functions return +1 references
+        NonNull::new(ptr.cast::<CFType>()).map(|ptr| unsafe { CFRetained::from_raw(ptr) })
+    }
+
+    fn attribute(element: &CFType, name: &str) -> Option<CFRetained<CFType>> {
+        let name = CFString::from_str(name);
+        let mut value = std::ptr::null_mut();
+        let result = unsafe {
+            AXUIElementCopyAttributeValue(
+                (element as *const CFType).cast(),
+                (&*name as *const CFString).cast(),
+                &mut value,
+            )
+        };
+        if result != AX_SUCCESS {
+            return None;
+        }
+        retained(value)
+    }
+
+    fn string(value: &CFType) -> Option<String> {
+        let text = value.downcast_ref::<CFString>()?.to_string();
+        (!text.trim().is_empty()).then_some(text)
+    }
+
+    /// `path` of attributes followed from the system-wide element, the last
+    /// one read as a string
+    pub fn read(path: &[&str]) -> Option<String> {
+        if !unsafe { AXIsProcessTrusted() } {
+            return None;
+        }
+        let mut element = retained(unsafe { AXUIElementCreateSystemWide() })?;
+        for name in path {
+            element = attribute(&element, name)?;
+        }
+        string(&element)
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn focused_window_title() -> Option<String> {
+    accessibility::read(&["AXFocusedApplication", "AXFocusedWindow", "AXTitle"])
+}
+
+/// Text selected in the focused app, if it exposes it to Accessibility
+#[cfg(target_os = "macos")]
+pub fn selected_text() -> Option<String> {
+    accessibility::read(&["AXFocusedUIElement", "AXSelectedText"])
+}
+
+#[cfg(not(target_os = "macos"))]
+fn focused_window_title() -> Option<String> {
+    None
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn selected_text() -> Option<String> {
+    None
+}
@@ -1,11 +1,42 @@
-use crate::config::{self, AppConfig, AzureOpenAIConfig, OpenAIConfig, Provider};
-use crate::keychain::{self, ProviderAccount};
-use crate::recording::{LastRecordingState, RecordingCommand};
+use crate::actions::{self, AppAction, PaletteAction};
+use crate::batch::{self, BatchOptions};
+use crate::clients::provider_catalog::{self, ProviderCatalog};
+use crate::clients::{azure_endpoint, reauth};
+use crate::clipboard_paste;
+use crate::config::managed::ManagedConfig;
+use crate::config::{self, AppConfig, AzureOpenAIConfig, OpenAIConfig, Provider, TriggerKey};
+use crate::conflicts::{self, DictationConflict, FnKeyUsage};
+use crate::display_options;
+use crate::energy::{self, EnergyStats};
+use crate::events::{
+    ComponentHealth, DisplayOptions, InputCaptureStatus, OnboardingStep, PermissionStatus,
+    StatsUpdated,
+};
+use crate::history::{self, HistoryEntry};
+use crate::keyboard_listener::{self, TriggerKeyState};
+use crate::keychain::{self, KeychainFailure, ProviderAccount};
+use crate::onboarding::{self, OnboardingResume, OnboardingResumeState, RestartStep};
+use crate::permissions;
+use crate::pipeline::{self, PipelineStepInfo};
+use crate::recording::{
+    JournalEntry, LastRecordingState, RecordingCommand, StateJournalState, TranscriptionQueueState,
+    TriggerSource,
+};
+use crate::rule_packs::{self, PackImport, Resolution};
 use crate::setup::{AudioLevelChannel, RecordingCommandSender};
+use crate::stats::{self, UsageStats};
+use crate::supervisor::SupervisorState;
+use crate::ui::tray;
+use crate::ui::window::{PopupMode, PopupSize};
+use crate::validation::{self, LiveValidation, ValidationIssue};
+use std::time::Duration;
 use tauri::ipc::Channel;
 use tauri::State;
 use tauri_plugin_store::StoreExt;
 
+/// How long `learn_trigger_key` waits for a key press
+const LEARN_TRIGGER_TIMEOUT: Duration = Duration::from_secs(10);
+
 #[tauri::command]
 pub fn check_accessibility_permission() -> bool {
     #[cfg(target_os = "macos")]
@@ -32,17 +63,48 @@ pub fn restart_app(app: tauri::AppHandle) {
     app.restart();
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn start_recording(sender: State<RecordingCommandSender>) -> Result<(), String> {
+    sender
+        .sender
+        .blocking_send(RecordingCommand::Start {
+            source: TriggerSource::Popup,
+        })
+        .map_err(|e| format!("Failed to send Start command: {}", e))?;
+
+    Ok(())
+}
+
+/// Stop the current recording and transcribe it. Unlike `toggle_recording`,
+/// this never starts a new one, whatever state the controller is in.
 #[tauri::command]
 #[specta::specta]
 pub fn stop_recording(sender: State<RecordingCommandSender>) -> Result<(), String> {
     sender
         .sender
-        .blocking_send(RecordingCommand::FnDown)
-        .map_err(|e| format!("Failed to send FnDown command: {}", e))?;
+        .blocking_send(RecordingCommand::Stop {
+            source: TriggerSource::Popup,
+        })
+        .map_err(|e| format!("Failed to send Stop command: {}", e))?;
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn toggle_recording(sender: State<RecordingCommandSender>) -> Result<(), String> {
+    sender
+        .sender
+        .blocking_send(RecordingCommand::Toggle {
+            source: TriggerSource::Popup,
+        })
+        .map_err(|e| format!("Failed to send Toggle command: {}", e))?;
 
     Ok(())
 }
 
+/// Discard the current recording without transcribing it
 #[tauri::command]
 #[specta::specta]
 pub fn cancel_recording(sender: State<RecordingCommandSender>) -> Result<(), String> {
@@ -54,6 +116,21 @@ pub fn cancel_recording(sender: State<RecordingCommandSender>) -> Result<(), Str
     Ok(())
 }
 
+/// Capture the selection in the app in front and record how to rewrite it;
+/// the rewritten text replaces the selection
+#[tauri::command]
+#[specta::specta]
+pub fn start_rewrite_selection(sender: State<RecordingCommandSender>) -> Result<(), String> {
+    println!("[Command] start_rewrite_selection called");
+
+    sender
+        .sender
+        .blocking_send(RecordingCommand::StartRewrite {
+            source: TriggerSource::Popup,
+        })
+        .map_err(|e| format!("Failed to send StartRewrite command: {}", e))
+}
+
 // ===== APP CONFIGURATION COMMANDS =====
 
 #[tauri::command]
@@ -69,6 +146,13 @@ pub fn load_app_config(app: tauri::AppHandle) -> Result<AppConfig, String> {
     Ok(config::load_app_config(&store))
 }
 
+/// Settings enforced through MDM, for preferences to show them as locked
+#[tauri::command]
+#[specta::specta]
+pub fn get_managed_config() -> ManagedConfig {
+    config::managed::current()
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn save_app_config(
@@ -86,16 +170,88 @@ pub fn save_app_config(
         }
     });
 
+    let store = app.store("config.json").map_err(|e| {
+        eprintln!("[Command] Failed to open store: {}", e);
+        format!("Failed to open store: {}", e)
+    })?;
+
+    // Keep the rest of the settings intact, only switch the provider
     let config = AppConfig {
         active_provider: provider,
+        ..config::load_app_config(&store)
     };
 
+    config::save_app_config(&store, &config)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn update_app_config(
+    app: tauri::AppHandle,
+    trigger_key: State<TriggerKeyState>,
+    config: AppConfig,
+) -> Result<(), String> {
+    println!("[Command] update_app_config called");
+
     let store = app.store("config.json").map_err(|e| {
         eprintln!("[Command] Failed to open store: {}", e);
         format!("Failed to open store: {}", e)
     })?;
 
-    config::save_app_config(&store, &config)
+    config::save_app_config(&store, &config)?;
+
+    // The key listener reads the trigger on every event
+    trigger_key.set(config.trigger_key);
+    energy::set_profile(config.energy_profile);
+    clipboard_paste::set_hide_from_clipboard_managers(config.hide_from_clipboard_managers);
+
+    // The icon theme may have changed
+    if let Err(e) = tray::refresh(&app) {
+        eprintln!("[Command] Failed to refresh tray icon: {}", e);
+    }
+
+    Ok(())
+}
+
+/// Capture the next key press and make it the push-to-talk trigger.
+/// Returns None if no key was pressed within 10 seconds.
+#[tauri::command]
+#[specta::specta]
+pub async fn learn_trigger_key(
+    app: tauri::AppHandle,
+    trigger_key: State<'_, TriggerKeyState>,
+) -> Result<Option<TriggerKey>, String> {
+    println!("[Command] learn_trigger_key called");
+
+    let Some(learned) = trigger_key.learn_next_key(LEARN_TRIGGER_TIMEOUT).await else {
+        println!("[Command] No key pressed while learning trigger");
+        return Ok(None);
+    };
+
+    let store = app.store("config.json").map_err(|e| {
+        eprintln!("[Command] Failed to open store: {}", e);
+        format!("Failed to open store: {}", e)
+    })?;
+
+    let config = AppConfig {
+        trigger_key: learned,
+        ..config::load_app_config(&store)
+    };
+    config::save_app_config(&store, &config)?;
+    trigger_key.set(learned);
+
+    Ok(Some(learned))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn describe_pipeline(app: tauri::AppHandle) -> Result<Vec<PipelineStepInfo>, String> {
+    let store = app.store("config.json").map_err(|e| {
+        eprintln!("[Command] Failed to open store: {}", e);
+        format!("Failed to open store: {}", e)
+    })?;
+
+    Ok(pipeline::describe(&config::load_app_config(&store)))
 }
 
 // ===== OPENAI PROVIDER COMMANDS =====
@@ -105,7 +261,7 @@ pub fn save_app_config(
 pub fn load_openai_config() -> Result<Option<OpenAIConfig>, String> {
     println!("[Command] load_openai_config called");
     keychain::load_provider_config::<OpenAIConfig>(ProviderAccount::OpenAI).map_err(|e| {
-        let error = format!("Failed to load OpenAI config: {}", e);
+        let error = keychain::describe_error(&e, "Failed to load OpenAI config");
         eprintln!("[Command] {}", error);
         error
     })
@@ -113,7 +269,7 @@ pub fn load_openai_config() -> Result<Option<OpenAIConfig>, String> {
 
 #[tauri::command]
 #[specta::specta]
-pub fn save_openai_config(api_key: String) -> Result<(), String> {
+pub fn save_openai_config(app: tauri::AppHandle, api_key: String) -> Result<(), String> {
     println!(
         "[Command] save_openai_config called with key length: {}",
         api_key.len()
@@ -122,10 +278,12 @@ pub fn save_openai_config(api_key: String) -> Result<(), String> {
     let config = OpenAIConfig { api_key };
 
     keychain::save_provider_config(ProviderAccount::OpenAI, &config).map_err(|e| {
-        let error = format!("Failed to save OpenAI config: {}", e);
+        let error = keychain::describe_error(&e, "Failed to save OpenAI config");
         eprintln!("[Command] {}", error);
         error
-    })
+    })?;
+    reauth::reset(&app, Provider::OpenAI);
+    Ok(())
 }
 
 #[tauri::command]
@@ -146,7 +304,7 @@ pub fn test_openai_config(api_key: String) -> Result<bool, String> {
 
     use crate::clients::openai::OpenAIClient;
 
-    OpenAIClient::test_api_key(Provider::OpenAI, &api_key, None).map_err(|e| {
+    OpenAIClient::test_api_key(Provider::OpenAI, &api_key, None, false).map_err(|e| {
         let error = format!("Failed to test OpenAI config: {}", e);
         eprintln!("[Command] {}", error);
         error
@@ -160,7 +318,7 @@ pub fn test_openai_config(api_key: String) -> Result<bool, String> {
 pub fn load_azure_openai_config() -> Result<Option<AzureOpenAIConfig>, String> {
     println!("[Command] load_azure_openai_config called");
     keychain::load_provider_config::<AzureOpenAIConfig>(ProviderAccount::AzureOpenAI).map_err(|e| {
-        let error = format!("Failed to load Azure OpenAI config: {}", e);
+        let error = keychain::describe_error(&e, "Failed to load Azure OpenAI config");
         eprintln!("[Command] {}", error);
         error
     })
@@ -168,20 +326,31 @@ pub fn load_azure_openai_config() -> Result<Option<AzureOpenAIConfig>, String> {
 
 #[tauri::command]
 #[specta::specta]
-pub fn save_azure_openai_config(api_key: String, endpoint: String) -> Result<(), String> {
+pub fn save_azure_openai_config(
+    app: tauri::AppHandle,
+    api_key: String,
+    endpoint: String,
+) -> Result<(), String> {
     println!(
         "[Command] save_azure_openai_config called with key length: {}, endpoint: {}",
         api_key.len(),
         endpoint
     );
 
+    // Malformed endpoints fail here rather than mid-dictation
+    let endpoint = azure_endpoint::normalize(&endpoint).map_err(|e| {
+        eprintln!("[Command] Invalid Azure endpoint: {}", e);
+        e.user_message()
+    })?;
     let config = AzureOpenAIConfig { api_key, endpoint };
 
     keychain::save_provider_config(ProviderAccount::AzureOpenAI, &config).map_err(|e| {
-        let error = format!("Failed to save Azure OpenAI config: {}", e);
+        let error = keychain::describe_error(&e, "Failed to save Azure OpenAI config");
         eprintln!("[Command] {}", error);
         error
-    })
+    })?;
+    reauth::reset(&app, Provider::AzureOpenAI);
+    Ok(())
 }
 
 #[tauri::command]
@@ -195,20 +364,178 @@ pub fn delete_azure_openai_config() -> Result<(), String> {
     })
 }
 
+/// Check an Azure key and endpoint. The default check sends no audio; `deep`
+/// runs a real (billed) transcription of a second of silence.
 #[tauri::command]
 #[specta::specta]
-pub fn test_azure_openai_config(api_key: String, endpoint: String) -> Result<bool, String> {
-    println!("[Command] test_azure_openai_config called");
+pub fn test_azure_openai_config(
+    api_key: String,
+    endpoint: String,
+    deep: bool,
+) -> Result<bool, String> {
+    println!("[Command] test_azure_openai_config called (deep: {})", deep);
+
+    let endpoint = azure_endpoint::normalize(&endpoint).map_err(|e| {
+        eprintln!("[Command] Invalid Azure endpoint: {}", e);
+        e.user_message()
+    })?;
 
     use crate::clients::openai::OpenAIClient;
 
-    OpenAIClient::test_api_key(Provider::AzureOpenAI, &api_key, Some(&endpoint)).map_err(|e| {
-        let error = format!("Failed to test Azure OpenAI config: {}", e);
+    OpenAIClient::test_api_key(Provider::AzureOpenAI, &api_key, Some(&endpoint), deep).map_err(
+        |e| {
+            let error = format!("Failed to test Azure OpenAI config: {}", e);
+            eprintln!("[Command] {}", error);
+            error
+        },
+    )
+}
+
+/// Check a provider config for mistakes before it's saved. With `probe`, also
+/// confirms with the provider that the key and model work (not billed).
+#[tauri::command]
+#[specta::specta]
+pub fn validate_provider_config(
+    provider: Provider,
+    api_key: String,
+    endpoint: Option<String>,
+    probe: bool,
+) -> Vec<ValidationIssue> {
+    println!(
+        "[Command] validate_provider_config called for {:?} (probe: {})",
+        provider, probe
+    );
+    validation::validate(provider, &api_key, endpoint.as_deref(), probe)
+}
+
+/// Price and latency of each provider, for comparing them in the picker
+#[tauri::command]
+#[specta::specta]
+pub fn get_provider_catalog(app: tauri::AppHandle) -> ProviderCatalog {
+    provider_catalog::catalog(&app)
+}
+
+// ===== KEYCHAIN =====
+
+/// Bearer token the phone intake expects, for setting up the Shortcut. None
+/// until the intake has started once.
+#[tauri::command]
+#[specta::specta]
+pub fn get_intake_token() -> Result<Option<String>, String> {
+    println!("[Command] get_intake_token called");
+    keychain::load_intake_token()
+        .map_err(|e| keychain::describe_error(&e, "Failed to load the intake token"))
+}
+
+/// Check whether the keychain can be read. Returns None when it's accessible.
+#[tauri::command]
+#[specta::specta]
+pub fn get_keychain_status() -> Option<KeychainFailure> {
+    keychain::probe()
+}
+
+/// Use an API key for the current run only, without storing it in the
+/// keychain. Lets users keep dictating while the keychain is locked.
+#[tauri::command]
+#[specta::specta]
+pub fn set_session_api_key(
+    app: tauri::AppHandle,
+    provider: Provider,
+    api_key: String,
+    endpoint: Option<String>,
+) -> Result<(), String> {
+    println!(
+        "[Command] set_session_api_key called for {:?} with key length: {}",
+        provider,
+        api_key.len()
+    );
+
+    let result = match &provider {
+        Provider::OpenAI => {
+            keychain::set_session_config(ProviderAccount::OpenAI, &OpenAIConfig { api_key })
+        }
+        Provider::AzureOpenAI => {
+            let endpoint = endpoint.ok_or("Azure OpenAI requires an endpoint")?;
+            keychain::set_session_config(
+                ProviderAccount::AzureOpenAI,
+                &AzureOpenAIConfig { api_key, endpoint },
+            )
+        }
+    };
+
+    result.map_err(|e| {
+        let error = format!("Failed to set session API key: {}", e);
         eprintln!("[Command] {}", error);
         error
+    })?;
+    reauth::reset(&app, provider);
+    Ok(())
+}
+
+/// Providers whose key was accepted before but is now rejected
+#[tauri::command]
+#[specta::specta]
+pub fn get_reauth_providers(app: tauri::AppHandle) -> Vec<Provider> {
+    reauth::flagged_providers(&app)
+}
+
+// ===== BATCH =====
+
+/// Transcribe every audio file in `folder` in the background, writing a
+/// transcript next to each. Progress arrives as `batch-progress` events.
+/// Returns the number of files found.
+#[tauri::command]
+#[specta::specta]
+pub fn transcribe_folder(
+    app: tauri::AppHandle,
+    folder: String,
+    options: BatchOptions,
+) -> Result<u32, String> {
+    println!("[Command] transcribe_folder called for {}", folder);
+    batch::start(&app, std::path::Path::new(&folder), options).map_err(|e| {
+        eprintln!("[Command] Batch transcription failed to start: {}", e);
+        e
+    })
+}
+
+/// Transcribe the audio file copied in Finder and put the transcript on the
+/// clipboard. Returns the transcript.
+#[tauri::command]
+#[specta::specta]
+pub async fn transcribe_copied_file(app: tauri::AppHandle) -> Result<String, String> {
+    println!("[Command] transcribe_copied_file called");
+    tauri::async_runtime::spawn_blocking(move || crate::copied_file::transcribe(&app))
+        .await
+        .map_err(|e| format!("Transcription failed: {}", e))?
+}
+
+// ===== COMMAND PALETTE =====
+
+/// Every action that applies right now, titled for the command palette
+#[tauri::command]
+#[specta::specta]
+pub fn list_actions(app: tauri::AppHandle) -> Vec<PaletteAction> {
+    actions::list(&app)
+}
+
+/// Close the command palette and run `action`
+#[tauri::command]
+#[specta::specta]
+pub fn invoke_action(app: tauri::AppHandle, action: AppAction) -> Result<(), String> {
+    actions::invoke(&app, action).map_err(|e| {
+        eprintln!("[Command] Action failed: {}", e);
+        e
     })
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn open_command_palette(app: tauri::AppHandle) -> Result<(), String> {
+    println!("[Command] open_command_palette called");
+    crate::ui::window::open_command_palette(&app)
+        .map_err(|e| format!("Failed to open command palette: {}", e))
+}
+
 // ===== AUDIO LEVEL CHANNEL =====
 
 #[tauri::command]
@@ -237,6 +564,100 @@ pub fn retry_transcription(sender: State<RecordingCommandSender>) -> Result<(),
     Ok(())
 }
 
+/// Paste (or otherwise deliver) the transcript held for exceeding `max_delivery_chars`
+#[tauri::command]
+#[specta::specta]
+pub fn deliver_held_transcript(sender: State<RecordingCommandSender>) -> Result<(), String> {
+    println!("[Command] deliver_held_transcript called");
+
+    sender
+        .sender
+        .blocking_send(RecordingCommand::DeliverHeld)
+        .map_err(|e| format!("Failed to send DeliverHeld command: {}", e))
+}
+
+/// Drop the held transcript and close the popup
+#[tauri::command]
+#[specta::specta]
+pub fn discard_held_transcript(sender: State<RecordingCommandSender>) -> Result<(), String> {
+    println!("[Command] discard_held_transcript called");
+
+    sender
+        .sender
+        .blocking_send(RecordingCommand::DiscardHeld)
+        .map_err(|e| format!("Failed to send DiscardHeld command: {}", e))
+}
+
+// ===== ONBOARDING =====
+
+/// Practise an onboarding step with the real trigger key. Until
+/// `stop_onboarding_practice`, transcripts are only emitted as
+/// `onboarding-practice-transcript` and never pasted.
+#[tauri::command]
+#[specta::specta]
+pub fn start_onboarding_practice(
+    sender: State<RecordingCommandSender>,
+    step: OnboardingStep,
+) -> Result<(), String> {
+    println!("[Command] start_onboarding_practice called: {:?}", step);
+
+    sender
+        .sender
+        .blocking_send(RecordingCommand::SetPractice { step: Some(step) })
+        .map_err(|e| format!("Failed to send SetPractice command: {}", e))
+}
+
+/// Leave onboarding practice so dictations are delivered again
+#[tauri::command]
+#[specta::specta]
+pub fn stop_onboarding_practice(sender: State<RecordingCommandSender>) -> Result<(), String> {
+    println!("[Command] stop_onboarding_practice called");
+
+    sender
+        .sender
+        .blocking_send(RecordingCommand::SetPractice { step: None })
+        .map_err(|e| format!("Failed to send SetPractice command: {}", e))
+}
+
+/// Remember `step`, then restart so a newly granted permission takes effect.
+/// Onboarding reopens at `step` on the next launch.
+#[tauri::command]
+#[specta::specta]
+pub fn restart_for_onboarding(app: tauri::AppHandle, step: RestartStep) -> Result<(), String> {
+    println!("[Command] restart_for_onboarding called: {:?}", step);
+
+    onboarding::mark_pending_restart(&app, step)?;
+    app.restart()
+}
+
+/// Where onboarding resumes after `restart_for_onboarding`, with freshly
+/// checked permissions. None when this launch wasn't such a restart.
+#[tauri::command]
+#[specta::specta]
+pub fn get_onboarding_resume(state: State<OnboardingResumeState>) -> Option<OnboardingResume> {
+    state.0.lock().unwrap().clone()
+}
+
+/// Validate a provider config as the user types: call on every change and
+/// ignore `superseded` results. Format issues come back at once; the provider
+/// is probed once typing pauses.
+#[tauri::command]
+#[specta::specta]
+pub async fn validate_provider_config_live(
+    provider: Provider,
+    api_key: String,
+    endpoint: Option<String>,
+) -> LiveValidation {
+    validation::validate_live(provider, api_key, endpoint).await
+}
+
+/// Supersede the pending live validation
+#[tauri::command]
+#[specta::specta]
+pub fn cancel_live_validation() {
+    validation::cancel_live();
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn dismiss_error(
@@ -247,10 +668,9 @@ pub fn dismiss_error(
 
     // Delete audio file if exists
     if let Ok(mut last_recording) = last_recording_state.lock() {
-        if let Some(path) = last_recording.audio_file_path.take() {
+        if let Some(path) = last_recording.take_retry() {
             crate::recording::cleanup_recording_file(&path);
         }
-        last_recording.audio_file_path = None;
     }
 
     // Close popup
@@ -266,3 +686,235 @@ pub fn resize_popup_for_error(app: tauri::AppHandle) -> Result<(), String> {
     crate::ui::window::resize_recording_popup_for_error(&app)
         .map_err(|e| format!("Failed to resize popup: {}", e))
 }
+
+/// Size the recording popup for what it shows. `desired_size` overrides the
+/// mode's default, e.g. to fit a long message; it's clamped to the monitor.
+/// Returns the size the popup got.
+#[tauri::command]
+#[specta::specta]
+pub fn set_popup_layout(
+    app: tauri::AppHandle,
+    mode: PopupMode,
+    desired_size: Option<PopupSize>,
+) -> Result<PopupSize, String> {
+    println!("[Command] set_popup_layout called: {:?}", mode);
+
+    crate::ui::window::set_popup_layout(&app, mode, desired_size)
+        .map_err(|e| format!("Failed to resize popup: {}", e))
+}
+
+// ===== STATS =====
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_usage_stats(app: tauri::AppHandle) -> UsageStats {
+    stats::load_stats(&app)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_stats_summary(app: tauri::AppHandle) -> StatsUpdated {
+    stats::load_summary(&app)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn export_usage_stats(app: tauri::AppHandle, path: String) -> Result<(), String> {
+    println!("[Command] export_usage_stats called");
+
+    stats::export_stats(&app, &path)
+}
+
+// ===== RULE PACKS =====
+
+/// Write the filler words and pipeline steps to `path` as a pack named `name`
+#[tauri::command]
+#[specta::specta]
+pub fn export_rule_pack(app: tauri::AppHandle, path: String, name: String) -> Result<(), String> {
+    println!("[Command] export_rule_pack called");
+
+    let store = app
+        .store("config.json")
+        .map_err(|e| format!("Failed to open store: {}", e))?;
+    rule_packs::export_to_file(&config::load_app_config(&store), &name, &path)
+}
+
+/// What importing the pack at `path` would change, without changing anything
+#[tauri::command]
+#[specta::specta]
+pub fn preview_rule_pack(app: tauri::AppHandle, path: String) -> Result<PackImport, String> {
+    println!("[Command] preview_rule_pack called");
+
+    let pack = rule_packs::read_file(&path)?;
+    let store = app
+        .store("config.json")
+        .map_err(|e| format!("Failed to open store: {}", e))?;
+    let mut config = config::load_app_config(&store);
+    Ok(rule_packs::merge(&mut config, &pack, Resolution::KeepMine))
+}
+
+/// Merge the pack at `path` into the config, settling conflicting steps with
+/// `resolution`
+#[tauri::command]
+#[specta::specta]
+pub fn import_rule_pack(
+    app: tauri::AppHandle,
+    path: String,
+    resolution: Resolution,
+) -> Result<PackImport, String> {
+    println!("[Command] import_rule_pack called");
+
+    let pack = rule_packs::read_file(&path)?;
+    let store = app
+        .store("config.json")
+        .map_err(|e| format!("Failed to open store: {}", e))?;
+    let mut config = config::load_app_config(&store);
+    let import = rule_packs::merge(&mut config, &pack, resolution);
+    config::save_app_config(&store, &config)?;
+    println!("[Command] Imported rule pack: {:?}", import);
+    Ok(import)
+}
+
+// ===== HISTORY =====
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_history(app: tauri::AppHandle) -> Vec<HistoryEntry> {
+    history::load_history(&app)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn clear_history(app: tauri::AppHandle) -> Result<(), String> {
+    println!("[Command] clear_history called");
+
+    history::clear(&app)
+}
+
+/// Write every history entry to `folder` as a Markdown file, named with
+/// `export_templates`. Returns how many files were written.
+#[tauri::command]
+#[specta::specta]
+pub fn export_history(app: tauri::AppHandle, folder: String) -> Result<u32, String> {
+    println!("[Command] export_history called");
+
+    let store = app
+        .store("config.json")
+        .map_err(|e| format!("Failed to load config: {}", e))?;
+    let config = config::load_app_config(&store);
+    history::export(&app, &config, std::path::Path::new(&folder))
+}
+
+// ===== CALENDAR =====
+
+/// Ask for calendar access so meetings can be looked up, waiting for the
+/// user's answer. Returns whether access is granted.
+#[tauri::command]
+#[specta::specta]
+pub async fn request_calendar_access() -> Result<bool, String> {
+    println!("[Command] request_calendar_access called");
+    tauri::async_runtime::spawn_blocking(crate::calendar::request_access)
+        .await
+        .map_err(|e| format!("Calendar access request failed: {}", e))?
+}
+
+// ===== SOUNDS =====
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_output_devices() -> Vec<String> {
+    crate::sound_player::list_output_devices()
+}
+
+/// Installed speech voices for spoken cues
+#[tauri::command]
+#[specta::specta]
+pub fn get_voices() -> Vec<String> {
+    crate::speech::list_voices()
+}
+
+/// Speak the last transcription with the cue voice, replacing a read-back
+/// already speaking
+#[tauri::command]
+#[specta::specta]
+pub fn read_back_last_recording(app: tauri::AppHandle) -> Result<(), String> {
+    crate::speech::read_back_last_recording(&app)
+}
+
+// ===== DIAGNOSTICS =====
+
+#[tauri::command]
+#[specta::specta]
+pub fn detect_dictation_conflicts() -> Vec<DictationConflict> {
+    conflicts::detect_conflicts()
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_fn_key_usage() -> FnKeyUsage {
+    conflicts::fn_key_usage()
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn disable_fn_key_action() -> Result<(), String> {
+    println!("[Command] disable_fn_key_action called");
+
+    conflicts::disable_fn_key_action()
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_permission_status() -> PermissionStatus {
+    permissions::check_permissions()
+}
+
+/// System reduce motion and increase contrast settings
+#[tauri::command]
+#[specta::specta]
+pub fn get_display_options() -> DisplayOptions {
+    display_options::current()
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_state_journal(journal: State<StateJournalState>) -> Vec<JournalEntry> {
+    journal.entries()
+}
+
+/// Cancel the running transcription, aborting its upload. Returns how many
+/// were cancelled.
+#[tauri::command]
+#[specta::specta]
+pub fn cancel_all_transcriptions(queue: State<TranscriptionQueueState>) -> u32 {
+    queue.abort_all()
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_component_health(supervisor: State<SupervisorState>) -> Vec<ComponentHealth> {
+    supervisor.health()
+}
+
+/// What works differently because the key listener fell back from the event
+/// tap; empty normally
+#[tauri::command]
+#[specta::specta]
+pub fn get_input_limitations(trigger_key: State<TriggerKeyState>) -> Vec<String> {
+    keyboard_listener::input_limitations(&trigger_key)
+}
+
+/// Whether the event tap could be created at the last check
+#[tauri::command]
+#[specta::specta]
+pub fn get_input_capture_status() -> InputCaptureStatus {
+    permissions::input_capture_status()
+}
+
+/// Event tap, audio level and timer activity since launch, for debugging
+/// battery usage
+#[tauri::command]
+#[specta::specta]
+pub fn get_energy_stats() -> EnergyStats {
+    energy::stats()
+}
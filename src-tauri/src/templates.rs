@@ -0,0 +1,235 @@
+//! Title, filename, webhook body and prompt templates.
+//!
+//! Templates are plain text with `{variable}` placeholders:
+//!
+//! - `{date}`: local date, `2026-03-14`
+//! - `{time}`: local time, `09-30`
+//! - `{app}`: app that was in front while dictating
+//! - `{first_words}`: first few words of the transcript
+//! - `{text}`: the whole transcript
+//! - `{profile}`: dictation profile, empty until profiles exist
+//! - `{name}`: source audio file name without extension (batch mode)
+//! - `{window_title}`, `{selection}`, `{clipboard}`: title of the focused
+//!   window, text selected in it and text on the clipboard, filled in by
+//!   `system_context` for webhooks and prompts
+//!
+//! Unknown placeholders are kept as typed so typos are visible in the
+//! result; known ones without a value render empty. Values are inserted as
+//! they are, never read as templates themselves, and [`render_json`] escapes
+//! them, so a selection can't break out of a webhook body.
+
+use chrono::{DateTime, Local};
+use std::path::{Path, PathBuf};
+
+/// Words taken for `{first_words}`
+const FIRST_WORDS: usize = 6;
+
+/// Longest filename stem produced, in characters
+const MAX_FILENAME_CHARS: usize = 100;
+
+/// Values available to a template
+#[derive(Debug, Clone)]
+pub struct TemplateVars {
+    pub timestamp: DateTime<Local>,
+    pub app: Option<String>,
+    pub first_words: String,
+    pub transcript: String,
+    pub profile: Option<String>,
+    pub name: Option<String>,
+    pub window_title: Option<String>,
+    pub selection: Option<String>,
+    pub clipboard: Option<String>,
+}
+
+impl TemplateVars {
+    pub fn new(timestamp: DateTime<Local>, transcript: &str) -> Self {
+        Self {
+            timestamp,
+            app: None,
+            first_words: first_words(transcript),
+            transcript: transcript.to_string(),
+            profile: None,
+            name: None,
+            window_title: None,
+            selection: None,
+            clipboard: None,
+        }
+    }
+
+    fn get(&self, variable: &str) -> Option<String> {
+        let value = match variable {
+            "date" => self.timestamp.format("%Y-%m-%d").to_string(),
+            "time" => self.timestamp.format("%H-%M").to_string(),
+            "app" => self.app.clone().unwrap_or_default(),
+            "first_words" => self.first_words.clone(),
+            "text" => self.transcript.clone(),
+            "profile" => self.profile.clone().unwrap_or_default(),
+            "name" => self.name.clone().unwrap_or_default(),
+            "window_title" => self.window_title.clone().unwrap_or_default(),
+            "selection" => self.selection.clone().unwrap_or_default(),
+            "clipboard" => self.clipboard.clone().unwrap_or_default(),
+            _ => return None,
+        };
+        Some(value)
+    }
+}
+
+fn first_words(transcript: &str) -> String {
+    transcript
+        .split_whitespace()
+        .take(FIRST_WORDS)
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches(|c: char| c.is_ascii_punctuation())
+        .to_string()
+}
+
+/// Whether `template` has a `{variable}` placeholder
+pub fn uses(template: &str, variable: &str) -> bool {
+    template.contains(&format!("{{{}}}", variable))
+}
+
+/// Fill in `template`, passing each value through `escape`
+fn fill(template: &str, vars: &TemplateVars, escape: impl Fn(&str) -> String) -> String {
+    let mut rendered = String::new();
+    let mut rest = template;
+
+    while let Some(open) = rest.find('{') {
+        rendered.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        match after.find('}') {
+            Some(close) => {
+                let variable = &after[..close];
+                match vars.get(variable) {
+                    Some(value) => rendered.push_str(&escape(&value)),
+                    None => rendered.push_str(&rest[open..open + close + 2]),
+                }
+                rest = &after[close + 1..];
+            }
+            None => {
+                rendered.push_str(&rest[open..]);
+                rest = "";
+            }
+        }
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+/// Fill in `template`, collapsing the whitespace left by empty values
+pub fn render(template: &str, vars: &TemplateVars) -> String {
+    fill(template, vars, str::to_string)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Fill in a multi-line `template`, e.g. a prompt, keeping line breaks
+pub fn render_text(template: &str, vars: &TemplateVars) -> String {
+    fill(template, vars, str::to_string).trim().to_string()
+}
+
+/// Fill in a JSON `template` with the values escaped for use inside JSON
+/// strings, e.g. `{"text": "{text}", "app": "{app}"}`. Fails if the result
+/// isn't JSON.
+pub fn render_json(template: &str, vars: &TemplateVars) -> Result<serde_json::Value, String> {
+    let rendered = fill(template, vars, |value| {
+        let quoted = serde_json::Value::String(value.to_string()).to_string();
+        quoted[1..quoted.len() - 1].to_string()
+    });
+    serde_json::from_str(&rendered).map_err(|e| format!("Template isn't valid JSON: {}", e))
+}
+
+/// Render `template` as a filename stem that's safe on macOS and in shells
+pub fn render_filename(template: &str, vars: &TemplateVars) -> String {
+    let stem: String = render(template, vars)
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
+            c if c.is_control() => '-',
+            c => c,
+        })
+        .take(MAX_FILENAME_CHARS)
+        .collect();
+
+    // Leading dots would hide the file
+    let stem = stem.trim().trim_start_matches('.').trim();
+    if stem.is_empty() {
+        "Untitled".to_string()
+    } else {
+        stem.to_string()
+    }
+}
+
+/// `dir/stem.extension`, numbered (`stem 2.extension`) if that file exists
+pub fn unique_path(dir: &Path, stem: &str, extension: &str) -> PathBuf {
+    let mut path = dir.join(format!("{}.{}", stem, extension));
+    let mut n = 2;
+    while path.exists() {
+        path = dir.join(format!("{} {}.{}", stem, n, extension));
+        n += 1;
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn vars() -> TemplateVars {
+        let timestamp = Local.with_ymd_and_hms(2026, 3, 14, 9, 30, 0).unwrap();
+        TemplateVars {
+            app: Some("Notes".to_string()),
+            ..TemplateVars::new(timestamp, "Okay, so the plan for next week is simple.")
+        }
+    }
+
+    #[test]
+    fn test_render_fills_known_variables() {
+        assert_eq!(
+            render("{date} {time} {app}: {first_words}", &vars()),
+            "2026-03-14 09-30 Notes: Okay, so the plan for next"
+        );
+        // Empty values don't leave double spaces; unknown names stay visible
+        assert_eq!(
+            render("{profile} {date} {dat}", &vars()),
+            "2026-03-14 {dat}"
+        );
+        assert_eq!(render("open { brace", &vars()), "open { brace");
+    }
+
+    #[test]
+    fn test_render_json_escapes_values() {
+        let mut vars = vars();
+        vars.selection = Some("He said \"hi\"\n{text}".to_string());
+        let body = render_json(r#"{"reply_to": "{selection}", "app": "{app}"}"#, &vars).unwrap();
+        assert_eq!(body["reply_to"], "He said \"hi\"\n{text}");
+        assert_eq!(body["app"], "Notes");
+
+        assert!(render_json(r#"{"text": {text}}"#, &vars).is_err());
+    }
+
+    #[test]
+    fn test_render_text_keeps_lines() {
+        let mut vars = vars();
+        vars.selection = Some("Line one\nLine two".to_string());
+        assert_eq!(
+            render_text("Reply to:\n{selection}\n", &vars),
+            "Reply to:\nLine one\nLine two"
+        );
+        assert!(uses("Reply to {selection}", "selection"));
+        assert!(!uses("Reply to {selection}", "clipboard"));
+    }
+
+    #[test]
+    fn test_render_filename_is_safe() {
+        let mut vars = vars();
+        vars.first_words = "a/b: c?".to_string();
+        assert_eq!(render_filename("{first_words}", &vars), "a-b- c-");
+
+        vars.first_words = String::new();
+        assert_eq!(render_filename("{first_words}", &vars), "Untitled");
+        assert_eq!(render_filename("..{first_words}x", &vars), "x");
+    }
+}
@@ -1,30 +1,154 @@
-use tauri::{App, Wry};
+//! The tray menu.
+//!
+//! The menu is rebuilt from a [`MenuModel`] whenever one of its dynamic
+//! sections changes, instead of patching items built once at startup. State
+//! changes go through [`update`], which rebuilds the menu only when the model
+//! actually changed and emits `tray-menu-changed` with the sections that did,
+//! so new dynamic sections (history entries, profiles, devices) only need a
+//! field in the model and a few items in [`build_menu`].
 
-pub struct MenuWithItems {
-    pub menu: tauri::menu::Menu<Wry>,
-    pub paste_last_item: tauri::menu::MenuItem<Wry>,
+use crate::events::{self, MenuSection, TrayMenuChanged};
+use crate::ui::tray::{TrayError, TRAY_ID};
+use std::sync::Mutex;
+use tauri::menu::{Menu, MenuBuilder, MenuItemBuilder};
+use tauri::{Manager, Wry};
+
+/// What the dynamic parts of the menu show
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MenuModel {
+    /// There is a last recording to paste or read back
+    pub can_paste: bool,
+    /// A provider rejected its key; "Re-enter API Key…" heads the menu
+    pub reauth_needed: bool,
+    /// The event tap can't be created; "Input Capture Not Working…" heads the menu
+    pub input_capture_broken: bool,
+}
+
+impl MenuModel {
+    /// Sections that differ between `self` and `other`
+    fn changed_sections(&self, other: &MenuModel) -> Vec<MenuSection> {
+        let mut sections = Vec::new();
+        if self.can_paste != other.can_paste {
+            sections.push(MenuSection::LastRecording);
+        }
+        if self.reauth_needed != other.reauth_needed {
+            sections.push(MenuSection::Reauth);
+        }
+        if self.input_capture_broken != other.input_capture_broken {
+            sections.push(MenuSection::InputCapture);
+        }
+        sections
+    }
 }
 
-pub fn build_menu(app: &App<Wry>) -> Result<MenuWithItems, Box<dyn std::error::Error>> {
+/// The model the current tray menu was built from
+#[derive(Default)]
+pub struct MenuState {
+    model: Mutex<MenuModel>,
+}
+
+pub fn build_menu<M: Manager<Wry>>(
+    manager: &M,
+    model: &MenuModel,
+) -> Result<Menu<Wry>, tauri::Error> {
     // Build menu items
-    let preferences_item =
-        tauri::menu::MenuItemBuilder::with_id("preferences", "Preferences").build(app)?;
-    let paste_last_item =
-        tauri::menu::MenuItemBuilder::with_id("paste_last_recording", "Paste Last Recording")
-            .enabled(false) // Initially disabled until first recording
-            .build(app)?;
-    let quit_item = tauri::menu::MenuItemBuilder::with_id("quit", "Quit").build(app)?;
+    let preferences_item = MenuItemBuilder::with_id("preferences", "Preferences").build(manager)?;
+    let command_palette_item =
+        MenuItemBuilder::with_id("command_palette", "Command Palette…").build(manager)?;
+    let paste_last_item = MenuItemBuilder::with_id("paste_last_recording", "Paste Last Recording")
+        .enabled(model.can_paste)
+        .build(manager)?;
+    let read_back_item =
+        MenuItemBuilder::with_id("read_back_last_recording", "Read Back Last Recording")
+            .enabled(model.can_paste)
+            .build(manager)?;
+    let transcribe_copied_item =
+        MenuItemBuilder::with_id("transcribe_copied_file", "Transcribe Copied Audio File")
+            .build(manager)?;
+    let cancel_transcriptions_item =
+        MenuItemBuilder::with_id("cancel_transcriptions", "Cancel Transcription").build(manager)?;
+    // Rebuilds the key listener when Fn or Space stop behaving
+    let reset_input_item =
+        MenuItemBuilder::with_id("reset_input", "Reset Input Handling").build(manager)?;
+    let quit_item = MenuItemBuilder::with_id("quit", "Quit").build(manager)?;
 
     // Build menu
-    let menu = tauri::menu::MenuBuilder::new(app)
-        .item(&preferences_item)
+    let mut menu = MenuBuilder::new(manager);
+    if model.input_capture_broken {
+        let input_capture_item =
+            MenuItemBuilder::with_id("input_capture", "⚠ Input Capture Not Working…")
+                .build(manager)?;
+        menu = menu.item(&input_capture_item);
+    }
+    if model.reauth_needed {
+        let reauth_item = MenuItemBuilder::with_id("reauth", "Re-enter API Key…").build(manager)?;
+        menu = menu.item(&reauth_item);
+    }
+    menu.item(&preferences_item)
+        .item(&command_palette_item)
         .item(&paste_last_item)
+        .item(&read_back_item)
+        .item(&transcribe_copied_item)
         .separator()
+        .item(&cancel_transcriptions_item)
+        .item(&reset_input_item)
         .item(&quit_item)
-        .build()?;
+        .build()
+}
+
+/// Apply `change` to the menu model and rebuild the tray menu if it changed
+pub fn update(
+    app_handle: &tauri::AppHandle,
+    change: impl FnOnce(&mut MenuModel),
+) -> Result<(), TrayError> {
+    let state = app_handle
+        .try_state::<MenuState>()
+        .ok_or(TrayError::StateNotFound)?;
+    let mut model = state.model.lock().unwrap_or_else(|e| e.into_inner());
+
+    let mut updated = model.clone();
+    change(&mut updated);
+    let sections = model.changed_sections(&updated);
+    if sections.is_empty() {
+        return Ok(());
+    }
+
+    let menu = build_menu(app_handle, &updated)
+        .map_err(|e| TrayError::IconSetFailed(format!("Failed to build menu: {}", e)))?;
+    app_handle
+        .tray_by_id(TRAY_ID)
+        .ok_or(TrayError::TrayNotFound)?
+        .set_menu(Some(menu))
+        .map_err(|e| TrayError::IconSetFailed(format!("Failed to set menu: {}", e)))?;
+    *model = updated;
+    drop(model);
+
+    println!("[Menu] Rebuilt for {:?}", sections);
+    events::emit_or_log(app_handle, TrayMenuChanged { sections });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changed_sections() {
+        let model = MenuModel::default();
+        assert!(model.changed_sections(&model.clone()).is_empty());
 
-    Ok(MenuWithItems {
-        menu,
-        paste_last_item,
-    })
+        let updated = MenuModel {
+            can_paste: true,
+            reauth_needed: true,
+            input_capture_broken: true,
+        };
+        assert_eq!(
+            model.changed_sections(&updated),
+            [
+                MenuSection::LastRecording,
+                MenuSection::Reauth,
+                MenuSection::InputCapture
+            ]
+        );
+    }
 }
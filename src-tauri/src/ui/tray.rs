@@ -1,10 +1,36 @@
+use crate::config::{self, TrayIconTheme};
+use crate::display_options;
+use crate::energy;
+use crate::events::RecordingStateChanged;
+use crate::ui::menu;
 use derive_more::Display;
+use image::{Rgba, RgbaImage};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
 use tauri::Manager;
+use tauri_plugin_store::StoreExt;
 
-// State for the paste last recording menu item
-pub struct PasteMenuItemState {
-    pub item: tauri::menu::MenuItem<tauri::Wry>,
-}
+/// Id of the menu bar icon, for `AppHandle::tray_by_id`
+pub const TRAY_ID: &str = "main";
+
+const TRAY_ICON_BYTES: &[u8] = include_bytes!("../../icons/tray-icon.png");
+
+/// Badge colors for the color themes
+const RED_DOT: Rgba<u8> = Rgba([255, 59, 48, 255]);
+/// Okabe-Ito blue, distinguishable with all common color vision deficiencies
+const COLORBLIND_BLUE: Rgba<u8> = Rgba([0, 114, 178, 255]);
+/// Okabe-Ito orange for the re-auth badge, distinct from both badge colors
+const ALERT_ORANGE: Rgba<u8> = Rgba([230, 159, 0, 255]);
+const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+const BLACK: Rgba<u8> = Rgba([0, 0, 0, 255]);
+const CLEAR: Rgba<u8> = Rgba([0, 0, 0, 0]);
+
+/// Transcribing animation: one of three dots lights up in turn
+const ANIMATION_FRAMES: usize = 3;
+const ANIMATION_INTERVAL: Duration = Duration::from_millis(300);
+/// Alpha of the dots that aren't lit in the current frame
+const DIM_DOT_ALPHA: u8 = 90;
 
 // Custom error type for tray operations
 #[derive(Debug, Display)]
@@ -13,25 +39,387 @@ pub enum TrayError {
     StateNotFound,
     #[display("Failed to set icon: {}", _0)]
     IconSetFailed(String),
+    #[display("Tray icon not found")]
+    TrayNotFound,
 }
 
 impl std::error::Error for TrayError {}
 
-/// Updates the "Paste Last Recording" menu item enabled state
+/// Updates the "Paste Last Recording" and "Read Back Last Recording" menu
+/// items enabled state
 pub fn update_paste_menu_item(
     app_handle: &tauri::AppHandle,
     enabled: bool,
 ) -> Result<(), TrayError> {
     println!("[Tray] Updating paste menu item - enabled: {}", enabled);
+    menu::update(app_handle, |model| model.can_paste = enabled)
+}
+
+/// What the menu bar icon shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TrayStatus {
+    #[default]
+    Idle,
+    Recording,
+    Transcribing,
+}
+
+impl TrayStatus {
+    pub fn from_event(event: &RecordingStateChanged) -> Self {
+        match event {
+            RecordingStateChanged::Started { .. } => TrayStatus::Recording,
+            RecordingStateChanged::Transcribing => TrayStatus::Transcribing,
+            _ => TrayStatus::Idle,
+        }
+    }
+}
+
+/// Status currently shown, so the icon can be re-rendered when the theme changes
+#[derive(Default)]
+pub struct TrayState {
+    status: Mutex<TrayStatus>,
+    /// Whether the transcribing animation thread is running
+    animating: AtomicBool,
+    /// A provider's key stopped working; shown as a badge on every status
+    reauth_needed: AtomicBool,
+}
+
+fn base_image() -> RgbaImage {
+    image::load_from_memory(TRAY_ICON_BYTES)
+        .expect("Failed to load tray icon")
+        .to_rgba8()
+}
+
+fn to_tauri_image(image: RgbaImage) -> tauri::image::Image<'static> {
+    let (width, height) = image.dimensions();
+    tauri::image::Image::new_owned(image.into_raw(), width, height)
+}
+
+/// Idle icon, used when building the tray
+pub fn base_icon() -> tauri::image::Image<'static> {
+    to_tauri_image(base_image())
+}
+
+/// Render the icon for `status`. Returns the image and whether it is a
+/// template image (monochrome, recolored by macOS for light/dark menu bars).
+/// Color themes can't be templates, so the glyph is drawn in the menu bar
+/// foreground color instead. `frame` selects the transcribing animation frame.
+pub fn render_icon(
+    theme: TrayIconTheme,
+    status: TrayStatus,
+    frame: usize,
+    dark_menu_bar: bool,
+) -> (RgbaImage, bool) {
+    let mut image = base_image();
+
+    match status {
+        TrayStatus::Idle => return (image, true),
+        TrayStatus::Transcribing => {
+            draw_progress_dots(&mut image, frame);
+            return (image, true);
+        }
+        TrayStatus::Recording => {}
+    }
+
+    let (width, height) = image.dimensions();
+    let radius = width.min(height) as f32 * 0.22;
+    let cx = width as f32 - radius - 0.5;
+    let cy = height as f32 - radius - 0.5;
+
+    match theme {
+        TrayIconTheme::Monochrome => {
+            // Cut a gap around the badge so it reads as a separate shape
+            fill_circle(&mut image, cx, cy, radius + 1.5, CLEAR);
+            // Template images only use alpha; match the glyph's color anyway
+            fill_circle(&mut image, cx, cy, radius, WHITE);
+            (image, true)
+        }
+        TrayIconTheme::RedDot => {
+            recolor(&mut image, foreground(dark_menu_bar));
+            fill_circle(&mut image, cx, cy, radius + 1.5, CLEAR);
+            fill_circle(&mut image, cx, cy, radius, RED_DOT);
+            (image, false)
+        }
+        TrayIconTheme::Colorblind => {
+            // Ring shape plus high-contrast center, so it doesn't rely on hue alone
+            recolor(&mut image, foreground(dark_menu_bar));
+            fill_circle(&mut image, cx, cy, radius + 1.5, CLEAR);
+            fill_circle(&mut image, cx, cy, radius, COLORBLIND_BLUE);
+            fill_circle(&mut image, cx, cy, radius * 0.45, WHITE);
+            (image, false)
+        }
+    }
+}
+
+/// Add the re-auth badge in the top-right corner, where it can't collide with
+/// the recording badge or the progress dots. Returns whether the image is
+/// still a template.
+fn draw_reauth_badge(
+    image: &mut RgbaImage,
+    theme: TrayIconTheme,
+    is_template: bool,
+    dark_menu_bar: bool,
+) -> bool {
+    let (width, height) = image.dimensions();
+    let radius = width.min(height) as f32 * 0.18;
+    let cx = width as f32 - radius - 0.5;
+    let cy = radius + 0.5;
+
+    if theme == TrayIconTheme::Monochrome {
+        fill_circle(image, cx, cy, radius + 1.5, CLEAR);
+        fill_circle(image, cx, cy, radius, WHITE);
+        return is_template;
+    }
+
+    if is_template {
+        recolor(image, foreground(dark_menu_bar));
+    }
+    fill_circle(image, cx, cy, radius + 1.5, CLEAR);
+    fill_circle(image, cx, cy, radius, ALERT_ORANGE);
+    false
+}
+
+/// Three dots along the bottom edge, the `frame`-th one lit.
+/// Drawn in alpha only so it works as a template image for every theme.
+fn draw_progress_dots(image: &mut RgbaImage, frame: usize) {
+    let (width, height) = image.dimensions();
+    let radius = width.min(height) as f32 * 0.09;
+    let spacing = radius * 3.0;
+    let cy = height as f32 - radius - 0.5;
+    let first_cx = width as f32 / 2.0 - spacing;
+
+    // Clear a strip behind the dots so they don't merge with the glyph
+    for (_, y, pixel) in image.enumerate_pixels_mut() {
+        if y as f32 + 0.5 >= cy - radius - 1.5 {
+            *pixel = CLEAR;
+        }
+    }
+
+    for dot in 0..ANIMATION_FRAMES {
+        let alpha = if dot == frame % ANIMATION_FRAMES {
+            255
+        } else {
+            DIM_DOT_ALPHA
+        };
+        let cx = first_cx + dot as f32 * spacing;
+        fill_circle(image, cx, cy, radius, Rgba([255, 255, 255, alpha]));
+    }
+}
+
+fn foreground(dark_menu_bar: bool) -> Rgba<u8> {
+    if dark_menu_bar {
+        WHITE
+    } else {
+        BLACK
+    }
+}
+
+/// Replace the color of every pixel, keeping its alpha
+fn recolor(image: &mut RgbaImage, color: Rgba<u8>) {
+    for pixel in image.pixels_mut() {
+        pixel.0 = [color[0], color[1], color[2], pixel[3]];
+    }
+}
+
+fn fill_circle(image: &mut RgbaImage, cx: f32, cy: f32, radius: f32, color: Rgba<u8>) {
+    for (x, y, pixel) in image.enumerate_pixels_mut() {
+        let dx = x as f32 + 0.5 - cx;
+        let dy = y as f32 + 0.5 - cy;
+        if dx * dx + dy * dy <= radius * radius {
+            *pixel = color;
+        }
+    }
+}
+
+/// Whether the menu bar is currently dark
+fn dark_menu_bar() -> bool {
+    #[cfg(target_os = "macos")]
+    {
+        crate::conflicts::read_default("-g", "AppleInterfaceStyle").as_deref() == Some("Dark")
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        false
+    }
+}
 
+fn configured_theme(app_handle: &tauri::AppHandle) -> TrayIconTheme {
+    app_handle
+        .store("config.json")
+        .map(|store| config::load_app_config(&store).tray_icon_theme)
+        .unwrap_or_default()
+}
+
+fn apply_icon(
+    app_handle: &tauri::AppHandle,
+    theme: TrayIconTheme,
+    status: TrayStatus,
+    frame: usize,
+) -> Result<(), TrayError> {
+    let tray = app_handle
+        .tray_by_id(TRAY_ID)
+        .ok_or(TrayError::TrayNotFound)?;
+
+    let dark = dark_menu_bar();
+    let (mut image, mut is_template) = render_icon(theme, status, frame, dark);
+    let reauth_needed = app_handle
+        .try_state::<TrayState>()
+        .is_some_and(|state| state.reauth_needed.load(Ordering::SeqCst));
+    if reauth_needed {
+        is_template = draw_reauth_badge(&mut image, theme, is_template, dark);
+    }
+
+    tray.set_icon(Some(to_tauri_image(image)))
+        .map_err(|e| TrayError::IconSetFailed(e.to_string()))?;
+    tray.set_icon_as_template(is_template)
+        .map_err(|e| TrayError::IconSetFailed(e.to_string()))?;
+
+    Ok(())
+}
+
+/// Show `status` in the menu bar icon
+pub fn set_status(app_handle: &tauri::AppHandle, status: TrayStatus) -> Result<(), TrayError> {
     let state = app_handle
-        .try_state::<PasteMenuItemState>()
+        .try_state::<TrayState>()
         .ok_or(TrayError::StateNotFound)?;
 
-    state.item.set_enabled(enabled).map_err(|e| {
-        TrayError::IconSetFailed(format!("Failed to set menu item enabled state: {}", e))
-    })?;
+    {
+        let mut current = state.status.lock().unwrap();
+        if *current == status {
+            return Ok(());
+        }
+        *current = status;
+    }
 
-    println!("[Tray]  Paste menu item updated successfully");
-    Ok(())
+    // Low-power mode and reduce motion show a static transcribing icon instead
+    if status == TrayStatus::Transcribing
+        && !energy::is_low_power()
+        && !display_options::reduce_motion()
+    {
+        start_animation(app_handle, &state);
+    }
+
+    apply_icon(app_handle, configured_theme(app_handle), status, 0)
+}
+
+/// Cycle the transcribing frames on a background thread until the status
+/// changes, so there's feedback even when the popup isn't visible
+fn start_animation(app_handle: &tauri::AppHandle, state: &TrayState) {
+    if state.animating.swap(true, Ordering::SeqCst) {
+        return; // Already running
+    }
+
+    let app_handle = app_handle.clone();
+    std::thread::spawn(move || {
+        let theme = configured_theme(&app_handle);
+        let mut frame = 0;
+
+        loop {
+            std::thread::sleep(ANIMATION_INTERVAL);
+
+            let Some(state) = app_handle.try_state::<TrayState>() else {
+                return;
+            };
+            if *state.status.lock().unwrap() != TrayStatus::Transcribing {
+                state.animating.store(false, Ordering::SeqCst);
+                return;
+            }
+
+            frame = (frame + 1) % ANIMATION_FRAMES;
+            if let Err(e) = apply_icon(&app_handle, theme, TrayStatus::Transcribing, frame) {
+                eprintln!("[Tray] Failed to update animation frame: {}", e);
+            }
+        }
+    });
+}
+
+/// Show or hide the re-auth badge and the "Re-enter API Key…" menu item
+pub fn set_reauth_needed(app_handle: &tauri::AppHandle, needed: bool) -> Result<(), TrayError> {
+    let state = app_handle
+        .try_state::<TrayState>()
+        .ok_or(TrayError::StateNotFound)?;
+    if state.reauth_needed.swap(needed, Ordering::SeqCst) == needed {
+        return Ok(());
+    }
+
+    menu::update(app_handle, |model| model.reauth_needed = needed)?;
+    refresh(app_handle)
+}
+
+/// Re-render the current status, e.g. after the icon theme changed
+pub fn refresh(app_handle: &tauri::AppHandle) -> Result<(), TrayError> {
+    let state = app_handle
+        .try_state::<TrayState>()
+        .ok_or(TrayError::StateNotFound)?;
+    let status = *state.status.lock().unwrap();
+
+    apply_icon(app_handle, configured_theme(app_handle), status, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_idle_icon_is_template_for_every_theme() {
+        for theme in [
+            TrayIconTheme::Monochrome,
+            TrayIconTheme::RedDot,
+            TrayIconTheme::Colorblind,
+        ] {
+            let (image, is_template) = render_icon(theme, TrayStatus::Idle, 0, true);
+            assert!(is_template);
+            assert_eq!(image, base_image());
+        }
+    }
+
+    #[test]
+    fn test_recording_badge_uses_theme_color() {
+        let (image, is_template) =
+            render_icon(TrayIconTheme::RedDot, TrayStatus::Recording, 0, false);
+        assert!(!is_template);
+        assert!(image.pixels().any(|p| *p == RED_DOT));
+
+        let (image, is_template) =
+            render_icon(TrayIconTheme::Monochrome, TrayStatus::Recording, 0, false);
+        assert!(is_template);
+        assert!(image
+            .pixels()
+            .all(|p| p[3] == 0 || p.0[..3] == [255, 255, 255]));
+    }
+
+    #[test]
+    fn test_reauth_badge_keeps_monochrome_a_template() {
+        let (mut image, is_template) =
+            render_icon(TrayIconTheme::Monochrome, TrayStatus::Idle, 0, true);
+        assert!(draw_reauth_badge(
+            &mut image,
+            TrayIconTheme::Monochrome,
+            is_template,
+            true
+        ));
+        assert_ne!(image, base_image());
+
+        let (mut image, is_template) =
+            render_icon(TrayIconTheme::RedDot, TrayStatus::Recording, 0, true);
+        assert!(!draw_reauth_badge(
+            &mut image,
+            TrayIconTheme::RedDot,
+            is_template,
+            true
+        ));
+        assert!(image.pixels().any(|p| *p == ALERT_ORANGE));
+        assert!(image.pixels().any(|p| *p == RED_DOT));
+    }
+
+    #[test]
+    fn test_transcribing_frames_differ_and_cycle() {
+        let render =
+            |frame| render_icon(TrayIconTheme::RedDot, TrayStatus::Transcribing, frame, true);
+
+        let (first, is_template) = render(0);
+        assert!(is_template);
+        assert_ne!(first, render(1).0);
+        assert_eq!(first, render(ANIMATION_FRAMES).0);
+    }
 }
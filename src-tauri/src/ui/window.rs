@@ -1,3 +1,5 @@
+use crate::energy;
+use serde::{Deserialize, Serialize};
 use std::sync::mpsc;
 use tauri::{Manager, Monitor};
 
@@ -6,7 +8,13 @@ type AnyError = Box<dyn std::error::Error + Send + Sync>;
 const POPUP_WIDTH_NORMAL: u32 = 80;
 const POPUP_WIDTH_ERROR: u32 = 400; // 5x wider for error display
 const POPUP_HEIGHT: u32 = 74;
+const POPUP_WIDTH_PREVIEW: u32 = 480;
+const POPUP_HEIGHT_PREVIEW: u32 = 160;
+const POPUP_HEIGHT_CONFIRM: u32 = 110;
 const BOTTOM_MARGIN: i32 = 100;
+/// Space kept free at the monitor's sides and top when clamping the popup
+const SIDE_MARGIN: f64 = 40.0;
+const TOP_MARGIN: f64 = 40.0;
 
 /// Show a window without stealing focus (macOS only).
 /// Uses `orderFront:` instead of `makeKeyAndOrderFront:` to avoid activating the app.
@@ -184,6 +192,7 @@ fn open_recording_popup_inner(app_handle: &tauri::AppHandle) -> Result<(), AnyEr
             eprintln!("[Window] Failed to show recording popup: {}", e);
             return Err(e);
         }
+        energy::set_popup_visible(true);
     } else {
         return Err("Recording popup window not found".into());
     }
@@ -197,6 +206,7 @@ fn close_recording_popup_inner(app_handle: &tauri::AppHandle) -> Result<(), AnyE
             eprintln!("[Window] Failed to hide recording popup: {}", e);
             return Err(Box::new(e));
         }
+        energy::set_popup_visible(false);
     } else {
         return Err("Recording popup window not found".into());
     }
@@ -205,56 +215,207 @@ fn close_recording_popup_inner(app_handle: &tauri::AppHandle) -> Result<(), AnyE
 }
 
 pub fn resize_recording_popup_for_error(app_handle: &tauri::AppHandle) -> Result<(), AnyError> {
+    set_popup_layout(app_handle, PopupMode::Error, None).map(|_| ())
+}
+
+/// What the recording popup shows, which decides its default size
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum PopupMode {
+    /// Waveform while recording or transcribing
+    Recording,
+    /// One-line error or notice with actions
+    Error,
+    /// Transcript text shown before it's delivered
+    Preview,
+    /// Question with confirm and cancel buttons
+    Confirm,
+}
+
+impl PopupMode {
+    fn default_size(&self) -> PopupSize {
+        let (width, height) = match self {
+            PopupMode::Recording => (POPUP_WIDTH_NORMAL, POPUP_HEIGHT),
+            PopupMode::Error => (POPUP_WIDTH_ERROR, POPUP_HEIGHT),
+            PopupMode::Preview => (POPUP_WIDTH_PREVIEW, POPUP_HEIGHT_PREVIEW),
+            PopupMode::Confirm => (POPUP_WIDTH_ERROR, POPUP_HEIGHT_CONFIRM),
+        };
+        PopupSize {
+            width: width as f64,
+            height: height as f64,
+        }
+    }
+}
+
+/// Logical size of the recording popup
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, specta::Type)]
+pub struct PopupSize {
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Window frame in logical coordinates, origin at the top left
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct LogicalFrame {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+impl LogicalFrame {
+    fn of_monitor(monitor: &Monitor) -> Self {
+        let scale_factor = monitor.scale_factor();
+        Self {
+            x: monitor.position().x as f64 / scale_factor,
+            y: monitor.position().y as f64 / scale_factor,
+            width: monitor.size().width as f64 / scale_factor,
+            height: monitor.size().height as f64 / scale_factor,
+        }
+    }
+}
+
+/// Frame for a popup of `size` on `monitor`: clamped to stay inside the
+/// monitor's margins, centered horizontally, and anchored above the bottom edge
+fn popup_frame(size: PopupSize, monitor: LogicalFrame) -> LogicalFrame {
+    let max_width = (monitor.width - 2.0 * SIDE_MARGIN).max(POPUP_WIDTH_NORMAL as f64);
+    let max_height = (monitor.height - BOTTOM_MARGIN as f64 - TOP_MARGIN).max(POPUP_HEIGHT as f64);
+    let width = size.width.clamp(POPUP_WIDTH_NORMAL as f64, max_width);
+    let height = size.height.clamp(POPUP_HEIGHT as f64, max_height);
+
+    LogicalFrame {
+        x: monitor.x + (monitor.width - width) / 2.0,
+        y: monitor.y + monitor.height - height - BOTTOM_MARGIN as f64,
+        width,
+        height,
+    }
+}
+
+/// Size the recording popup for `mode`, or to `desired_size` if given, within
+/// the bounds of the monitor it's on. The change is animated unless reduce
+/// motion is on. Returns the size after clamping.
+pub fn set_popup_layout(
+    app_handle: &tauri::AppHandle,
+    mode: PopupMode,
+    desired_size: Option<PopupSize>,
+) -> Result<PopupSize, AnyError> {
+    let size = desired_size.unwrap_or_else(|| mode.default_size());
     let app_handle_for_closure = app_handle.clone();
     run_on_main_thread_sync(app_handle, move || {
-        resize_recording_popup_inner(&app_handle_for_closure, POPUP_WIDTH_ERROR)
+        set_popup_frame_inner(&app_handle_for_closure, size)
     })
 }
 
-fn resize_recording_popup_inner(app_handle: &tauri::AppHandle, width: u32) -> Result<(), AnyError> {
-    if let Some(window) = app_handle.get_webview_window("recording-popup") {
-        // Set new size
+fn set_popup_frame_inner(
+    app_handle: &tauri::AppHandle,
+    size: PopupSize,
+) -> Result<PopupSize, AnyError> {
+    let Some(window) = app_handle.get_webview_window("recording-popup") else {
+        return Err("Recording popup window not found".into());
+    };
+
+    // Recalculate centered position
+    let monitor =
+        get_monitor_at_cursor(app_handle).or_else(|| app_handle.primary_monitor().ok().flatten());
+    let Some(monitor) = monitor else {
         window.set_size(tauri::Size::Logical(tauri::LogicalSize {
-            width: width as f64,
-            height: POPUP_HEIGHT as f64,
+            width: size.width,
+            height: size.height,
         }))?;
+        return Ok(size);
+    };
 
-        // Recalculate centered position
-        let monitor = get_monitor_at_cursor(app_handle)
-            .or_else(|| app_handle.primary_monitor().ok().flatten());
-
-        if let Some(monitor) = monitor {
-            let scale_factor = monitor.scale_factor();
-            let monitor_size = monitor.size();
-            let monitor_position = monitor.position();
-
-            let logical_width = monitor_size.width as f64 / scale_factor;
-            let logical_height = monitor_size.height as f64 / scale_factor;
-            let logical_x = monitor_position.x as f64 / scale_factor;
-            let logical_y = monitor_position.y as f64 / scale_factor;
+    let frame = popup_frame(size, LogicalFrame::of_monitor(&monitor));
+    if !crate::display_options::reduce_motion() {
+        if let Err(e) = set_frame_animated(app_handle, &window, frame) {
+            eprintln!("[Window] Animated resize failed, resizing directly: {}", e);
+            set_frame(&window, frame)?;
+        }
+    } else {
+        set_frame(&window, frame)?;
+    }
 
-            // Center horizontally with new width
-            let x = logical_x + (logical_width - width as f64) / 2.0;
-            let y = logical_y + logical_height - POPUP_HEIGHT as f64 - BOTTOM_MARGIN as f64;
+    Ok(PopupSize {
+        width: frame.width,
+        height: frame.height,
+    })
+}
 
-            window.set_position(tauri::Position::Logical(tauri::LogicalPosition { x, y }))?;
-        }
+fn set_frame(window: &tauri::WebviewWindow, frame: LogicalFrame) -> Result<(), AnyError> {
+    window.set_size(tauri::Size::Logical(tauri::LogicalSize {
+        width: frame.width,
+        height: frame.height,
+    }))?;
+    window.set_position(tauri::Position::Logical(tauri::LogicalPosition {
+        x: frame.x,
+        y: frame.y,
+    }))?;
+    Ok(())
+}
 
-        Ok(())
-    } else {
-        Err("Recording popup window not found".into())
+/// Resize with the native window animation. AppKit frames are measured from
+/// the bottom left of the primary screen, so the y axis is flipped.
+#[cfg(target_os = "macos")]
+fn set_frame_animated(
+    app_handle: &tauri::AppHandle,
+    window: &tauri::WebviewWindow,
+    frame: LogicalFrame,
+) -> Result<(), AnyError> {
+    use objc2::msg_send;
+    use objc2::runtime::AnyObject;
+    use objc2_foundation::{NSPoint, NSRect, NSSize};
+
+    let primary = app_handle
+        .primary_monitor()?
+        .ok_or("No primary monitor")
+        .map(|monitor| LogicalFrame::of_monitor(&monitor))?;
+    let ns_window = window.ns_window()? as *mut AnyObject;
+    let rect = NSRect::new(
+        NSPoint::new(frame.x, primary.height - frame.y - frame.height),
+        NSSize::new(frame.width, frame.height),
+    );
+
+    // Safety: ns_window is a valid NSWindow pointer from Tauri, used on the main thread
+    unsafe {
+        let _: () = msg_send![ns_window, setFrame: rect, display: true, animate: true];
     }
+    Ok(())
 }
 
+#[cfg(not(target_os = "macos"))]
+fn set_frame_animated(
+    _app_handle: &tauri::AppHandle,
+    window: &tauri::WebviewWindow,
+    frame: LogicalFrame,
+) -> Result<(), AnyError> {
+    set_frame(window, frame)
+}
+
+const COMMAND_PALETTE_LABEL: &str = "command-palette";
+
+/// Preferences page with the provider API keys
+pub const API_KEYS_PAGE: &str = "preferences/api-keys";
+
 pub fn open_preferences_window(app_handle: &tauri::AppHandle) -> Result<(), AnyError> {
+    open_preferences_page(app_handle, "preferences")
+}
+
+/// Open the preferences window on `page`, e.g. [`API_KEYS_PAGE`]. An already
+/// open window navigates there.
+pub fn open_preferences_page(app_handle: &tauri::AppHandle, page: &str) -> Result<(), AnyError> {
     let (width, height) = (750.0, 650.0);
 
     let window = match app_handle.get_webview_window("preferences") {
-        Some(w) => w,
+        Some(w) => {
+            if page != "preferences" {
+                w.eval(&format!("window.location.replace('/{}')", page))?;
+            }
+            w
+        }
         None => tauri::WebviewWindowBuilder::new(
             app_handle,
             "preferences",
-            tauri::WebviewUrl::App("preferences".into()),
+            tauri::WebviewUrl::App(page.into()),
         )
         .title("Preferences")
         .inner_size(width, height)
@@ -270,3 +431,112 @@ pub fn open_preferences_window(app_handle: &tauri::AppHandle) -> Result<(), AnyE
 
     Ok(())
 }
+
+/// Open the command palette, or focus it if it's already open
+pub fn open_command_palette(app_handle: &tauri::AppHandle) -> Result<(), AnyError> {
+    let (width, height) = (560.0, 380.0);
+
+    let window = match app_handle.get_webview_window(COMMAND_PALETTE_LABEL) {
+        Some(w) => w,
+        None => tauri::WebviewWindowBuilder::new(
+            app_handle,
+            COMMAND_PALETTE_LABEL,
+            tauri::WebviewUrl::App("command-palette".into()),
+        )
+        .title("Command Palette")
+        .inner_size(width, height)
+        .decorations(false)
+        .always_on_top(true)
+        .resizable(false)
+        .skip_taskbar(true)
+        .visible(false)
+        .build()?,
+    };
+
+    window.show()?;
+    window.set_focus()?;
+    window.center()?;
+
+    Ok(())
+}
+
+pub fn close_command_palette(app_handle: &tauri::AppHandle) -> Result<(), AnyError> {
+    if let Some(window) = app_handle.get_webview_window(COMMAND_PALETTE_LABEL) {
+        window.close()?;
+    }
+    Ok(())
+}
+
+/// Open the onboarding window on `page`, e.g. "onboarding/accessibility". An
+/// already open window navigates there.
+pub fn open_onboarding_window(app_handle: &tauri::AppHandle, page: &str) -> Result<(), AnyError> {
+    let (width, height) = (600.0, 520.0);
+
+    let window = match app_handle.get_webview_window("onboarding") {
+        Some(w) => {
+            w.eval(&format!("window.location.replace('/{}')", page))?;
+            w
+        }
+        None => tauri::WebviewWindowBuilder::new(
+            app_handle,
+            "onboarding",
+            tauri::WebviewUrl::App(page.into()),
+        )
+        .title("Welcome to Dictara")
+        .inner_size(width, height)
+        .resizable(false)
+        .visible(false)
+        .build()?,
+    };
+
+    window.show()?;
+    window.set_focus()?;
+    window.center()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MONITOR: LogicalFrame = LogicalFrame {
+        x: 1440.0,
+        y: 0.0,
+        width: 1280.0,
+        height: 800.0,
+    };
+
+    #[test]
+    fn test_popup_frame_clamps_to_monitor() {
+        let error = popup_frame(PopupMode::Error.default_size(), MONITOR);
+        assert_eq!(
+            error,
+            LogicalFrame {
+                x: 1440.0 + 440.0,
+                y: 800.0 - 74.0 - 100.0,
+                width: 400.0,
+                height: 74.0,
+            }
+        );
+
+        let huge = popup_frame(
+            PopupSize {
+                width: 5000.0,
+                height: 5000.0,
+            },
+            MONITOR,
+        );
+        assert_eq!((huge.width, huge.height), (1200.0, 660.0));
+        assert_eq!((huge.x, huge.y), (1480.0, 40.0));
+
+        let tiny = popup_frame(
+            PopupSize {
+                width: 10.0,
+                height: 10.0,
+            },
+            MONITOR,
+        );
+        assert_eq!((tiny.width, tiny.height), (80.0, 74.0));
+    }
+}
@@ -1,4 +1,6 @@
 #[cfg(not(debug_assertions))]
+use crate::supervisor::{RestartPolicy, SupervisorState};
+#[cfg(not(debug_assertions))]
 use std::sync::Mutex;
 use std::sync::{
     atomic::{AtomicBool, AtomicU8, Ordering},
@@ -117,7 +119,15 @@ fn get_idle_seconds() -> f64 {
 /// Start periodic update checking and idle-based installation
 /// Should be called from setup after the app is initialized
 #[cfg(not(debug_assertions))]
-pub fn start_periodic_update_check(app_handle: tauri::AppHandle, updater_state: Arc<UpdaterState>) {
+pub fn start_periodic_update_check(
+    app_handle: tauri::AppHandle,
+    updater_state: Arc<UpdaterState>,
+    supervisor: &SupervisorState,
+) {
+    if crate::config::managed::current().offline {
+        println!("[Updater] Offline mode is enforced, not checking for updates");
+        return;
+    }
     println!("[Updater] Starting periodic update check (every 30 minutes for testing)");
 
     // Initial check after a short delay
@@ -129,43 +139,57 @@ pub fn start_periodic_update_check(app_handle: tauri::AppHandle, updater_state:
         check_and_download_update(handle, state).await;
     });
 
+    // The periodic check and idle monitor loop forever, so an exit is a failure
+    let policy = RestartPolicy {
+        restart_on_exit: true,
+        ..RestartPolicy::default()
+    };
+
     // Periodic checks for new updates
     let handle = app_handle.clone();
     let state = updater_state.clone();
-    tauri::async_runtime::spawn(async move {
-        loop {
-            tokio::time::sleep(UPDATE_CHECK_INTERVAL).await;
-            println!("[Updater] Periodic update check triggered");
-            check_and_download_update(handle.clone(), state.clone()).await;
+    supervisor.spawn_async("updater_check", policy, move || {
+        let handle = handle.clone();
+        let state = state.clone();
+        async move {
+            loop {
+                tokio::time::sleep(crate::energy::timer_interval(UPDATE_CHECK_INTERVAL)).await;
+                println!("[Updater] Periodic update check triggered");
+                check_and_download_update(handle.clone(), state.clone()).await;
+            }
         }
     });
 
     // Idle monitor - checks if user is idle and installs pending update
-    tauri::async_runtime::spawn(async move {
-        loop {
-            tokio::time::sleep(IDLE_CHECK_INTERVAL).await;
-
-            // Only proceed if there's a pending install
-            if !updater_state.has_pending_install() {
-                continue;
-            }
+    supervisor.spawn_async("updater_idle_monitor", policy, move || {
+        let app_handle = app_handle.clone();
+        let updater_state = updater_state.clone();
+        async move {
+            loop {
+                tokio::time::sleep(crate::energy::timer_interval(IDLE_CHECK_INTERVAL)).await;
+
+                // Only proceed if there's a pending install
+                if !updater_state.has_pending_install() {
+                    continue;
+                }
 
-            // Don't install if app is busy
-            if updater_state.is_busy() {
-                println!("[Updater] App busy, deferring install");
-                continue;
-            }
+                // Don't install if app is busy
+                if updater_state.is_busy() {
+                    println!("[Updater] App busy, deferring install");
+                    continue;
+                }
 
-            // Check idle time
-            #[cfg(target_os = "macos")]
-            {
-                let idle_seconds = get_idle_seconds();
-                if idle_seconds >= REQUIRED_IDLE_SECONDS {
-                    println!(
-                        "[Updater] User idle for {:.0}s (>= {:.0}s), installing update...",
-                        idle_seconds, REQUIRED_IDLE_SECONDS
-                    );
-                    install_pending_update(&app_handle, &updater_state);
+                // Check idle time
+                #[cfg(target_os = "macos")]
+                {
+                    let idle_seconds = get_idle_seconds();
+                    if idle_seconds >= REQUIRED_IDLE_SECONDS {
+                        println!(
+                            "[Updater] User idle for {:.0}s (>= {:.0}s), installing update...",
+                            idle_seconds, REQUIRED_IDLE_SECONDS
+                        );
+                        install_pending_update(&app_handle, &updater_state);
+                    }
                 }
             }
         }
@@ -312,6 +336,9 @@ pub async fn check_for_updates(
     show_no_update_message: bool,
 ) -> Result<bool, String> {
     println!("[Updater] Manual update check requested");
+    if crate::config::managed::current().offline {
+        return Err("Your organization turned off update checks".to_string());
+    }
 
     // Get updater state
     let updater_state = app_handle
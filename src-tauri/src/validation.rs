@@ -0,0 +1,457 @@
+//! Provider config validation.
+//!
+//! Checks a provider config before it's saved, so the settings UI can point at
+//! the field that needs fixing instead of surfacing an API error mid-dictation.
+//! Local checks are format heuristics only: a key that passes may still be
+//! rejected by the provider, which is what the optional model probe is for.
+//!
+//! Onboarding validates as the user types with [`validate_live`]: format
+//! checks answer right away, and the probe only runs once typing has paused.
+//! Each live call supersedes the previous one, so a slow probe for an old key
+//! never overwrites the result for the current one.
+
+use crate::clients::azure_endpoint;
+use crate::clients::openai::{ModelProbe, OpenAIClient};
+use crate::config::Provider;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// OpenAI keys are far longer than this; anything shorter is a partial paste
+const OPENAI_MIN_KEY_LEN: usize = 20;
+/// Azure resource keys: 32 hex characters (older resources) or 84 characters
+const AZURE_KEY_LENS: [usize; 2] = [32, 84];
+
+/// How long typing must pause before a live validation probes the provider
+const LIVE_PROBE_DEBOUNCE: Duration = Duration::from_millis(600);
+
+/// Bumped by every live validation and by [`cancel_live`]; a call whose
+/// generation is no longer current has been superseded
+static LIVE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum ConfigField {
+    ApiKey,
+    Endpoint,
+    Model,
+}
+
+/// What went wrong, so the UI can react to specific mistakes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum IssueKind {
+    Missing,
+    /// Spaces or line breaks inside the key
+    Whitespace,
+    /// Shorter than any real key, usually a partial copy
+    TooShort,
+    /// The leading characters of the key didn't make it into the paste
+    TruncatedStart,
+    /// An OpenAI key without the "sk-" prefix
+    MissingPrefix,
+    /// Copied together with `OPENAI_API_KEY=`, `Bearer ` or quotes
+    ExtraText,
+    /// An endpoint URL pasted into the API key field
+    EndpointInKeyField,
+    /// A key for the other provider
+    WrongProvider,
+    /// Outside the usual key lengths
+    UnusualLength,
+    InvalidEndpoint,
+    /// The provider rejected the key
+    KeyRejected,
+    /// The transcription model or Azure deployment wasn't found
+    ModelMissing,
+    /// The provider couldn't be reached to check the key
+    ProbeFailed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum IssueSeverity {
+    /// The config can't work as entered
+    Error,
+    /// Unusual, but might still work
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationIssue {
+    pub field: ConfigField,
+    pub kind: IssueKind,
+    pub severity: IssueSeverity,
+    /// User-facing explanation of what to fix
+    pub message: String,
+}
+
+impl ValidationIssue {
+    fn error(field: ConfigField, kind: IssueKind, message: impl Into<String>) -> Self {
+        Self {
+            field,
+            kind,
+            severity: IssueSeverity::Error,
+            message: message.into(),
+        }
+    }
+
+    fn warning(field: ConfigField, kind: IssueKind, message: impl Into<String>) -> Self {
+        Self {
+            field,
+            kind,
+            severity: IssueSeverity::Warning,
+            message: message.into(),
+        }
+    }
+}
+
+/// Result of a live validation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum LiveValidation {
+    /// A newer call or `cancel_live_validation` came in first; ignore this one
+    Superseded,
+    Checked {
+        issues: Vec<ValidationIssue>,
+        /// Whether the provider was asked, i.e. the format checks passed
+        probed: bool,
+    },
+}
+
+fn has_errors(issues: &[ValidationIssue]) -> bool {
+    issues
+        .iter()
+        .any(|issue| issue.severity == IssueSeverity::Error)
+}
+
+fn check_key_basics(key: &str) -> Option<ValidationIssue> {
+    if key.is_empty() {
+        return Some(ValidationIssue::error(
+            ConfigField::ApiKey,
+            IssueKind::Missing,
+            "Enter your API key.",
+        ));
+    }
+    let lowercase = key.to_ascii_lowercase();
+    if lowercase.starts_with("http://")
+        || lowercase.starts_with("https://")
+        || lowercase.contains(".openai.azure.com")
+    {
+        return Some(ValidationIssue::error(
+            ConfigField::ApiKey,
+            IssueKind::EndpointInKeyField,
+            "This is an endpoint URL, not an API key. Endpoints go in the Azure OpenAI endpoint field.",
+        ));
+    }
+    if lowercase.starts_with("openai_api_key=")
+        || lowercase.starts_with("bearer ")
+        || key.starts_with(['"', '\''])
+    {
+        return Some(ValidationIssue::error(
+            ConfigField::ApiKey,
+            IssueKind::ExtraText,
+            "Paste only the key itself, without the variable name, \"Bearer\" or quotes.",
+        ));
+    }
+    if key.chars().any(char::is_whitespace) {
+        return Some(ValidationIssue::error(
+            ConfigField::ApiKey,
+            IssueKind::Whitespace,
+            "The API key contains spaces or line breaks. Copy it again without them.",
+        ));
+    }
+    None
+}
+
+fn check_openai_key(key: &str) -> Vec<ValidationIssue> {
+    if let Some(issue) = check_key_basics(key) {
+        return vec![issue];
+    }
+    if key.len() < OPENAI_MIN_KEY_LEN {
+        return vec![ValidationIssue::error(
+            ConfigField::ApiKey,
+            IssueKind::TooShort,
+            "The API key is too short. Make sure you copied all of it.",
+        )];
+    }
+    // "k-proj-…" or "-proj-…": the selection started a character or two late
+    if key.starts_with("k-") || key.starts_with('-') {
+        return vec![ValidationIssue::error(
+            ConfigField::ApiKey,
+            IssueKind::TruncatedStart,
+            "The start of the key is missing. OpenAI keys begin with \"sk-\".",
+        )];
+    }
+    if !key.starts_with("sk-") {
+        return vec![ValidationIssue::warning(
+            ConfigField::ApiKey,
+            IssueKind::MissingPrefix,
+            "OpenAI keys usually start with \"sk-\". Check that this is an OpenAI key.",
+        )];
+    }
+    Vec::new()
+}
+
+fn check_azure_key(key: &str) -> Vec<ValidationIssue> {
+    if let Some(issue) = check_key_basics(key) {
+        return vec![issue];
+    }
+    if key.starts_with("sk-") {
+        return vec![ValidationIssue::warning(
+            ConfigField::ApiKey,
+            IssueKind::WrongProvider,
+            "This looks like an OpenAI key. Azure OpenAI needs a key from your Azure resource.",
+        )];
+    }
+    if !AZURE_KEY_LENS.contains(&key.len()) {
+        return vec![ValidationIssue::warning(
+            ConfigField::ApiKey,
+            IssueKind::UnusualLength,
+            "Azure keys are usually 32 or 84 characters. Make sure you copied all of it.",
+        )];
+    }
+    Vec::new()
+}
+
+/// Format checks that don't touch the network
+pub fn check_format(
+    provider: Provider,
+    api_key: &str,
+    endpoint: Option<&str>,
+) -> Vec<ValidationIssue> {
+    match provider {
+        Provider::OpenAI => check_openai_key(api_key),
+        Provider::AzureOpenAI => {
+            let mut issues = check_azure_key(api_key);
+            if let Err(e) = azure_endpoint::normalize(endpoint.unwrap_or("")) {
+                issues.push(ValidationIssue::error(
+                    ConfigField::Endpoint,
+                    IssueKind::InvalidEndpoint,
+                    e.user_message(),
+                ));
+            }
+            issues
+        }
+    }
+}
+
+/// Validate a provider config. With `probe`, a config that passes the format
+/// checks is also checked against the provider to confirm the key works and
+/// the transcription model (or Azure deployment) exists. The probe doesn't
+/// transcribe anything, so it isn't billed.
+pub fn validate(
+    provider: Provider,
+    api_key: &str,
+    endpoint: Option<&str>,
+    probe: bool,
+) -> Vec<ValidationIssue> {
+    let api_key = api_key.trim();
+    let mut issues = check_format(provider, api_key, endpoint);
+    if !probe || has_errors(&issues) {
+        return issues;
+    }
+
+    let endpoint = endpoint.and_then(|e| azure_endpoint::normalize(e).ok());
+    match OpenAIClient::probe_model(provider, api_key, endpoint.as_deref()) {
+        Ok(ModelProbe::Available) => {}
+        Ok(ModelProbe::KeyRejected) => issues.push(ValidationIssue::error(
+            ConfigField::ApiKey,
+            IssueKind::KeyRejected,
+            "The provider rejected this API key.",
+        )),
+        Ok(ModelProbe::ModelMissing) => issues.push(match provider {
+            Provider::OpenAI => ValidationIssue::error(
+                ConfigField::Model,
+                IssueKind::ModelMissing,
+                "This key has no access to the transcription model.",
+            ),
+            Provider::AzureOpenAI => ValidationIssue::error(
+                ConfigField::Endpoint,
+                IssueKind::ModelMissing,
+                "Deployment not found. Check the deployment name in the endpoint URL.",
+            ),
+        }),
+        Err(e) => issues.push(ValidationIssue::warning(
+            ConfigField::Model,
+            IssueKind::ProbeFailed,
+            format!("Couldn't check the model: {}", e),
+        )),
+    }
+
+    issues
+}
+
+/// Validate while the user types. Format problems come back at once; a
+/// config without errors is probed after [`LIVE_PROBE_DEBOUNCE`] unless
+/// another call arrives meanwhile, which makes this one `Superseded`.
+pub async fn validate_live(
+    provider: Provider,
+    api_key: String,
+    endpoint: Option<String>,
+) -> LiveValidation {
+    let generation = LIVE_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let is_current = || LIVE_GENERATION.load(Ordering::SeqCst) == generation;
+
+    let issues = check_format(provider, api_key.trim(), endpoint.as_deref());
+    if has_errors(&issues) {
+        return LiveValidation::Checked {
+            issues,
+            probed: false,
+        };
+    }
+
+    tokio::time::sleep(LIVE_PROBE_DEBOUNCE).await;
+    if !is_current() {
+        return LiveValidation::Superseded;
+    }
+
+    let probe = tauri::async_runtime::spawn_blocking(move || {
+        validate(provider, &api_key, endpoint.as_deref(), true)
+    });
+    let issues = match probe.await {
+        Ok(issues) => issues,
+        Err(e) => {
+            eprintln!("[Validation] Live probe failed: {}", e);
+            return LiveValidation::Checked {
+                issues,
+                probed: false,
+            };
+        }
+    };
+
+    // The request can't be aborted mid-flight, but its result is dropped
+    if !is_current() {
+        return LiveValidation::Superseded;
+    }
+    LiveValidation::Checked {
+        issues,
+        probed: true,
+    }
+}
+
+/// Supersede the pending live validation, e.g. when onboarding moves on
+pub fn cancel_live() {
+    LIVE_GENERATION.fetch_add(1, Ordering::SeqCst);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AZURE_ENDPOINT: &str = "https://acme.openai.azure.com/openai/deployments/whisper";
+
+    fn severities(issues: &[ValidationIssue]) -> Vec<(ConfigField, IssueSeverity)> {
+        issues.iter().map(|i| (i.field, i.severity)).collect()
+    }
+
+    fn kinds(issues: &[ValidationIssue]) -> Vec<IssueKind> {
+        issues.iter().map(|i| i.kind).collect()
+    }
+
+    #[test]
+    fn test_openai_key_heuristics() {
+        assert!(
+            check_format(Provider::OpenAI, "sk-proj-abcdefghijklmnopqrstuvwxyz", None).is_empty()
+        );
+        assert_eq!(
+            severities(&check_format(Provider::OpenAI, "", None)),
+            [(ConfigField::ApiKey, IssueSeverity::Error)]
+        );
+        assert_eq!(
+            severities(&check_format(Provider::OpenAI, "sk-abc def", None)),
+            [(ConfigField::ApiKey, IssueSeverity::Error)]
+        );
+        assert_eq!(
+            severities(&check_format(Provider::OpenAI, "sk-short", None)),
+            [(ConfigField::ApiKey, IssueSeverity::Error)]
+        );
+        assert_eq!(
+            severities(&check_format(
+                Provider::OpenAI,
+                "abcdefghijklmnopqrstuvwxyz",
+                None
+            )),
+            [(ConfigField::ApiKey, IssueSeverity::Warning)]
+        );
+    }
+
+    #[test]
+    fn test_detects_common_paste_mistakes() {
+        assert_eq!(
+            kinds(&check_format(Provider::OpenAI, AZURE_ENDPOINT, None)),
+            [IssueKind::EndpointInKeyField]
+        );
+        assert_eq!(
+            kinds(&check_format(
+                Provider::OpenAI,
+                "OPENAI_API_KEY=sk-proj-abcdefghijklmnopqrstuvwxyz",
+                None
+            )),
+            [IssueKind::ExtraText]
+        );
+        assert_eq!(
+            kinds(&check_format(
+                Provider::OpenAI,
+                "k-proj-abcdefghijklmnopqrstuvwxyz",
+                None
+            )),
+            [IssueKind::TruncatedStart]
+        );
+        assert_eq!(
+            kinds(&check_format(
+                Provider::OpenAI,
+                "abcdefghijklmnopqrstuvwxyz",
+                None
+            )),
+            [IssueKind::MissingPrefix]
+        );
+    }
+
+    #[test]
+    fn test_azure_key_and_endpoint_heuristics() {
+        let key = "0123456789abcdef0123456789abcdef";
+        assert!(check_format(Provider::AzureOpenAI, key, Some(AZURE_ENDPOINT)).is_empty());
+        assert_eq!(
+            severities(&check_format(
+                Provider::AzureOpenAI,
+                "sk-proj-abcdefghijklmnopqrstuvwxyz",
+                Some(AZURE_ENDPOINT)
+            )),
+            [(ConfigField::ApiKey, IssueSeverity::Warning)]
+        );
+        assert_eq!(
+            severities(&check_format(
+                Provider::AzureOpenAI,
+                "abc123",
+                Some(AZURE_ENDPOINT)
+            )),
+            [(ConfigField::ApiKey, IssueSeverity::Warning)]
+        );
+        assert_eq!(
+            severities(&check_format(
+                Provider::AzureOpenAI,
+                key,
+                Some("http://acme.openai.azure.com/openai/deployments/whisper")
+            )),
+            [(ConfigField::Endpoint, IssueSeverity::Error)]
+        );
+        assert_eq!(
+            severities(&check_format(Provider::AzureOpenAI, "", None)),
+            [
+                (ConfigField::ApiKey, IssueSeverity::Error),
+                (ConfigField::Endpoint, IssueSeverity::Error)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_skips_probe_on_errors() {
+        // An empty key never reaches the network, probe or not
+        let issues = validate(Provider::OpenAI, "  ", None, true);
+        assert_eq!(
+            severities(&issues),
+            [(ConfigField::ApiKey, IssueSeverity::Error)]
+        );
+    }
+}
@@ -0,0 +1,158 @@
+//! Automatic transcription of a watched folder.
+//!
+//! With `watch_folder.enabled`, the folder is checked every few seconds. A new
+//! audio file without a transcript is transcribed once its size has stopped
+//! changing between two checks, so files still being copied or synced (voice
+//! memos arriving through iCloud) are left alone until they're complete. The
+//! transcript is written next to it as in a batch transcription, and
+//! `watch-folder-transcribed` reports each file.
+//!
+//! Files already in the folder when watching starts are left to batch
+//! transcription, and each file is tried once per launch, whatever the outcome.
+
+use crate::batch::{self, BatchOptions};
+use crate::clients::openai::OpenAIClient;
+use crate::config;
+use crate::energy;
+use crate::events::{self, WatchFolderTranscribed};
+use crate::supervisor::{RestartPolicy, SupervisorState};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri_plugin_store::StoreExt;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Sizes seen at the last check, to tell finished files from growing ones
+#[derive(Debug, Default)]
+struct Settling {
+    sizes: HashMap<PathBuf, u64>,
+}
+
+impl Settling {
+    /// Note the current size of each file and return the ones that haven't
+    /// changed since the last check
+    fn settled(&mut self, files: Vec<(PathBuf, u64)>) -> Vec<PathBuf> {
+        let previous = std::mem::take(&mut self.sizes);
+        let mut settled = Vec::new();
+        for (path, size) in files {
+            if size > 0 && previous.get(&path) == Some(&size) {
+                settled.push(path);
+            } else {
+                self.sizes.insert(path, size);
+            }
+        }
+        settled
+    }
+}
+
+/// Watch the configured folder for the lifetime of the app
+pub fn start(app_handle: &tauri::AppHandle, supervisor: &SupervisorState) {
+    let app_handle = app_handle.clone();
+    supervisor.spawn_thread("watch_folder", RestartPolicy::default(), move || {
+        run(&app_handle)
+    });
+}
+
+fn run(app_handle: &tauri::AppHandle) {
+    let client = OpenAIClient::new();
+    let mut watching: Option<PathBuf> = None;
+    let mut settling = Settling::default();
+    let mut handled: HashSet<PathBuf> = HashSet::new();
+    let mut last_error: Option<String> = None;
+
+    loop {
+        std::thread::sleep(energy::timer_interval(POLL_INTERVAL));
+
+        let Ok(store) = app_handle.store("config.json") else {
+            continue;
+        };
+        let config = config::load_app_config(&store);
+        let watch = &config.watch_folder;
+        let Some(folder) = watch.folder.as_ref().filter(|_| watch.enabled) else {
+            watching = None;
+            continue;
+        };
+        let folder = PathBuf::from(folder);
+
+        let files = match batch::collect_files(&folder, false) {
+            Ok(files) => files,
+            Err(e) => {
+                let error = e.to_string();
+                if last_error.as_ref() != Some(&error) {
+                    eprintln!("[Watch Folder] Can't read {:?}: {}", folder, error);
+                    last_error = Some(error);
+                }
+                continue;
+            }
+        };
+        last_error = None;
+
+        if watching.as_ref() != Some(&folder) {
+            println!("[Watch Folder] Watching {:?}", folder);
+            handled = files.into_iter().collect();
+            settling = Settling::default();
+            watching = Some(folder);
+            continue;
+        }
+
+        let options = BatchOptions {
+            concurrency: 1,
+            format: watch.format,
+            recursive: false,
+            overwrite: false,
+            filename_template: None,
+            title_template: None,
+        };
+        let candidates: Vec<(PathBuf, u64)> = files
+            .into_iter()
+            .filter(|audio| !handled.contains(audio) && !has_transcript(audio, &options))
+            .filter_map(|audio| {
+                let size = fs::metadata(&audio).ok()?.len();
+                Some((audio, size))
+            })
+            .collect();
+
+        for audio in settling.settled(candidates) {
+            println!("[Watch Folder] Transcribing {:?}", audio);
+            let outcome = batch::transcribe_one(&client, &audio, &options, &config);
+            handled.insert(audio.clone());
+            events::emit_or_log(
+                app_handle,
+                WatchFolderTranscribed {
+                    file: audio.to_string_lossy().to_string(),
+                    outcome,
+                },
+            );
+        }
+    }
+}
+
+fn has_transcript(audio: &Path, options: &BatchOptions) -> bool {
+    batch::sidecar_path(audio, options.format).exists()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_files_are_settled_once_their_size_stops_changing() {
+        let mut settling = Settling::default();
+        let memo = PathBuf::from("memo.m4a");
+        let empty = PathBuf::from("empty.m4a");
+
+        assert!(settling
+            .settled(vec![(memo.clone(), 100), (empty.clone(), 0)])
+            .is_empty());
+        // Still syncing
+        assert!(settling
+            .settled(vec![(memo.clone(), 250), (empty.clone(), 0)])
+            .is_empty());
+        assert_eq!(
+            settling.settled(vec![(memo.clone(), 250), (empty.clone(), 0)]),
+            [memo]
+        );
+    }
+}
@@ -0,0 +1,11 @@
+//! Reading WAV headers, for recordings and for files given to transcribe.
+
+use std::path::Path;
+
+/// Duration of a WAV file from its header. None for other formats, which are
+/// left to the provider.
+pub fn wav_duration_ms(path: &Path) -> Option<u64> {
+    let reader = hound::WavReader::open(path).ok()?;
+    let rate = reader.spec().sample_rate as u64;
+    (rate > 0).then(|| reader.duration() as u64 * 1000 / rate)
+}
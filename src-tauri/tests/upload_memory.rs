@@ -0,0 +1,115 @@
+//! A 25MB recording goes out as a multipart request without being loaded into
+//! memory.
+//!
+//! The upload is sent through reqwest to a local listener that reads it to the
+//! end, while a counting global allocator tracks peak heap use. The allocator
+//! replaces the one of this whole binary, so it holds this test only and runs
+//! without the test harness, single-threaded apart from the listener.
+
+use dictara_lib::api::providers::audio_part;
+use reqwest::blocking::multipart::Form;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+const FILE_SIZE: usize = 25 * 1024 * 1024;
+
+/// Allowed peak heap growth while the request is sent
+const MAX_PEAK_GROWTH: usize = 4 * 1024 * 1024;
+
+/// Tracks live heap bytes so the test can observe peak usage
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let now = ALLOCATED.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+            PEAK.fetch_max(now, Ordering::SeqCst);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::SeqCst);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Accept one request, read it to the end and answer with an empty 200.
+/// Returns how many bytes arrived.
+fn serve_one(listener: TcpListener) -> thread::JoinHandle<usize> {
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut buf = vec![0u8; 64 * 1024];
+        let mut received = 0;
+        loop {
+            // Past the file, the end of the body is whatever arrives before a pause
+            if received >= FILE_SIZE {
+                stream
+                    .set_read_timeout(Some(Duration::from_millis(200)))
+                    .unwrap();
+            }
+            match stream.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(read) => received += read,
+            }
+        }
+        stream
+            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+            .unwrap();
+        received
+    })
+}
+
+fn main() {
+    let path = std::env::temp_dir().join(format!("dictara-upload-{}.wav", std::process::id()));
+    {
+        let mut file = File::create(&path).unwrap();
+        let chunk = vec![0x5Au8; 1024 * 1024];
+        for _ in 0..FILE_SIZE / chunk.len() {
+            file.write_all(&chunk).unwrap();
+        }
+    }
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+    let server = serve_one(listener);
+    let client = reqwest::blocking::Client::new();
+
+    let baseline = ALLOCATED.load(Ordering::SeqCst);
+    PEAK.store(baseline, Ordering::SeqCst);
+
+    let form = Form::new().part("file", audio_part(&path).unwrap());
+    let response = client.post(&url).multipart(form).send();
+
+    let peak_growth = PEAK.load(Ordering::SeqCst).saturating_sub(baseline);
+    let received = server.join().unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    assert!(response.unwrap().status().is_success());
+    assert!(
+        received > FILE_SIZE,
+        "only {} bytes of the request arrived",
+        received
+    );
+    assert!(
+        peak_growth < MAX_PEAK_GROWTH,
+        "peak heap growth {} bytes while streaming",
+        peak_growth
+    );
+    println!(
+        "upload_memory: streamed {} bytes with {} bytes peak heap growth",
+        received, peak_growth
+    );
+}
@@ -15,6 +15,12 @@ async loadAppConfig() : Promise<Result<AppConfig, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Settings enforced through MDM, for preferences to show them as locked
+ */
+async getManagedConfig() : Promise<ManagedConfig> {
+    return await TAURI_INVOKE("get_managed_config");
+},
 async saveAppConfig(activeProvider: string | null) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("save_app_config", { activeProvider }) };
@@ -23,6 +29,34 @@ async saveAppConfig(activeProvider: string | null) : Promise<Result<null, string
     else return { status: "error", error: e  as any };
 }
 },
+async updateAppConfig(config: AppConfig) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_app_config", { config }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Capture the next key press and make it the push-to-talk trigger.
+ * Returns None if no key was pressed within 10 seconds.
+ */
+async learnTriggerKey() : Promise<Result<TriggerKey | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("learn_trigger_key") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async describePipeline() : Promise<Result<PipelineStepInfo[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("describe_pipeline") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async loadOpenaiConfig() : Promise<Result<OpenAIConfig | null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("load_openai_config") };
@@ -79,14 +113,79 @@ async deleteAzureOpenaiConfig() : Promise<Result<null, string>> {
     else return { status: "error", error: e  as any };
 }
 },
-async testAzureOpenaiConfig(apiKey: string, endpoint: string) : Promise<Result<boolean, string>> {
+/**
+ * Check an Azure key and endpoint. The default check sends no audio; `deep`
+ * runs a real (billed) transcription of a second of silence.
+ */
+async testAzureOpenaiConfig(apiKey: string, endpoint: string, deep: boolean) : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("test_azure_openai_config", { apiKey, endpoint, deep }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Check a provider config for mistakes before it's saved. With `probe`, also
+ * confirms with the provider that the key and model work (not billed).
+ */
+async validateProviderConfig(provider: Provider, apiKey: string, endpoint: string | null, probe: boolean) : Promise<ValidationIssue[]> {
+    return await TAURI_INVOKE("validate_provider_config", { provider, apiKey, endpoint, probe });
+},
+/**
+ * Price and latency of each provider, for comparing them in the picker
+ */
+async getProviderCatalog() : Promise<ProviderCatalog> {
+    return await TAURI_INVOKE("get_provider_catalog");
+},
+/**
+ * Check whether the keychain can be read. Returns None when it's accessible.
+ */
+async getKeychainStatus() : Promise<KeychainFailure | null> {
+    return await TAURI_INVOKE("get_keychain_status");
+},
+/**
+ * Bearer token the phone intake expects, for setting up the Shortcut. None
+ * until the intake has started once.
+ */
+async getIntakeToken() : Promise<Result<string | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_intake_token") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Use an API key for the current run only, without storing it in the
+ * keychain. Lets users keep dictating while the keychain is locked.
+ */
+async setSessionApiKey(provider: Provider, apiKey: string, endpoint: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_session_api_key", { provider, apiKey, endpoint }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Providers whose key was accepted before but is now rejected
+ */
+async getReauthProviders() : Promise<Provider[]> {
+    return await TAURI_INVOKE("get_reauth_providers");
+},
+async startRecording() : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("test_azure_openai_config", { apiKey, endpoint }) };
+    return { status: "ok", data: await TAURI_INVOKE("start_recording") };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Stop the current recording and transcribe it. Unlike `toggle_recording`,
+ * this never starts a new one, whatever state the controller is in.
+ */
 async stopRecording() : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("stop_recording") };
@@ -95,6 +194,17 @@ async stopRecording() : Promise<Result<null, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+async toggleRecording() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("toggle_recording") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Discard the current recording without transcribing it
+ */
 async cancelRecording() : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("cancel_recording") };
@@ -103,6 +213,18 @@ async cancelRecording() : Promise<Result<null, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Capture the selection in the app in front and record how to rewrite it;
+ * the rewritten text replaces the selection
+ */
+async startRewriteSelection() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_rewrite_selection") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async retryTranscription() : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("retry_transcription") };
@@ -111,6 +233,28 @@ async retryTranscription() : Promise<Result<null, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Paste (or otherwise deliver) the transcript held for exceeding `max_delivery_chars`
+ */
+async deliverHeldTranscript() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("deliver_held_transcript") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Drop the held transcript and close the popup
+ */
+async discardHeldTranscript() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("discard_held_transcript") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async dismissError() : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("dismiss_error") };
@@ -127,6 +271,19 @@ async resizePopupForError() : Promise<Result<null, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Size the recording popup for what it shows. `desired_size` overrides the
+ * mode's default, e.g. to fit a long message; it's clamped to the monitor.
+ * Returns the size the popup got.
+ */
+async setPopupLayout(mode: PopupMode, desiredSize: PopupSize | null) : Promise<Result<PopupSize, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_popup_layout", { mode, desiredSize }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async registerAudioLevelChannel(channel: TAURI_CHANNEL<number>) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("register_audio_level_channel", { channel }) };
@@ -136,78 +293,1546 @@ async registerAudioLevelChannel(channel: TAURI_CHANNEL<number>) : Promise<Result
 }
 },
 /**
- * Manual update check triggered from frontend
- * Returns: true if update is available, false otherwise
+ * Practise an onboarding step with the real trigger key. Until
+ * `stop_onboarding_practice`, transcripts are only emitted as
+ * `onboarding-practice-transcript` and never pasted.
  */
-async checkForUpdates(showNoUpdateMessage: boolean) : Promise<Result<boolean, string>> {
+async startOnboardingPractice(step: OnboardingStep) : Promise<Result<null, string>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("check_for_updates", { showNoUpdateMessage }) };
+    return { status: "ok", data: await TAURI_INVOKE("start_onboarding_practice", { step }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
+},
+/**
+ * Leave onboarding practice so dictations are delivered again
+ */
+async stopOnboardingPractice() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("stop_onboarding_practice") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
 }
+},
+/**
+ * Remember `step`, then restart so a newly granted permission takes effect.
+ * Onboarding reopens at `step` on the next launch.
+ */
+async restartForOnboarding(step: RestartStep) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("restart_for_onboarding", { step }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
 }
-
-/** user-defined events **/
-
-
-export const events = __makeEvents__<{
-recordingStateChanged: RecordingStateChanged
-}>({
-recordingStateChanged: "recording-state-changed"
-})
-
-/** user-defined constants **/
-
-
-
-/** user-defined types **/
-
+},
 /**
- * App configuration (stored locally)
+ * Where onboarding resumes after `restart_for_onboarding`, with freshly
+ * checked permissions. None when this launch wasn't such a restart.
  */
-export type AppConfig = { 
+async getOnboardingResume() : Promise<OnboardingResume | null> {
+    return await TAURI_INVOKE("get_onboarding_resume");
+},
 /**
- * Currently active provider (only one can be active)
+ * Validate a provider config as the user types: call on every change and
+ * ignore `superseded` results. Format issues come back at once; the provider
+ * is probed once typing pauses.
  */
-active_provider: Provider | null }
+async validateProviderConfigLive(provider: Provider, apiKey: string, endpoint: string | null) : Promise<LiveValidation> {
+    return await TAURI_INVOKE("validate_provider_config_live", { provider, apiKey, endpoint });
+},
 /**
- * Azure OpenAI provider configuration (stored in keychain)
+ * Supersede the pending live validation
  */
-export type AzureOpenAIConfig = { api_key: string; endpoint: string }
+async cancelLiveValidation() : Promise<null> {
+    return await TAURI_INVOKE("cancel_live_validation");
+},
 /**
- * OpenAI provider configuration (stored in keychain)
+ * Transcribe every audio file in `folder` in the background, writing a
+ * transcript next to each. Progress arrives as `batch-progress` events.
+ * Returns the number of files found.
  */
-export type OpenAIConfig = { api_key: string }
+async transcribeFolder(folder: string, options: BatchOptions) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("transcribe_folder", { folder, options }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Provider types supported by the application
+ * Transcribe the audio file copied in Finder and put the transcript on the
+ * clipboard. Returns the transcript.
  */
-export type Provider = "open_ai" | "azure_open_ai"
+async transcribeCopiedFile() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("transcribe_copied_file") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Recording state change event - single event stream for all state transitions
+ * Every action that applies right now, titled for the command palette
  */
-export type RecordingStateChanged = 
+async listActions() : Promise<PaletteAction[]> {
+    return await TAURI_INVOKE("list_actions");
+},
 /**
- * Recording has started
+ * Close the command palette and run `action`
  */
-{ state: "started" } | 
+async invokeAction(action: AppAction) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("invoke_action", { action }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async openCommandPalette() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("open_command_palette") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getUsageStats() : Promise<UsageStats> {
+    return await TAURI_INVOKE("get_usage_stats");
+},
+async getStatsSummary() : Promise<StatsUpdated> {
+    return await TAURI_INVOKE("get_stats_summary");
+},
+async exportUsageStats(path: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_usage_stats", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Recording is being transcribed
+ * Write the filler words and pipeline steps to `path` as a pack named `name`
  */
-{ state: "transcribing" } | 
+async exportRulePack(path: string, name: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_rule_pack", { path, name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Recording completed successfully
+ * What importing the pack at `path` would change, without changing anything
  */
-{ state: "stopped"; text: string } | 
+async previewRulePack(path: string) : Promise<Result<PackImport, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("preview_rule_pack", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Recording was cancelled by user
+ * Merge the pack at `path` into the config, settling conflicting steps with
+ * `resolution`
  */
-{ state: "cancelled" } | 
+async importRulePack(path: string, resolution: Resolution) : Promise<Result<PackImport, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_rule_pack", { path, resolution }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getHistory() : Promise<HistoryEntry[]> {
+    return await TAURI_INVOKE("get_history");
+},
+async clearHistory() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("clear_history") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * An error occurred during recording or transcription
+ * Write every history entry to `folder` as a Markdown file, named with
+ * `export_templates`. Returns how many files were written.
  */
-{ state: "error"; errorType: string; errorMessage: string; userMessage: string; audioFilePath: string | null }
+async exportHistory(folder: string) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_history", { folder }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Ask for calendar access so meetings can be looked up, waiting for the
+ * user's answer. Returns whether access is granted.
+ */
+async requestCalendarAccess() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("request_calendar_access") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getOutputDevices() : Promise<string[]> {
+    return await TAURI_INVOKE("get_output_devices");
+},
+/**
+ * Installed speech voices for spoken cues
+ */
+async getVoices() : Promise<string[]> {
+    return await TAURI_INVOKE("get_voices");
+},
+/**
+ * Speak the last transcription with the cue voice, replacing a read-back
+ * already speaking
+ */
+async readBackLastRecording() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_back_last_recording") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async detectDictationConflicts() : Promise<DictationConflict[]> {
+    return await TAURI_INVOKE("detect_dictation_conflicts");
+},
+async getFnKeyUsage() : Promise<FnKeyUsage> {
+    return await TAURI_INVOKE("get_fn_key_usage");
+},
+async disableFnKeyAction() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("disable_fn_key_action") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getPermissionStatus() : Promise<PermissionStatus> {
+    return await TAURI_INVOKE("get_permission_status");
+},
+/**
+ * System reduce motion and increase contrast settings
+ */
+async getDisplayOptions() : Promise<DisplayOptions> {
+    return await TAURI_INVOKE("get_display_options");
+},
+async getStateJournal() : Promise<JournalEntry[]> {
+    return await TAURI_INVOKE("get_state_journal");
+},
+/**
+ * Cancel the running transcription, aborting its upload. Returns how many
+ * were cancelled.
+ */
+async cancelAllTranscriptions() : Promise<number> {
+    return await TAURI_INVOKE("cancel_all_transcriptions");
+},
+async getComponentHealth() : Promise<ComponentHealth[]> {
+    return await TAURI_INVOKE("get_component_health");
+},
+/**
+ * What works differently because the key listener fell back from the event
+ * tap; empty normally
+ */
+async getInputLimitations() : Promise<string[]> {
+    return await TAURI_INVOKE("get_input_limitations");
+},
+/**
+ * Whether the event tap could be created at the last check
+ */
+async getInputCaptureStatus() : Promise<InputCaptureStatus> {
+    return await TAURI_INVOKE("get_input_capture_status");
+},
+/**
+ * Event tap, audio level and timer activity since launch, for debugging
+ * battery usage
+ */
+async getEnergyStats() : Promise<EnergyStats> {
+    return await TAURI_INVOKE("get_energy_stats");
+},
+/**
+ * Manual update check triggered from frontend
+ * Returns: true if update is available, false otherwise
+ */
+async checkForUpdates(showNoUpdateMessage: boolean) : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("check_for_updates", { showNoUpdateMessage }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+}
+}
+
+/** user-defined events **/
+
+
+export const events = __makeEvents__<{
+batchFinished: BatchFinished,
+batchProgress: BatchProgress,
+componentHealth: ComponentHealth,
+dictationCompleted: DictationCompleted,
+displayOptions: DisplayOptions,
+historyChanged: HistoryChanged,
+inputCaptureStatus: InputCaptureStatus,
+inputDegraded: InputDegraded,
+intakeTranscribed: IntakeTranscribed,
+onboardingPracticeTranscript: OnboardingPracticeTranscript,
+onboardingStepCompleted: OnboardingStepCompleted,
+pasteDiagnosed: PasteDiagnosed,
+pasteFailed: PasteFailed,
+permissionStatus: PermissionStatus,
+providerAuthChanged: ProviderAuthChanged,
+recordingControls: RecordingControls,
+recordingLocked: RecordingLocked,
+recordingStateChanged: RecordingStateChanged,
+statsUpdated: StatsUpdated,
+trayMenuChanged: TrayMenuChanged,
+watchFolderTranscribed: WatchFolderTranscribed
+}>({
+batchFinished: "batch-finished",
+batchProgress: "batch-progress",
+componentHealth: "component-health",
+dictationCompleted: "dictation-completed",
+displayOptions: "display-options",
+historyChanged: "history-changed",
+inputCaptureStatus: "input-capture-status",
+inputDegraded: "input-degraded",
+intakeTranscribed: "intake-transcribed",
+onboardingPracticeTranscript: "onboarding-practice-transcript",
+onboardingStepCompleted: "onboarding-step-completed",
+pasteDiagnosed: "paste-diagnosed",
+pasteFailed: "paste-failed",
+permissionStatus: "permission-status",
+providerAuthChanged: "provider-auth-changed",
+recordingControls: "recording-controls",
+recordingLocked: "recording-locked",
+recordingStateChanged: "recording-state-changed",
+statsUpdated: "stats-updated",
+trayMenuChanged: "tray-menu-changed",
+watchFolderTranscribed: "watch-folder-transcribed"
+})
+
+/** user-defined constants **/
+
+
+
+/** user-defined types **/
+
+export type ActionItem = { task: string; 
+/**
+ * Who should do it, if the transcript says
+ */
+owner: string | null; 
+/**
+ * When it's due, as said in the transcript (e.g. "by Friday")
+ */
+due: string | null }
+/**
+ * Where action items go, in order
+ */
+export type ActionItemSinkConfig = 
+/**
+ * Create a reminder per item. `list` is the Reminders list name
+ * (None = the default list).
+ */
+{ type: "reminders"; list: string | null } | 
+/**
+ * Append unchecked Markdown tasks to a file, e.g. `~/Notes/todo.md`
+ */
+{ type: "todoFile"; path: string }
+/**
+ * Where the cursor goes once the paste sink has pasted
+ */
+export type AfterPaste = 
+/**
+ * Leave it right after the pasted text
+ */
+"stay" | 
+/**
+ * Move to the end of the line (⌘→)
+ */
+"lineEnd" | 
+/**
+ * Add a line break (⇧↩, so chat apps don't send) and continue on it
+ */
+"newline" | 
+/**
+ * Select the pasted text for a quick review or retype
+ */
+"selectPasted"
+export type AppAction = { kind: "switchProvider"; provider: Provider } | { kind: "toggle"; setting: ToggleSetting } | { kind: "openPreferences" } | { kind: "openLogs" } | { kind: "retryTranscription" } | { kind: "pasteLastRecording" } | 
+/**
+ * Paste a history entry again, newest first
+ */
+{ kind: "pasteHistoryEntry"; index: number } | { kind: "readBackLastRecording" } | 
+/**
+ * Dictate how to rewrite the text selected in the app in front
+ */
+{ kind: "rewriteSelection" } | { kind: "transcribeCopiedFile" } | { kind: "cancelTranscriptions" } | { kind: "resetInput" }
+/**
+ * App configuration (stored locally)
+ */
+export type AppConfig = { 
+/**
+ * Currently active provider (only one can be active)
+ */
+active_provider: Provider | null; 
+/**
+ * Show a brief "No speech detected" notice when the transcript comes back empty
+ */
+show_no_speech_notice: boolean; 
+/**
+ * Feedback sound settings
+ */
+sounds: SoundConfig; 
+/**
+ * Schedule during which sounds and notices are suppressed
+ */
+quiet_hours: QuietHoursConfig; 
+/**
+ * Meetings looked up in the calendar
+ */
+calendar: CalendarConfig; 
+/**
+ * Return a canned transcript instead of calling the provider (for demos)
+ */
+dry_run: boolean; 
+/**
+ * Minimum time between transcription requests in milliseconds (0 = no limit)
+ */
+min_transcription_interval_ms: number; 
+/**
+ * Let any trigger source stop a session, not just the one that started it
+ */
+allow_cross_source_stop: boolean; 
+/**
+ * Where the transcript goes, in order
+ */
+output_sinks: OutputSinkConfig[]; 
+/**
+ * Order and enabled state of transcript processing steps (empty = defaults)
+ */
+pipeline: PipelineStepConfig[]; 
+/**
+ * External executables that can rewrite the transcript
+ */
+plugins: PluginConfig[]; 
+/**
+ * Key (or modifier chord) held for push-to-talk
+ */
+trigger_key: TriggerKey; 
+/**
+ * Menu bar icon style
+ */
+tray_icon_theme: TrayIconTheme; 
+/**
+ * Trade UI smoothness and background polling for battery life
+ */
+energy_profile: EnergyProfile; 
+/**
+ * Maximum audio level updates per second sent to the popup (1-120).
+ * Peaks between updates are held, so lower rates still show loud bursts.
+ */
+audio_level_rate_hz: number; 
+/**
+ * Sample rate and bit depth of recordings
+ */
+recorder: RecorderConfig; 
+/**
+ * Pick the provider per dictation by price and latency
+ */
+provider_policy: ProviderPolicyConfig; 
+/**
+ * Chat model for post-processing steps such as summaries
+ */
+llm: LlmConfig; 
+/**
+ * Summaries of long hands-free recordings
+ */
+summary: SummaryConfig; 
+/**
+ * Past transcripts kept on this Mac
+ */
+history: HistoryConfig; 
+/**
+ * Latest dictations sent along as context for the next one
+ */
+recent_context: RecentContextConfig; 
+/**
+ * Names and titles of exported transcripts
+ */
+export_templates: ExportTemplateConfig; 
+/**
+ * Mark auto-pasted text as transient so clipboard managers (Paste, Maccy)
+ * don't keep it in their history
+ */
+hide_from_clipboard_managers: boolean; 
+/**
+ * Have VoiceOver speak state changes ("Recording started", "Pasted 42 words")
+ */
+announcements: boolean; 
+/**
+ * Transcripts longer than this many characters wait for confirmation
+ * before they're delivered (0 = no limit)
+ */
+max_delivery_chars: number; 
+/**
+ * Words dropped by the `remove_filler_words` pipeline step
+ */
+filler_words: FillerWordsConfig; 
+/**
+ * Include per-stage timings in `dictation-completed`, to find out where
+ * a slow dictation spent its time
+ */
+show_timing_breakdown: boolean; watch_folder: WatchFolderConfig; intake: IntakeConfig }
+/**
+ * Sample rate and bit depth of a recording. Recordings are always mono.
+ */
+export type AudioFormat = { sample_rate: number; 
+/**
+ * 16 or 24
+ */
+bits_per_sample: number; 
+/**
+ * Keep the microphone's own rate when it's at most `sample_rate`, which
+ * skips local resampling. Only for providers that resample on their side.
+ */
+native_rate: boolean }
+/**
+ * Azure OpenAI provider configuration (stored in keychain)
+ */
+export type AzureOpenAIConfig = { api_key: string; endpoint: string }
+/**
+ * Result for one file of a batch transcription
+ */
+export type BatchFileOutcome = { status: "transcribed"; 
+/**
+ * Path of the transcript file
+ */
+output: string } | { status: "skipped"; reason: string } | { status: "failed"; error: string }
+/**
+ * The batch transcription finished
+ */
+export type BatchFinished = { transcribed: number; skipped: number; failed: number }
+export type BatchOptions = { 
+/**
+ * Files transcribed at the same time (1-8)
+ */
+concurrency: number; format: SidecarFormat; 
+/**
+ * Include files in subfolders
+ */
+recursive: boolean; 
+/**
+ * Transcribe files that already have a transcript, replacing it
+ */
+overwrite: boolean; 
+/**
+ * Sidecar file name without extension (None = the audio file's name).
+ * With a template, existing transcripts are only found after transcribing.
+ */
+filenameTemplate: string | null; 
+/**
+ * Markdown heading (None = the audio file's name)
+ */
+titleTemplate: string | null }
+/**
+ * A file of the running batch transcription finished
+ */
+export type BatchProgress = { 
+/**
+ * Path of the audio file
+ */
+file: string; outcome: BatchFileOutcome; 
+/**
+ * Files finished so far, including this one
+ */
+completed: number; total: number }
+/**
+ * What changes while a calendar meeting is in progress (macOS only)
+ */
+export type CalendarConfig = { 
+/**
+ * Look up the meeting in progress. Needs calendar access, asked for
+ * with `request_calendar_access`.
+ */
+enabled: boolean; 
+/**
+ * Suppress sounds and notices during a meeting, like quiet hours
+ */
+mute_sounds: boolean; 
+/**
+ * Tag history entries with the meeting's title
+ */
+tag_history: boolean }
+/**
+ * Health of a supervised component, emitted whenever its status changes
+ */
+export type ComponentHealth = { 
+/**
+ * Component name, e.g. "controller" or "key_listener"
+ */
+name: string; status: ComponentStatus; restarts: number; lastError: string | null }
+/**
+ * Lifecycle state of a supervised background component
+ */
+export type ComponentStatus = "running" | 
+/**
+ * Crashed and waiting for the next restart attempt
+ */
+"restarting" | 
+/**
+ * Exited normally (e.g. on shutdown)
+ */
+"stopped" | 
+/**
+ * Gave up after too many restarts - the component is degraded
+ */
+"failed"
+export type ConfigField = "apiKey" | "endpoint" | "model"
+/**
+ * Totals for a single day
+ */
+export type DailyStats = { dictations: number; words: number }
+/**
+ * Summary of a successful dictation, emitted once it has been delivered.
+ * History and stats record the same values.
+ */
+export type DictationCompleted = { 
+/**
+ * Local time in RFC 3339 format
+ */
+timestamp: string; 
+/**
+ * Length of the recording
+ */
+durationMs: number; 
+/**
+ * Words in the transcript after post-processing
+ */
+words: number; provider: Provider | null; 
+/**
+ * Time the provider took to return the transcript
+ */
+latencyMs: number; mode: DictationMode; 
+/**
+ * App that was in front while dictating
+ */
+app: string | null; 
+/**
+ * Tags this dictation's `[Trace]` log lines
+ */
+traceId: number; 
+/**
+ * Time spent in each stage, with `show_timing_breakdown` on
+ */
+timing: StageTiming[] | null }
+/**
+ * A tool that may react to the same trigger as Dictara
+ */
+export type DictationConflict = { 
+/**
+ * Display name of the conflicting tool
+ */
+tool: string; 
+/**
+ * What the user should change to stop double-handling
+ */
+guidance: string }
+/**
+ * How a dictation was recorded
+ */
+export type DictationMode = 
+/**
+ * Trigger held for the whole recording
+ */
+"pushToTalk" | 
+/**
+ * Recording locked with Space while holding the trigger
+ */
+"handsFree" | 
+/**
+ * Saved recording transcribed again after a failure
+ */
+"retry"
+/**
+ * System display accessibility options, emitted when the user changes them
+ */
+export type DisplayOptions = { 
+/**
+ * System Settings → Accessibility → Display → Reduce motion
+ */
+reduceMotion: boolean; 
+/**
+ * System Settings → Accessibility → Display → Increase contrast
+ */
+increaseContrast: boolean }
+/**
+ * Energy profile for background work
+ */
+export type EnergyProfile = "balanced" | 
+/**
+ * Background timers wake less often and the tray icon doesn't animate
+ */
+"lowPower"
+/**
+ * Snapshot of energy-related activity since launch
+ */
+export type EnergyStats = { profile: EnergyProfile; uptimeSecs: number; 
+/**
+ * Keyboard events that went through the event tap
+ */
+tapEvents: number; 
+/**
+ * Audio level messages sent to the popup
+ */
+levelSends: number; 
+/**
+ * Audio buffers whose level wasn't computed because the popup was hidden
+ */
+levelSendsSkipped: number; 
+/**
+ * Wakeups of background polling timers (permissions, updater)
+ */
+timerWakeups: number }
+/**
+ * Templates for saved transcripts; see `templates` for the variables
+ */
+export type ExportTemplateConfig = { 
+/**
+ * File name without extension
+ */
+filename: string; 
+/**
+ * Heading at the top of the file
+ */
+title: string }
+/**
+ * Filler words for the `remove_filler_words` pipeline step
+ */
+export type FillerWordsConfig = { 
+/**
+ * Built-in lists to use, by language code: "en", "de", "fr" or "es"
+ */
+languages: string[]; 
+/**
+ * Extra words or phrases to drop, e.g. "like" or "sort of"
+ */
+custom: string[] }
+/**
+ * What the Fn (globe) key does ("Press 🌐 key to" in System Settings → Keyboard)
+ */
+export type FnKeyUsage = "doNothing" | "changeInputSource" | "showEmoji" | "startDictation" | 
+/**
+ * Not set or not on macOS
+ */
+"unknown"
+/**
+ * History was added to or cleared
+ */
+export type HistoryChanged = { entries: number }
+/**
+ * Transcript history settings
+ */
+export type HistoryConfig = { enabled: boolean; 
+/**
+ * Oldest entries are dropped beyond this
+ */
+max_entries: number }
+export type HistoryEntry = { 
+/**
+ * Local time in RFC 3339 format
+ */
+timestamp: string; transcript: string; summary: string | null; 
+/**
+ * Action items extracted with the summary
+ */
+action_items: ActionItem[]; 
+/**
+ * Provider that transcribed the recording
+ */
+provider: Provider | null; 
+/**
+ * App that was in front while dictating
+ */
+app: string | null; 
+/**
+ * Title of the calendar meeting in progress while dictating
+ */
+meeting: string | null; duration_ms: number }
+/**
+ * Whether an event tap can be created, checked at launch and whenever a
+ * permission changes
+ */
+export type InputCaptureStatus = { 
+/**
+ * The trigger key will be picked up
+ */
+functional: boolean; 
+/**
+ * Why the event tap couldn't be created
+ */
+error: string | null }
+/**
+ * The key listener fell back to rdev because the event tap couldn't be created
+ */
+export type InputDegraded = { 
+/**
+ * What works differently now, for the notice
+ */
+limitations: string[] }
+/**
+ * Local network endpoint for audio pushed from a phone, e.g. an iOS
+ * Shortcut sharing a voice memo
+ */
+export type IntakeConfig = { enabled: boolean; port: number; 
+/**
+ * Format of the transcript written next to each memo
+ */
+format: SidecarFormat }
+/**
+ * A memo pushed to the local network intake was transcribed
+ */
+export type IntakeTranscribed = { 
+/**
+ * Path of the saved audio file
+ */
+file: string; outcome: BatchFileOutcome }
+/**
+ * What went wrong, so the UI can react to specific mistakes
+ */
+export type IssueKind = "missing" | 
+/**
+ * Spaces or line breaks inside the key
+ */
+"whitespace" | 
+/**
+ * Shorter than any real key, usually a partial copy
+ */
+"tooShort" | 
+/**
+ * The leading characters of the key didn't make it into the paste
+ */
+"truncatedStart" | 
+/**
+ * An OpenAI key without the "sk-" prefix
+ */
+"missingPrefix" | 
+/**
+ * Copied together with `OPENAI_API_KEY=`, `Bearer ` or quotes
+ */
+"extraText" | 
+/**
+ * An endpoint URL pasted into the API key field
+ */
+"endpointInKeyField" | 
+/**
+ * A key for the other provider
+ */
+"wrongProvider" | 
+/**
+ * Outside the usual key lengths
+ */
+"unusualLength" | 
+"invalidEndpoint" | 
+/**
+ * The provider rejected the key
+ */
+"keyRejected" | 
+/**
+ * The transcription model or Azure deployment wasn't found
+ */
+"modelMissing" | 
+/**
+ * The provider couldn't be reached to check the key
+ */
+"probeFailed"
+export type IssueSeverity = 
+/**
+ * The config can't work as entered
+ */
+"error" | 
+/**
+ * Unusual, but might still work
+ */
+"warning"
+/**
+ * A single journal entry
+ */
+export type JournalEntry = { 
+/**
+ * Local time in RFC 3339 with milliseconds
+ */
+timestamp: string; event: JournalEvent }
+/**
+ * What happened
+ */
+export type JournalEvent = 
+/**
+ * A command reached the Controller (key events arrive as commands)
+ */
+{ kind: "command"; command: string } | 
+/**
+ * The Controller changed state
+ */
+{ kind: "transition"; from: string; to: string } | 
+/**
+ * The provider policy picked a provider for a dictation
+ */
+{ kind: "providerSelected"; provider: string; reason: string }
+/**
+ * Why a keychain operation failed, for targeted user messages
+ */
+export type KeychainFailure = 
+/**
+ * The keychain is locked, or a prompt to unlock it can't be shown
+ */
+"locked" | 
+/**
+ * The user (or a managed policy) denied access to the item
+ */
+"denied" | 
+/**
+ * No keychain is available at all
+ */
+"unavailable" | "other"
+/**
+ * How sure the analyzer is about a problem, most likely first
+ */
+export type Likelihood = 
+/**
+ * The error or the system state points straight at it
+ */
+"likely" | 
+/**
+ * It's present but may not be what stopped the paste
+ */
+"possible"
+/**
+ * Result of a live validation
+ */
+export type LiveValidation = 
+/**
+ * A newer call or `cancel_live_validation` came in first; ignore this one
+ */
+{ status: "superseded" } | { status: "checked"; issues: ValidationIssue[]; probed: boolean }
+/**
+ * Chat model used with the active provider's key. OpenAI takes a model name;
+ * Azure needs a chat deployment on the same resource as the Whisper deployment.
+ */
+export type LlmConfig = { 
+/**
+ * OpenAI model name
+ */
+model: string; 
+/**
+ * Azure OpenAI chat deployment name (None = not available on Azure)
+ */
+azure_deployment: string | null; 
+/**
+ * Instructions for the `llm_cleanup` step, a template (see `templates`)
+ */
+cleanup_prompt: string; 
+/**
+ * Instructions for rewriting a selection as the user dictates
+ */
+rewrite_prompt: string }
+/**
+ * The managed profile's settings; empty without a profile
+ */
+export type ManagedConfig = { provider: Provider | null; azure_endpoint: string | null; offline: boolean; blocked_apps: string[] }
+/**
+ * A dynamic part of the tray menu
+ */
+export type MenuSection = 
+/**
+ * "Paste Last Recording" and "Read Back Last Recording"
+ */
+"lastRecording" | 
+/**
+ * "Re-enter API Key…"
+ */
+"reauth" | 
+/**
+ * "Input Capture Not Working…"
+ */
+"inputCapture"
+/**
+ * Transcript of a practice dictation. It's only sent to onboarding: never
+ * pasted, and not kept in history or stats.
+ */
+export type OnboardingPracticeTranscript = { step: OnboardingStep; text: string; mode: DictationMode }
+/**
+ * Onboarding picking up where it left off before a restart
+ */
+export type OnboardingResume = { step: RestartStep; 
+/**
+ * Checked after the restart, so the step shows whether the grant took
+ */
+permissions: PermissionStatus }
+/**
+ * Onboarding step practised with the real trigger key
+ */
+export type OnboardingStep = 
+/**
+ * Hold Fn while speaking, release to transcribe
+ */
+"fnHold" | 
+/**
+ * Press Space while holding Fn to lock the recording
+ */
+"fnSpace"
+/**
+ * The user did what the practised step asks: a push-to-talk dictation for
+ * `fnHold`, locking the recording for `fnSpace`
+ */
+export type OnboardingStepCompleted = { step: OnboardingStep }
+/**
+ * OpenAI provider configuration (stored in keychain)
+ */
+export type OpenAIConfig = { api_key: string }
+/**
+ * Configured sink, in the order it should run
+ */
+export type OutputSinkConfig = 
+/**
+ * Put the text on the clipboard and press ⌘V
+ */
+{ type: "paste"; after_paste: AfterPaste } | 
+/**
+ * Only copy the text to the clipboard
+ */
+{ type: "clipboard" } | 
+/**
+ * Append the text as a new line to a file
+ */
+{ type: "fileAppend"; path: string } | 
+/**
+ * POST `{"text": ...}` as JSON to a URL, or `body` rendered as a JSON
+ * template (see `templates`), e.g. `{"text": "{text}", "app": "{app}"}`
+ */
+{ type: "webhook"; url: string; body: string | null }
+/**
+ * What importing a pack changes, or would change when previewing
+ */
+export type PackImport = { name: string; 
+/**
+ * Filler words and languages that weren't there yet
+ */
+added_words: number; 
+/**
+ * Filler words and languages already there
+ */
+duplicate_words: number; conflicts: StepConflict[]; 
+/**
+ * Plugin steps and steps this version doesn't know
+ */
+skipped_steps: string[] }
+/**
+ * An action as the palette lists it
+ */
+export type PaletteAction = { action: AppAction; title: string; 
+/**
+ * Secondary line, e.g. when a history entry was dictated
+ */
+subtitle: string | null }
+/**
+ * Why a paste failed or a dictation came back empty, sent after
+ * `paste-failed` or the no-speech notice
+ */
+export type PasteDiagnosed = { 
+/**
+ * Most likely cause first; empty when none of the known causes apply
+ */
+explanations: PasteExplanation[] }
+export type PasteExplanation = { problem: PasteProblem; likelihood: Likelihood; 
+/**
+ * What went wrong, in the user's terms
+ */
+explanation: string; 
+/**
+ * What the user can do about it
+ */
+fix: string }
+/**
+ * Auto-paste failed after a successful transcription
+ */
+export type PasteFailed = { 
+/**
+ * The transcribed text that could not be pasted
+ */
+text: string; 
+/**
+ * Technical error message for debugging
+ */
+reason: string; 
+/**
+ * User-friendly, actionable error message
+ */
+userMessage: string; 
+/**
+ * Whether the text was left on the clipboard for a manual paste
+ */
+textOnClipboard: boolean }
+/**
+ * A known reason for a dictation not ending up in the target app
+ */
+export type PasteProblem = "secureInput" | "emptyTranscript" | "targetAppChanged" | "accessibilityRevoked" | "clipboardLocked"
+/**
+ * State of a single privacy permission
+ */
+export type PermissionState = "granted" | "denied" | 
+/**
+ * The user hasn't been asked yet
+ */
+"notDetermined"
+/**
+ * Current status of all permissions, emitted periodically
+ */
+export type PermissionStatus = { accessibility: PermissionState; microphone: PermissionState; inputMonitoring: PermissionState }
+/**
+ * Step entry in the config
+ */
+export type PipelineStepConfig = { id: string; enabled: boolean }
+/**
+ * Resolved step, as returned by `describe_pipeline`
+ */
+export type PipelineStepInfo = { id: string; description: string; enabled: boolean }
+/**
+ * External executable that rewrites the transcript
+ */
+export type PluginConfig = { 
+/**
+ * Unique name, used as the `plugin:<name>` pipeline step id
+ */
+name: string; 
+/**
+ * Absolute path to the executable
+ */
+command: string; args: string[]; 
+/**
+ * Kill the plugin if it runs longer than this
+ */
+timeout_ms: number }
+/**
+ * What the recording popup shows, which decides its default size
+ */
+export type PopupMode = 
+/**
+ * Waveform while recording or transcribing
+ */
+"recording" | 
+/**
+ * One-line error or notice with actions
+ */
+"error" | 
+/**
+ * Transcript text shown before it's delivered
+ */
+"preview" | 
+/**
+ * Question with confirm and cancel buttons
+ */
+"confirm"
+/**
+ * Logical size of the recording popup
+ */
+export type PopupSize = { width: number; height: number }
+/**
+ * Provider types supported by the application
+ */
+export type Provider = "open_ai" | "azure_open_ai"
+export type ProviderAudioFormat = { provider: Provider; format: AudioFormat }
+/**
+ * A provider's key stopped working, or works again
+ */
+export type ProviderAuthChanged = { provider: Provider; 
+/**
+ * The key was accepted before and is now rejected; re-enter it
+ */
+needsReauth: boolean }
+export type ProviderCatalog = { 
+/**
+ * Date the figures were checked ("YYYY-MM-DD")
+ */
+updated: string; entries: ProviderCatalogEntry[] }
+export type ProviderCatalogEntry = { provider: Provider; name: string; 
+/**
+ * Model (OpenAI) or model family behind the deployment (Azure)
+ */
+model: string; 
+/**
+ * List price in US dollars per minute of audio
+ */
+usdPerMinute: number; 
+/**
+ * Transcription time per second of audio, as commonly seen
+ */
+typicalMsPerAudioSec: number; 
+/**
+ * Transcription time per second of audio measured here, None until the
+ * provider has transcribed something
+ */
+measuredMsPerAudioSec: number | null; 
+/**
+ * Largest file the provider accepts
+ */
+maxFileBytes: number; 
+/**
+ * Highest recording sample rate the provider takes
+ */
+maxSampleRate: number; maxBitsPerSample: number; 
+/**
+ * Whether recordings can keep the microphone's own rate
+ */
+acceptsNativeRate: boolean }
+/**
+ * Cost-aware provider selection. When enabled, each dictation goes to the
+ * cheapest configured provider expected to finish within `max_latency_ms`,
+ * instead of always to `active_provider`.
+ */
+export type ProviderPolicyConfig = { enabled: boolean; 
+/**
+ * Latency target for a transcription in milliseconds
+ */
+max_latency_ms: number; 
+/**
+ * Price per minute of audio. Providers without an entry are tried last.
+ */
+prices: ProviderPrice[] }
+export type ProviderPrice = { provider: Provider; 
+/**
+ * US dollars per minute of audio
+ */
+usd_per_minute: number }
+/**
+ * Quiet hours schedule (local time)
+ */
+export type QuietHoursConfig = { enabled: boolean; 
+/**
+ * Start time in "HH:MM" format, e.g. "22:00"
+ */
+start: string; 
+/**
+ * End time in "HH:MM" format, e.g. "08:00". May be earlier than start (spans midnight).
+ */
+end: string }
+/**
+ * Context from recent dictations, so one that continues them keeps their
+ * terminology and casing. The transcripts are only kept in memory.
+ */
+export type RecentContextConfig = { enabled: boolean; 
+/**
+ * How many of the latest dictations into the same app to include (1-2)
+ */
+dictations: number; 
+/**
+ * Longest context in characters, cut from the end
+ */
+max_chars: number; 
+/**
+ * Dictations older than this are left out
+ */
+max_age_minutes: number }
+/**
+ * Recording format, for providers that do better with higher fidelity
+ */
+export type RecorderConfig = { format: AudioFormat; 
+/**
+ * Replaces `format` when recording for one of these providers
+ */
+provider_formats: ProviderAudioFormat[] }
+/**
+ * Which popup buttons apply, sent whenever the controller state changes
+ */
+export type RecordingControls = { 
+/**
+ * `stop_recording` would stop a recording
+ */
+canStop: boolean; 
+/**
+ * `cancel_recording` would discard a recording
+ */
+canCancel: boolean; 
+/**
+ * Releasing the trigger no longer stops the recording, only Stop does
+ */
+locked: boolean }
+/**
+ * Space locked the recording for hands-free dictation; it now runs until
+ * stopped
+ */
+export type RecordingLocked = { 
+/**
+ * How long the recording had been running when it was locked
+ */
+elapsedMs: number }
+/**
+ * Recording state change event - single event stream for all state transitions
+ */
+export type RecordingStateChanged = 
+/**
+ * Recording has started
+ */
+{ state: "started"; deviceName: string; sampleRate: number } | 
+/**
+ * Recording is being transcribed
+ */
+{ state: "transcribing" } | 
+/**
+ * Recording completed successfully
+ */
+{ state: "stopped"; text: string } | 
+/**
+ * Transcription finished but the provider returned no text
+ */
+{ state: "noSpeech" } | 
+/**
+ * Recording was cancelled by user
+ */
+{ state: "cancelled" } | 
+/**
+ * The transcript is longer than `max_delivery_chars` and waits for
+ * `deliver_held_transcript` or `discard_held_transcript`
+ */
+{ state: "held"; chars: number; maxChars: number; preview: string } | 
+/**
+ * An error occurred during recording or transcription
+ */
+{ state: "error"; errorType: string; errorMessage: string; userMessage: string; audioFilePath: string | null }
+/**
+ * How to settle a step the pack sets differently from the current config
+ */
+export type Resolution = "keepMine" | "useTheirs"
+/**
+ * Onboarding step that needs a restart to take effect
+ */
+export type RestartStep = "accessibility" | "inputMonitoring"
+/**
+ * Transcript file written next to each audio file
+ */
+export type SidecarFormat = "txt" | 
+/**
+ * Markdown with the file name as a heading
+ */
+"md" | 
+/**
+ * SubRip subtitles, from the provider's segment timestamps
+ */
+"srt" | 
+/**
+ * WebVTT subtitles, from the provider's segment timestamps
+ */
+"vtt"
+/**
+ * Feedback sound settings
+ */
+export type SoundConfig = { 
+/**
+ * Master switch for all feedback sounds
+ */
+enabled: boolean; 
+/**
+ * Play a cue when recording starts
+ */
+start: boolean; 
+/**
+ * Play a cue when Space locks the recording for hands-free dictation
+ */
+locked: boolean; 
+/**
+ * Play a cue once the transcript has been pasted
+ */
+done: boolean; 
+/**
+ * Play a cue when recording, transcription or paste fails
+ */
+error: boolean; 
+/**
+ * Output device name for cues. None follows the system default output.
+ */
+output_device: string | null; 
+/**
+ * Cue volume from 0.0 to 1.0, applied on top of the system volume
+ */
+volume: number; 
+/**
+ * Speak short confirmations ("Locked", "Cancelled") instead of beeps
+ */
+spoken: boolean; 
+/**
+ * Speech voice for spoken cues, from `get_voices`. None uses the system voice.
+ */
+voice: string | null; 
+/**
+ * Leave the start cue out of the recording: what the microphone picks up
+ * while it plays is dropped, so a cue leaking from the speakers can't
+ * end up in the transcript
+ */
+gate_start_cue: boolean }
+export type StageTiming = { stage: TraceStage; ms: number }
+/**
+ * Summary for the gamification surface, emitted after each dictation
+ */
+export type StatsUpdated = { totalDictations: number; totalWords: number; wordsToday: number; 
+/**
+ * Consecutive days with at least one dictation, ending today (or yesterday)
+ */
+currentStreakDays: number; longestStreakDays: number }
+/**
+ * A pipeline step the pack and the current config disagree on
+ */
+export type StepConflict = { id: string; 
+/**
+ * Whether the step runs now
+ */
+mine: boolean; 
+/**
+ * Whether the pack runs it
+ */
+theirs: boolean }
+/**
+ * Bullet summary and action items for locked (hands-free) recordings, e.g.
+ * meetings. Both the transcript and the summary are kept in history.
+ */
+export type SummaryConfig = { enabled: boolean; 
+/**
+ * Shorter transcripts aren't summarized
+ */
+min_words: number; 
+/**
+ * What gets pasted when a summary was made
+ */
+paste: SummaryPaste; 
+/**
+ * Where extracted action items go, e.g. Reminders or a todo.md
+ */
+action_item_sinks: ActionItemSinkConfig[] }
+export type SummaryPaste = "transcript" | "summary" | 
+/**
+ * Summary first, then the full transcript
+ */
+"both"
+/**
+ * A setting the palette turns on and off
+ */
+export type ToggleSetting = "dryRun" | "summary" | "sounds" | "announcements"
+/**
+ * Stage of a dictation, from releasing the trigger to delivery
+ */
+export type TraceStage = 
+/**
+ * Stopping the audio stream
+ */
+"captureStop" | 
+/**
+ * Writing out the WAV file
+ */
+"fileFinalize" | 
+/**
+ * Sending the audio to the provider
+ */
+"upload" | 
+/**
+ * Waiting for the provider's response
+ */
+"provider" | 
+/**
+ * Pipeline steps and summary
+ */
+"postProcess" | 
+/**
+ * Paste and the other output sinks
+ */
+"delivery"
+/**
+ * Menu bar icon style. The idle icon is always a template image that macOS
+ * tints for the menu bar; color badges can't be templates, so those icons are
+ * redrawn for light/dark mode instead.
+ */
+export type TrayIconTheme = 
+/**
+ * Template image with a dot badge while recording
+ */
+"monochrome" | 
+/**
+ * Red recording dot
+ */
+"redDot" | 
+/**
+ * Blue ring with a white center, readable without relying on hue
+ */
+"colorblind"
+/**
+ * The tray menu was rebuilt because these sections changed
+ */
+export type TrayMenuChanged = { sections: MenuSection[] }
+/**
+ * Push-to-talk trigger. Modifier triggers help on external keyboards without Fn.
+ */
+export type TriggerKey = "fn" | "rightOption" | "rightCommand" | "rightControl" | "rightShift" | 
+/**
+ * Control and Option held together (either side)
+ */
+"controlOption" | 
+/**
+ * Raw key captured by `learn_trigger_key`, e.g. a key remapped with
+ * Karabiner-Elements. `modifier_mask` holds the flag bits that are set while
+ * a modifier key is held; None for regular keys.
+ */
+{ custom: { keycode: number; modifier_mask: number | null } }
+/**
+ * Aggregate usage statistics (stored locally)
+ */
+export type UsageStats = { 
+/**
+ * Number of dictations where the provider returned no text
+ */
+empty_transcripts: number; 
+/**
+ * Number of dictations that produced text
+ */
+total_dictations: number; 
+/**
+ * Number of words across all dictations
+ */
+total_words: number; 
+/**
+ * Per-day totals keyed by local date ("YYYY-MM-DD")
+ */
+daily: { [key in string]: DailyStats } }
+export type ValidationIssue = { field: ConfigField; kind: IssueKind; severity: IssueSeverity; 
+/**
+ * User-facing explanation of what to fix
+ */
+message: string }
+/**
+ * Folder whose new audio files are transcribed as they arrive, e.g. voice
+ * memos synced from a phone
+ */
+export type WatchFolderConfig = { enabled: boolean; folder: string | null; 
+/**
+ * Format of the transcript written next to each file
+ */
+format: SidecarFormat }
+/**
+ * A file dropped into the watch folder was transcribed
+ */
+export type WatchFolderTranscribed = { 
+/**
+ * Path of the audio file
+ */
+file: string; outcome: BatchFileOutcome }
 
 /** tauri-specta globals **/
 
@@ -0,0 +1,23 @@
+import { AlertTriangle } from 'lucide-react'
+import { useDictationConflicts } from '@/hooks/useDictationConflicts'
+import { Alert, AlertDescription, AlertTitle } from '../ui/alert'
+
+export function ConflictWarnings() {
+  const { data: conflicts } = useDictationConflicts()
+
+  if (!conflicts || conflicts.length === 0) {
+    return null
+  }
+
+  return (
+    <div className="mb-6 space-y-2">
+      {conflicts.map((conflict) => (
+        <Alert key={conflict.tool}>
+          <AlertTriangle className="h-4 w-4" />
+          <AlertTitle>{conflict.tool} may also react to Fn</AlertTitle>
+          <AlertDescription>{conflict.guidance}</AlertDescription>
+        </Alert>
+      ))}
+    </div>
+  )
+}
@@ -0,0 +1,49 @@
+import { ask } from '@tauri-apps/plugin-dialog'
+import { Keyboard } from 'lucide-react'
+import { useDisableFnKeyAction, useFnKeyUsage } from '@/hooks/useFnKeyUsage'
+import { Alert, AlertDescription, AlertTitle } from '../ui/alert'
+import { Button } from '../ui/button'
+
+export function FnKeySetting() {
+  const { data: usage } = useFnKeyUsage()
+  const disableFnKeyAction = useDisableFnKeyAction()
+
+  // Dictation is reported by ConflictWarnings, only the emoji picker is handled here
+  if (usage !== 'showEmoji') {
+    return null
+  }
+
+  const handleDisable = async () => {
+    const confirmed = await ask(
+      'Dictara will change "Press 🌐 key to" in System Settings → Keyboard to "Do Nothing". The change applies after you log out and back in.',
+      { title: 'Change Fn key setting?', kind: 'info' }
+    )
+    if (!confirmed) {
+      return
+    }
+
+    try {
+      await disableFnKeyAction.mutateAsync()
+    } catch (e) {
+      console.error('[FnKeySetting] Failed to change Fn key setting:', e)
+    }
+  }
+
+  return (
+    <Alert className="mb-6">
+      <Keyboard className="h-4 w-4" />
+      <AlertTitle>Fn opens the emoji picker</AlertTitle>
+      <AlertDescription className="space-y-2">
+        <p>macOS may show the character palette while you hold Fn to dictate.</p>
+        <Button
+          variant="outline"
+          size="sm"
+          onClick={handleDisable}
+          disabled={disableFnKeyAction.isPending}
+        >
+          Set Fn to Do Nothing
+        </Button>
+      </AlertDescription>
+    </Alert>
+  )
+}
@@ -2,6 +2,8 @@ import { Link, useRouterState } from '@tanstack/react-router'
 import { Download, Info, Key } from 'lucide-react'
 import { useState, type ReactNode } from 'react'
 import { Separator } from '../ui/separator'
+import { ConflictWarnings } from './ConflictWarnings'
+import { FnKeySetting } from './FnKeySetting'
 import {
   Sidebar,
   SidebarContent,
@@ -78,7 +80,11 @@ export function PreferencesLayout({ children }: PreferencesLayoutProps) {
           <Separator orientation="vertical" className="mr-2 h-4" />
           <span className="font-medium">{pageTitle}</span>
         </header>
-        <main className="flex-1 overflow-y-auto overflow-x-hidden p-6">{children}</main>
+        <main className="flex-1 overflow-y-auto overflow-x-hidden p-6">
+          <ConflictWarnings />
+          <FnKeySetting />
+          {children}
+        </main>
       </SidebarInset>
     </SidebarProvider>
   )
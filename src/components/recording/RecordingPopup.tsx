@@ -10,6 +10,9 @@ import { useResizePopupForError } from "@/hooks/useRecording";
 import { RecordingState } from "./states/RecordingState";
 import { TranscribingState } from "./states/TranscribingState";
 import { ErrorState } from "./states/ErrorState";
+import { NoSpeechState } from "./states/NoSpeechState";
+import { PasteFailedState } from "./states/PasteFailedState";
+import { HeldTranscriptState } from "./states/HeldTranscriptState";
 
 function RecordingPopup() {
   const { smoothedLevel } = useAudioLevel();
@@ -34,7 +37,9 @@ function RecordingPopup() {
 
         case "transcribing":
         case "stopped":
+        case "noSpeech":
         case "cancelled":
+        case "held":
           timerFns.cleanupTimer();
           break;
 
@@ -50,14 +55,23 @@ function RecordingPopup() {
   const {
     state,
     error,
+    source,
+    pasteFailure,
+    held,
+    locked,
+    controls,
     handleCancel,
     handleStop,
     handleRetry,
     handleDismiss,
+    handleDeliverHeld,
+    handleDiscardHeld,
     isCancelPending,
     isStopPending,
     isRetryPending,
     isDismissPending,
+    isDeliverHeldPending,
+    isDiscardHeldPending,
   } = useRecordingStateMachine(handleRecordingEvent);
 
   const { elapsedMs, startTimer, cleanupTimer } = useRecordingTimer(handleStop);
@@ -78,6 +92,29 @@ function RecordingPopup() {
         />
       )}
 
+      {/* Paste Failed State */}
+      {state === "pasteFailed" && pasteFailure && (
+        <PasteFailedState
+          failure={pasteFailure}
+          onDismiss={handleDismiss}
+          isDismissPending={isDismissPending}
+        />
+      )}
+
+      {/* Held Transcript State */}
+      {state === "held" && held && (
+        <HeldTranscriptState
+          held={held}
+          onDeliver={handleDeliverHeld}
+          onDiscard={handleDiscardHeld}
+          isDeliverPending={isDeliverHeldPending}
+          isDiscardPending={isDiscardHeldPending}
+        />
+      )}
+
+      {/* No Speech State */}
+      {state === "noSpeech" && <NoSpeechState />}
+
       {/* Transcribing State */}
       {state === "transcribing" && <TranscribingState />}
 
@@ -86,8 +123,12 @@ function RecordingPopup() {
         <RecordingState
           elapsedMs={elapsedMs}
           smoothedLevel={smoothedLevel}
+          deviceName={source?.deviceName ?? null}
+          locked={locked !== null || controls.locked}
           onCancel={handleCancel}
           onStop={handleStop}
+          canCancel={controls.canCancel}
+          canStop={controls.canStop}
           isCancelPending={isCancelPending}
           isStopPending={isStopPending}
         />
@@ -5,10 +5,24 @@ import {
   useStopRecording,
   useRetryTranscription,
   useDismissError,
+  useDeliverHeldTranscript,
+  useDiscardHeldTranscript,
 } from "@/hooks/useRecording";
-import { events, type RecordingStateChanged } from "@/bindings";
+import {
+  events,
+  type PasteFailed,
+  type RecordingControls,
+  type RecordingLocked,
+  type RecordingStateChanged,
+} from "@/bindings";
 
-export type RecordingState = "recording" | "transcribing" | "error";
+export type RecordingState =
+  | "recording"
+  | "transcribing"
+  | "noSpeech"
+  | "pasteFailed"
+  | "held"
+  | "error";
 
 // Extract error type from the discriminated union
 export type RecordingErrorPayload = Extract<
@@ -16,6 +30,18 @@ export type RecordingErrorPayload = Extract<
   { state: "error" }
 >;
 
+// Extract started payload (input device info) from the discriminated union
+export type RecordingSourcePayload = Extract<
+  RecordingStateChanged,
+  { state: "started" }
+>;
+
+// Extract held payload (over-long transcript awaiting confirmation)
+export type HeldTranscriptPayload = Extract<
+  RecordingStateChanged,
+  { state: "held" }
+>;
+
 // Re-export for external use
 export type { RecordingStateChanged };
 
@@ -25,14 +51,23 @@ export type RecordingEventHandler = (event: RecordingStateChanged) => void;
 interface UseRecordingStateMachineResult {
   state: RecordingState;
   error: RecordingErrorPayload | null;
+  source: RecordingSourcePayload | null;
+  pasteFailure: PasteFailed | null;
+  held: HeldTranscriptPayload | null;
+  locked: RecordingLocked | null;
+  controls: RecordingControls;
   handleCancel: () => Promise<void>;
   handleStop: () => Promise<void>;
   handleRetry: () => Promise<void>;
   handleDismiss: () => Promise<void>;
+  handleDeliverHeld: () => Promise<void>;
+  handleDiscardHeld: () => Promise<void>;
   isCancelPending: boolean;
   isStopPending: boolean;
   isRetryPending: boolean;
   isDismissPending: boolean;
+  isDeliverHeldPending: boolean;
+  isDiscardHeldPending: boolean;
 }
 
 export function useRecordingStateMachine(
@@ -40,12 +75,24 @@ export function useRecordingStateMachine(
 ): UseRecordingStateMachineResult {
   const [state, setState] = useState<RecordingState>("recording");
   const [error, setError] = useState<RecordingErrorPayload | null>(null);
+  const [source, setSource] = useState<RecordingSourcePayload | null>(null);
+  const [pasteFailure, setPasteFailure] = useState<PasteFailed | null>(null);
+  const [held, setHeld] = useState<HeldTranscriptPayload | null>(null);
+  const [locked, setLocked] = useState<RecordingLocked | null>(null);
+  // The popup opens for a running recording, so its buttons start enabled
+  const [controls, setControls] = useState<RecordingControls>({
+    canStop: true,
+    canCancel: true,
+    locked: false,
+  });
 
   // TanStack Query mutation hooks
   const cancelRecording = useCancelRecording();
   const stopRecording = useStopRecording();
   const retryTranscription = useRetryTranscription();
   const dismissError = useDismissError();
+  const deliverHeldTranscript = useDeliverHeldTranscript();
+  const discardHeldTranscript = useDiscardHeldTranscript();
 
   // Keep onEvent in a ref to avoid re-subscribing when callback changes
   const onEventRef = useRef(onEvent);
@@ -94,6 +141,24 @@ export function useRecordingStateMachine(
     }
   }, [dismissError]);
 
+  const handleDeliverHeld = useCallback(async () => {
+    console.log("Paste held transcript clicked");
+    try {
+      await deliverHeldTranscript.mutateAsync();
+    } catch (err) {
+      console.error("Failed to deliver held transcript:", err);
+    }
+  }, [deliverHeldTranscript]);
+
+  const handleDiscardHeld = useCallback(async () => {
+    console.log("Discard held transcript clicked");
+    try {
+      await discardHeldTranscript.mutateAsync();
+    } catch (err) {
+      console.error("Failed to discard held transcript:", err);
+    }
+  }, [discardHeldTranscript]);
+
   // Set up single typesafe event listener
   useEffect(() => {
     const setupListener = async () => {
@@ -106,23 +171,39 @@ export function useRecordingStateMachine(
           case "started":
             setState("recording");
             setError(null);
+            setPasteFailure(null);
+            setHeld(null);
+            setLocked(null);
+            setSource(payload);
             break;
 
           case "transcribing":
             setState("transcribing");
+            setLocked(null);
             break;
 
           case "stopped":
             setState("recording");
             break;
 
+          case "noSpeech":
+            setState("noSpeech");
+            break;
+
           case "cancelled":
             setState("recording");
+            setLocked(null);
+            break;
+
+          case "held":
+            setState("held");
+            setHeld(payload);
             break;
 
           case "error":
             setState("error");
             setError(payload);
+            setLocked(null);
             break;
         }
 
@@ -143,16 +224,88 @@ export function useRecordingStateMachine(
     };
   }, []);
 
+  // Which buttons apply in the controller's current state
+  useEffect(() => {
+    const setupListener = async () => {
+      const unlisten = await events.recordingControls.listen((event) => {
+        setControls(event.payload);
+      });
+
+      return unlisten;
+    };
+
+    let cleanup: (() => void) | undefined;
+    setupListener().then((cleanupFn) => {
+      cleanup = cleanupFn;
+    });
+
+    return () => {
+      if (cleanup) cleanup();
+    };
+  }, []);
+
+  // Space locked the recording; it keeps running without the trigger held
+  useEffect(() => {
+    const setupListener = async () => {
+      const unlisten = await events.recordingLocked.listen((event) => {
+        console.log("[Popup] Recording locked after", event.payload.elapsedMs, "ms");
+        setLocked(event.payload);
+      });
+
+      return unlisten;
+    };
+
+    let cleanup: (() => void) | undefined;
+    setupListener().then((cleanupFn) => {
+      cleanup = cleanupFn;
+    });
+
+    return () => {
+      if (cleanup) cleanup();
+    };
+  }, []);
+
+  // Paste failures arrive on their own event, after the "stopped" state change
+  useEffect(() => {
+    const setupListener = async () => {
+      const unlisten = await events.pasteFailed.listen((event) => {
+        console.log("[Popup] Paste failed:", event.payload.reason);
+        setPasteFailure(event.payload);
+        setState("pasteFailed");
+      });
+
+      return unlisten;
+    };
+
+    let cleanup: (() => void) | undefined;
+    setupListener().then((cleanupFn) => {
+      cleanup = cleanupFn;
+    });
+
+    return () => {
+      if (cleanup) cleanup();
+    };
+  }, []);
+
   return {
     state,
     error,
+    source,
+    pasteFailure,
+    held,
+    locked,
+    controls,
     handleCancel,
     handleStop,
     handleRetry,
     handleDismiss,
+    handleDeliverHeld,
+    handleDiscardHeld,
     isCancelPending: cancelRecording.isPending,
     isStopPending: stopRecording.isPending,
     isRetryPending: retryTranscription.isPending,
     isDismissPending: dismissError.isPending,
+    isDeliverHeldPending: deliverHeldTranscript.isPending,
+    isDiscardHeldPending: discardHeldTranscript.isPending,
   };
 }
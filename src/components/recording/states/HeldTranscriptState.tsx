@@ -0,0 +1,53 @@
+import { X } from "lucide-react";
+import type { RecordingStateChanged } from "@/bindings";
+
+// Extract the held variant from the discriminated union
+type HeldTranscript = Extract<RecordingStateChanged, { state: "held" }>;
+
+interface HeldTranscriptStateProps {
+  held: HeldTranscript;
+  onDeliver: () => void;
+  onDiscard: () => void;
+  isDeliverPending: boolean;
+  isDiscardPending: boolean;
+}
+
+export function HeldTranscriptState({
+  held,
+  onDeliver,
+  onDiscard,
+  isDeliverPending,
+  isDiscardPending,
+}: HeldTranscriptStateProps) {
+  return (
+    <div className="flex items-center justify-between w-full h-full px-3 py-2 gap-2">
+      {/* Summary and preview */}
+      <div className="flex-1 min-w-0 overflow-hidden">
+        <div className="text-yellow-400 text-xs font-semibold">
+          Long Transcript ({held.chars.toLocaleString()} characters)
+        </div>
+        <div className="text-gray-300 text-[10px] leading-tight line-clamp-2">
+          {held.preview}
+        </div>
+      </div>
+
+      {/* Action Buttons */}
+      <div className="flex gap-1.5 flex-shrink-0">
+        <button
+          onClick={onDeliver}
+          disabled={isDeliverPending}
+          className="h-6 px-2 text-[10px] rounded bg-gray-600 hover:bg-gray-500 text-white font-medium transition-colors flex items-center disabled:opacity-50"
+        >
+          {isDeliverPending ? "..." : "Paste"}
+        </button>
+        <button
+          onClick={onDiscard}
+          disabled={isDiscardPending}
+          className="w-6 h-6 rounded bg-gray-600 hover:bg-gray-500 flex items-center justify-center transition-colors disabled:opacity-50"
+        >
+          <X className="w-3.5 h-3.5 text-white" strokeWidth={2.5} />
+        </button>
+      </div>
+    </div>
+  );
+}
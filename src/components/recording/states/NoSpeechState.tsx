@@ -0,0 +1,15 @@
+import { MicOff } from "lucide-react";
+
+export function NoSpeechState() {
+  return (
+    <div className="flex items-center justify-center w-full h-full px-3 py-2 gap-2">
+      <MicOff
+        className="w-4 h-4 text-gray-400 flex-shrink-0"
+        strokeWidth={2.5}
+      />
+      <div className="text-gray-300 text-xs font-semibold">
+        No speech detected
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,39 @@
+import { X } from "lucide-react";
+import type { PasteFailed } from "@/bindings";
+
+interface PasteFailedStateProps {
+  failure: PasteFailed;
+  onDismiss: () => void;
+  isDismissPending: boolean;
+}
+
+export function PasteFailedState({
+  failure,
+  onDismiss,
+  isDismissPending,
+}: PasteFailedStateProps) {
+  return (
+    <div className="flex items-center justify-between w-full h-full px-3 py-2 gap-2">
+      {/* Error Message */}
+      <div className="flex-1 min-w-0 overflow-hidden">
+        <div className="text-yellow-400 text-xs font-semibold">
+          {failure.textOnClipboard ? "Copied, Not Pasted" : "Paste Failed"}
+        </div>
+        <div className="text-gray-300 text-[10px] leading-tight line-clamp-2">
+          {failure.userMessage}
+        </div>
+      </div>
+
+      {/* Action Buttons */}
+      <div className="flex gap-1.5 flex-shrink-0">
+        <button
+          onClick={onDismiss}
+          disabled={isDismissPending}
+          className="w-6 h-6 rounded bg-gray-600 hover:bg-gray-500 flex items-center justify-center transition-colors disabled:opacity-50"
+        >
+          <X className="w-3.5 h-3.5 text-white" strokeWidth={2.5} />
+        </button>
+      </div>
+    </div>
+  );
+}
@@ -1,10 +1,15 @@
-import { Square, X } from "lucide-react";
+import { Lock, Square, X } from "lucide-react";
 
 interface RecordingStateProps {
   elapsedMs: number;
   smoothedLevel: number;
+  deviceName: string | null;
+  // Locked with Space: the recording runs until stopped, so the stop button leads
+  locked: boolean;
   onCancel: () => void;
   onStop: () => void;
+  canCancel: boolean;
+  canStop: boolean;
   isCancelPending: boolean;
   isStopPending: boolean;
 }
@@ -28,8 +33,12 @@ function formatTime(ms: number): string {
 export function RecordingState({
   elapsedMs,
   smoothedLevel,
+  deviceName,
+  locked,
   onCancel,
   onStop,
+  canCancel,
+  canStop,
   isCancelPending,
   isStopPending,
 }: RecordingStateProps) {
@@ -39,28 +48,44 @@ export function RecordingState({
       style={{ boxShadow: getInsetShadow(smoothedLevel) }}
     >
       {/* Timer Display */}
-      <div className="text-gray-300 font-mono text-xs mb-2">
+      <div className="flex items-center gap-1 text-gray-300 font-mono text-xs mb-2">
+        {locked && (
+          <Lock className="w-3 h-3 text-amber-400" strokeWidth={2.5} aria-label="Locked" />
+        )}
         {formatTime(elapsedMs)}
       </div>
 
+      {/* Input Device - catches recording from the wrong microphone early */}
+      {deviceName && (
+        <div
+          className="text-gray-400 text-[10px] leading-none mb-2 max-w-full px-2 truncate"
+          title={`Recording from ${deviceName}`}
+        >
+          {deviceName}
+        </div>
+      )}
+
       {/* Button Row */}
       <div className="flex gap-2">
         {/* Cancel Button */}
         <button
           onClick={onCancel}
-          disabled={isCancelPending}
+          disabled={!canCancel || isCancelPending}
+          title="Cancel without transcribing"
           className="w-6 h-6 aspect-square rounded-lg shrink-0 bg-gray-700 hover:bg-gray-600 flex items-center justify-center transition-colors cursor-pointer disabled:opacity-50"
         >
           <X className="w-4 h-4 text-white" strokeWidth={2.5} />
         </button>
 
-        {/* Stop Recording Button */}
+        {/* Stop Recording Button - wider once locked, since it's the way out */}
         <button
           onClick={onStop}
-          disabled={isStopPending}
-          className="w-6 h-6 aspect-square rounded-lg shrink-0 bg-red-500 hover:bg-red-600 flex items-center justify-center transition-colors cursor-pointer disabled:opacity-50"
+          disabled={!canStop || isStopPending}
+          title="Stop and transcribe"
+          className={`${locked ? "w-12 gap-1 px-2" : "w-6 aspect-square"} h-6 rounded-lg shrink-0 bg-red-500 hover:bg-red-600 flex items-center justify-center transition-colors cursor-pointer disabled:opacity-50`}
         >
           <Square className="w-3 h-3 text-white" fill="white" strokeWidth={0} />
+          {locked && <span className="text-white text-[10px] leading-none">Stop</span>}
         </button>
       </div>
     </div>
@@ -48,6 +48,8 @@ export function useSaveAzureOpenAIConfig() {
 interface TestAzureOpenAIConfigParams {
   apiKey: string
   endpoint: string
+  /** Run a real (billed) transcription instead of the lightweight check */
+  deep?: boolean
 }
 
 /**
@@ -56,7 +58,11 @@ interface TestAzureOpenAIConfigParams {
 export function useTestAzureOpenAIConfig() {
   return useMutation({
     mutationFn: async (params: TestAzureOpenAIConfigParams): Promise<boolean> => {
-      const result = await commands.testAzureOpenaiConfig(params.apiKey, params.endpoint)
+      const result = await commands.testAzureOpenaiConfig(
+        params.apiKey,
+        params.endpoint,
+        params.deep ?? false
+      )
       if (result.status === 'error') {
         throw new Error(result.error)
       }
@@ -0,0 +1,12 @@
+import { useQuery } from '@tanstack/react-query'
+import { commands, type DictationConflict } from '@/bindings'
+
+/**
+ * Hook to detect other dictation tools bound to the Fn key.
+ */
+export function useDictationConflicts() {
+  return useQuery({
+    queryKey: ['dictationConflicts'],
+    queryFn: async (): Promise<DictationConflict[]> => commands.detectDictationConflicts(),
+  })
+}
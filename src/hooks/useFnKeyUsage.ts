@@ -0,0 +1,32 @@
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query'
+import { commands, type FnKeyUsage } from '@/bindings'
+
+/**
+ * Hook to read the macOS "Press 🌐 key to" setting.
+ */
+export function useFnKeyUsage() {
+  return useQuery({
+    queryKey: ['fnKeyUsage'],
+    queryFn: async (): Promise<FnKeyUsage> => commands.getFnKeyUsage(),
+  })
+}
+
+/**
+ * Hook to set "Press 🌐 key to" to "Do Nothing".
+ */
+export function useDisableFnKeyAction() {
+  const queryClient = useQueryClient()
+
+  return useMutation({
+    mutationFn: async () => {
+      const result = await commands.disableFnKeyAction()
+      if (result.status === 'error') {
+        throw new Error(result.error)
+      }
+    },
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['fnKeyUsage'] })
+      queryClient.invalidateQueries({ queryKey: ['dictationConflicts'] })
+    },
+  })
+}
@@ -0,0 +1,24 @@
+import { useEffect, useState } from 'react'
+import { commands, events, type PermissionStatus } from '@/bindings'
+
+/**
+ * Hook that tracks live permission status.
+ * Loads the current status once, then follows `permission-status` events.
+ */
+export function usePermissionStatus() {
+  const [status, setStatus] = useState<PermissionStatus | null>(null)
+
+  useEffect(() => {
+    commands.getPermissionStatus().then(setStatus)
+
+    const unlisten = events.permissionStatus.listen((event) => {
+      setStatus(event.payload)
+    })
+
+    return () => {
+      unlisten.then((fn) => fn())
+    }
+  }, [])
+
+  return status
+}
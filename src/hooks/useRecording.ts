@@ -2,7 +2,7 @@ import { useMutation } from '@tanstack/react-query'
 import { commands } from '@/bindings'
 
 /**
- * Hook to cancel recording.
+ * Hook to cancel recording without transcribing it.
  */
 export function useCancelRecording() {
   return useMutation({
@@ -16,7 +16,7 @@ export function useCancelRecording() {
 }
 
 /**
- * Hook to stop recording.
+ * Hook to stop recording and transcribe it.
  */
 export function useStopRecording() {
   return useMutation({
@@ -70,3 +70,31 @@ export function useResizePopupForError() {
     },
   })
 }
+
+/**
+ * Hook to deliver a transcript held for exceeding the length cap.
+ */
+export function useDeliverHeldTranscript() {
+  return useMutation({
+    mutationFn: async (): Promise<void> => {
+      const result = await commands.deliverHeldTranscript()
+      if (result.status === 'error') {
+        throw new Error(result.error)
+      }
+    },
+  })
+}
+
+/**
+ * Hook to discard a transcript held for exceeding the length cap.
+ */
+export function useDiscardHeldTranscript() {
+  return useMutation({
+    mutationFn: async (): Promise<void> => {
+      const result = await commands.discardHeldTranscript()
+      if (result.status === 'error') {
+        throw new Error(result.error)
+      }
+    },
+  })
+}